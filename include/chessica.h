@@ -0,0 +1,40 @@
+/* C interface of the engine, see src/ffi.rs. Link against the library `cargo build --release` builds
+   (target/release/libchess_engine.so, .dylib or chess_engine.dll) */
+#ifndef CHESSICA_H
+#define CHESSICA_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Chessica Chessica;
+
+/* `score` in centipawns from the side to move's point of view, `pv` space-separated UCI moves,
+   only valid during the call */
+typedef void (*chessica_info_callback)(void *user_data, uint32_t depth, int32_t score, const char *pv);
+
+Chessica *chessica_new(size_t hash_mb);
+void chessica_free(Chessica *engine);
+
+/* Both return false, and leave the position as it was, on a bad FEN or an illegal move */
+bool chessica_set_fen(Chessica *engine, const char *fen);
+bool chessica_make_move(Chessica *engine, const char *uci);
+
+/* NULL turns the reports off */
+void chessica_set_info_callback(Chessica *engine, chessica_info_callback callback, void *user_data);
+
+/* Blocks for about `movetime_ms`. False if there is no legal move */
+bool chessica_go_movetime(Chessica *engine, uint64_t movetime_ms);
+
+/* UCI notation, NULL if the last search found nothing. Valid until the next search or chessica_free */
+const char *chessica_best_move(const Chessica *engine);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,183 @@
+// Fits the score -> expected result mapping from self-play PGNs annotated with
+// `[%eval]` comments (in pawns, from White's point of view, like lichess does)
+//
+// Usage: eval_calibration <games.pgn>... > calibration.csv
+//
+// Writes a CSV with one row per (phase, score bucket): the mean score actually
+// achieved by White next to the logistic fit 1 / (1 + 10^(-cp / scale)).
+// The fitted scale for every phase is printed to stderr. If the scale drifts
+// after an evaluation change, the evaluation got stretched or compressed.
+use std::{collections::BTreeMap, env, fs};
+
+const BUCKET_CP: i32 = 50;
+const MAX_CP: i32 = 1000;  // scores beyond this (and mates) carry no information
+
+// The phase is approximated by the ply count, because evaluating a
+// position from the PGN would require replaying the moves
+const PHASES: [(&str, usize); 3] = [
+    ("opening",    20),
+    ("middlegame", 80),
+    ("endgame",    usize::MAX),
+];
+
+// (phase index, score in centipawns, result for White: 1, 0.5 or 0)
+type Sample = (usize, i32, f64);
+
+fn phase_of(ply: usize) -> usize {
+    PHASES.iter().position(|&(_, until)| ply < until).unwrap()
+}
+
+fn parse_result(tag: &str) -> Option<f64> {
+    match tag {
+        "1-0"     => Some(1.0),
+        "0-1"     => Some(0.0),
+        "1/2-1/2" => Some(0.5),
+        _ => None,
+    }
+}
+
+// Returns centipawns, None for mate scores and garbage
+fn parse_eval(comment: &str) -> Option<i32> {
+    let start = comment.find("[%eval ")? + "[%eval ".len();
+    let value = comment[start..].split([']', ' ']).next()?;
+    if value.starts_with('#') {
+        return None;
+    }
+    let pawns: f64 = value.parse().ok()?;
+    Some((pawns * 100.0).round() as i32)
+}
+
+fn is_move_token(token: &str) -> bool {
+    let number = token.trim_end_matches('.');
+    !(token.is_empty() ||
+      number.chars().all(|c| c.is_ascii_digit()) ||
+      token.starts_with('$') ||
+      parse_result(token).is_some() ||
+      token == "*")
+}
+
+// Collects samples from every game with a decisive or drawn result
+fn parse_pgn(text: &str, samples: &mut Vec<Sample>) {
+    let mut result = None;
+    let mut game_samples: Vec<(usize, i32)> = Vec::new();
+    let mut ply = 0;
+
+    let mut flush = |result: &mut Option<f64>, game_samples: &mut Vec<(usize, i32)>| {
+        if let Some(r) = result.take() {
+            samples.extend(game_samples.iter().map(|&(ply, cp)| (phase_of(ply), cp, r)));
+        }
+        game_samples.clear();
+    };
+
+    let mut in_comment = false;
+    let mut comment = String::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if !in_comment && line.starts_with('[') {
+            if line.starts_with("[Event ") {
+                flush(&mut result, &mut game_samples);
+                ply = 0;
+            } else if let Some(rest) = line.strip_prefix("[Result \"") {
+                result = parse_result(rest.trim_end_matches("\"]"));
+            }
+            continue;
+        }
+
+        let mut token = String::new();
+        for c in line.chars().chain(std::iter::once(' ')) {
+            if in_comment {
+                if c == '}' {
+                    in_comment = false;
+                    if let Some(cp) = parse_eval(&comment) {
+                        game_samples.push((ply, cp));
+                    }
+                    comment.clear();
+                } else {
+                    comment.push(c);
+                }
+            } else if c == '{' || c.is_whitespace() {
+                if is_move_token(&token) {
+                    ply += 1;
+                }
+                token.clear();
+                in_comment = c == '{';
+            } else {
+                token.push(c);
+            }
+        }
+        if in_comment {
+            comment.push(' ');
+        }
+    }
+    flush(&mut result, &mut game_samples);
+}
+
+fn expected(cp: f64, scale: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-cp / scale))
+}
+
+// Least squares over all samples, golden-section search on the scale
+fn fit_scale(samples: &[(i32, f64)]) -> f64 {
+    let error = |scale: f64| -> f64 {
+        samples.iter().map(|&(cp, r)| (expected(cp as f64, scale) - r).powi(2)).sum()
+    };
+
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut lo, mut hi) = (10.0, 2000.0);
+    while hi - lo > 0.5 {
+        let a = hi - ratio * (hi - lo);
+        let b = lo + ratio * (hi - lo);
+        if error(a) < error(b) {
+            hi = b;
+        } else {
+            lo = a;
+        }
+    }
+    (lo + hi) / 2.0
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let paths: Vec<String> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("Usage: eval_calibration <games.pgn>...");
+        std::process::exit(1);
+    }
+
+    let mut samples = Vec::new();
+    for path in &paths {
+        parse_pgn(&fs::read_to_string(path)?, &mut samples);
+    }
+    samples.retain(|&(_, cp, _)| cp.abs() <= MAX_CP);
+    eprintln!("{} annotated positions", samples.len());
+
+    println!("phase,score_cp,samples,actual,fitted");
+    for (phase, &(name, _)) in PHASES.iter().enumerate() {
+        let phase_samples: Vec<(i32, f64)> = samples.iter()
+            .filter(|&&(p, _, _)| p == phase)
+            .map(|&(_, cp, r)| (cp, r))
+            .collect();
+        if phase_samples.is_empty() {
+            continue;
+        }
+
+        let scale = fit_scale(&phase_samples);
+        eprintln!("{}: {} positions, scale {:.1}", name, phase_samples.len(), scale);
+
+        // bucket -> (samples, sum of results)
+        let mut buckets: BTreeMap<i32, (usize, f64)> = BTreeMap::new();
+        for &(cp, r) in &phase_samples {
+            let bucket = (cp as f64 / BUCKET_CP as f64).round() as i32;
+            let entry = buckets.entry(bucket).or_insert((0, 0.0));
+            entry.0 += 1;
+            entry.1 += r;
+        }
+
+        for (bucket, (n, sum)) in buckets {
+            let center = bucket * BUCKET_CP;
+            println!("{},{},{},{:.4},{:.4}", name, center, n, sum / n as f64, expected(center as f64, scale));
+        }
+    }
+
+    Ok(())
+}
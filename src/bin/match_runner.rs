@@ -0,0 +1,751 @@
+// Plays UCI engines against each other and writes the games as PGN, to measure
+// the strength difference between two builds without external tools
+//
+// Usage: match_runner [options]
+//   --engine <command>         up to two, the first one is the engine under test.
+//                              With one, it plays itself. Default: the chess-engine next to this binary
+//   --games <n>                default 2, played in pairs with colors swapped
+//   --tc <base>[+<inc>]        in seconds, default 10+0.1
+//   --movetime <ms>            instead of --tc
+//   --openings <file>          EPD or PGN, each opening is played by both sides in turn
+//   --pgn <file>               where the games go, default: nowhere
+//   --resign <moves> <cp>      a side that reports <= -cp for <moves> of its moves in a row loses
+//   --draw <move> <moves> <cp> from move <move> on, |score| <= cp for <moves> moves by both sides is a draw
+//   --max-moves <n>            a draw once both sides have played this many moves
+//
+// Besides the rules, a game is adjudicated a draw once neither side can force mate
+// (KNN vs K, a knight against a minor piece).
+//   --option <name>=<value>    sent to both engines, e.g. --option Hash=64 --option OwnBook=false
+//
+// Every move in the PGN carries the mover's score as a `[%eval]` comment from White's point
+// of view, the way `eval_calibration` reads it.
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+use chess_engine::core::{
+    chess_move::Move,
+    game::Game,
+    outcome::Outcome,
+    pgn::{self, add_opening_tags, write_pgn, PgnMove},
+    player::Player,
+    rules::draw::is_unwinnable_for_both,
+};
+
+// A bit of slack for the pipes, on top of the clock
+const TIME_MARGIN_MS: i64 = 100;
+// How late a `go movetime` answer may be before it is forfeited
+const MOVETIME_GRACE_MS: u64 = 5000;
+// For everything that is not a search: `uciok`, `readyok`, `bestmove` after `stop`
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+const MATE_CP: i32 = 100_000;
+
+#[derive(Debug, Clone, Copy)]
+enum TimeControl {
+    Clock { base_ms: i64, inc_ms: i64 },
+    MoveTime(u64),
+}
+
+impl std::fmt::Display for TimeControl {
+    // PGN `TimeControl` tag: "10+0.1", "1/5" for 5 seconds a move
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            TimeControl::Clock { base_ms, inc_ms: 0 } => write!(f, "{}", base_ms as f64 / 1000.0),
+            TimeControl::Clock { base_ms, inc_ms }    => write!(f, "{}+{}", base_ms as f64 / 1000.0, inc_ms as f64 / 1000.0),
+            TimeControl::MoveTime(ms)                 => write!(f, "1/{}", ms as f64 / 1000.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Resign {
+    moves: usize,
+    cp: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Draw {
+    from_move: usize,
+    moves: usize,
+    cp: i32,
+}
+
+#[derive(Debug)]
+struct Config {
+    engines: Vec<String>,
+    games: usize,
+    time_control: TimeControl,
+    openings: Option<String>,
+    pgn: Option<String>,
+    resign: Option<Resign>,
+    draw: Option<Draw>,
+    max_moves: Option<usize>,
+    options: Vec<(String, String)>,
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: match_runner [--engine <command>]... [--games <n>] [--tc <base>[+<inc>] | --movetime <ms>]");
+    eprintln!("                    [--openings <file.epd|file.pgn>] [--pgn <file>] [--resign <moves> <cp>]");
+    eprintln!("                    [--draw <move> <moves> <cp>] [--max-moves <n>] [--option <name>=<value>]...");
+    std::process::exit(1);
+}
+
+fn parse_args(args: &[String]) -> Result<Config, String> {
+    let mut config = Config {
+        engines: Vec::new(),
+        games: 2,
+        time_control: TimeControl::Clock { base_ms: 10_000, inc_ms: 100 },
+        openings: None,
+        pgn: None,
+        resign: None,
+        draw: None,
+        max_moves: None,
+        options: Vec::new(),
+    };
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let mut value = |what: &str| args.next().cloned().ok_or(format!("{} needs {}", flag, what));
+        fn number<T: std::str::FromStr>(s: String) -> Result<T, String> {
+            s.parse().map_err(|_| format!("Not a number: {}", s))
+        }
+        let seconds = |s: &str| -> Result<i64, String> {
+            let secs: f64 = s.parse().map_err(|_| format!("Not a number: {}", s))?;
+            Ok((secs * 1000.0).round() as i64)
+        };
+
+        match flag.as_str() {
+            "--engine"    => config.engines.push(value("a command")?),
+            "--games"     => config.games = number(value("a count")?)?,
+            "--movetime"  => config.time_control = TimeControl::MoveTime(number(value("milliseconds")?)?),
+            "--openings"  => config.openings = Some(value("a file")?),
+            "--pgn"       => config.pgn = Some(value("a file")?),
+            "--max-moves" => config.max_moves = Some(number(value("a move count")?)?),
+            "--tc" => {
+                let tc = value("a time control")?;
+                let (base, inc) = tc.split_once('+').unwrap_or((&tc, "0"));
+                config.time_control = TimeControl::Clock { base_ms: seconds(base)?, inc_ms: seconds(inc)? };
+            }
+            "--resign" => {
+                config.resign = Some(Resign { moves: number(value("a move count")?)?, cp: number(value("a score")?)? });
+            }
+            "--draw" => {
+                config.draw = Some(Draw {
+                    from_move: number(value("a move number")?)?,
+                    moves: number(value("a move count")?)?,
+                    cp: number(value("a score")?)?,
+                });
+            }
+            "--option" => {
+                let option = value("<name>=<value>")?;
+                let (name, value) = option.split_once('=').ok_or(format!("Expected <name>=<value>: {}", option))?;
+                config.options.push((name.to_string(), value.to_string()));
+            }
+            "--help" | "-h" => usage(),
+            _ => return Err(format!("Unknown argument: {}", flag)),
+        }
+    }
+
+    if config.engines.len() > 2 {
+        return Err("At most two engines".to_string());
+    }
+    if config.engines.is_empty() {
+        let exe = env::current_exe().map_err(|e| e.to_string())?;
+        config.engines.push(exe.with_file_name("chess-engine").to_string_lossy().into_owned());
+    }
+    if config.engines.len() == 1 {
+        config.engines.push(config.engines[0].clone());
+    }
+    Ok(config)
+}
+
+
+// Starting point of a game: a position and the moves played from it, in UCI notation
+#[derive(Debug, Clone, Default)]
+struct Opening {
+    fen: Option<String>,
+    moves: Vec<String>,
+}
+
+impl Opening {
+    fn start(&self) -> Result<Game, String> {
+        match &self.fen {
+            Some(fen) => Game::from_fen(fen).map_err(|e| format!("{:?} in {}", e, fen)),
+            None => Ok(Game::default()),
+        }
+    }
+
+    fn game(&self) -> Result<Game, String> {
+        let mut game = self.start()?;
+        for m in &self.moves {
+            if !game.try_to_make_uci_move(m) {
+                return Err(format!("Illegal opening move {}", m));
+            }
+        }
+        Ok(game)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.fen.is_none() && self.moves.is_empty()
+    }
+}
+
+// One position per line, the operations after the four FEN fields are ignored
+fn parse_epd(text: &str) -> Vec<Opening> {
+    text.lines()
+        .map(|line| line.split_whitespace().take(4).collect::<Vec<_>>())
+        .filter(|fields| fields.len() == 4)
+        .map(|fields| Opening { fen: Some(fields.join(" ")), moves: Vec::new() })
+        .filter(|opening| opening.game().is_ok())
+        .collect()
+}
+
+// Every game is an opening: its `FEN` tag and the main line
+fn parse_pgn(text: &str) -> Vec<Opening> {
+    pgn::parse_pgn(text)
+        .into_iter()
+        .map(|game| Opening {
+            fen: game.tag("FEN").map(str::to_string),
+            moves: game.moves.iter().map(Move::to_string).collect(),
+        })
+        .filter(|opening| !opening.is_empty() && opening.game().is_ok())
+        .collect()
+}
+
+fn load_openings(path: &str) -> Result<Vec<Opening>, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)?;
+    let openings = if path.to_ascii_lowercase().ends_with(".pgn") { parse_pgn(&text) } else { parse_epd(&text) };
+    if openings.is_empty() {
+        return Err(format!("No openings in {}", path).into());
+    }
+    Ok(openings)
+}
+
+
+// From the point of view of the engine that reported it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Score {
+    Cp(i32),
+    Mate(i32),
+}
+
+impl Score {
+    fn cp(&self) -> i32 {
+        match *self {
+            Score::Cp(cp) => cp,
+            Score::Mate(n) if n > 0 => MATE_CP - n,
+            Score::Mate(n) => -MATE_CP - n,
+        }
+    }
+
+    // "[%eval 0.35]", "[%eval #-3]", from White's point of view
+    fn pgn_comment(&self, mover: Player) -> String {
+        let sign = if mover == Player::White { 1 } else { -1 };
+        match *self {
+            Score::Cp(cp)  => format!("[%eval {:.2}]", (sign * cp) as f64 / 100.0),
+            Score::Mate(n) => format!("[%eval #{}]", sign * n),
+        }
+    }
+}
+
+fn parse_score(info: &str) -> Option<Score> {
+    let tokens: Vec<&str> = info.split_whitespace().collect();
+    let i = tokens.iter().position(|&t| t == "score")?;
+    let value = tokens.get(i + 2)?.parse().ok()?;
+    match *tokens.get(i + 1)? {
+        "cp"   => Some(Score::Cp(value)),
+        "mate" => Some(Score::Mate(value)),
+        _ => None,
+    }
+}
+
+
+#[derive(Debug)]
+enum EngineError {
+    Timeout,
+    Crashed,
+}
+
+struct Engine {
+    name: String,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl Engine {
+    fn start(command: &str, options: &[(String, String)]) -> Result<Engine, Box<dyn std::error::Error>> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or("Empty engine command")?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Cannot start {}: {}", command, e))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        // A reader thread, so that waiting for a line can time out
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Engine { name: command.to_string(), child, stdin, lines };
+        engine.send("uci")?;
+        for line in engine.read_until("uciok", COMMAND_TIMEOUT).map_err(|e| format!("{}: {:?}", command, e))? {
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = name.to_string();
+            }
+        }
+        for (name, value) in options {
+            engine.send(&format!("setoption name {} value {}", name, value))?;
+        }
+        Ok(engine)
+    }
+
+    fn send(&mut self, command: &str) -> io::Result<()> {
+        writeln!(self.stdin, "{}", command)?;
+        self.stdin.flush()
+    }
+
+    // Every line up to and including the first one starting with `prefix`
+    fn read_until(&mut self, prefix: &str, timeout: Duration) -> Result<Vec<String>, EngineError> {
+        let deadline = Instant::now() + timeout;
+        let mut read = Vec::new();
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match self.lines.recv_timeout(left) {
+                Ok(line) => {
+                    let done = line.starts_with(prefix);
+                    read.push(line);
+                    if done {
+                        return Ok(read);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout)      => return Err(EngineError::Timeout),
+                Err(RecvTimeoutError::Disconnected) => return Err(EngineError::Crashed),
+            }
+        }
+    }
+
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        self.send("ucinewgame").map_err(|_| EngineError::Crashed)?;
+        self.send("isready").map_err(|_| EngineError::Crashed)?;
+        self.read_until("readyok", COMMAND_TIMEOUT).map(|_| ())
+    }
+
+    // The move in UCI notation and the last score reported for it
+    fn think(&mut self, position: &str, go: &str, timeout: Duration) -> Result<(String, Option<Score>), EngineError> {
+        self.send(position).map_err(|_| EngineError::Crashed)?;
+        self.send(go).map_err(|_| EngineError::Crashed)?;
+
+        let read = match self.read_until("bestmove", timeout) {
+            Err(EngineError::Timeout) => {
+                // Out of time, but the next game should not get this `bestmove`
+                let _ = self.send("stop");
+                let _ = self.read_until("bestmove", COMMAND_TIMEOUT);
+                return Err(EngineError::Timeout);
+            }
+            result => result?,
+        };
+        let score = read.iter().rev().filter(|l| l.starts_with("info")).find_map(|l| parse_score(l));
+        let best = read.last().unwrap().split_whitespace().nth(1).unwrap_or("").to_string();
+        Ok((best, score))
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ending {
+    Rules(Outcome),
+    TimeForfeit(Player),  // the one who lost
+    IllegalMove(Player),
+    Crash(Player),
+    Resignation(Player),
+    AdjudicatedDraw,
+    MoveLimit,
+}
+
+impl Ending {
+    fn result(&self) -> &'static str {
+        let loss = |loser: &Player| if *loser == Player::White { "0-1" } else { "1-0" };
+        match self {
+            Ending::Rules(outcome) => outcome.result(),
+            Ending::TimeForfeit(loser) | Ending::IllegalMove(loser) | Ending::Crash(loser) | Ending::Resignation(loser) => loss(loser),
+            Ending::AdjudicatedDraw | Ending::MoveLimit => "1/2-1/2",
+        }
+    }
+
+    // PGN `Termination` tag
+    fn termination(&self) -> &'static str {
+        match self {
+            Ending::Rules(_)       => "normal",
+            Ending::TimeForfeit(_) => "time forfeit",
+            Ending::IllegalMove(_) => "rules infraction",
+            Ending::Crash(_)       => "abandoned",
+            Ending::Resignation(_) | Ending::AdjudicatedDraw | Ending::MoveLimit => "adjudication",
+        }
+    }
+
+    fn description(&self) -> String {
+        match self {
+            Ending::Rules(outcome)      => outcome.to_string(),
+            Ending::TimeForfeit(loser)  => format!("{} ({:?} loses on time)", self.result(), loser),
+            Ending::IllegalMove(loser)  => format!("{} ({:?} made an illegal move)", self.result(), loser),
+            Ending::Crash(loser)        => format!("{} ({:?} disconnected)", self.result(), loser),
+            Ending::Resignation(loser)  => format!("{} ({:?} resigns)", self.result(), loser),
+            Ending::AdjudicatedDraw     => format!("{} (adjudicated draw)", self.result()),
+            Ending::MoveLimit           => format!("{} (move limit)", self.result()),
+        }
+    }
+}
+
+struct GameRecord {
+    white: String,
+    black: String,
+    start_fen: Option<String>,
+    start: Game,
+    moves: Vec<PgnMove>,  // with their `[%eval]` comments
+    ending: Ending,
+}
+
+impl GameRecord {
+    fn pgn(&self, round: usize, time_control: TimeControl) -> String {
+        let mut tags = vec![
+            ("Event", "match_runner".to_string()),
+            ("Site", "?".to_string()),
+            ("Date", "????.??.??".to_string()),
+            ("Round", round.to_string()),
+            ("White", self.white.clone()),
+            ("Black", self.black.clone()),
+            ("Result", self.ending.result().to_string()),
+        ];
+        if let Some(fen) = &self.start_fen {
+            tags.push(("SetUp", "1".to_string()));
+            tags.push(("FEN", fen.clone()));
+        }
+        let mut game = self.start.clone();
+        for m in &self.moves {
+            game.try_to_make_move(&m.m);
+        }
+        add_opening_tags(&mut tags, &game);
+        tags.push(("TimeControl", time_control.to_string()));
+        tags.push(("Termination", self.ending.termination().to_string()));
+        write_pgn(&tags, &self.start, &self.moves, self.ending.result())
+    }
+}
+
+// `engines[0]` plays White
+fn play_game(engines: &mut [Engine; 2], opening: &Opening, config: &Config) -> Result<GameRecord, String> {
+    // The opening moves are part of the game, just not the engines' own
+    let start = opening.start()?;
+    let mut game = start.clone();
+    let mut moves = Vec::new();
+    for uci in &opening.moves {
+        let m = Move::from_uci(&game.position, uci).map_err(|e| format!("{:?}", e))?;
+        moves.push(m.into());
+        game.try_to_make_move(&m);
+    }
+    let mut record = GameRecord {
+        white: engines[0].name.clone(),
+        black: engines[1].name.clone(),
+        start_fen: opening.fen.clone(),
+        start,
+        moves,
+        ending: Ending::MoveLimit,
+    };
+
+    for engine in engines.iter_mut() {
+        if engine.new_game().is_err() {
+            return Err(format!("{} is not responding", engine.name));
+        }
+    }
+
+    let position_command = match &opening.fen {
+        Some(fen) => format!("position fen {} moves", fen),
+        None => "position startpos moves".to_string(),
+    };
+    let mut uci_moves: Vec<String> = opening.moves.clone();
+
+    let mut clocks = match config.time_control {
+        TimeControl::Clock { base_ms, .. } => [base_ms, base_ms],
+        TimeControl::MoveTime(_) => [0, 0],
+    };
+    let mut losing_streak = [0, 0];  // moves in a row below the resignation score
+    let mut drawish_plies = 0;
+
+    record.ending = loop {
+        let outcome = game.outcome();
+        if outcome.is_over() {
+            break Ending::Rules(outcome);
+        }
+        if is_unwinnable_for_both(&game.position) {
+            break Ending::AdjudicatedDraw;
+        }
+        let mover = game.position.player_to_move;
+        let side = mover.index();
+        if config.max_moves.is_some_and(|max| game.fullmove_number > max) {
+            break Ending::MoveLimit;
+        }
+
+        let (go, timeout) = match config.time_control {
+            TimeControl::Clock { inc_ms, .. } => (
+                format!("go wtime {} btime {} winc {} binc {}", clocks[0], clocks[1], inc_ms, inc_ms),
+                Duration::from_millis((clocks[side] + TIME_MARGIN_MS) as u64),
+            ),
+            TimeControl::MoveTime(ms) => (format!("go movetime {}", ms), Duration::from_millis(ms + MOVETIME_GRACE_MS)),
+        };
+        let position = format!("{} {}", position_command, uci_moves.join(" "));
+
+        let started = Instant::now();
+        let (best, score) = match engines[side].think(position.trim_end(), &go, timeout) {
+            Ok(answer) => answer,
+            Err(EngineError::Timeout) => break Ending::TimeForfeit(mover),
+            Err(EngineError::Crashed) => break Ending::Crash(mover),
+        };
+        let elapsed = started.elapsed().as_millis() as i64;
+
+        if let TimeControl::Clock { inc_ms, .. } = config.time_control {
+            if elapsed > clocks[side] + TIME_MARGIN_MS {
+                break Ending::TimeForfeit(mover);
+            }
+            clocks[side] = (clocks[side] - elapsed).max(0) + inc_ms;
+        }
+
+        let m = match Move::from_uci(&game.position, &best) {
+            Ok(m) => m,
+            Err(_) => break Ending::IllegalMove(mover),
+        };
+        record.moves.push(PgnMove { m, suffix: None, comment: score.map(|s| s.pgn_comment(mover)) });
+        game.try_to_make_move(&m);
+        uci_moves.push(best);
+
+        let Some(score) = score else { continue };
+        if let Some(resign) = config.resign {
+            losing_streak[side] = if score.cp() <= -resign.cp { losing_streak[side] + 1 } else { 0 };
+            if losing_streak[side] >= resign.moves {
+                break Ending::Resignation(mover);
+            }
+        }
+        if let Some(draw) = config.draw {
+            drawish_plies = if score.cp().abs() <= draw.cp { drawish_plies + 1 } else { 0 };
+            if game.fullmove_number > draw.from_move && drawish_plies >= 2 * draw.moves {
+                break Ending::AdjudicatedDraw;
+            }
+        }
+    };
+
+    Ok(record)
+}
+
+
+// From the first engine's point of view
+#[derive(Debug, Default)]
+struct Tally {
+    wins: usize,
+    losses: usize,
+    draws: usize,
+    scores: Vec<f64>,
+}
+
+impl Tally {
+    fn add(&mut self, score: f64) {
+        match score {
+            1.0 => self.wins += 1,
+            0.0 => self.losses += 1,
+            _   => self.draws += 1,
+        }
+        self.scores.push(score);
+    }
+
+    fn games(&self) -> usize {
+        self.scores.len()
+    }
+
+    fn mean(&self) -> f64 {
+        self.scores.iter().sum::<f64>() / self.games() as f64
+    }
+
+    // Elo difference and the half-width of its 95% confidence interval
+    fn elo(&self) -> Option<(f64, f64)> {
+        let n = self.games() as f64;
+        let mean = self.mean();
+        let deviation = (self.scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let margin = 1.96 * deviation / n.sqrt();
+        let elo = |score: f64| -400.0 * (1.0 / score - 1.0).log10() + 0.0;  // not -0.0
+        if mean <= 0.0 || mean >= 1.0 {
+            return None;
+        }
+        let low = elo((mean - margin).max(1e-6));
+        let high = elo((mean + margin).min(1.0 - 1e-6));
+        Some((elo(mean), (high - low) / 2.0))
+    }
+}
+
+impl std::fmt::Display for Tally {
+    // "+12 -8 =20 [0.550] 40"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "+{} -{} ={} [{:.3}] {}", self.wins, self.losses, self.draws, self.mean(), self.games())
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let config = parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        usage();
+    });
+
+    let openings = match &config.openings {
+        Some(path) => load_openings(path)?,
+        None => vec![Opening::default()],
+    };
+    let mut pgn_file = config.pgn.as_ref().map(fs::File::create).transpose()?;
+
+    let mut engines = [
+        Engine::start(&config.engines[0], &config.options)?,
+        Engine::start(&config.engines[1], &config.options)?,
+    ];
+    let names = [engines[0].name.clone(), engines[1].name.clone()];
+    eprintln!("{} vs {}, {} games at {}", names[0], names[1], config.games, config.time_control);
+
+    let mut tally = Tally::default();
+    for round in 1..=config.games {
+        // Both colors for each opening
+        let opening = &openings[(round - 1) / 2 % openings.len()];
+        let first_is_white = round % 2 == 1;
+        if !first_is_white {
+            engines.swap(0, 1);
+        }
+        let record = play_game(&mut engines, opening, &config);
+        if !first_is_white {
+            engines.swap(0, 1);
+        }
+        let record = record?;
+
+        let first_score = match (record.ending.result(), first_is_white) {
+            ("1-0", true) | ("0-1", false) => 1.0,
+            ("0-1", true) | ("1-0", false) => 0.0,
+            _ => 0.5,
+        };
+        tally.add(first_score);
+        eprintln!(
+            "Game {}/{}: {} vs {}: {}. Score of {} vs {}: {}",
+            round, config.games, record.white, record.black, record.ending.description(), names[0], names[1], tally
+        );
+
+        if let Some(file) = &mut pgn_file {
+            file.write_all(record.pgn(round, config.time_control).as_bytes())?;
+            file.flush()?;
+        }
+    }
+
+    println!("Score of {} vs {}: {}", names[0], names[1], tally);
+    match tally.elo() {
+        Some((elo, margin)) => println!("Elo difference: {:.1} +/- {:.1}", elo, margin),
+        None => println!("Elo difference: unbounded, one side won everything"),
+    }
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epd() {
+        let openings = parse_epd(concat!(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 bm e5; id \"1\";\n",
+            "\n",
+            "8/8 w\n",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x - -\n",
+            "4k3/8/8/8/8/8/8/4K2R w K -\n",
+        ));
+        let fens: Vec<_> = openings.iter().map(|o| o.fen.as_deref()).collect();
+        assert_eq!(fens, [
+            Some("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3"),
+            Some("4k3/8/8/8/8/8/8/4K2R w K -"),
+        ]);
+        assert!(openings.iter().all(|o| o.moves.is_empty()));
+    }
+
+    #[test]
+    fn scores() {
+        assert_eq!(parse_score("info depth 5 score cp 35 nodes 1200 pv e2e4"), Some(Score::Cp(35)));
+        assert_eq!(parse_score("info depth 9 seldepth 9 score mate -3 pv h7h8"), Some(Score::Mate(-3)));
+        assert_eq!(parse_score("info depth 5 score cp"), None);
+        assert_eq!(parse_score("info depth 5 score wdl 500 300 200"), None);
+        assert_eq!(parse_score("info string score"), None);
+
+        assert_eq!(Score::Cp(-40).cp(), -40);
+        assert_eq!(Score::Mate(3).cp(), MATE_CP - 3);
+        assert_eq!(Score::Mate(-3).cp(), -MATE_CP + 3);
+        // A quicker mate is worth more, and any mate more than any score
+        assert!(Score::Mate(2).cp() > Score::Mate(5).cp() && Score::Mate(5).cp() > Score::Cp(5000).cp());
+
+        assert_eq!(Score::Cp(35).pgn_comment(Player::White), "[%eval 0.35]");
+        assert_eq!(Score::Cp(35).pgn_comment(Player::Black), "[%eval -0.35]");
+        assert_eq!(Score::Mate(-3).pgn_comment(Player::Black), "[%eval #3]");
+    }
+
+    fn tally(wins: usize, losses: usize, draws: usize) -> Tally {
+        let mut tally = Tally::default();
+        for (score, count) in [(1.0, wins), (0.0, losses), (0.5, draws)] {
+            for _ in 0..count {
+                tally.add(score);
+            }
+        }
+        tally
+    }
+
+    #[test]
+    fn elo() {
+        let even = tally(1, 1, 2);
+        assert_eq!(even.to_string(), "+1 -1 =2 [0.500] 4");
+        // A standard deviation of 0.354 over 4 games, the interval is 0.154..0.846
+        let (elo, error) = even.elo().unwrap();
+        assert_eq!(elo, 0.0);
+        assert!((error - 296.6).abs() < 0.1, "{}", error);
+
+        // 75% is 191 Elo. A deviation of 0.433 over 40 games, 0.616..0.884
+        let (elo, error) = tally(30, 10, 0).elo().unwrap();
+        assert!((elo - 190.8).abs() < 0.1, "{}", elo);
+        assert!((error - 135.6).abs() < 0.1, "{}", error);
+
+        // The same score with draws instead is the same Elo, with less of an error
+        let (elo, narrower) = tally(20, 0, 20).elo().unwrap();
+        assert!((elo - 190.8).abs() < 0.1 && narrower < error);
+
+        // No finite difference for a clean sweep
+        assert_eq!(tally(3, 0, 0).elo(), None);
+        assert_eq!(tally(0, 2, 0).elo(), None);
+    }
+}
@@ -0,0 +1,43 @@
+// Extracts Polyglot's `Random64` array from a file that lists it, such as `random.cpp`
+// from the Polyglot sources or the book format description, and writes it to
+// `src/constants/polyglot.rs`. Needed for the `polyglot` feature
+//
+// Usage: polyglot_keys_gen <file listing Random64>
+use std::{env, fs::{self, File}, io::{BufWriter, Write}};
+
+const POLYGLOT_KEYS: usize = 781;
+
+// Every 64-bit hex literal in the text, in order
+fn hex_literals(text: &str) -> Vec<u64> {
+    let mut keys = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("0x").or_else(|| rest.find("0X")) {
+        let digits: String = rest[start + 2..].chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+        if digits.len() == 16 {
+            keys.push(u64::from_str_radix(&digits, 16).unwrap());
+        }
+        rest = &rest[start + 2 + digits.len()..];
+    }
+    keys
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = env::args().nth(1).ok_or("usage: polyglot_keys_gen <file listing Random64>")?;
+    let keys = hex_literals(&fs::read_to_string(path)?);
+    if keys.len() != POLYGLOT_KEYS {
+        return Err(format!("expected {} keys, found {}", POLYGLOT_KEYS, keys.len()).into());
+    }
+
+    let file = File::create("src/constants/polyglot.rs").expect("Failed to create file");
+    let mut writer = BufWriter::new(file);
+
+    writeln!(writer, "// Generated by `src/bin/polyglot_keys_gen.rs`\n")?;
+    writeln!(writer, "// Polyglot's `Random64`: pieces, castling, en passant, side to move")?;
+    writeln!(writer, "pub const POLYGLOT_RANDOM: [u64; {POLYGLOT_KEYS}] = [")?;
+    for key in keys {
+        writeln!(writer, "    0x{:016X},", key)?;
+    }
+    writeln!(writer, "];")?;
+
+    Ok(())
+}
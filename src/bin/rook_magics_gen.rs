@@ -66,9 +66,9 @@ fn generate_blocker_variations(mask: u64) -> Vec<u64> {
 
     for i in 0..(1 << n) {
         let mut blockers = 0u64;
-        for j in 0..n {
+        for (j, &sq) in bits.iter().enumerate() {
             if (i & (1 << j)) != 0 {
-                blockers |= bit(bits[j]);
+                blockers |= bit(sq);
             }
         }
         result.push(blockers);
@@ -112,8 +112,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut magics = Vec::new();
     let mut shifts = Vec::new();
     let mut tables = Vec::new();
-    for sq in 0..64 {
-        let mask = ROOK_MASKS[sq];
+    for (sq, &mask) in ROOK_MASKS.iter().enumerate() {
         let bits = n_bits_set(mask);
         let shift = 64 - bits;
         let (magic, table) = find_magic(sq, mask, shift);
@@ -2,7 +2,14 @@ pub mod magics;
 pub mod board;
 pub mod attacks;
 pub mod masks;
+pub mod distance;
+pub mod lines;
 pub mod zobrist;
+// Generated by `polyglot_keys_gen`, which needs Polyglot's key listing
+#[cfg(feature = "polyglot")]
+pub mod polyglot;
+pub mod openings;
+pub mod book;
 
 pub const NAME: &str = "chess-engine";
 pub const AUTHOR: &str = "Kirill Trofimov";
@@ -11,5 +18,9 @@ pub const AUTHOR: &str = "Kirill Trofimov";
 pub const GAME_HISTORY_CAPACITY: usize = 256;
 pub const MOVE_LIST_CAPACITY: usize = 256;
 
+// Iterative deepening stops here, even without a depth limit
+pub const MAX_DEPTH: usize = 128;
+
 pub const CHECKMATE_EVAL: i32 = 2_000_000_000;
+pub const EVAL_INFINITY: i32 = i32::MAX;  // not i32::MIN, it has to survive negation
 pub const DRAW_EVAL: i32 = 0;
@@ -106,58 +106,58 @@ mod tests {
 
     #[test]
     fn white_pawn_attack_table() {
-        assert_eq!(PAWN_ATTACKS_WHITE[10], sq_to_bb(&[17, 19]));
-        assert_eq!(PAWN_ATTACKS_WHITE[21], sq_to_bb(&[28, 30]));
-        assert_eq!(PAWN_ATTACKS_WHITE[31], sq_to_bb(&[38]));
-        assert_eq!(PAWN_ATTACKS_WHITE[35], sq_to_bb(&[42, 44]));
-        assert_eq!(PAWN_ATTACKS_WHITE[40], sq_to_bb(&[49]));
+        assert_eq!(PAWN_ATTACKS_WHITE[10], sq_to_bb(&[B3, D3]));
+        assert_eq!(PAWN_ATTACKS_WHITE[21], sq_to_bb(&[E4, G4]));
+        assert_eq!(PAWN_ATTACKS_WHITE[31], sq_to_bb(&[G5]));
+        assert_eq!(PAWN_ATTACKS_WHITE[35], sq_to_bb(&[C6, E6]));
+        assert_eq!(PAWN_ATTACKS_WHITE[40], sq_to_bb(&[B7]));
     }
 
     #[test]
     fn black_pawn_attack_table() {
-        assert_eq!(PAWN_ATTACKS_BLACK[52], sq_to_bb(&[43, 45]));
-        assert_eq!(PAWN_ATTACKS_BLACK[47], sq_to_bb(&[38]));
-        assert_eq!(PAWN_ATTACKS_BLACK[29], sq_to_bb(&[20, 22]));
-        assert_eq!(PAWN_ATTACKS_BLACK[24], sq_to_bb(&[17]));
-        assert_eq!(PAWN_ATTACKS_BLACK[19], sq_to_bb(&[10, 12]));
+        assert_eq!(PAWN_ATTACKS_BLACK[52], sq_to_bb(&[D6, F6]));
+        assert_eq!(PAWN_ATTACKS_BLACK[47], sq_to_bb(&[G5]));
+        assert_eq!(PAWN_ATTACKS_BLACK[29], sq_to_bb(&[E3, G3]));
+        assert_eq!(PAWN_ATTACKS_BLACK[24], sq_to_bb(&[B3]));
+        assert_eq!(PAWN_ATTACKS_BLACK[19], sq_to_bb(&[C2, E2]));
     }
 
     #[test]
     fn knight_attack_table() {
-        assert_eq!(KNIGHT_ATTACKS[0],  sq_to_bb(&[10, 17]));
-        assert_eq!(KNIGHT_ATTACKS[1],  sq_to_bb(&[11, 16, 18]));
-        assert_eq!(KNIGHT_ATTACKS[8],  sq_to_bb(&[2, 18, 25]));
+        assert_eq!(KNIGHT_ATTACKS[0],  sq_to_bb(&[C2, B3]));
+        assert_eq!(KNIGHT_ATTACKS[1],  sq_to_bb(&[D2, A3, C3]));
+        assert_eq!(KNIGHT_ATTACKS[8],  sq_to_bb(&[C1, C3, B4]));
 
-        assert_eq!(KNIGHT_ATTACKS[6],  sq_to_bb(&[12, 21, 23]));
-        assert_eq!(KNIGHT_ATTACKS[7],  sq_to_bb(&[13, 22]));
-        assert_eq!(KNIGHT_ATTACKS[15], sq_to_bb(&[5, 21, 30]));
+        assert_eq!(KNIGHT_ATTACKS[6],  sq_to_bb(&[E2, F3, H3]));
+        assert_eq!(KNIGHT_ATTACKS[7],  sq_to_bb(&[F2, G3]));
+        assert_eq!(KNIGHT_ATTACKS[15], sq_to_bb(&[F1, F3, G4]));
 
-        assert_eq!(KNIGHT_ATTACKS[48], sq_to_bb(&[33, 42, 58]));
-        assert_eq!(KNIGHT_ATTACKS[56], sq_to_bb(&[41, 50]));
-        assert_eq!(KNIGHT_ATTACKS[57], sq_to_bb(&[40, 42, 51]));
+        assert_eq!(KNIGHT_ATTACKS[48], sq_to_bb(&[B5, C6, C8]));
+        assert_eq!(KNIGHT_ATTACKS[56], sq_to_bb(&[B6, C7]));
+        assert_eq!(KNIGHT_ATTACKS[57], sq_to_bb(&[A6, C6, D7]));
 
-        assert_eq!(KNIGHT_ATTACKS[55], sq_to_bb(&[38, 45, 61]));
-        assert_eq!(KNIGHT_ATTACKS[62], sq_to_bb(&[45, 47, 52]));
-        assert_eq!(KNIGHT_ATTACKS[63], sq_to_bb(&[46, 53]));
+        assert_eq!(KNIGHT_ATTACKS[55], sq_to_bb(&[G5, F6, F8]));
+        assert_eq!(KNIGHT_ATTACKS[62], sq_to_bb(&[F6, H6, E7]));
+        assert_eq!(KNIGHT_ATTACKS[63], sq_to_bb(&[G6, F7]));
 
-        assert_eq!(KNIGHT_ATTACKS[11], sq_to_bb(&[1, 5, 17, 21, 26, 28]));
-        assert_eq!(KNIGHT_ATTACKS[25], sq_to_bb(&[8, 10, 19, 35, 40, 42]));
-        assert_eq!(KNIGHT_ATTACKS[36], sq_to_bb(&[19, 21, 26, 30, 42, 46, 51, 53]));
+        assert_eq!(KNIGHT_ATTACKS[11], sq_to_bb(&[B1, F1, B3, F3, C4, E4]));
+        assert_eq!(KNIGHT_ATTACKS[25], sq_to_bb(&[A2, C2, D3, D5, A6, C6]));
+        assert_eq!(KNIGHT_ATTACKS[36], sq_to_bb(&[D3, F3, C4, G4, C6, G6, D7, F7]));
     }
 
     #[test]
     fn king_attack_table() {
-        assert_eq!(KING_ATTACKS[0],  sq_to_bb(&[1, 8, 9]));
-        assert_eq!(KING_ATTACKS[7],  sq_to_bb(&[6, 14, 15])); 
-        assert_eq!(KING_ATTACKS[56], sq_to_bb(&[48, 49, 57]));
-        assert_eq!(KING_ATTACKS[63], sq_to_bb(&[54, 55, 62]));
-
-        assert_eq!(KING_ATTACKS[3],  sq_to_bb(&[2, 4, 10, 11, 12]));
-        assert_eq!(KING_ATTACKS[16], sq_to_bb(&[8, 9, 17, 24, 25]));
-        assert_eq!(KING_ATTACKS[39], sq_to_bb(&[30, 31, 38, 46, 47]));
-        assert_eq!(KING_ATTACKS[58], sq_to_bb(&[49, 50, 51, 57, 59]));
-
-        assert_eq!(KING_ATTACKS[42], sq_to_bb(&[33, 34, 35, 41, 43, 49, 50, 51]));
-        assert_eq!(KING_ATTACKS[19], sq_to_bb(&[10, 11, 12, 18, 20, 26, 27, 28]));
+        assert_eq!(KING_ATTACKS[0],  sq_to_bb(&[B1, A2, B2]));
+        assert_eq!(KING_ATTACKS[7],  sq_to_bb(&[G1, G2, H2])); 
+        assert_eq!(KING_ATTACKS[56], sq_to_bb(&[A7, B7, B8]));
+        assert_eq!(KING_ATTACKS[63], sq_to_bb(&[G7, H7, G8]));
+
+        assert_eq!(KING_ATTACKS[3],  sq_to_bb(&[C1, E1, C2, D2, E2]));
+        assert_eq!(KING_ATTACKS[16], sq_to_bb(&[A2, B2, B3, A4, B4]));
+        assert_eq!(KING_ATTACKS[39], sq_to_bb(&[G4, H4, G5, G6, H6]));
+        assert_eq!(KING_ATTACKS[58], sq_to_bb(&[B7, C7, D7, B8, D8]));
+
+        assert_eq!(KING_ATTACKS[42], sq_to_bb(&[B5, C5, D5, B6, D6, B7, C7, D7]));
+        assert_eq!(KING_ATTACKS[19], sq_to_bb(&[C2, D2, E2, C3, E3, C4, D4, E4]));
     }
 }
@@ -1,3 +1,5 @@
+use crate::core::square::Square;
+
 // Assuming [Little-Endian Rank-File Mapping](https://www.chessprogramming.org/Square_Mapping_Considerations#Little-Endian_Rank-File_Mapping)
 
 pub const RANK: [u64; 8+1] = [
@@ -27,74 +29,77 @@ pub const FILE: [u64; 8+1] = [
     FILE_E, FILE_F, FILE_G, FILE_H,
 ];
 
-pub const A1: u8 = 0;
-pub const B1: u8 = 1;
-pub const C1: u8 = 2;
-pub const D1: u8 = 3;
-pub const E1: u8 = 4;
-pub const F1: u8 = 5;
-pub const G1: u8 = 6;
-pub const H1: u8 = 7;
+pub const LIGHT_SQUARES: u64 = 0x55AA55AA55AA55AA;  // h1 is light, a1 is dark
+pub const DARK_SQUARES:  u64 = !LIGHT_SQUARES;
+
+pub const A1: Square = Square(0);
+pub const B1: Square = Square(1);
+pub const C1: Square = Square(2);
+pub const D1: Square = Square(3);
+pub const E1: Square = Square(4);
+pub const F1: Square = Square(5);
+pub const G1: Square = Square(6);
+pub const H1: Square = Square(7);
 
-pub const A2: u8 = 8;
-pub const B2: u8 = 9;
-pub const C2: u8 = 10;
-pub const D2: u8 = 11;
-pub const E2: u8 = 12;
-pub const F2: u8 = 13;
-pub const G2: u8 = 14;
-pub const H2: u8 = 15;
+pub const A2: Square = Square(8);
+pub const B2: Square = Square(9);
+pub const C2: Square = Square(10);
+pub const D2: Square = Square(11);
+pub const E2: Square = Square(12);
+pub const F2: Square = Square(13);
+pub const G2: Square = Square(14);
+pub const H2: Square = Square(15);
 
-pub const A3: u8 = 16;
-pub const B3: u8 = 17;
-pub const C3: u8 = 18;
-pub const D3: u8 = 19;
-pub const E3: u8 = 20;
-pub const F3: u8 = 21;
-pub const G3: u8 = 22;
-pub const H3: u8 = 23;
+pub const A3: Square = Square(16);
+pub const B3: Square = Square(17);
+pub const C3: Square = Square(18);
+pub const D3: Square = Square(19);
+pub const E3: Square = Square(20);
+pub const F3: Square = Square(21);
+pub const G3: Square = Square(22);
+pub const H3: Square = Square(23);
 
-pub const A4: u8 = 24;
-pub const B4: u8 = 25;
-pub const C4: u8 = 26;
-pub const D4: u8 = 27;
-pub const E4: u8 = 28;
-pub const F4: u8 = 29;
-pub const G4: u8 = 30;
-pub const H4: u8 = 31;
+pub const A4: Square = Square(24);
+pub const B4: Square = Square(25);
+pub const C4: Square = Square(26);
+pub const D4: Square = Square(27);
+pub const E4: Square = Square(28);
+pub const F4: Square = Square(29);
+pub const G4: Square = Square(30);
+pub const H4: Square = Square(31);
 
-pub const A5: u8 = 32;
-pub const B5: u8 = 33;
-pub const C5: u8 = 34;
-pub const D5: u8 = 35;
-pub const E5: u8 = 36;
-pub const F5: u8 = 37;
-pub const G5: u8 = 38;
-pub const H5: u8 = 39;
+pub const A5: Square = Square(32);
+pub const B5: Square = Square(33);
+pub const C5: Square = Square(34);
+pub const D5: Square = Square(35);
+pub const E5: Square = Square(36);
+pub const F5: Square = Square(37);
+pub const G5: Square = Square(38);
+pub const H5: Square = Square(39);
 
-pub const A6: u8 = 40;
-pub const B6: u8 = 41;
-pub const C6: u8 = 42;
-pub const D6: u8 = 43;
-pub const E6: u8 = 44;
-pub const F6: u8 = 45;
-pub const G6: u8 = 46;
-pub const H6: u8 = 47;
+pub const A6: Square = Square(40);
+pub const B6: Square = Square(41);
+pub const C6: Square = Square(42);
+pub const D6: Square = Square(43);
+pub const E6: Square = Square(44);
+pub const F6: Square = Square(45);
+pub const G6: Square = Square(46);
+pub const H6: Square = Square(47);
 
-pub const A7: u8 = 48;
-pub const B7: u8 = 49;
-pub const C7: u8 = 50;
-pub const D7: u8 = 51;
-pub const E7: u8 = 52;
-pub const F7: u8 = 53;
-pub const G7: u8 = 54;
-pub const H7: u8 = 55;
+pub const A7: Square = Square(48);
+pub const B7: Square = Square(49);
+pub const C7: Square = Square(50);
+pub const D7: Square = Square(51);
+pub const E7: Square = Square(52);
+pub const F7: Square = Square(53);
+pub const G7: Square = Square(54);
+pub const H7: Square = Square(55);
 
-pub const A8: u8 = 56;
-pub const B8: u8 = 57;
-pub const C8: u8 = 58;
-pub const D8: u8 = 59;
-pub const E8: u8 = 60;
-pub const F8: u8 = 61;
-pub const G8: u8 = 62;
-pub const H8: u8 = 63;
+pub const A8: Square = Square(56);
+pub const B8: Square = Square(57);
+pub const C8: Square = Square(58);
+pub const D8: Square = Square(59);
+pub const E8: Square = Square(60);
+pub const F8: Square = Square(61);
+pub const G8: Square = Square(62);
+pub const H8: Square = Square(63);
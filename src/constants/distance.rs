@@ -0,0 +1,67 @@
+// Distances between squares, [from][to]
+
+// King moves (Chebyshev distance)
+pub static DISTANCE: [[u8; 64]; 64] = {
+    let mut table = [[0u8; 64]; 64];
+    let mut a: usize = 0;
+    while a < 64 {
+        let mut b: usize = 0;
+        while b < 64 {
+            let file = (a % 8).abs_diff(b % 8);
+            let rank = (a / 8).abs_diff(b / 8);
+            table[a][b] = if file > rank { file } else { rank } as u8;
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+};
+
+// Rook moves on an empty board, one step at a time (Manhattan distance)
+pub static MANHATTAN_DISTANCE: [[u8; 64]; 64] = {
+    let mut table = [[0u8; 64]; 64];
+    let mut a: usize = 0;
+    while a < 64 {
+        let mut b: usize = 0;
+        while b < 64 {
+            table[a][b] = ((a % 8).abs_diff(b % 8) + (a / 8).abs_diff(b / 8)) as u8;
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+};
+
+// Steps to the nearest of the four central squares (Manhattan distance), 0 in the centre, 6 in the corners
+pub static CENTER_DISTANCE: [u8; 64] = {
+    let mut table = [0u8; 64];
+    let mut sq: usize = 0;
+    while sq < 64 {
+        let file = sq % 8;
+        let rank = sq / 8;
+        let file = if file < 4 { 3 - file } else { file - 4 };
+        let rank = if rank < 4 { 3 - rank } else { rank - 4 };
+        table[sq] = (file + rank) as u8;
+        sq += 1;
+    }
+    table
+};
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::board::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(DISTANCE[A1.index()][H8.index()], 7);
+        assert_eq!(DISTANCE[E4.index()][F6.index()], 2);
+        assert_eq!(DISTANCE[C3.index()][C3.index()], 0);
+        assert_eq!(MANHATTAN_DISTANCE[A1.index()][H8.index()], 14);
+        assert_eq!(MANHATTAN_DISTANCE[E4.index()][F6.index()], 3);
+        assert_eq!(CENTER_DISTANCE[D5.index()], 0);
+        assert_eq!(CENTER_DISTANCE[B2.index()], 4);
+        assert_eq!(CENTER_DISTANCE[H8.index()], 6);
+    }
+}
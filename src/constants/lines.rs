@@ -0,0 +1,89 @@
+// Squares on the lines through two squares, [from][to]. Both are empty unless the squares share a
+// rank, a file or a diagonal
+
+// The direction from `a` to `b` as (file step, rank step), `None` if they are not aligned
+const fn direction(a: usize, b: usize) -> Option<(i32, i32)> {
+    let file = (b % 8) as i32 - (a % 8) as i32;
+    let rank = (b / 8) as i32 - (a / 8) as i32;
+    if a == b || (file != 0 && rank != 0 && file.abs() != rank.abs()) {
+        return None;
+    }
+    Some((file.signum(), rank.signum()))
+}
+
+// Squares from `sq` (not included) to the edge of the board
+const fn ray(sq: usize, file_step: i32, rank_step: i32) -> u64 {
+    let mut bb = 0u64;
+    let mut file = (sq % 8) as i32 + file_step;
+    let mut rank = (sq / 8) as i32 + rank_step;
+    while file >= 0 && file < 8 && rank >= 0 && rank < 8 {
+        bb |= 1u64 << (rank * 8 + file);
+        file += file_step;
+        rank += rank_step;
+    }
+    bb
+}
+
+// Strictly between the two squares: the squares a slider has to pass, the ones that block a check
+pub static BETWEEN: [[u64; 64]; 64] = {
+    let mut table = [[0u64; 64]; 64];
+    let mut a: usize = 0;
+    while a < 64 {
+        let mut b: usize = 0;
+        while b < 64 {
+            if let Some((file_step, rank_step)) = direction(a, b) {
+                table[a][b] = ray(a, file_step, rank_step) & ray(b, -file_step, -rank_step);
+            }
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+};
+
+// The whole line through both squares, from edge to edge, both of them included
+pub static LINE: [[u64; 64]; 64] = {
+    let mut table = [[0u64; 64]; 64];
+    let mut a: usize = 0;
+    while a < 64 {
+        let mut b: usize = 0;
+        while b < 64 {
+            if let Some((file_step, rank_step)) = direction(a, b) {
+                table[a][b] = ray(a, file_step, rank_step) | ray(a, -file_step, -rank_step) | (1u64 << a);
+            }
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+};
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::board::*;
+    use crate::utility::sq_to_bb;
+
+    #[test]
+    fn between() {
+        assert_eq!(BETWEEN[A1.index()][D1.index()], sq_to_bb(&[B1, C1]));
+        assert_eq!(BETWEEN[D1.index()][A1.index()], sq_to_bb(&[B1, C1]));
+        assert_eq!(BETWEEN[E2.index()][E5.index()], sq_to_bb(&[E3, E4]));
+        assert_eq!(BETWEEN[A1.index()][H8.index()], sq_to_bb(&[B2, C3, D4, E5, F6, G7]));
+        assert_eq!(BETWEEN[H1.index()][F3.index()], sq_to_bb(&[G2]));
+        assert_eq!(BETWEEN[E4.index()][E5.index()], 0);  // adjacent
+        assert_eq!(BETWEEN[E4.index()][F6.index()], 0);  // not aligned
+        assert_eq!(BETWEEN[E4.index()][E4.index()], 0);
+    }
+
+    #[test]
+    fn line() {
+        assert_eq!(LINE[B1.index()][D1.index()], RANK[1]);
+        assert_eq!(LINE[C7.index()][C2.index()], FILE_C);
+        assert_eq!(LINE[C3.index()][E5.index()], sq_to_bb(&[A1, B2, C3, D4, E5, F6, G7, H8]));
+        assert_eq!(LINE[B5.index()][D3.index()], sq_to_bb(&[A6, B5, C4, D3, E2, F1]));
+        assert_eq!(LINE[E4.index()][F6.index()], 0);
+        assert_eq!(LINE[E4.index()][E4.index()], 0);
+    }
+}
@@ -82,6 +82,89 @@ pub static BISHOP_MASKS: [u64; 64] = {
     masks
 };
 
+// The files to the left and to the right of the square's file
+pub static ADJACENT_FILES: [u64; 64] = {
+    let mut table = [0u64; 64];
+    let mut sq = 0;
+    while sq < 64 {
+        let file = sq % 8;
+        if file > 0 {
+            table[sq] |= FILE_A << (file - 1);
+        }
+        if file < 7 {
+            table[sq] |= FILE_A << (file + 1);
+        }
+        sq += 1;
+    }
+    table
+};
+
+// Every rank strictly in front of the square, from White's / Black's point of view
+pub static FORWARD_RANKS_WHITE: [u64; 64] = {
+    let mut table = [0u64; 64];
+    let mut sq = 0;
+    while sq < 56 {
+        table[sq] = !0u64 << ((sq / 8 + 1) * 8);
+        sq += 1;
+    }
+    table
+};
+
+pub static FORWARD_RANKS_BLACK: [u64; 64] = {
+    let mut table = [0u64; 64];
+    let mut sq = 8;
+    while sq < 64 {
+        table[sq] = !0u64 >> ((8 - sq / 8) * 8);
+        sq += 1;
+    }
+    table
+};
+
+// The squares in front of a pawn, on its own and the adjacent files. No enemy pawns there = passed pawn
+pub static PASSED_PAWN_MASKS_WHITE: [u64; 64] = {
+    let mut table = [0u64; 64];
+    let mut sq = 0;
+    while sq < 56 {
+        let files = (FILE_A << (sq % 8)) | ADJACENT_FILES[sq];
+        table[sq] = files & (!0u64 << ((sq / 8 + 1) * 8));
+        sq += 1;
+    }
+    table
+};
+
+pub static PASSED_PAWN_MASKS_BLACK: [u64; 64] = {
+    let mut table = [0u64; 64];
+    let mut sq = 8;
+    while sq < 64 {
+        let files = (FILE_A << (sq % 8)) | ADJACENT_FILES[sq];
+        table[sq] = files & (!0u64 >> ((8 - sq / 8) * 8));
+        sq += 1;
+    }
+    table
+};
+
+// The squares on the adjacent files in front of a pawn, every square it may attack one day.
+// A piece with no enemy pawns in its own span can never be chased away by a pawn
+pub static PAWN_ATTACK_SPAN_WHITE: [u64; 64] = {
+    let mut table = [0u64; 64];
+    let mut sq = 0;
+    while sq < 56 {
+        table[sq] = ADJACENT_FILES[sq] & (!0u64 << ((sq / 8 + 1) * 8));
+        sq += 1;
+    }
+    table
+};
+
+pub static PAWN_ATTACK_SPAN_BLACK: [u64; 64] = {
+    let mut table = [0u64; 64];
+    let mut sq = 8;
+    while sq < 64 {
+        table[sq] = ADJACENT_FILES[sq] & (!0u64 >> ((8 - sq / 8) * 8));
+        sq += 1;
+    }
+    table
+};
+
 
 #[cfg(test)]
 mod tests {
@@ -90,23 +173,38 @@ mod tests {
 
     #[test]
     fn rook_masks() {
-        assert_eq!(ROOK_MASKS[0],  (FILE_A | RANK[1]) & !sq_to_bb(&[0, 7, 56]));
-        assert_eq!(ROOK_MASKS[3],  (FILE_D | RANK[1]) & !sq_to_bb(&[3, 0, 7, 59]));
-        assert_eq!(ROOK_MASKS[9],  (FILE_B | RANK[2]) & !sq_to_bb(&[9, 8, 15, 1, 57]));
-        assert_eq!(ROOK_MASKS[19], (FILE_D | RANK[3]) & !sq_to_bb(&[19, 16, 23, 3, 59]));
-        assert_eq!(ROOK_MASKS[24], (FILE_A | RANK[4]) & !sq_to_bb(&[24, 0, 56, 31]));
-        assert_eq!(ROOK_MASKS[38], (FILE_G | RANK[5]) & !sq_to_bb(&[38, 32, 39, 6, 62]));
-        assert_eq!(ROOK_MASKS[55], (FILE_H | RANK[7]) & !sq_to_bb(&[55, 48, 7, 63]));
+        assert_eq!(ROOK_MASKS[0],  (FILE_A | RANK[1]) & !sq_to_bb(&[A1, H1, A8]));
+        assert_eq!(ROOK_MASKS[3],  (FILE_D | RANK[1]) & !sq_to_bb(&[D1, A1, H1, D8]));
+        assert_eq!(ROOK_MASKS[9],  (FILE_B | RANK[2]) & !sq_to_bb(&[B2, A2, H2, B1, B8]));
+        assert_eq!(ROOK_MASKS[19], (FILE_D | RANK[3]) & !sq_to_bb(&[D3, A3, H3, D1, D8]));
+        assert_eq!(ROOK_MASKS[24], (FILE_A | RANK[4]) & !sq_to_bb(&[A4, A1, A8, H4]));
+        assert_eq!(ROOK_MASKS[38], (FILE_G | RANK[5]) & !sq_to_bb(&[G5, A5, H5, G1, G8]));
+        assert_eq!(ROOK_MASKS[55], (FILE_H | RANK[7]) & !sq_to_bb(&[H7, A7, H1, H8]));
     }
 
     #[test]
     fn bishop_masks() {
-        assert_eq!(BISHOP_MASKS[0],  sq_to_bb(&[9, 18, 27, 36, 45, 54]));
-        assert_eq!(BISHOP_MASKS[3],  sq_to_bb(&[10, 17, 12, 21, 30]));
-        assert_eq!(BISHOP_MASKS[13], sq_to_bb(&[20, 27, 34, 41, 22]));
-        assert_eq!(BISHOP_MASKS[24], sq_to_bb(&[17, 10, 33, 42, 51]));
-        assert_eq!(BISHOP_MASKS[38], sq_to_bb(&[29, 20, 11, 45, 52]));
-        assert_eq!(BISHOP_MASKS[55], sq_to_bb(&[46, 37, 28, 19, 10]));
-        assert_eq!(BISHOP_MASKS[56], sq_to_bb(&[49, 42, 35, 28, 21, 14]));
+        assert_eq!(BISHOP_MASKS[0],  sq_to_bb(&[B2, C3, D4, E5, F6, G7]));
+        assert_eq!(BISHOP_MASKS[3],  sq_to_bb(&[C2, B3, E2, F3, G4]));
+        assert_eq!(BISHOP_MASKS[13], sq_to_bb(&[E3, D4, C5, B6, G3]));
+        assert_eq!(BISHOP_MASKS[24], sq_to_bb(&[B3, C2, B5, C6, D7]));
+        assert_eq!(BISHOP_MASKS[38], sq_to_bb(&[F4, E3, D2, F6, E7]));
+        assert_eq!(BISHOP_MASKS[55], sq_to_bb(&[G6, F5, E4, D3, C2]));
+        assert_eq!(BISHOP_MASKS[56], sq_to_bb(&[B7, C6, D5, E4, F3, G2]));
+    }
+
+    #[test]
+    fn pawn_masks() {
+        assert_eq!(ADJACENT_FILES[0], FILE_B);
+        assert_eq!(ADJACENT_FILES[28], FILE_D | FILE_F);
+        assert_eq!(ADJACENT_FILES[63], FILE_G);
+        assert_eq!(FORWARD_RANKS_WHITE[12], RANK[3] | RANK[4] | RANK[5] | RANK[6] | RANK[7] | RANK[8]);
+        assert_eq!(FORWARD_RANKS_WHITE[60], 0);
+        assert_eq!(FORWARD_RANKS_BLACK[52], RANK[1] | RANK[2] | RANK[3] | RANK[4] | RANK[5] | RANK[6]);
+        assert_eq!(FORWARD_RANKS_BLACK[4], 0);
+        assert_eq!(PASSED_PAWN_MASKS_WHITE[52], sq_to_bb(&[D8, E8, F8]));
+        assert_eq!(PASSED_PAWN_MASKS_BLACK[8], sq_to_bb(&[A1, B1]));
+        assert_eq!(PAWN_ATTACK_SPAN_WHITE[44], sq_to_bb(&[D7, F7, D8, F8]));
+        assert_eq!(PAWN_ATTACK_SPAN_BLACK[16], sq_to_bb(&[B2, B1]));
     }
 }
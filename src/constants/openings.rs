@@ -0,0 +1,136 @@
+// (ECO code, opening name, moves from the starting position in UCI notation)
+// Lookup is done by the Zobrist hash of the final position, so transpositions
+// are recognized too. If two lines reach the same position, the first one wins
+pub static OPENINGS: &[(&str, &str, &str)] = &[
+    // Flank and irregular openings
+    ("A00", "Polish Opening", "b2b4"),
+    ("A00", "Grob Opening", "g2g4"),
+    ("A00", "Van't Kruijs Opening", "e2e3"),
+    ("A00", "Mieses Opening", "d2d3"),
+    ("A00", "Saragossa Opening", "c2c3"),
+    ("A01", "Nimzo-Larsen Attack", "b2b3"),
+    ("A02", "Bird's Opening", "f2f4"),
+    ("A04", "Zukertort Opening", "g1f3"),
+    ("A07", "King's Indian Attack", "g1f3 d7d5 g2g3"),
+    ("A09", "Reti Opening", "g1f3 d7d5 c2c4"),
+
+    // English Opening
+    ("A10", "English Opening", "c2c4"),
+    ("A13", "English, Agincourt Defense", "c2c4 e7e6"),
+    ("A15", "English, Anglo-Indian Defense", "c2c4 g8f6"),
+    ("A20", "English, King's English Variation", "c2c4 e7e5"),
+    ("A30", "English, Symmetrical Variation", "c2c4 c7c5"),
+
+    // Queen's pawn: Indian systems, Benoni, Dutch
+    ("A40", "Queen's Pawn Game", "d2d4"),
+    ("A43", "Old Benoni Defense", "d2d4 c7c5"),
+    ("A45", "Indian Defense", "d2d4 g8f6"),
+    ("A56", "Benoni Defense", "d2d4 g8f6 c2c4 c7c5"),
+    ("A57", "Benko Gambit", "d2d4 g8f6 c2c4 c7c5 d4d5 b7b5"),
+    ("A60", "Modern Benoni", "d2d4 g8f6 c2c4 c7c5 d4d5 e7e6"),
+    ("A80", "Dutch Defense", "d2d4 f7f5"),
+
+    // Semi-open games
+    ("B00", "King's Pawn Opening", "e2e4"),
+    ("B01", "Scandinavian Defense", "e2e4 d7d5"),
+    ("B01", "Scandinavian, Main Line", "e2e4 d7d5 e4d5 d8d5"),
+    ("B02", "Alekhine's Defense", "e2e4 g8f6"),
+    ("B06", "Modern Defense", "e2e4 g7g6"),
+    ("B07", "Pirc Defense", "e2e4 d7d6 d2d4 g8f6"),
+    ("B10", "Caro-Kann Defense", "e2e4 c7c6"),
+    ("B12", "Caro-Kann, Advance Variation", "e2e4 c7c6 d2d4 d7d5 e4e5"),
+    ("B13", "Caro-Kann, Exchange Variation", "e2e4 c7c6 d2d4 d7d5 e4d5 c6d5"),
+    ("B15", "Caro-Kann Defense", "e2e4 c7c6 d2d4 d7d5 b1c3"),
+    ("B17", "Caro-Kann, Karpov Variation", "e2e4 c7c6 d2d4 d7d5 b1c3 d5e4 c3e4 b8d7"),
+    ("B18", "Caro-Kann, Classical Variation", "e2e4 c7c6 d2d4 d7d5 b1c3 d5e4 c3e4 c8f5"),
+
+    // Sicilian Defense
+    ("B20", "Sicilian Defense", "e2e4 c7c5"),
+    ("B21", "Sicilian, Smith-Morra Gambit", "e2e4 c7c5 d2d4 c5d4 c2c3"),
+    ("B22", "Sicilian, Alapin Variation", "e2e4 c7c5 c2c3"),
+    ("B23", "Sicilian, Closed", "e2e4 c7c5 b1c3"),
+    ("B27", "Sicilian Defense", "e2e4 c7c5 g1f3"),
+    ("B30", "Sicilian, Old Sicilian", "e2e4 c7c5 g1f3 b8c6"),
+    ("B30", "Sicilian, Rossolimo Variation", "e2e4 c7c5 g1f3 b8c6 f1b5"),
+    ("B32", "Sicilian, Open", "e2e4 c7c5 g1f3 b8c6 d2d4 c5d4 f3d4"),
+    ("B33", "Sicilian, Sveshnikov Variation", "e2e4 c7c5 g1f3 b8c6 d2d4 c5d4 f3d4 g8f6 b1c3 e7e5"),
+    ("B34", "Sicilian, Accelerated Dragon", "e2e4 c7c5 g1f3 b8c6 d2d4 c5d4 f3d4 g7g6"),
+    ("B40", "Sicilian, French Variation", "e2e4 c7c5 g1f3 e7e6"),
+    ("B41", "Sicilian, Kan Variation", "e2e4 c7c5 g1f3 e7e6 d2d4 c5d4 f3d4 a7a6"),
+    ("B44", "Sicilian, Taimanov Variation", "e2e4 c7c5 g1f3 e7e6 d2d4 c5d4 f3d4 b8c6"),
+    ("B50", "Sicilian Defense", "e2e4 c7c5 g1f3 d7d6"),
+    ("B51", "Sicilian, Moscow Variation", "e2e4 c7c5 g1f3 d7d6 f1b5"),
+    ("B54", "Sicilian, Open", "e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4"),
+    ("B56", "Sicilian, Classical Variation", "e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4 g8f6 b1c3 b8c6"),
+    ("B70", "Sicilian, Dragon Variation", "e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4 g8f6 b1c3 g7g6"),
+    ("B75", "Sicilian, Dragon, Yugoslav Attack", "e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4 g8f6 b1c3 g7g6 c1e3 f8g7 f2f3"),
+    ("B80", "Sicilian, Scheveningen Variation", "e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4 g8f6 b1c3 e7e6"),
+    ("B90", "Sicilian, Najdorf", "e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4 g8f6 b1c3 a7a6"),
+
+    // French Defense
+    ("C00", "French Defense", "e2e4 e7e6"),
+    ("C01", "French, Exchange Variation", "e2e4 e7e6 d2d4 d7d5 e4d5"),
+    ("C02", "French, Advance Variation", "e2e4 e7e6 d2d4 d7d5 e4e5"),
+    ("C03", "French, Tarrasch Variation", "e2e4 e7e6 d2d4 d7d5 b1d2"),
+    ("C10", "French, Rubinstein Variation", "e2e4 e7e6 d2d4 d7d5 b1c3 d5e4"),
+    ("C11", "French, Classical Variation", "e2e4 e7e6 d2d4 d7d5 b1c3 g8f6"),
+    ("C15", "French, Winawer Variation", "e2e4 e7e6 d2d4 d7d5 b1c3 f8b4"),
+
+    // Open games
+    ("C20", "King's Pawn Game", "e2e4 e7e5"),
+    ("C21", "Center Game", "e2e4 e7e5 d2d4 e5d4"),
+    ("C23", "Bishop's Opening", "e2e4 e7e5 f1c4"),
+    ("C24", "Bishop's Opening, Berlin Defense", "e2e4 e7e5 f1c4 g8f6"),
+    ("C25", "Vienna Game", "e2e4 e7e5 b1c3"),
+    ("C30", "King's Gambit", "e2e4 e7e5 f2f4"),
+    ("C33", "King's Gambit Accepted", "e2e4 e7e5 f2f4 e5f4"),
+    ("C40", "King's Knight Opening", "e2e4 e7e5 g1f3"),
+    ("C41", "Philidor Defense", "e2e4 e7e5 g1f3 d7d6"),
+    ("C42", "Petrov's Defense", "e2e4 e7e5 g1f3 g8f6"),
+    ("C44", "King's Pawn Game", "e2e4 e7e5 g1f3 b8c6"),
+    ("C44", "Ponziani Opening", "e2e4 e7e5 g1f3 b8c6 c2c3"),
+    ("C45", "Scotch Game", "e2e4 e7e5 g1f3 b8c6 d2d4"),
+    ("C46", "Three Knights Game", "e2e4 e7e5 g1f3 b8c6 b1c3"),
+    ("C47", "Four Knights Game", "e2e4 e7e5 g1f3 b8c6 b1c3 g8f6"),
+    ("C50", "Italian Game", "e2e4 e7e5 g1f3 b8c6 f1c4"),
+    ("C50", "Giuoco Piano", "e2e4 e7e5 g1f3 b8c6 f1c4 f8c5"),
+    ("C51", "Evans Gambit", "e2e4 e7e5 g1f3 b8c6 f1c4 f8c5 b2b4"),
+    ("C53", "Giuoco Piano, Classical Variation", "e2e4 e7e5 g1f3 b8c6 f1c4 f8c5 c2c3"),
+    ("C55", "Two Knights Defense", "e2e4 e7e5 g1f3 b8c6 f1c4 g8f6"),
+    ("C57", "Two Knights, Knight Attack", "e2e4 e7e5 g1f3 b8c6 f1c4 g8f6 f3g5"),
+
+    // Ruy Lopez
+    ("C60", "Ruy Lopez", "e2e4 e7e5 g1f3 b8c6 f1b5"),
+    ("C65", "Ruy Lopez, Berlin Defense", "e2e4 e7e5 g1f3 b8c6 f1b5 g8f6"),
+    ("C68", "Ruy Lopez, Exchange Variation", "e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 b5c6"),
+    ("C70", "Ruy Lopez, Morphy Defense", "e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 b5a4"),
+    ("C80", "Ruy Lopez, Open", "e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 b5a4 g8f6 e1g1 f6e4"),
+    ("C84", "Ruy Lopez, Closed", "e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 b5a4 g8f6 e1g1 f8e7"),
+    ("C89", "Ruy Lopez, Marshall Attack", "e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 b5a4 g8f6 e1g1 f8e7 f1e1 b7b5 a4b3 e8g8 c2c3 d7d5"),
+
+    // Closed games
+    ("D00", "Queen's Pawn Game", "d2d4 d7d5"),
+    ("D00", "Queen's Pawn Game, London System", "d2d4 d7d5 c1f4"),
+    ("D06", "Queen's Gambit", "d2d4 d7d5 c2c4"),
+    ("D07", "Queen's Gambit Declined, Chigorin Defense", "d2d4 d7d5 c2c4 b8c6"),
+    ("D08", "Queen's Gambit Declined, Albin Countergambit", "d2d4 d7d5 c2c4 e7e5"),
+    ("D10", "Slav Defense", "d2d4 d7d5 c2c4 c7c6"),
+    ("D20", "Queen's Gambit Accepted", "d2d4 d7d5 c2c4 d5c4"),
+    ("D30", "Queen's Gambit Declined", "d2d4 d7d5 c2c4 e7e6"),
+    ("D35", "Queen's Gambit Declined, Exchange Variation", "d2d4 d7d5 c2c4 e7e6 b1c3 g8f6 c4d5"),
+    ("D43", "Semi-Slav Defense", "d2d4 d7d5 c2c4 c7c6 g1f3 g8f6 b1c3 e7e6"),
+    ("D80", "Grunfeld Defense", "d2d4 g8f6 c2c4 g7g6 b1c3 d7d5"),
+    ("D85", "Grunfeld, Exchange Variation", "d2d4 g8f6 c2c4 g7g6 b1c3 d7d5 c4d5 f6d5"),
+
+    // Indian defenses
+    ("E01", "Catalan Opening", "d2d4 g8f6 c2c4 e7e6 g2g3"),
+    ("E11", "Bogo-Indian Defense", "d2d4 g8f6 c2c4 e7e6 g1f3 f8b4"),
+    ("E12", "Queen's Indian Defense", "d2d4 g8f6 c2c4 e7e6 g1f3 b7b6"),
+    ("E20", "Nimzo-Indian Defense", "d2d4 g8f6 c2c4 e7e6 b1c3 f8b4"),
+    ("E32", "Nimzo-Indian, Classical Variation", "d2d4 g8f6 c2c4 e7e6 b1c3 f8b4 d1c2"),
+    ("E40", "Nimzo-Indian, Rubinstein Variation", "d2d4 g8f6 c2c4 e7e6 b1c3 f8b4 e2e3"),
+    ("E60", "King's Indian Defense", "d2d4 g8f6 c2c4 g7g6"),
+    ("E76", "King's Indian, Four Pawns Attack", "d2d4 g8f6 c2c4 g7g6 b1c3 f8g7 e2e4 d7d6 f2f4"),
+    ("E80", "King's Indian, Samisch Variation", "d2d4 g8f6 c2c4 g7g6 b1c3 f8g7 e2e4 d7d6 f2f3"),
+    ("E92", "King's Indian, Classical Variation", "d2d4 g8f6 c2c4 g7g6 b1c3 f8g7 e2e4 d7d6 g1f3 e8g8 f1e2 e7e5"),
+];
@@ -1,841 +1,81 @@
-// Generated by `src/bin/zobrist_gen.rs`
+// Zobrist keys, generated at compile time by SplitMix64 from a fixed seed: the same on every build,
+// and with no generated file to go out of sync with its generator
+
+const SEED: u64 = 0x6368_6573_7369_6361;  // "chessica"
+
+// One step of SplitMix64: the next state, and the number it gives
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (state, z ^ (z >> 31))
+}
+
+// The keys one after the other, in the order of the tables below
+const fn keys<const N: usize>(skip: usize) -> [u64; N] {
+    let mut state = SEED;
+    let mut i = 0;
+    while i < skip {
+        state = splitmix64(state).0;
+        i += 1;
+    }
+    let mut keys = [0u64; N];
+    let mut i = 0;
+    while i < N {
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys[i] = key;
+        i += 1;
+    }
+    keys
+}
+
+const PIECE_KEYS: usize = 6 * 2 * 64;
 
 // Zobrist keys for [piece][color][square]
-pub const ZOBRIST_PIECE: [[[u64; 64]; 2]; 6] = [
-	[
-		[
-			0xee032661ff7a9c55,
-			0x8ebb528cfbe838c6,
-			0xd3e99707193bce6d,
-			0x083f44f1bc074847,
-			0x03a90e712935dcb1,
-			0xe868df16a649640d,
-			0xa8dc36348b4dc625,
-			0x18a91dd934cc8cd5,
-			0x291cf3e8b160a6d2,
-			0x8a0e9d462631d6da,
-			0x3f775f5260fd1150,
-			0xd55bbd367ab32c54,
-			0x81c30dace14f3bbc,
-			0x54d0ed4de47628f0,
-			0x790cc5da62f7fa96,
-			0x0aed004b831b95b8,
-			0x018a4e832be04b02,
-			0x0236cd28734ad2d5,
-			0x4228afb2871bc062,
-			0xd47df59ccb73114e,
-			0xc51d86abfa85b05b,
-			0x78ba3a6b7c017d9b,
-			0x101a117818816598,
-			0x99087d85a80e2c1e,
-			0xa2af0a12a885b426,
-			0xb84b670771510635,
-			0x88fa0a8cf5d91227,
-			0x9c15ca7abacee829,
-			0xb594aaf837e0be41,
-			0x13969dfd953845fb,
-			0x3bd30ccf4acbf85e,
-			0x9cc1032f3bca4b6b,
-			0x1dd1552790e656bd,
-			0xd698ad69e70538c7,
-			0xe7cc140e12f0cbfb,
-			0x237963ce49db02bf,
-			0x2e859cf1034b7513,
-			0x0407d4aa6e2fefce,
-			0x2f620e6696042dd2,
-			0x120addd4dacfacd2,
-			0xb03a445b7a6e0fcc,
-			0xaeefd063cc8f4826,
-			0xebd5eca2f83ffd9c,
-			0xa9fb6c28829a5a96,
-			0x244aba8d2a897236,
-			0x1fdf7933ec8726db,
-			0x5f036703d8f8a232,
-			0xccf324af1708b93f,
-			0x6b251a683a9b117b,
-			0x4f1610e4a034c056,
-			0x9de967f80ed457a6,
-			0xad1c82f13b8b10e2,
-			0x740ad796565aeb94,
-			0x3db3f26c83245eb6,
-			0x505f4777ca9ffb8c,
-			0xeb92da0b49504c85,
-			0xd1c440418695a5b2,
-			0x220f97290eb683db,
-			0x5eb472bd7e075e08,
-			0xf6872c5efc9d9ebf,
-			0xd8fb3288a2ad34f1,
-			0xf70395a6bdd1917a,
-			0x90099e5a1bc3a4b7,
-			0xbd301aea7d9f99d4,
-		],
-		[
-			0x688acce208456ccf,
-			0x16cd438881d1bdab,
-			0x1b92fe239f448de2,
-			0x8f0470164829531d,
-			0x09673b5ed1953bf0,
-			0x3e02ff2e8dfc2205,
-			0x9a59b4cac3500515,
-			0x20176a7bc2ce0855,
-			0xab937dfc48b2e684,
-			0x1c399e54e634de62,
-			0xa4b24a0535dc24cf,
-			0x1de0921410733a8c,
-			0x5080addf21e6b180,
-			0x7620927985ef27ce,
-			0xaddb4ea868759527,
-			0xc2d82711dda38f0c,
-			0x5ec9687d279f5d8c,
-			0x0639c43bcebe98ae,
-			0xe26a110f2055faba,
-			0xacf76c1893298374,
-			0x946cd043c6dd9f90,
-			0x4809b994d84f105e,
-			0xec17ea26992ee00f,
-			0x74258b12e807aa50,
-			0x858bf6a5483ada1b,
-			0xe86ac4f9bedf9611,
-			0xbebe70a3af5bcd28,
-			0xee9a2217759e6558,
-			0x7ebbd499141966a5,
-			0x7a26daf6f2b4386f,
-			0x0e08a3521dd7eacf,
-			0xd53e4846ad6c455c,
-			0x2d57b7bb7b1e54ca,
-			0xce29a7e0f8f72144,
-			0x2b5060812503bb25,
-			0x898ec918ad189377,
-			0x477e728e7454f490,
-			0x7d2681c08b36dce6,
-			0xca3d9911f9236c32,
-			0xc90d90f8ec9c2594,
-			0x1a1edfeb8c2c1c6f,
-			0x1daec6a3861ae7c4,
-			0x5af5f4ed3cc29b25,
-			0x01302d0f32312421,
-			0x504667d9f39b3362,
-			0xbb4493ff6431a5d3,
-			0xafa339b7feb587e9,
-			0xee43070f9f829e5c,
-			0xb3a61716f6982682,
-			0xc2bc28fa0428ded1,
-			0x7879789ce83bc148,
-			0x4f5d036738cadb6a,
-			0x5628dc830d905ab5,
-			0x64ef5138a29aee19,
-			0xfc2bb948bc983a53,
-			0x60d447e3f166a84d,
-			0xa4a103d5610dab6e,
-			0xbc2990c7d03b9cbc,
-			0xec2c95ba393edede,
-			0x6232671c4e17718c,
-			0xb062e07f1aefbb0c,
-			0x9f5a6b70f930d69c,
-			0x7ace12d75b852c22,
-			0xf646b05de0c4b99f,
-		],
-	],
-	[
-		[
-			0xe70e48458542c459,
-			0x96d3396d8ccac16f,
-			0x2cb4ee34f68ea862,
-			0x3f4580afa1a99ebb,
-			0x41fa4a553736d7c1,
-			0x0ca5a34f929edec3,
-			0xc33a41e19f7ca67a,
-			0x77e261c85bb83d17,
-			0x76bfa35cdee17828,
-			0x51cbf6cec39ffc4a,
-			0x0ad27465427e71ef,
-			0x2ade3f2831f3c8cb,
-			0xc16f023962c5919e,
-			0xd9c7cff440760a7d,
-			0x0e70d4b91ecb4f96,
-			0xf7e1abeacfd0259a,
-			0x98da3ac324507185,
-			0x238d0590fc2335ea,
-			0xffd947fa8d0dc0fb,
-			0x5dca9d8e3fe4d64e,
-			0x997e59eb0f1c86fc,
-			0xf78baab64e7cf840,
-			0x9dd1811de6a39072,
-			0xe967aef1557478d5,
-			0x231d721fde858ace,
-			0x39001c6a78042253,
-			0x30fcbb89c02691f0,
-			0xe44faedf1e59178a,
-			0x537cc470c1a88346,
-			0x9b25b1713dcb6f43,
-			0xe2ad73c002b48750,
-			0x6012a22d7e5bdfd8,
-			0xc48629db4b205d02,
-			0xab16d64397a98391,
-			0xf4ef4be4d80e4828,
-			0x3e872f35dbf37097,
-			0x58b368e285dc665f,
-			0x41f7245fababf8e6,
-			0xcc0ec47e2679db28,
-			0xbc93ba9862f01327,
-			0x179ba14a0d3baade,
-			0x3cbd6f2ba80d452e,
-			0xa142e88d775e69a8,
-			0xb784d7e0163f6bab,
-			0x6a86a8ba7a35399b,
-			0xeeed4953895e5306,
-			0x1ff079421b1a95d9,
-			0xf46a4db98789a72c,
-			0x0d49733967a6beb0,
-			0x8946afd79f9dafb1,
-			0x23a045d8d9c7eca4,
-			0x73a8fcdfef9fafac,
-			0xab82ee0ff3bc9f63,
-			0xe0cb34c6ab3cc47b,
-			0xeb7c868f3488bace,
-			0xf0a877c5b073df8f,
-			0x233d3e74795cfade,
-			0x32ec671a0964b1aa,
-			0xc9956b82dc170e02,
-			0xd308102c11c97ee3,
-			0xe8a7a93db5000c4b,
-			0xd6c06471449f008c,
-			0x8353dc4d82b7bdef,
-			0x31408a0db9bd991e,
-		],
-		[
-			0x9ab3b8e5d5c97e97,
-			0x541a95e6dc8b1749,
-			0x323e40cfd9402be4,
-			0xb43d5f2d2a44f803,
-			0x246f4c0d8198eda6,
-			0xbb4098d1e5c9e8f0,
-			0x622cda788da2e0ea,
-			0x21413ed298b4523e,
-			0xd3b1087b8bc82b52,
-			0x8141a51dc0a55ae2,
-			0x728d5bba013dee2a,
-			0x562f8bf6d5279ec0,
-			0xa29e8e74aeed7c35,
-			0x75f7a0770cf3eca9,
-			0xf72fbcc7b79862fc,
-			0xe765433f0c9e469c,
-			0x594ce723224c4821,
-			0xfc4ffd0cd2b0bb04,
-			0x1e7e4570e0ddb159,
-			0x5c5acd13d9d105ec,
-			0x0824527cfb032e82,
-			0xbd00a2bda80a6785,
-			0xc11a624ea45493e5,
-			0xb3c8b4ba8c2e09a5,
-			0x51e7e7ee6f37bd72,
-			0x23ca91ac7250bf12,
-			0xd8dc3852d5176376,
-			0xc0e96d53ed2e27dc,
-			0xccb65705740a04f6,
-			0xf085dd948219a770,
-			0x197629970f32debd,
-			0x0b2bceb76b948a13,
-			0x76bef560269ae074,
-			0xf0f44cbb7a29525c,
-			0xe2e116d12f061163,
-			0x6979ce2fa4d26777,
-			0x10ea25eb4efbb80b,
-			0x1ae28af8ecb760de,
-			0x2c9638d19e1ff8c6,
-			0xf2f6df72cc9f3cbd,
-			0x0fcd88f5b83073bc,
-			0x645947ac8978a22b,
-			0x8ab9b33f817c8c0d,
-			0xd8e929077ad20774,
-			0xbcf755ba010ab79c,
-			0x1e67b917c796f4f0,
-			0x7e6cf571f8e8260e,
-			0x9f6e9cb99fa9984b,
-			0x5f639b58195b3549,
-			0x69370eb9a1d460e0,
-			0xdc44dde970ef8ab5,
-			0xc931c28ebeb91dc1,
-			0x49849c770d6f9aac,
-			0x0b7cc21212580eb4,
-			0xb31bad0f40762838,
-			0x7d69c010799c2595,
-			0x250201642d561b73,
-			0xd7b61163964e8c80,
-			0xe9ba83ac3b8d87c5,
-			0xa8b79b0c477363f7,
-			0xb43b17645aeebb88,
-			0x78986e982adcec8f,
-			0x06eeca14c57cf849,
-			0x897d09aac7d285db,
-		],
-	],
-	[
-		[
-			0x61f66bbfed7aed88,
-			0x351fda75e11731aa,
-			0xdd21d4385b8dfedb,
-			0xe555314be11d1cbc,
-			0x6172329fc85e3193,
-			0x92627550ead3592b,
-			0x4cfd67330113e7a8,
-			0xb485379d398db2a3,
-			0x534e616ac09fa360,
-			0xd9935abbd32e3626,
-			0x55410e3f6b51c5a8,
-			0xe65158173f653c4d,
-			0xbbe20a2461979c79,
-			0x963d055a0e1c486e,
-			0xe0a2f2f6ab66b858,
-			0x9d17eea61d288f82,
-			0x0898eac438699acd,
-			0x446a7ebc3e82d690,
-			0x5b4b2e5e38bfed10,
-			0x2a161a1afd526684,
-			0x71dcc822ff2aca1d,
-			0xc4e9d1ffdf835a23,
-			0x8a68b5aeb8dc8b2b,
-			0x5abf64018afd7467,
-			0xe26a61d03552caf7,
-			0x66ac0779ecffa399,
-			0x62497db85cc391e8,
-			0x64d11b8e412c09f9,
-			0x69ada397e1807c63,
-			0xb7cbe234f53ec748,
-			0xcb3dc71ef8c9a055,
-			0xfee6ac5179aedea0,
-			0xcf9936ea7d7f94d5,
-			0x99075c586324a00b,
-			0x9a2a31b5e3c17922,
-			0x386a7954b6f21d1e,
-			0x2870eb5c29d2bb78,
-			0x1aa09311b09086dc,
-			0xae1ed82ad6ae4648,
-			0xce46396865adcac3,
-			0xc6a3da64909bcd68,
-			0xecd6559723765cbb,
-			0xa559c319fa4b3ef6,
-			0x4c06dfed6c062564,
-			0x57ca37a32f71635c,
-			0x8a5fb551c80b69bb,
-			0x137059c00c20c7a5,
-			0x8ddbd7853a09f23d,
-			0x031f5ba70fc3d0ba,
-			0xe66202a528de172d,
-			0x1ad727a945131277,
-			0xf5a5c05bfe9e0b64,
-			0xdd456917afd230e6,
-			0xa8d91d53882bfc55,
-			0xdfe2b62913434b09,
-			0x43174544c1310741,
-			0x17f5dd7472a05b53,
-			0x7ab5e45bf427c793,
-			0x676715dc332c6cc4,
-			0x257e3eb1a0a55515,
-			0xdb8eb8bc8a5e4cba,
-			0x85ecefe2d2b331e9,
-			0x0ad006354b79be95,
-			0x6b0b0828a384066f,
-		],
-		[
-			0xe97c5d436fbc7d2f,
-			0x56f3d0a6877395e3,
-			0xb62279678aba97a5,
-			0xbc3065202e77db98,
-			0x349a53818d10cac3,
-			0x532786ca8bd322a2,
-			0xb4e8a4ba474098a6,
-			0xa3cece5a8d20f598,
-			0xaa3eab63220e78f6,
-			0x1ea4a26500fbec0e,
-			0x81ecdf7178a03331,
-			0xc10f847710be2b6c,
-			0x9a8e1be000bf1e76,
-			0x551ba5ad27b6a4d6,
-			0xead6118197d79f44,
-			0x7c4659fb9da9f175,
-			0x99b06835117f9649,
-			0x55d8043b8f0d00a8,
-			0x0cc39f3685742271,
-			0xc914a3988fe4a279,
-			0x6824974f4f76352b,
-			0x84df8eae50b98264,
-			0xecf68c2ac3602ec2,
-			0xac95e647c40dd1df,
-			0x9dedc94bffaa9eef,
-			0xa029952f6f4f8ea9,
-			0x73c9ba1903b628d8,
-			0xb654ca49a19f6e7a,
-			0x1f516e74a1e6cca9,
-			0xb3e1a2568d678330,
-			0x23951199dcb909c6,
-			0x2cf358248f4d2457,
-			0xd74a2a6e3e5acccb,
-			0xf15ddc347e40ff61,
-			0x7440071af0926835,
-			0x343ea85cf2ae4a29,
-			0xa8914c494b17e084,
-			0xa79dcd26ad82dce6,
-			0x1f36de9f464b4fbc,
-			0xc44945b7de438078,
-			0xa39238bb58002a24,
-			0x6535fb3a46ba55f8,
-			0x6ad038146db9a405,
-			0x20a0f8891f5479ca,
-			0x28f77f9b31a524fc,
-			0x687d1ce65d006d26,
-			0x4fb53d7819ea980f,
-			0xdebb34c7f5a84def,
-			0xad407725d726cf3c,
-			0x86be29c66d44131c,
-			0xfe9d3cf09fc9e0e6,
-			0xd76fb43d9bc5925f,
-			0x11fc6054925212e4,
-			0x8c5be25d71096cd8,
-			0x4dae546449e88191,
-			0xe9ac8b1c10ef4dce,
-			0xe069ac4f87dd1a92,
-			0x32d8984796a387d0,
-			0x54f1ea85ae22d430,
-			0x6590160fd7037ca6,
-			0xf8d2521ac91f02a6,
-			0x7d3f14fbd0a5b741,
-			0x8660590841206e46,
-			0x9780c0d4998d14a1,
-		],
-	],
-	[
-		[
-			0xd274b80f7d814278,
-			0x6943f123a4fe9276,
-			0x9c601b67a7745c84,
-			0x209ac15b818fce56,
-			0x4c453d2f5e5a9e48,
-			0xef71223cc5dade8a,
-			0xd333e0eb3c78a568,
-			0x9e93b77e644e308a,
-			0xb578c2ac0bec1b54,
-			0xa1c803c23e676369,
-			0x594dea8a042014e4,
-			0x38d9fae24cfd9fd3,
-			0x25884b987059bfbc,
-			0xde5b05bdb32a168c,
-			0x38e935520583392e,
-			0x11e2a576ead3560e,
-			0xdd56779a6d13349d,
-			0x34c2e281b6015b99,
-			0xa57b91828a214401,
-			0x3adc92f0cd1d7b96,
-			0x8e97d167923516be,
-			0xee0aa6a608df089d,
-			0x8da40ff88e4fb30b,
-			0x4ab6fb229cbd6080,
-			0x35157763130ebccf,
-			0x2cb6279cabecd510,
-			0x62db5a8a26ece02d,
-			0xca4c22c9ce95fa75,
-			0x098f9314bd616347,
-			0x094a9115e7e161b7,
-			0xc006d23dcc4dfa5e,
-			0x27d5a456fc7b6252,
-			0x0aa8d05d4d4a8c29,
-			0x9afa4be9ae5bcc6d,
-			0x2fa6c28a3455ee5c,
-			0xdb019f11f091a76e,
-			0x10c7f5efe94172f0,
-			0x081dccb88d5b3190,
-			0x250fc7c92893d200,
-			0xe655b1d6cc64b9ed,
-			0x9bc6c5f4b08e948a,
-			0xfaf68cf5ef93c768,
-			0x8e20e9f4ba787a51,
-			0xc6bc5d81153d60e9,
-			0xf598d3d1828f7d22,
-			0xf11fef144b57f468,
-			0x0fa51c7ae19ad637,
-			0xc3a09685fbee1df2,
-			0xdbd30ac70fcb3c3f,
-			0x88e52bb95395f5f4,
-			0x2e7f11bbd62cd146,
-			0x687d74618fb015ca,
-			0x3e1dcac98b400ade,
-			0x65b68519dc3d58e6,
-			0x10e9a5f2ca8159d5,
-			0x3afc8cec82b23adc,
-			0x13e865b6be3636c7,
-			0x65a62ba59e90b516,
-			0x19621110c5181881,
-			0xf5d92662e5576622,
-			0x7cc3b291d5c39ab4,
-			0x1da15947bbc8f48a,
-			0x5955158709981fc0,
-			0x923d192f239d05aa,
-		],
-		[
-			0xa4ac891cdc5c390c,
-			0x0564a32ae17cdd01,
-			0x7d25976acd069d86,
-			0x8bdc9d5411c4e0c5,
-			0x4450d240a4664473,
-			0x75e0c0582b355bb6,
-			0x36cc6349e0d916b8,
-			0x09a9e12cd6109a9c,
-			0xf7ed361eca341107,
-			0x0547159d4f146658,
-			0x29bbec95dccb6aa1,
-			0x25d32a328fb23d36,
-			0xf46e6b26d8fb519f,
-			0x1ac56c25016a3dcd,
-			0x07a17e4e28ade668,
-			0xbf82d59f8fca6b31,
-			0xa77eb24bca429d34,
-			0x345b17ef7f49294f,
-			0xfbd597ae389d74cd,
-			0x5ddd54ed50731d6a,
-			0x97769afa2beb3757,
-			0xac3c991534baa6dd,
-			0xd53b67b3c321ae1c,
-			0xc7166f947fcb8bd3,
-			0x439fee8d6455b789,
-			0x5dcfe90544d8653b,
-			0x59128d5b1c6bf684,
-			0x96c1a1c8e5da6669,
-			0xfd19ce60e6e20b81,
-			0x0215d8b80c05f477,
-			0xc5aba89b668070b1,
-			0xf3641295ce254da1,
-			0x3ec0fff7e90d7743,
-			0x8f01d131b8032aa9,
-			0x612e419d6bf580b6,
-			0xdb2f184ffe2833c9,
-			0xec41adf1c7fc9dbe,
-			0x3176cc6c1e28aadf,
-			0x1a05bded5213b184,
-			0x2d5f8f077d0c7a83,
-			0xc7c8fef05eb16d04,
-			0x865aac611c118d2e,
-			0xd89260be3b9e93bc,
-			0x950eb392a239d9eb,
-			0xc37c4b8a791743a7,
-			0x56df6408127f3a84,
-			0x72066044d914dc62,
-			0x62756d515ac9848c,
-			0x933a94ed861c480c,
-			0xfb4a7d1291d0b663,
-			0x81dc2fb6ce3bc107,
-			0x4ff2c9926e9c3ed1,
-			0xa73ea07f007b5679,
-			0x3cfbca8d96dee552,
-			0x729a1e72466e9f19,
-			0x4ebd5891910a637e,
-			0x61598c897ddde4b8,
-			0xf3462734228fc82f,
-			0x1738b6a554d7d99b,
-			0x7d34e2fd3bef25ef,
-			0x867b7be4a41fcc0b,
-			0xb74ad2f36ff65680,
-			0x4196bb96c448dd0d,
-			0xfb68893d45819af7,
-		],
-	],
-	[
-		[
-			0x2a35655861374cda,
-			0x2f1b804116684668,
-			0x0a91348e437ebb67,
-			0xbe27e3475deede66,
-			0x56682fd6663b57b9,
-			0x76f8d905e1c5ed63,
-			0x00d4fc5ad8a211dc,
-			0xc8e61a4656297d77,
-			0x803cd1a6c51c3b2d,
-			0x9ef6bd9fd1fcfdb9,
-			0x75bca8d165cb948b,
-			0x041e0afae56f4af2,
-			0x85fb8294bb4fe062,
-			0x9fc8a0de978d72d6,
-			0x2250847371b2503e,
-			0x40e1d209d1a8d8da,
-			0xce0b8c3fe94037fe,
-			0xba81cb215e49e8a4,
-			0xe057cc668fb9a4bb,
-			0x037a64c7cbc0e03d,
-			0xf9abaf30b74b2a8c,
-			0xb1ce811381b212da,
-			0xdbfcc1cb19d548a5,
-			0x01eb16df58156fb1,
-			0xb96d0a2845196eb1,
-			0x0cccb471fd085f37,
-			0x8955c0d49eeaaf74,
-			0x4abe56bef62b724a,
-			0xea1b4a85a5a22197,
-			0xbf57309c1c81e993,
-			0x680a04321dcfc228,
-			0x1f058e0f31f7ff47,
-			0x1c1cc10617f591e2,
-			0x40b68e76ca6139b9,
-			0x839930782d55b8ee,
-			0x4dce9d07c0e392f2,
-			0x37b6de99700da6fa,
-			0xdd23f6cb20a5d8cf,
-			0x0402b177ecbd60c0,
-			0x1583fe9e0029b79b,
-			0x026cc45ea228bb8d,
-			0x84a467850a9ea035,
-			0x54daffe2583d9f78,
-			0xbe0270bf31ed8dde,
-			0xa340a62b1ed2759c,
-			0x247865881fe1cf54,
-			0x13b57ec61146f603,
-			0x19f9ba9fccae8945,
-			0xa01c296259c3eb45,
-			0xfd083189c8ab63a1,
-			0x8bcb71b8bed57aaf,
-			0x58988a835305c6e2,
-			0x0d26069ef408fbe9,
-			0x9d2288653a2cc59a,
-			0x4473d26db1f4d0a4,
-			0x0cd26712cd22f3dd,
-			0xeee975e5242200c2,
-			0x0541446e01232e6f,
-			0xc5a62e507059143f,
-			0x20d083030a9b2ac8,
-			0x3aa6ea276dc01520,
-			0xae443051a2ae938c,
-			0xff138002e274fb35,
-			0xc10ec6a362e40efd,
-		],
-		[
-			0x2d16684c66288f1d,
-			0x5660c5e1c29c5d88,
-			0x53db488bc2dae319,
-			0x08fe1ad03e37ad94,
-			0x51915fcadd4e2710,
-			0xb1ed68c8c63d53e0,
-			0x6352ace034f3ab6d,
-			0xc89d3904d9fd9f8c,
-			0xc8917619cb6ae7c6,
-			0x2918ab8bfbba3992,
-			0x077a4de118a0f8f7,
-			0xaed026d877c0a0e2,
-			0xb63786f86dad36c4,
-			0xaa2f016efd3379de,
-			0x7349bb6c468bb141,
-			0x698cc7e5b62be9c5,
-			0x82e324ffa70edbdc,
-			0xba73fd9361d91593,
-			0xf13921ba8572f810,
-			0x3aaa4663d95378ea,
-			0x93b6a8e8204b0451,
-			0xf4160db02a68c7d5,
-			0x38b6d81151f19c2c,
-			0xd36482f21a3f9431,
-			0x29a2917e4f42bb53,
-			0xf67a047171456601,
-			0x92860caec299c3f3,
-			0xf314547e6005a126,
-			0x6cf64fbe29bd27c7,
-			0xd8c41733f7ba2db0,
-			0xd2baa9b2959d579a,
-			0x464d5a172b6d38f1,
-			0xec553e86c662212f,
-			0xc8b0cf64186fe0e4,
-			0x376e700bfb7425d9,
-			0x5cfc3314e5612729,
-			0x4c46fc734f4560af,
-			0x57ac5f202b03408d,
-			0xab5adff2aa4e5a30,
-			0x28ec5cd5382f3c8c,
-			0xd4c2f6e95325281d,
-			0x2ab0f39d5bb2daf8,
-			0x6153a5019a036bf4,
-			0x76a17e10d2798f08,
-			0x49468febf7a48ef3,
-			0xc0a0c082045508b9,
-			0x59d931c7182671da,
-			0x921d2e8a854a36b4,
-			0x1f9bdca755d0b0d9,
-			0xb15c8517888e2b83,
-			0xfd7591ce1f4ab7e5,
-			0x5dcb056880d9635d,
-			0x96d739e0bb8c017c,
-			0x7b7e5c1d99d5811d,
-			0x732e69f2236e621a,
-			0x467b5607ac150612,
-			0x83b366f16c4855c6,
-			0xce12525909c902cc,
-			0x518444a672cac54c,
-			0xc33c7ce93c95f3a5,
-			0x9e4ce3c64ad70d42,
-			0x9757d61a5d6e416b,
-			0x2b788cce3ce4c6b5,
-			0x0d854437d890f0f5,
-		],
-	],
-	[
-		[
-			0x866027651185765e,
-			0xa82aba7680b367f7,
-			0x3f07ad6fc648fee5,
-			0xddb0d8a6d3b0f876,
-			0x68dfe542b10342fc,
-			0xd1daf410182405ad,
-			0x52bfd643c3b5035b,
-			0x6bf341c96ffa7daa,
-			0xd46ab4095502c6c4,
-			0x6f9340e103201c11,
-			0x08234529e0a37e82,
-			0xfa90e75b53459c0f,
-			0x09559cafe4e19dd7,
-			0x5302227be68a9517,
-			0xbac9dbbf61863409,
-			0xdf56466104e79c46,
-			0x32278e38dfe3b414,
-			0x471103c631f3e642,
-			0x871e4376fca7a802,
-			0xdd45750c5b68856b,
-			0x80347e33f6cf1287,
-			0x385d0410f7a9de0d,
-			0x7e6c30d38734cccf,
-			0x19059c2b3c6b42b0,
-			0xa8dba89a716a7dfd,
-			0xeb054d09f59eb465,
-			0x35807bd7a78c3108,
-			0xfa88f701edf13833,
-			0xc94ba229b49181fa,
-			0x6c287bc09127c737,
-			0x229f5724ef0955dd,
-			0xb07029300fe371b6,
-			0x01f92734c5cb57d5,
-			0x543288fa8337d0d4,
-			0x515b89ce30af1e69,
-			0x2fc68aa9fce09157,
-			0x1695459c3a2138b3,
-			0x593cbce41e09af46,
-			0x3e4c05fe3f964741,
-			0x6fda40af190b1f83,
-			0x5b4d0ca9e10676fb,
-			0xe5417249b9f67780,
-			0x447e84ddbc458d4f,
-			0x85927df1c17f5147,
-			0x4b86e73f157ec983,
-			0xa03762998321a0c4,
-			0xc0c49cd220b6fe86,
-			0x38e17b60141203d7,
-			0x7cdf215cfa85ec1d,
-			0xcb668a97937f7474,
-			0x8b1a3d33bfa1ac23,
-			0x2b9f2b99e25fbb92,
-			0xc6920f728d24b2da,
-			0xe65dd0ff0105ac4b,
-			0x7c850d57b880aa65,
-			0x07e2e96d26456781,
-			0xe4a41be2a2a4a3e3,
-			0x7ea25207fdfbbca5,
-			0xc91cb35d517edb63,
-			0xb112acb0cb4a1eb3,
-			0x3185699026d6a47a,
-			0xe6cbd27fd31e135a,
-			0xc1631c8f122ae53d,
-			0x4d3c9610ff57eff6,
-		],
-		[
-			0xdc111ebab40868c3,
-			0x3147be4f8e5d5664,
-			0x6d243b217c30e6fa,
-			0x7ec45b4067dd177d,
-			0xb3f25cceda50da9a,
-			0x8a13645a3733db5d,
-			0xf28ce89651761a2f,
-			0xa89e8cc4b0fae225,
-			0x811fcfe303e378f0,
-			0x08d1d6f71a31fe21,
-			0x3c691d9bd677fb2a,
-			0x0e6ef3c59b519b01,
-			0x9381aa618780cb88,
-			0x2199c6280391dde7,
-			0xeb5d165b260f0103,
-			0x53427089643b331d,
-			0x69dd9ae704756d0b,
-			0xabdecc0772ad3129,
-			0x1ca4ddbe9d8220cf,
-			0x98c194ccbf79fec8,
-			0x6dc9a18cfb9b128c,
-			0xa948064fdecb69c1,
-			0x4b75ba0fa6b1edb5,
-			0xed698f24c9fdccb4,
-			0xf1252d98a62a232d,
-			0x44216aff1724df58,
-			0xc95cd0914fda6d13,
-			0x7801f81a8cf41e8c,
-			0x4de03da59e3ca9d6,
-			0x7db0510261dffed1,
-			0x723d94b77b931292,
-			0xcb09d5605bc09bdd,
-			0xffc65122c34f8960,
-			0x14435d63c95a0fcb,
-			0x035440de98281cdf,
-			0x138232eb9390476e,
-			0x18adbe56cf75d457,
-			0x8e1f8cf37c3f0633,
-			0x775399e2542c2fce,
-			0x4f70dfc79f9699fa,
-			0xe34b37fd2d735e88,
-			0xaafad00f0863e7d8,
-			0x3b5a1390afebdc82,
-			0x4413377056333839,
-			0x020f4c8e074ff694,
-			0xea9f45b5cb07ac5c,
-			0x19fe88c2d28eb313,
-			0xeecdb9b88beeaff7,
-			0x5d52bbd79c060b11,
-			0x97be698735edf9f2,
-			0xeaf912bb5c8291b5,
-			0x7e428d658d8397b3,
-			0x4c5aa13bb1f50049,
-			0xf5d43458ea75b461,
-			0xd9b9244bd3fcb9ea,
-			0xcb77ce290d0c7cb9,
-			0x9c6139373250e775,
-			0x75524bc00374b90e,
-			0xbe3eb478af542e7a,
-			0x16a6ecfc7728c9fe,
-			0x848294b81c50194f,
-			0x354ba2f617b853f0,
-			0x1c86e1a5d970fb7b,
-			0x9f56ec62c98261d2,
-		],
-	],
-];
+pub const ZOBRIST_PIECE: [[[u64; 64]; 2]; 6] = {
+    let flat: [u64; PIECE_KEYS] = keys(0);
+    let mut table = [[[0u64; 64]; 2]; 6];
+    let mut i = 0;
+    while i < PIECE_KEYS {
+        table[i / 128][i / 64 % 2][i % 64] = flat[i];
+        i += 1;
+    }
+    table
+};
+
+// [castling rights], as `CastlingRights::encode` numbers them
+pub const ZOBRIST_CASTLING: [u64; 16] = keys(PIECE_KEYS);
+
+pub const ZOBRIST_EN_PASSANT_FILE: [u64; 8] = keys(PIECE_KEYS + 16);
+
+pub const ZOBRIST_SIDE_BLACK: u64 = keys::<1>(PIECE_KEYS + 16 + 8)[0];
+
 
-pub const ZOBRIST_CASTLING: [u64; 16] = [
-    0x721a99d6460169bb,
-    0x172182fdfcbeb821,
-    0xb7c752b4454c5269,
-    0x044e7fe126e7aa6b,
-    0xdae80197d998dd60,
-    0x013157f7002dee8c,
-    0x40572b956a3e758c,
-    0xb186d73f6d722afe,
-    0x842f1286b5f1663d,
-    0x4d75f93ee1d5f3cf,
-    0xae1a957eec3c3aa9,
-    0xa145c1ca94954120,
-    0x590763fd4d5c262e,
-    0x52a8ccb72d0d81b5,
-    0x5c41584604bf17b8,
-    0xa556a2fc46f78a8b,
-];
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-pub const ZOBRIST_EN_PASSANT_FILE: [u64; 8] = [
-    0x04577667bc009d3a,
-    0x6343fda61347e875,
-    0xf2200d64725ae923,
-    0x38e6f5a3b293413b,
-    0xc8ea375b01267b2f,
-    0xe0c61327185ff9aa,
-    0x9248657332b24d72,
-    0x3ced2b0cf4800013,
-];
+    #[test]
+    fn splitmix64_reference() {
+        // The first outputs for seed 0, as the reference implementation gives them
+        let (state, first) = splitmix64(0);
+        assert_eq!(first, 0xe220_a839_7b1d_cdaf);
+        assert_eq!(splitmix64(state).1, 0x6e78_9e6a_a1b9_65f4);
+    }
 
-pub const ZOBRIST_SIDE_BLACK: u64 = 0xdd7af243f76ed0f2;
+    #[test]
+    fn keys_are_distinct() {
+        let mut all: Vec<u64> = ZOBRIST_PIECE.iter().flatten().flatten().copied()
+            .chain(ZOBRIST_CASTLING)
+            .chain(ZOBRIST_EN_PASSANT_FILE)
+            .chain([ZOBRIST_SIDE_BLACK])
+            .collect();
+        let n = all.len();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), n);
+        assert!(!all.contains(&0));
+    }
+}
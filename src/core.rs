@@ -5,7 +5,20 @@ pub mod perft;
 pub mod chess_move;
 pub mod piece;
 pub mod bitboard;
+pub mod square;
 pub mod player;
 pub mod evaluate;
 pub mod rules;
 pub mod zobrist;
+#[cfg(feature = "polyglot")]
+pub mod polyglot;
+pub mod opening;
+pub mod book;
+pub mod pgn;
+pub mod analysis;
+pub mod outcome;
+pub mod search;
+pub mod tt;
+pub mod bench;
+pub mod clock;
+pub mod variations;
@@ -0,0 +1,231 @@
+// Reviews a game move by move: the engine's choice in every position, and how many
+// centipawns the move that was played gave away compared to it
+use std::sync::{atomic::AtomicBool, Arc};
+use crate::core::{
+    chess_move::Move,
+    game::Game,
+    outcome::Outcome,
+    pgn::PgnMove,
+    player::Player,
+    search::{iterative_deepening, SearchInfo, SearchLimits},
+    tt::TranspositionTable,
+};
+
+// Centipawns a move has to lose to be called one
+pub const INACCURACY_CP: i32 = 50;
+pub const MISTAKE_CP:    i32 = 100;
+pub const BLUNDER_CP:    i32 = 300;
+
+// Scores are clamped to this, mates included: a move that is still winning by a mile
+// is not a blunder just because a quicker win was missed
+const SCORE_CAP: i32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    pub fn from_loss(loss: i32) -> Option<Judgement> {
+        match loss {
+            l if l >= BLUNDER_CP    => Some(Judgement::Blunder),
+            l if l >= MISTAKE_CP    => Some(Judgement::Mistake),
+            l if l >= INACCURACY_CP => Some(Judgement::Inaccuracy),
+            _ => None,
+        }
+    }
+
+    // Move suffix annotation
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            Judgement::Inaccuracy => "?!",
+            Judgement::Mistake    => "?",
+            Judgement::Blunder    => "??",
+        }
+    }
+}
+
+impl std::fmt::Display for Judgement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Judgement::Inaccuracy => "Inaccuracy",
+            Judgement::Mistake    => "Mistake",
+            Judgement::Blunder    => "Blunder",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Scores are from the mover's point of view, in clamped centipawns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveReview {
+    pub mover: Player,
+    pub played: Move,
+    pub best: Move,
+    pub played_score: i32,
+    pub best_score: i32,
+}
+
+impl MoveReview {
+    pub fn loss(&self) -> i32 {
+        (self.best_score - self.played_score).max(0)
+    }
+
+    pub fn judgement(&self) -> Option<Judgement> {
+        Judgement::from_loss(self.loss())
+    }
+}
+
+// Flagged moves and the average centipawn loss of one side
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReviewSummary {
+    pub moves: usize,
+    pub inaccuracies: usize,
+    pub mistakes: usize,
+    pub blunders: usize,
+    pub total_loss: i64,
+}
+
+impl ReviewSummary {
+    pub fn average_loss(&self) -> f64 {
+        self.total_loss as f64 / self.moves.max(1) as f64
+    }
+}
+
+// Searches `depth` plies deep. The best move and the score for the side to move,
+// no move if the game is already over
+fn search(game: &mut Game, depth: usize, tt: &mut TranspositionTable) -> (Option<Move>, i32) {
+    match game.outcome() {
+        Outcome::Ongoing => {}
+        Outcome::Draw(_) => return (None, 0),
+        _ => return (None, -SCORE_CAP),  // checkmated
+    }
+
+    let mut score = 0;
+    let report = |info: &SearchInfo| score = info.score;
+    let result = iterative_deepening(game, SearchLimits::depth(depth), None, Arc::new(AtomicBool::new(false)), tt, report);
+    (result.best_move, score.clamp(-SCORE_CAP, SCORE_CAP))
+}
+
+// Every move of `moves`, played from `start`, against a `depth`-ply search. A played move that
+// differs from the engine's is searched a ply less deep after it is made, so both scores
+// look equally far ahead. Stops at the first illegal move
+pub fn review_game(start: &Game, moves: &[Move], depth: usize, tt: &mut TranspositionTable) -> Vec<MoveReview> {
+    let mut game = start.clone();
+    let mut reviews = Vec::new();
+    for &played in moves {
+        let mover = game.position.player_to_move;
+        let (best, best_score) = search(&mut game, depth, tt);
+        let Some(best) = best else { break };
+        if !game.try_to_make_move(&played) {
+            break;
+        }
+
+        let played_score = if played == best {
+            best_score
+        } else {
+            -search(&mut game, depth.saturating_sub(1).max(1), tt).1
+        };
+        reviews.push(MoveReview { mover, played, best, played_score, best_score });
+    }
+    reviews
+}
+
+pub fn summarize(reviews: &[MoveReview], player: Player) -> ReviewSummary {
+    let mut summary = ReviewSummary::default();
+    for review in reviews.iter().filter(|r| r.mover == player) {
+        summary.moves += 1;
+        summary.total_loss += review.loss() as i64;
+        match review.judgement() {
+            Some(Judgement::Inaccuracy) => summary.inaccuracies += 1,
+            Some(Judgement::Mistake)    => summary.mistakes += 1,
+            Some(Judgement::Blunder)    => summary.blunders += 1,
+            None => {}
+        }
+    }
+    summary
+}
+
+// The reviewed moves for `pgn::write_pgn`: flagged moves get "?!", "?" or "??" and a comment
+// with the loss and the better move, every move gets its score from White's point of view
+pub fn annotate(start: &Game, reviews: &[MoveReview]) -> Vec<PgnMove> {
+    let mut game = start.clone();
+    reviews.iter().map(|review| {
+        let sign = if review.mover == Player::White { 1 } else { -1 };
+        let eval = format!("[%eval {:.2}]", (sign * review.played_score) as f64 / 100.0);
+        let judgement = review.judgement();
+        let comment = match judgement {
+            Some(j) => format!("{} ({} cp), {} was best {}", j, -review.loss(), review.best.to_san(&game.position), eval),
+            None => eval,
+        };
+        game.try_to_make_move(&review.played);
+        PgnMove { m: review.played, suffix: judgement.map(|j| j.suffix()), comment: Some(comment) }
+    }).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    fn review(fen: &str, moves: &[&str], depth: usize) -> Result<(Game, Vec<MoveReview>), FenParseError> {
+        let start = Game::from_fen(fen)?;
+        let mut game = start.clone();
+        let moves: Vec<Move> = moves.iter().map(|&san| {
+            let m = Move::from_san(&game.position, san).unwrap();
+            game.try_to_make_move(&m);
+            m
+        }).collect();
+        let reviews = review_game(&start, &moves, depth, &mut TranspositionTable::new(1));
+        Ok((start, reviews))
+    }
+
+    #[test]
+    fn judgements() {
+        assert_eq!(Judgement::from_loss(0), None);
+        assert_eq!(Judgement::from_loss(INACCURACY_CP - 1), None);
+        assert_eq!(Judgement::from_loss(INACCURACY_CP), Some(Judgement::Inaccuracy));
+        assert_eq!(Judgement::from_loss(MISTAKE_CP), Some(Judgement::Mistake));
+        assert_eq!(Judgement::from_loss(BLUNDER_CP + 500), Some(Judgement::Blunder));
+    }
+
+    #[test]
+    fn hanging_the_queen() -> Result<(), FenParseError> {
+        // Qd4 walks into exd4, Qxd5 wins a pawn
+        let (start, reviews) = review("4k3/8/8/3pp3/8/8/8/3QK3 w - - 0 1", &["Qd4", "exd4"], 3)?;
+        assert_eq!(reviews.len(), 2);
+        assert_eq!(reviews[0].judgement(), Some(Judgement::Blunder));
+        assert_eq!(reviews[0].mover, Player::White);
+        assert_eq!(reviews[1].loss(), 0);
+
+        let annotated = annotate(&start, &reviews);
+        assert_eq!(annotated[0].suffix, Some("??"));
+        assert!(annotated[0].comment.as_ref().unwrap().starts_with("Blunder ("));
+
+        let white = summarize(&reviews, Player::White);
+        assert_eq!((white.moves, white.blunders), (1, 1));
+        assert!(white.average_loss() >= BLUNDER_CP as f64);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_mate_while_winning_is_fine() -> Result<(), FenParseError> {
+        // Ra8 mates, Rb7 still wins easily
+        let (_, reviews) = review("6k1/8/6K1/8/8/8/1R6/R7 w - - 0 1", &["Rb7"], 2)?;
+        assert_eq!(reviews[0].best.to_string(), "a1a8");
+        assert_eq!(reviews[0].best_score, SCORE_CAP);
+        assert_eq!(reviews[0].judgement(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn stops_when_the_game_is_over() -> Result<(), FenParseError> {
+        let (_, reviews) = review("6k1/8/6K1/8/8/8/8/R7 w - - 0 1", &["Ra8#"], 2)?;
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].played_score, SCORE_CAP);
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+use crate::core::{
+    clock::Instant,
+    game::Game,
+    search::{SearchControl, SearchLimits},
+    tt::TranspositionTable,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchCategory {
+    Tactical,
+    Positional,
+    Endgame,
+}
+
+impl BenchCategory {
+    pub fn all_variants() -> [BenchCategory; 3] {
+        [BenchCategory::Tactical, BenchCategory::Positional, BenchCategory::Endgame]
+    }
+}
+
+impl std::fmt::Display for BenchCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BenchCategory::Tactical   => "tactical",
+            BenchCategory::Positional => "positional",
+            BenchCategory::Endgame    => "endgame",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub const BENCH_POSITIONS: &[(BenchCategory, &str)] = &[
+    // Win At Chess and Kiwipete: sharp positions with forcing lines
+    (BenchCategory::Tactical, "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1"),
+    (BenchCategory::Tactical, "8/7p/5k2/5p2/p1p2P2/Pr1pPK2/1P1R3P/8 b - - 0 1"),
+    (BenchCategory::Tactical, "5rk1/1ppb3p/p1pb4/6q1/3P1p1r/2P1R2P/PP1BQ1P1/5RKN w - - 0 1"),
+    (BenchCategory::Tactical, "r1bq2rk/pp3pbp/2p1p1pQ/7P/3P4/2PB1N2/PP3PPR/2KR4 w - - 0 1"),
+    (BenchCategory::Tactical, "5k2/6pp/p1qN4/1p1p4/3P4/2PKP2Q/PP3r2/3R4 b - - 0 1"),
+    (BenchCategory::Tactical, "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"),
+
+    // Quiet openings and middlegames
+    (BenchCategory::Positional, "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+    (BenchCategory::Positional, "r1bqkbnr/pppp1ppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3"),
+    (BenchCategory::Positional, "rnbq1rk1/ppp1ppbp/3p1np1/8/2PPP3/2N2N2/PP2BPPP/R1BQK2R b KQ - 3 6"),
+    (BenchCategory::Positional, "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10"),
+    (BenchCategory::Positional, "r2q1rk1/pp2bppp/2n1pn2/2pp4/3P4/2PBPN2/PP1N1PPP/R2QK2R w KQ - 0 9"),
+
+    // Few pieces, long maneuvering lines
+    (BenchCategory::Endgame, "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"),
+    (BenchCategory::Endgame, "8/8/8/4k3/8/8/4P3/4K3 w - - 0 1"),
+    (BenchCategory::Endgame, "8/k7/3p4/p2P1p2/P2P1P2/8/8/K7 w - - 0 1"),
+    (BenchCategory::Endgame, "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1"),
+    (BenchCategory::Endgame, "8/5pk1/6p1/8/8/6P1/5PK1/3R4 w - - 0 1"),
+];
+
+const BENCH_HASH_MB: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryReport {
+    pub category: BenchCategory,
+    pub positions: usize,
+    pub nodes: u64,
+    pub depth_sum: usize,  // deepest fully searched iteration, summed over positions
+    pub time: Duration,
+}
+
+impl CategoryReport {
+    pub fn average_depth(&self) -> f64 {
+        self.depth_sum as f64 / self.positions.max(1) as f64
+    }
+
+    pub fn nps(&self) -> u64 {
+        (self.nodes as f64 / self.time.as_secs_f64().max(1e-9)) as u64
+    }
+}
+
+// Searches a single position with iterative deepening, silently.
+// Returns (nodes, deepest completed depth)
+fn bench_position(game: &mut Game, limits: SearchLimits, tt: &mut TranspositionTable) -> (u64, usize) {
+    let mut ctrl = SearchControl::new(limits, Arc::new(AtomicBool::new(false)));
+    let mut completed_depth = 0;
+
+    for depth in 1..=limits.depth.unwrap_or(usize::MAX) {
+        let (_, _, _, _, unwind) = game.find_best_move(depth, &mut ctrl, tt);
+        if unwind {
+            break;
+        }
+        completed_depth = depth;
+    }
+
+    (ctrl.nodes, completed_depth)
+}
+
+// `limits` are applied to every position, and have to contain
+// at least one of depth, movetime or nodes for the bench to terminate
+pub fn bench(limits: SearchLimits) -> Vec<CategoryReport> {
+    let mut tt = TranspositionTable::new(BENCH_HASH_MB);
+
+    BenchCategory::all_variants().iter().map(|&category| {
+        let mut report = CategoryReport { category, positions: 0, nodes: 0, depth_sum: 0, time: Duration::ZERO };
+
+        for (_, fen) in BENCH_POSITIONS.iter().filter(|(c, _)| *c == category) {
+            let mut game = Game::from_fen(fen).unwrap();
+            tt.clear();  // every position starts from scratch, so the node counts are reproducible
+            let start = Instant::now();
+            let (nodes, depth) = bench_position(&mut game, limits, &mut tt);
+            report.time += start.elapsed();
+            report.positions += 1;
+            report.nodes += nodes;
+            report.depth_sum += depth;
+        }
+
+        report
+    }).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_valid() {
+        for (_, fen) in BENCH_POSITIONS {
+            assert!(Game::from_fen(fen).is_ok(), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn bench_depth() {
+        let reports = bench(SearchLimits::depth(2));
+        assert_eq!(reports.len(), 3);
+        for report in &reports {
+            let expected = BENCH_POSITIONS.iter().filter(|(c, _)| *c == report.category).count();
+            assert_eq!(report.positions, expected);
+            assert_eq!(report.depth_sum, 2 * expected);
+            assert!(report.nodes > 0);
+        }
+    }
+
+    #[test]
+    fn bench_is_deterministic() {
+        let nodes = |r: Vec<CategoryReport>| r.iter().map(|r| r.nodes).collect::<Vec<_>>();
+        assert_eq!(nodes(bench(SearchLimits::depth(2))), nodes(bench(SearchLimits::depth(2))));
+    }
+}
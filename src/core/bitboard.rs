@@ -1,19 +1,199 @@
-use crate::{core::piece::Piece, utility::bit};
+use std::{fmt, ops::*};
+use crate::{core::{piece::Piece, square::*}, utility::bit};
 
-pub type Bitboard = u64;
+// A set of squares, bit `n` is `Square(n)`. `squares` (or a `for` loop) yields them, lowest first
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
 
-pub trait BitOps {
-    fn set_bit(self, n: u8) -> Self;
-    fn unset_bit(self, n: u8) -> Self;
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(!0);
+
+    pub const fn from_square(sq: Square) -> Self {
+        Bitboard(bit(sq.0))
+    }
+
+    pub const fn set_bit(self, sq: Square) -> Self {
+        Bitboard(self.0 | bit(sq.0))
+    }
+
+    pub const fn unset_bit(self, sq: Square) -> Self {
+        Bitboard(self.0 & !bit(sq.0))
+    }
+
+    pub const fn contains(self, sq: Square) -> bool {
+        self.0 & bit(sq.0) != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn count_ones(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    // The lowest square, `Square(64)` if there is none
+    pub const fn lsb(self) -> Square {
+        Square(self.0.trailing_zeros() as u8)
+    }
+
+    pub fn pop_lsb(&mut self) -> Square {
+        let sq = self.lsb();
+        self.0 &= self.0.wrapping_sub(1);
+        sq
+    }
+
+    // Towards higher squares if `offset` is positive. Squares shifted off the board are lost
+    pub const fn shift(self, offset: i8) -> Self {
+        if offset >= 0 {
+            Bitboard(self.0 << offset)
+        } else {
+            Bitboard(self.0 >> -offset)
+        }
+    }
+
+    // Rank 1 <-> rank 8
+    pub const fn flip_vertical(self) -> Self {
+        Bitboard(self.0.swap_bytes())
+    }
+
+    // File A <-> file H
+    pub const fn flip_horizontal(self) -> Self {
+        Bitboard(self.0.reverse_bits().swap_bytes())
+    }
+
+    pub fn squares(self) -> Squares {
+        Squares(self)
+    }
+}
+
+// The squares of a bitboard, lowest first. A copy of it is used up, not the bitboard itself
+#[derive(Debug, Clone)]
+pub struct Squares(Bitboard);
+
+impl Iterator for Squares {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Square> {
+        if self.0.is_empty() {
+            None
+        } else {
+            Some(self.0.pop_lsb())
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.0.count_ones() as usize;
+        (n, Some(n))
+    }
 }
 
-impl BitOps for Bitboard {
-    fn set_bit(self, n: u8) -> Self {
-        self | (1 << n)
+impl ExactSizeIterator for Squares {}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = Squares;
+
+    fn into_iter(self) -> Squares {
+        Squares(self)
     }
+}
 
-    fn unset_bit(self, n: u8) -> Self {
-        self & !(1 << n)
+impl From<u64> for Bitboard {
+    fn from(bb: u64) -> Self {
+        Bitboard(bb)
+    }
+}
+
+impl From<Bitboard> for u64 {
+    fn from(bb: Bitboard) -> Self {
+        bb.0
+    }
+}
+
+impl PartialEq<u64> for Bitboard {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+// `Bitboard op Bitboard`, `Bitboard op u64` and `u64 op Bitboard`, so that the tables in
+// `constants` can stay plain `u64`s
+macro_rules! bit_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl $op for Bitboard {
+            type Output = Bitboard;
+            fn $fn(self, rhs: Bitboard) -> Bitboard {
+                Bitboard($op::$fn(self.0, rhs.0))
+            }
+        }
+
+        impl $op<u64> for Bitboard {
+            type Output = Bitboard;
+            fn $fn(self, rhs: u64) -> Bitboard {
+                Bitboard($op::$fn(self.0, rhs))
+            }
+        }
+
+        impl $op<Bitboard> for u64 {
+            type Output = Bitboard;
+            fn $fn(self, rhs: Bitboard) -> Bitboard {
+                Bitboard($op::$fn(self, rhs.0))
+            }
+        }
+
+        impl $op_assign for Bitboard {
+            fn $fn_assign(&mut self, rhs: Bitboard) {
+                $op_assign::$fn_assign(&mut self.0, rhs.0);
+            }
+        }
+
+        impl $op_assign<u64> for Bitboard {
+            fn $fn_assign(&mut self, rhs: u64) {
+                $op_assign::$fn_assign(&mut self.0, rhs);
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+bit_op!(BitOr,  bitor,  BitOrAssign,  bitor_assign);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Shl<u8> for Bitboard {
+    type Output = Bitboard;
+    fn shl(self, rhs: u8) -> Bitboard {
+        Bitboard(self.0 << rhs)
+    }
+}
+
+impl Shr<u8> for Bitboard {
+    type Output = Bitboard;
+    fn shr(self, rhs: u8) -> Bitboard {
+        Bitboard(self.0 >> rhs)
+    }
+}
+
+// A board diagram, White at the bottom
+impl fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rank in (0..8).rev() {
+            write!(f, "{} ", rank + 1)?;
+            for file in 0..8 {
+                let c = if self.contains(Square::new(File(file), Rank(rank))) { '+' } else { '.' };
+                write!(f, "{} ", c)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "  a b c d e f g h")
     }
 }
 
@@ -46,7 +226,7 @@ impl BitboardSet {
         }
     }
 
-    fn piece_to_bb(&self, piece: Piece) -> &Bitboard {
+    pub fn piece_to_bb(&self, piece: Piece) -> &Bitboard {
         match piece {
             Piece::Knight => &self.knights,
             Piece::Bishop => &self.bishops,
@@ -57,20 +237,20 @@ impl BitboardSet {
         }
     }
 
-    pub fn unset_bit(&mut self, n: u8) {
-        self.all     = self.all.unset_bit(n);
-        self.pawns   = self.pawns.unset_bit(n);
-        self.knights = self.knights.unset_bit(n);
-        self.bishops = self.bishops.unset_bit(n);
-        self.rooks   = self.rooks.unset_bit(n);
-        self.queens  = self.queens.unset_bit(n);
-        self.king    = self.king.unset_bit(n);
+    pub fn unset_bit(&mut self, sq: Square) {
+        self.all     = self.all.unset_bit(sq);
+        self.pawns   = self.pawns.unset_bit(sq);
+        self.knights = self.knights.unset_bit(sq);
+        self.bishops = self.bishops.unset_bit(sq);
+        self.rooks   = self.rooks.unset_bit(sq);
+        self.queens  = self.queens.unset_bit(sq);
+        self.king    = self.king.unset_bit(sq);
     }
 
-    pub fn set_bit(&mut self, n: u8, piece: Piece) {
-        self.all     = self.all.set_bit(n);
+    pub fn set_bit(&mut self, sq: Square, piece: Piece) {
+        self.all     = self.all.set_bit(sq);
         let bb = self.piece_to_bb_mut(piece);
-        *bb = bb.set_bit(n);
+        *bb = bb.set_bit(sq);
     }
 
     pub fn count(&self, piece: Piece) -> u32 {
@@ -85,22 +265,105 @@ impl BitboardSet {
         result
     }
 
-    pub fn what(&self, sq_idx: u8) -> Option<Piece> {
-        let bb = bit(sq_idx);
-        if self.pawns & bb != 0 {
+    // Applies `f` to every bitboard of the set
+    pub fn map(&self, f: impl Fn(Bitboard) -> Bitboard) -> Self {
+        BitboardSet {
+            all:     f(self.all),
+            pawns:   f(self.pawns),
+            knights: f(self.knights),
+            bishops: f(self.bishops),
+            rooks:   f(self.rooks),
+            queens:  f(self.queens),
+            king:    f(self.king),
+        }
+    }
+
+    pub fn what(&self, sq: Square) -> Option<Piece> {
+        if self.pawns.contains(sq) {
             Some(Piece::Pawn)
-        } else if self.knights & bb != 0 {
+        } else if self.knights.contains(sq) {
             Some(Piece::Knight)
-        } else if self.bishops & bb != 0 {
+        } else if self.bishops.contains(sq) {
             Some(Piece::Bishop)
-        } else if self.rooks & bb != 0 {
+        } else if self.rooks.contains(sq) {
             Some(Piece::Rook)
-        } else if self.queens & bb != 0 {
+        } else if self.queens.contains(sq) {
             Some(Piece::Queen)
-        } else if self.king & bb != 0 {
+        } else if self.king.contains(sq) {
             Some(Piece::King)
         } else {
             None
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::board::*;
+    use crate::utility::sq_to_bb;
+
+    #[test]
+    fn operators() {
+        let a = Bitboard(sq_to_bb(&[A1, E4]));
+        let b = Bitboard(sq_to_bb(&[E4, H8]));
+        assert_eq!(a & b, Bitboard::from_square(E4));
+        assert_eq!(a | b, sq_to_bb(&[A1, E4, H8]));
+        assert_eq!(a ^ b, sq_to_bb(&[A1, H8]));
+        assert_eq!((!a).count_ones(), 62);
+        assert_eq!(a & RANK[4], Bitboard::from_square(E4));
+        assert_eq!(FILE_A & a, Bitboard::from_square(A1));
+    }
+
+    #[test]
+    fn pop_lsb() {
+        let mut bb = Bitboard(0b101010);
+        assert_eq!(bb.pop_lsb(), B1);
+        assert_eq!(bb, 0b101000);
+        assert_eq!(bb.pop_lsb(), D1);
+        assert_eq!(bb.pop_lsb(), F1);
+        assert_eq!(bb, Bitboard::EMPTY);
+    }
+
+    #[test]
+    fn shift() {
+        assert_eq!(Bitboard(0b01100001).shift(1), 0b11000010);
+        assert_eq!(Bitboard(0b01100001).shift(-1), 0b00110000);
+        assert_eq!(Bitboard(0b10000000).shift(-7), 0b00000001);
+    }
+
+    #[test]
+    fn flip() {
+        let bb = Bitboard(sq_to_bb(&[A1, D4]));
+        assert_eq!(bb.flip_vertical(), sq_to_bb(&[A8, D5]));
+        assert_eq!(bb.flip_horizontal(), sq_to_bb(&[H1, E4]));
+        assert_eq!(Bitboard(FILE_A).flip_horizontal(), FILE_H);
+        assert_eq!(Bitboard(RANK[2]).flip_vertical(), RANK[7]);
+    }
+
+    #[test]
+    fn squares() {
+        let bb = Bitboard(sq_to_bb(&[H8, B2, E4]));
+        assert_eq!(bb.squares().collect::<Vec<_>>(), vec![B2, E4, H8]);
+        assert_eq!(bb.squares().len(), 3);
+        assert_eq!(Bitboard::EMPTY.squares().count(), 0);
+        // Iterating goes through a copy, the bitboard is left as it was
+        let mut squares = Vec::new();
+        for sq in bb {
+            squares.push(sq);
+        }
+        assert_eq!(squares, [B2, E4, H8]);
+        assert_eq!(bb.count_ones(), 3);
+        assert!(bb.contains(E4) && !bb.contains(E5));
+    }
+
+    #[test]
+    fn diagram() {
+        let diagram = Bitboard::from_square(A8).set_bit(H1).to_string();
+        let lines: Vec<_> = diagram.lines().collect();
+        assert_eq!(lines[0], "8 + . . . . . . . ");
+        assert_eq!(lines[7], "1 . . . . . . . + ");
+        assert_eq!(lines[8], "  a b c d e f g h");
+    }
+}
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, sync::OnceLock};
+use rand::Rng;
+use crate::constants::book::BOOK_LINES;
+use crate::core::{chess_move::Move, game::Game, movegen::legal_moves, position::Position, zobrist::ZobristHash};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BookMove {
+    pub m: Move,
+    pub weight: u32,  // how many book lines play it
+}
+
+static BOOK: OnceLock<HashMap<ZobristHash, Vec<BookMove>>> = OnceLock::new();
+
+// Replays every book line once, on the first lookup
+fn book() -> &'static HashMap<ZobristHash, Vec<BookMove>> {
+    BOOK.get_or_init(|| {
+        let mut map: HashMap<ZobristHash, Vec<BookMove>> = HashMap::new();
+        for (i, line) in BOOK_LINES.iter().enumerate() {
+            let mut game = Game::default();
+            for uci in line.split_whitespace() {
+                let m = Move::from_uci(&game.position, uci)
+                    .unwrap_or_else(|e| panic!("Illegal move {} in book line {}: {:?}", uci, i, e));
+                let moves = map.entry(game.position.zobrist_hash).or_default();
+                match moves.iter_mut().find(|entry| entry.m == m) {
+                    Some(entry) => entry.weight += 1,
+                    None => moves.push(BookMove { m, weight: 1 }),
+                }
+                game.try_to_make_move(&m);
+            }
+        }
+        map
+    })
+}
+
+// Book moves in this position, in the order the book first plays them
+pub fn book_moves(pos: &Position) -> &'static [BookMove] {
+    book().get(&pos.zobrist_hash).map_or(&[], |moves| moves.as_slice())
+}
+
+// A random book move, more popular moves are picked more often
+pub fn pick_book_move(pos: &Position, rng: &mut impl Rng) -> Option<Move> {
+    let moves = book_moves(pos);
+    let total: u32 = moves.iter().map(|entry| entry.weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = rng.random_range(0..total);
+    let m = moves.iter().find(|entry| {
+        if roll < entry.weight {
+            return true;
+        }
+        roll -= entry.weight;
+        false
+    })?.m;
+
+    // Guards against a hash collision with a position outside the book
+    legal_moves(pos).contains(&m).then_some(m)
+}
+
+pub fn position_count() -> usize {
+    book().len()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_line_is_legal() {
+        // Panics on the first illegal move in the table
+        assert!(position_count() > 2000);
+    }
+
+    #[test]
+    fn start_position() {
+        let game = Game::default();
+        let moves = book_moves(&game.position);
+        let uci: Vec<String> = moves.iter().map(|entry| entry.m.to_string()).collect();
+        assert!(uci.contains(&"e2e4".to_string()) && uci.contains(&"d2d4".to_string()));
+
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let m = pick_book_move(&game.position, &mut rng).unwrap();
+            assert!(moves.iter().any(|entry| entry.m == m));
+        }
+    }
+
+    #[test]
+    fn transpositions_are_merged() {
+        // A Reti line and a Catalan line meet here
+        let mut game = Game::default();
+        for m in ["g1f3", "d7d5", "c2c4", "e7e6", "g2g3", "g8f6", "f1g2", "f8e7", "e1g1", "e8g8", "d2d4", "d5c4"] {
+            assert!(game.try_to_make_uci_move(m));
+        }
+        let catalan = book_moves(&game.position);
+        assert!(catalan.iter().any(|entry| entry.m.to_string() == "d1c2" && entry.weight >= 2));
+    }
+
+    #[test]
+    fn out_of_book() {
+        let game = Game::from_fen("8/8/8/4k3/8/8/8/KR6 w - - 0 1").unwrap();
+        assert!(book_moves(&game.position).is_empty());
+        assert_eq!(pick_book_move(&game.position, &mut rand::rng()), None);
+    }
+}
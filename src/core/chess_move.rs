@@ -1,10 +1,20 @@
-use crate::{constants::board, core::{piece::Piece, player::Player}, utility::square_idx_to_string};
+use crate::{
+    constants::board,
+    core::{
+        movegen::{has_legal_move, is_legal, legal_moves},
+        piece::Piece,
+        player::Player,
+        position::Position,
+        rules::{checks::is_king_in_check, make::make_move},
+        square::{File, Rank, Square},
+    },
+};
 
 // Tightly-packing this does not improve performance
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Move {
-    pub from: u8,
-    pub to: u8,
+    pub from: Square,
+    pub to: Square,
     pub piece: Piece,
     pub capture: bool,
     pub promotion: Option<Piece>,
@@ -17,17 +27,33 @@ pub struct Move {
 impl std::fmt::Display for Move {
     // Long algebraic notation, UCI-compliant
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_null() {
+            return write!(f, "0000");
+        }
         let prom_char = if let Some(promotion_piece) = self.promotion {
             promotion_piece.to_char().to_string()
         } else {
             "".to_string()
         };
-        write!(f, "{}{}{}", square_idx_to_string(self.from), square_idx_to_string(self.to), prom_char)
+        write!(f, "{}{}{}", self.from, self.to, prom_char)
     }
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum UciMoveParseError {
+    InvalidSyntax(String),
+    IllegalMove(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SanParseError {
+    InvalidSyntax(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+}
+
 impl Move {
-    pub fn new(from: u8, to: u8, piece: Piece, capture: bool) -> Self {
+    pub fn new(from: Square, to: Square, piece: Piece, capture: bool) -> Self {
         Move {
             from,
             to,
@@ -41,7 +67,7 @@ impl Move {
         }
     }
 
-    pub fn pawn(from: u8, to: u8, capture: bool, promotion: Option<Piece>, en_passant: bool) -> Self {
+    pub fn pawn(from: Square, to: Square, capture: bool, promotion: Option<Piece>, en_passant: bool) -> Self {
         Move {
             from,
             to,
@@ -49,7 +75,7 @@ impl Move {
             capture,
             promotion,
             en_passant,
-            double_push: to.wrapping_sub(from) == 16 || from.wrapping_sub(to) == 16,
+            double_push: from.0.abs_diff(to.0) == 16,
             kingside_castling: false,
             queenside_castling: false,
         }
@@ -80,6 +106,213 @@ impl Move {
     pub fn is_castling(&self) -> bool {
         self.kingside_castling | self.queenside_castling
     }
+
+    // Passing the turn, see `make_null_move`. Never generated, never legal
+    pub fn null() -> Self {
+        Move::new(board::A1, board::A1, Piece::King, false)
+    }
+
+    pub fn is_null(&self) -> bool {
+        self.from == self.to
+    }
+
+    // Every field in 24 bits, for the transposition table and the undo data
+    pub fn pack(&self) -> u32 {
+        self.from.0 as u32 |
+        (self.to.0 as u32) << 6 |
+        (self.piece.index() as u32) << 12 |
+        (self.capture as u32) << 15 |
+        (self.promotion.map_or(0, |p| p.index() as u32 + 1)) << 16 |
+        (self.en_passant as u32) << 19 |
+        (self.double_push as u32) << 20 |
+        (self.kingside_castling as u32) << 21 |
+        (self.queenside_castling as u32) << 22
+    }
+
+    pub fn unpack(packed: u32) -> Move {
+        Move::try_unpack(packed).expect("a packed move")
+    }
+
+    // None if the piece or the promotion bits are out of range, for bits read from outside
+    pub fn try_unpack(packed: u32) -> Option<Move> {
+        let bits = |at: u32, width: u32| (packed >> at) & ((1 << width) - 1);
+        let piece = |index: u32| Piece::all_variants().get(index as usize).copied();
+        let promotion = match bits(16, 3) {
+            0 => None,
+            p => Some(piece(p - 1)?),
+        };
+        Some(Move {
+            from: Square(bits(0, 6) as u8),
+            to: Square(bits(6, 6) as u8),
+            piece: piece(bits(12, 3))?,
+            capture: bits(15, 1) == 1,
+            promotion,
+            en_passant: bits(19, 1) == 1,
+            double_push: bits(20, 1) == 1,
+            kingside_castling: bits(21, 1) == 1,
+            queenside_castling: bits(22, 1) == 1,
+        })
+    }
+
+    // Long algebraic notation ("e2e4", "e7e8q", "e1g1"). The flags are inferred from the position
+    pub fn from_uci(pos: &Position, uci: &str) -> Result<Move, UciMoveParseError> {
+        let invalid = || UciMoveParseError::InvalidSyntax(uci.to_string());
+        let illegal = || UciMoveParseError::IllegalMove(uci.to_string());
+
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(invalid());
+        }
+        let from = Square::parse(&uci[0..2]).ok_or_else(invalid)?;
+        let to = Square::parse(&uci[2..4]).ok_or_else(invalid)?;
+        let promotion = match uci[4..].chars().next() {
+            Some(c @ ('n' | 'b' | 'r' | 'q')) => Piece::from_char(c),
+            Some(_) => return Err(invalid()),
+            None => None,
+        };
+
+        let (player, piece) = pos.what(from).ok_or_else(illegal)?;
+        let capture = pos.what(to).is_some();
+
+        let m = match piece {
+            Piece::King if from.0.abs_diff(to.0) == 2 => {
+                let side = if to > from { CastlingSide::KingSide } else { CastlingSide::QueenSide };
+                Move::castling(player, side)
+            }
+            Piece::Pawn => {
+                let en_passant = pos.en_passant_square == Some(to) && from.file() != to.file();
+                Move::pawn(from, to, capture || en_passant, promotion, en_passant)
+            }
+            _ => Move { promotion, ..Move::new(from, to, piece, capture) },
+        };
+
+        if !is_legal(pos, &m) {
+            return Err(illegal());
+        }
+        Ok(m)
+    }
+
+    // Standard Algebraic Notation: "Nbd7", "exd6 e.p.", "O-O-O", "e8=Q+".
+    // Check/mate markers and annotations are accepted, but not verified
+    pub fn from_san(pos: &Position, san: &str) -> Result<Move, SanParseError> {
+        let invalid = || SanParseError::InvalidSyntax(san.to_string());
+
+        let s = san.trim();
+        let s = s.strip_suffix("e.p.").unwrap_or(s).trim_end();
+        let s = s.trim_end_matches(['+', '#', '!', '?']);
+
+        let legal = legal_moves(pos);
+        let candidates: Vec<Move> = match s {
+            "O-O" | "0-0" => legal.into_iter().filter(|m| m.kingside_castling).collect(),
+            "O-O-O" | "0-0-0" => legal.into_iter().filter(|m| m.queenside_castling).collect(),
+            _ => {
+                let (piece, rest) = match s.chars().next() {
+                    Some('N') => (Piece::Knight, &s[1..]),
+                    Some('B') => (Piece::Bishop, &s[1..]),
+                    Some('R') => (Piece::Rook,   &s[1..]),
+                    Some('Q') => (Piece::Queen,  &s[1..]),
+                    Some('K') => (Piece::King,   &s[1..]),
+                    Some(_)   => (Piece::Pawn,   s),
+                    None      => return Err(invalid()),
+                };
+
+                // "e8=Q" and "e8Q"
+                let (rest, promotion) = match rest.char_indices().last() {
+                    Some((i, c)) if piece == Piece::Pawn && "NBRQ".contains(c) => {
+                        let promotion = Piece::from_char(c.to_ascii_lowercase());
+                        (rest[..i].trim_end_matches('='), promotion)
+                    }
+                    _ => (rest, None),
+                };
+
+                if rest.len() < 2 || !rest.is_ascii() {
+                    return Err(invalid());
+                }
+                let (disambiguation, to) = rest.split_at(rest.len() - 2);
+                let to = Square::parse(to).ok_or_else(invalid)?;
+
+                // Whatever is left can be a source file, rank or square, and a capture marker
+                let mut from_file = None;
+                let mut from_rank = None;
+                for c in disambiguation.chars() {
+                    match c {
+                        'a'..='h' => from_file = File::from_char(c),
+                        '1'..='8' => from_rank = Rank::from_char(c),
+                        'x' | ':' => {}
+                        _ => return Err(invalid()),
+                    }
+                }
+
+                legal.into_iter().filter(|m| {
+                    m.piece == piece &&
+                    m.to == to &&
+                    m.promotion == promotion &&
+                    !m.is_castling() &&
+                    from_file.is_none_or(|f| f == m.from.file()) &&
+                    from_rank.is_none_or(|r| r == m.from.rank())
+                }).collect()
+            }
+        };
+
+        match candidates.len() {
+            0 => Err(SanParseError::IllegalMove(san.to_string())),
+            1 => Ok(candidates[0]),
+            _ => Err(SanParseError::AmbiguousMove(san.to_string())),
+        }
+    }
+
+    // Standard Algebraic Notation, as `from_san` reads it and PGN wants it: "Nbd7", "exd6",
+    // "e8=Q+", "O-O#". The move has to be legal in `pos`
+    pub fn to_san(&self, pos: &Position) -> String {
+        // What PGN readers take for a null move
+        if self.is_null() {
+            return "--".to_string();
+        }
+        let mut san = if self.kingside_castling {
+            "O-O".to_string()
+        } else if self.queenside_castling {
+            "O-O-O".to_string()
+        } else {
+            let mut san = String::new();
+            let (file, rank) = (self.from.file(), self.from.rank());
+            if self.piece == Piece::Pawn {
+                if self.capture {
+                    san.push(file.to_char());
+                }
+            } else {
+                san.push(self.piece.to_char().to_ascii_uppercase());
+
+                // The same kind of piece that can also go there
+                let rivals: Vec<Move> = legal_moves(pos).into_iter()
+                    .filter(|m| m.piece == self.piece && m.to == self.to && m.from != self.from)
+                    .collect();
+                if !rivals.is_empty() {
+                    if rivals.iter().all(|m| m.from.file() != file) {
+                        san.push(file.to_char());
+                    } else if rivals.iter().all(|m| m.from.rank() != rank) {
+                        san.push(rank.to_char());
+                    } else {
+                        san.push_str(&self.from.to_string());
+                    }
+                }
+            }
+            if self.capture {
+                san.push('x');
+            }
+            san.push_str(&self.to.to_string());
+            if let Some(promotion) = self.promotion {
+                san.push('=');
+                san.push(promotion.to_char().to_ascii_uppercase());
+            }
+            san
+        };
+
+        let mut after = *pos;
+        make_move(&mut after, self);
+        if is_king_in_check(&after, after.player_to_move) {
+            san.push(if has_legal_move(&after) { '+' } else { '#' });
+        }
+        san
+    }
 }
 
 
@@ -166,6 +399,15 @@ impl CastlingRights {
         ((self.black_queenside as u8) << 3)
     }
 
+    pub fn decode(bits: u8) -> Self {
+        CastlingRights {
+            white_kingside:  bits & 1 != 0,
+            white_queenside: bits & (1 << 1) != 0,
+            black_kingside:  bits & (1 << 2) != 0,
+            black_queenside: bits & (1 << 3) != 0,
+        }
+    }
+
     pub fn reset(&mut self, player: Player) {
         match player {
             Player::White => {
@@ -188,6 +430,20 @@ impl CastlingRights {
         }
     }
 
+    // Rights of the other color, used when mirroring a position
+    pub fn swapped(&self) -> Self {
+        CastlingRights {
+            white_kingside: self.black_kingside,
+            white_queenside: self.black_queenside,
+            black_kingside: self.white_kingside,
+            black_queenside: self.white_queenside,
+        }
+    }
+
+    pub fn none() -> Self {
+        CastlingRights::from_string("-")
+    }
+
     pub fn any(&self, player: Player) -> bool {
         match player {
             Player::White => self.white_kingside | self.white_queenside,
@@ -195,3 +451,156 @@ impl CastlingRights {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn san(fen: &str, san: &str) -> Result<Move, SanParseError> {
+        let (pos, _) = Position::from_fen(fen).unwrap();
+        Move::from_san(&pos, san)
+    }
+
+    fn uci(fen: &str, uci: &str) -> Result<Move, UciMoveParseError> {
+        let (pos, _) = Position::from_fen(fen).unwrap();
+        Move::from_uci(&pos, uci)
+    }
+
+    #[test]
+    fn uci_flags() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let double = uci(start, "e2e4").unwrap();
+        assert!(double.double_push);
+        assert_eq!(double, Move::pawn(board::E2, board::E4, false, None, false));
+        assert_eq!(uci(start, "g1f3"), Ok(Move::new(board::G1, board::F3, Piece::Knight, false)));
+
+        let ep = "8/8/5k2/1KPp4/8/8/8/8 w - d6 0 1";
+        assert_eq!(uci(ep, "c5d6"), Ok(Move::pawn(board::C5, board::D6, true, None, true)));
+        assert_eq!(uci(ep, "c5c6"), Ok(Move::pawn(board::C5, board::C6, false, None, false)));
+
+        let castling = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1";
+        assert_eq!(uci(castling, "e8g8"), Ok(Move::castling(Player::Black, CastlingSide::KingSide)));
+        assert_eq!(uci(castling, "e8c8"), Ok(Move::castling(Player::Black, CastlingSide::QueenSide)));
+        assert_eq!(uci(castling, "a8a1"), Ok(Move::new(board::A8, board::A1, Piece::Rook, true)));
+
+        let promo = "3r4/4P3/8/8/8/2k5/8/4K3 w - - 0 1";
+        assert_eq!(uci(promo, "e7d8n"), Ok(Move::pawn(board::E7, board::D8, true, Some(Piece::Knight), false)));
+        assert_eq!(uci(promo, "e7e8q"), Ok(Move::pawn(board::E7, board::E8, false, Some(Piece::Queen), false)));
+    }
+
+    #[test]
+    fn pack() {
+        for fen in [
+            "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1",
+            "8/8/5k2/1KPp4/8/8/8/8 w - d6 0 1",
+            "3r4/4P3/8/8/8/2k5/8/4K3 w - - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        ] {
+            let (pos, _) = Position::from_fen(fen).unwrap();
+            for m in crate::core::movegen::pseudo_moves(&pos) {
+                assert_eq!(Move::unpack(m.pack()), m, "{}", fen);
+            }
+        }
+        assert!(Move::unpack(Move::null().pack()).is_null());
+        for bits in 0..16 {
+            assert_eq!(CastlingRights::decode(bits).encode(), bits);
+        }
+    }
+
+    #[test]
+    fn uci_errors() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        for garbage in ["", "e2", "e2e4qq", "e2e9", "E2E4", "e2e4k", "zzzz", "é2e4"] {
+            assert_eq!(uci(start, garbage), Err(UciMoveParseError::InvalidSyntax(garbage.to_string())));
+        }
+        for illegal in ["e2e5", "e7e5", "e3e4", "e1g1", "g1e2", "e2e4q"] {
+            assert_eq!(uci(start, illegal), Err(UciMoveParseError::IllegalMove(illegal.to_string())));
+        }
+        let promo = "3r4/4P3/8/8/8/2k5/8/4K3 w - - 0 1";
+        assert_eq!(uci(promo, "e7e8"), Err(UciMoveParseError::IllegalMove("e7e8".to_string())));
+    }
+
+    #[test]
+    fn san_simple() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(san(start, "e4"), Ok(Move::pawn(board::E2, board::E4, false, None, false)));
+        assert_eq!(san(start, "Nf3"), Ok(Move::new(board::G1, board::F3, Piece::Knight, false)));
+        assert_eq!(san(start, "Nf3!?"), Ok(Move::new(board::G1, board::F3, Piece::Knight, false)));
+        assert_eq!(san(start, "e5"), Err(SanParseError::IllegalMove("e5".to_string())));
+        assert_eq!(san(start, "Ke2"), Err(SanParseError::IllegalMove("Ke2".to_string())));
+        assert_eq!(san(start, "Zz9"), Err(SanParseError::InvalidSyntax("Zz9".to_string())));
+        assert_eq!(san(start, ""), Err(SanParseError::InvalidSyntax("".to_string())));
+    }
+
+    #[test]
+    fn san_disambiguation() {
+        let fen = "rnbqkb1r/ppp2ppp/3p1n2/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 3";
+        assert_eq!(san(fen, "Nd7"), Err(SanParseError::AmbiguousMove("Nd7".to_string())));
+        assert_eq!(san(fen, "Nbd7"), Ok(Move::new(board::B8, board::D7, Piece::Knight, false)));
+        assert_eq!(san(fen, "Nfd7"), Ok(Move::new(board::F6, board::D7, Piece::Knight, false)));
+
+        let rooks = "4k3/8/8/R7/8/8/8/R3K3 w Q - 0 1";
+        assert_eq!(san(rooks, "Ra3"), Err(SanParseError::AmbiguousMove("Ra3".to_string())));
+        assert_eq!(san(rooks, "R1a3"), Ok(Move::new(board::A1, board::A3, Piece::Rook, false)));
+        assert_eq!(san(rooks, "R5a3"), Ok(Move::new(board::A5, board::A3, Piece::Rook, false)));
+
+        // Neither file nor rank alone is enough
+        let queens = "8/7k/8/7K/8/Q7/8/Q1Q5 w - - 0 1";
+        assert_eq!(san(queens, "Qab2"), Err(SanParseError::AmbiguousMove("Qab2".to_string())));
+        assert_eq!(san(queens, "Q1b2"), Err(SanParseError::AmbiguousMove("Q1b2".to_string())));
+        assert_eq!(san(queens, "Qa1b2"), Ok(Move::new(board::A1, board::B2, Piece::Queen, false)));
+        assert_eq!(san(queens, "Qa1xb2"), Ok(Move::new(board::A1, board::B2, Piece::Queen, false)));
+    }
+
+    #[test]
+    fn san_pawns() {
+        let ep = "8/8/5k2/1KPp4/8/8/8/8 w - d6 0 1";
+        assert_eq!(san(ep, "cxd6 e.p."), Ok(Move::pawn(board::C5, board::D6, true, None, true)));
+        assert_eq!(san(ep, "cxd6"), Ok(Move::pawn(board::C5, board::D6, true, None, true)));
+        assert_eq!(san(ep, "cd6"), Ok(Move::pawn(board::C5, board::D6, true, None, true)));
+
+        let promo = "3r4/4P3/8/8/8/2k5/8/4K3 w - - 0 1";
+        assert_eq!(san(promo, "e8=Q+"), Ok(Move::pawn(board::E7, board::E8, false, Some(Piece::Queen), false)));
+        assert_eq!(san(promo, "e8N"), Ok(Move::pawn(board::E7, board::E8, false, Some(Piece::Knight), false)));
+        assert_eq!(san(promo, "exd8=R"), Ok(Move::pawn(board::E7, board::D8, true, Some(Piece::Rook), false)));
+        assert_eq!(san(promo, "e8"), Err(SanParseError::IllegalMove("e8".to_string())));
+    }
+
+    #[test]
+    fn san_castling() {
+        let fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1";
+        assert_eq!(san(fen, "O-O-O"), Ok(Move::castling(Player::Black, CastlingSide::QueenSide)));
+        assert_eq!(san(fen, "0-0"), Ok(Move::castling(Player::Black, CastlingSide::KingSide)));
+        assert_eq!(san(fen, "O-O#"), Ok(Move::castling(Player::Black, CastlingSide::KingSide)));
+        assert_eq!(san("r3k2r/8/8/8/8/8/8/R3K2R b KQ - 0 1", "O-O"), Err(SanParseError::IllegalMove("O-O".to_string())));
+    }
+
+    #[test]
+    fn to_san() {
+        // Every legal move is written unambiguously and read back as itself
+        for fen in [
+            "rnbqkb1r/ppp2ppp/3p1n2/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 3",
+            "8/7k/8/7K/8/Q7/8/Q1Q5 w - - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "3r4/4P3/8/8/8/2k5/8/4K3 w - - 0 1",
+            "8/8/5k2/1KPp4/8/8/8/8 w - d6 0 1",
+        ] {
+            let (pos, _) = Position::from_fen(fen).unwrap();
+            for m in legal_moves(&pos) {
+                assert_eq!(Move::from_san(&pos, &m.to_san(&pos)), Ok(m), "{} in {}", m, fen);
+            }
+        }
+
+        let (pos, _) = Position::from_fen("rnbqkb1r/ppp2ppp/3p1n2/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 3").unwrap();
+        assert_eq!(Move::new(board::B8, board::D7, Piece::Knight, false).to_san(&pos), "Nbd7");
+        let (pos, _) = Position::from_fen("8/7k/8/7K/8/Q7/8/Q1Q5 w - - 0 1").unwrap();
+        assert_eq!(Move::new(board::A1, board::B2, Piece::Queen, false).to_san(&pos), "Qa1b2");
+        let (pos, _) = Position::from_fen("3r4/4P3/8/8/8/2k5/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(Move::pawn(board::E7, board::D8, true, Some(Piece::Queen), false).to_san(&pos), "exd8=Q");
+        let (pos, _) = Position::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        assert_eq!(Move::new(board::A1, board::A8, Piece::Rook, false).to_san(&pos), "Ra8#");
+        let (pos, _) = Position::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+        assert_eq!(Move::castling(Player::Black, CastlingSide::QueenSide).to_san(&pos), "O-O-O");
+    }
+}
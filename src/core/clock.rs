@@ -0,0 +1,5 @@
+// `std::time::Instant::now` panics on wasm32-unknown-unknown, the browser's clock stands in for it there
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub use web_time::Instant;
@@ -1,13 +1,244 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 use crate::core::{
     position::Position,
     piece::Piece,
+    player::Player,
 };
+use cache::EvalCache;
+use pawns::{evaluate_pawns, PawnEntry, PawnTable};
+use tune::TEMPO;
 
+pub mod cache;
+pub mod pawns;
+pub mod imbalance;
+pub mod rooks;
+pub mod tropism;
+pub mod outposts;
+pub mod mopup;
+pub mod scaling;
+pub mod symmetry;
+pub mod trace;
+pub mod wdl;
+pub mod tune;
+
+// A (midgame, endgame) pair, blended by `taper` according to the game phase
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub mg: i32,
+    pub eg: i32,
+}
+
+pub const fn s(mg: i32, eg: i32) -> Score {
+    Score { mg, eg }
+}
+
+// Phase weights of the non-pawn pieces, the starting position is `MAX_PHASE`
+const KNIGHT_PHASE: i32 = 1;
+const BISHOP_PHASE: i32 = 1;
+const ROOK_PHASE:   i32 = 2;
+const QUEEN_PHASE:  i32 = 4;
+pub const MAX_PHASE: i32 = 24;
+
+impl Score {
+    pub const ZERO: Score = s(0, 0);
+
+    // `phase` goes from `MAX_PHASE` (all the pieces are on the board) down to 0 (pawn endgame)
+    pub fn taper(self, phase: i32) -> i32 {
+        let phase = phase.min(MAX_PHASE);
+        (self.mg * phase + self.eg * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl Add for Score {
+    type Output = Score;
+    fn add(self, rhs: Score) -> Score {
+        s(self.mg + rhs.mg, self.eg + rhs.eg)
+    }
+}
+
+impl Sub for Score {
+    type Output = Score;
+    fn sub(self, rhs: Score) -> Score {
+        s(self.mg - rhs.mg, self.eg - rhs.eg)
+    }
+}
+
+impl Neg for Score {
+    type Output = Score;
+    fn neg(self) -> Score {
+        s(-self.mg, -self.eg)
+    }
+}
+
+impl Mul<i32> for Score {
+    type Output = Score;
+    fn mul(self, rhs: i32) -> Score {
+        s(self.mg * rhs, self.eg * rhs)
+    }
+}
+
+impl AddAssign for Score {
+    fn add_assign(&mut self, rhs: Score) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Score {
+    fn sub_assign(&mut self, rhs: Score) {
+        *self = *self - rhs;
+    }
+}
+
+// What a piece adds to the game phase, summed up in `Material::phase`
+pub const fn phase_weight(piece: Piece) -> i32 {
+    match piece {
+        Piece::Knight             => KNIGHT_PHASE,
+        Piece::Bishop             => BISHOP_PHASE,
+        Piece::Rook               => ROOK_PHASE,
+        Piece::Queen              => QUEEN_PHASE,
+        Piece::Pawn | Piece::King => 0,
+    }
+}
+
+pub fn game_phase(pos: &Position) -> i32 {
+    pos.material.phase.min(MAX_PHASE)  // promotions can push it over
+}
+
+// Caches that survive between evaluations, owned by whoever drives the search
+#[derive(Debug, Default, Clone)]
+pub struct EvalTables {
+    pub pawns: PawnTable,
+    pub evals: EvalCache,
+}
+
+impl EvalTables {
+    pub fn clear(&mut self) {
+        self.pawns.clear();
+        self.evals.clear();
+    }
+}
+
+// From the point of view of the side to move
 pub fn evaluate(pos: &Position) -> i32 {
-    let mut score = 0;
-    for piece in Piece::all_variants() {
-        score += piece.value() * pos.w.count(piece) as i32;
-        score -= piece.value() * pos.b.count(piece) as i32;
+    evaluate_with_pawns(pos, &evaluate_pawns(pos))
+}
+
+// Same as `evaluate`, but looks the position, then its pawn structure up in `tables` first
+pub fn evaluate_cached(pos: &Position, tables: &mut EvalTables) -> i32 {
+    if let Some(eval) = tables.evals.get(pos.zobrist_hash) {
+        return eval;
+    }
+    let pawns = tables.pawns.probe(pos);
+    let eval = evaluate_with_pawns(pos, &pawns);
+    tables.evals.store(pos.zobrist_hash, eval);
+    eval
+}
+
+fn evaluate_with_pawns(pos: &Position, pawns: &PawnEntry) -> i32 {
+    let score = pawns.score +
+                imbalance::imbalance(pos) +
+                rooks::rook_placement(pos) +
+                tropism::king_tropism(pos) +
+                outposts::knight_outposts(pos);
+    let material = pos.material.score;
+    let white = material + score.taper(game_phase(pos)) + mopup::mop_up(pos, material);
+    let white = white * scaling::scale_factor(pos, white) / scaling::SCALE_NORMAL;
+    let relative = match pos.player_to_move {
+        Player::White => white,
+        Player::Black => -white,
+    };
+    relative + TEMPO.get()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::board::{A8, B7};
+    use crate::core::{chess_move::Move, position::FenParseError, rules::{make::make_move, unmake::unmake_move}};
+
+    #[test]
+    fn taper() {
+        let score = s(100, -20);
+        assert_eq!(score.taper(MAX_PHASE), 100);
+        assert_eq!(score.taper(0), -20);
+        assert_eq!(score.taper(MAX_PHASE / 2), 40);
+        assert_eq!(score.taper(MAX_PHASE + 5), 100);
+    }
+
+    #[test]
+    fn phase() -> Result<(), FenParseError> {
+        assert_eq!(game_phase(&Position::start()), MAX_PHASE);
+        let (rooks, _) = Position::from_fen("r3k3/pppp4/8/8/8/8/PPPP4/R3K3 w - - 0 1")?;
+        assert_eq!(game_phase(&rooks), 2 * ROOK_PHASE);
+        let (pawns, _) = Position::from_fen("4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1")?;
+        assert_eq!(game_phase(&pawns), 0);
+
+        // Promoting to a queen adds to it, capturing a rook takes from it
+        let (mut pos, _) = Position::from_fen("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1")?;
+        assert_eq!(game_phase(&pos), ROOK_PHASE);
+        let promotion = Move::pawn(B7, A8, true, Some(Piece::Queen), false);
+        let undo = make_move(&mut pos, &promotion);
+        assert_eq!(game_phase(&pos), QUEEN_PHASE);
+        unmake_move(&mut pos, undo);
+        assert_eq!(game_phase(&pos), ROOK_PHASE);
+        Ok(())
+    }
+
+    #[test]
+    fn cached_eval_matches() {
+        let mut tables = EvalTables::default();
+        let mut game = crate::core::game::Game::default();
+        for m in ["e2e4", "d7d5", "e4d5", "d8d5", "b1c3", "d5a5"] {
+            assert!(game.try_to_make_uci_move(m));
+            for _ in 0..2 {  // miss, then hit
+                assert_eq!(evaluate_cached(&game.position, &mut tables), evaluate(&game.position));
+            }
+        }
+    }
+
+    const SYMMETRY_FENS: [&str; 5] = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "4k3/pp4pp/2p5/8/8/1P3P2/P1P3P1/4K3 b - - 0 1",
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    ];
+
+    #[test]
+    fn side_to_move() -> Result<(), FenParseError> {
+        // Symmetric position: the side to move is ahead by exactly the tempo
+        let (white, _) = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")?;
+        let (black, _) = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1")?;
+        assert_eq!(evaluate(&white), TEMPO.get());
+        assert_eq!(evaluate(&black), TEMPO.get());
+
+        // An extra queen is good for whoever has it, whoever is to move
+        let (w_up, _) = Position::from_fen("3qk3/8/8/8/8/8/8/3QKQ2 w - - 0 1")?;
+        let (b_to_move, _) = Position::from_fen("3qk3/8/8/8/8/8/8/3QKQ2 b - - 0 1")?;
+        assert!(evaluate(&w_up) > 0);
+        assert!(evaluate(&b_to_move) < 0);
+        assert_eq!(evaluate(&w_up) - TEMPO.get(), -(evaluate(&b_to_move) - TEMPO.get()));
+        Ok(())
+    }
+
+    // Swapping the colors (with the side to move) changes nothing for the side to move
+    #[test]
+    fn color_symmetry() -> Result<(), FenParseError> {
+        for fen in SYMMETRY_FENS {
+            let (pos, _) = Position::from_fen(fen)?;
+            assert_eq!(evaluate(&pos.mirror()), evaluate(&pos), "{}", fen);
+        }
+        Ok(())
+    }
+
+    // None of the terms care about the queenside and the kingside
+    #[test]
+    fn horizontal_symmetry() -> Result<(), FenParseError> {
+        for fen in SYMMETRY_FENS {
+            let (pos, _) = Position::from_fen(fen)?;
+            assert_eq!(evaluate(&pos.flip_horizontal()), evaluate(&pos), "{}", fen);
+        }
+        Ok(())
     }
-    score
 }
@@ -0,0 +1,57 @@
+use crate::core::zobrist::ZobristHash;
+
+const EVAL_CACHE_SIZE: usize = 1 << 16;  // entries, a power of two
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Entry {
+    key: ZobristHash,
+    eval: i32,
+}
+
+// Position hash -> static evaluation. Always-replace, a collision just costs a re-evaluation
+#[derive(Debug, Default, Clone)]
+pub struct EvalCache {
+    entries: Vec<Entry>,  // allocated on the first store
+}
+
+impl EvalCache {
+    pub fn get(&self, key: ZobristHash) -> Option<i32> {
+        let entry = self.entries.get(key as usize & (EVAL_CACHE_SIZE - 1))?;
+        (entry.key == key).then_some(entry.eval)
+    }
+
+    pub fn store(&mut self, key: ZobristHash, eval: i32) {
+        if self.entries.is_empty() {
+            self.entries = vec![Entry::default(); EVAL_CACHE_SIZE];
+        }
+        self.entries[key as usize & (EVAL_CACHE_SIZE - 1)] = Entry { key, eval };
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(Entry::default());
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_and_get() {
+        let mut cache = EvalCache::default();
+        assert_eq!(cache.get(42), None);
+        cache.store(42, -150);
+        assert_eq!(cache.get(42), Some(-150));
+
+        // Same slot, different key
+        let other = 42 + EVAL_CACHE_SIZE as u64;
+        assert_eq!(cache.get(other), None);
+        cache.store(other, 7);
+        assert_eq!(cache.get(other), Some(7));
+        assert_eq!(cache.get(42), None);
+
+        cache.clear();
+        assert_eq!(cache.get(other), None);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::core::{bitboard::BitboardSet, piece::Piece, position::Position};
+use super::{s, Score};
+
+const BISHOP_PAIR: Score = s(25, 45);
+
+// Knights gain value with every own pawn above `PAWN_BASELINE` (Kaufman)
+const PAWN_BASELINE: i32 = 5;
+const KNIGHT_PER_PAWN: Score = s(6, 6);
+
+// Per pair of pawns blocking each other: closed centers favor knights over bishops
+const KNIGHT_PER_LOCKED_PAWN: Score = s(3, 1);
+const BISHOP_PER_LOCKED_PAWN: Score = s(-3, -1);
+
+// From White's point of view
+pub fn imbalance(pos: &Position) -> Score {
+    let [white, black] = imbalance_by_side(pos);
+    white - black
+}
+
+// Indexed by `Player::index()`
+pub(super) fn imbalance_by_side(pos: &Position) -> [Score; 2] {
+    // A white pawn with a black pawn right in front of it
+    let locked = (pos.w.pawns.shift(8) & pos.b.pawns).count_ones() as i32;
+    [side_imbalance(&pos.w, locked), side_imbalance(&pos.b, locked)]
+}
+
+fn side_imbalance(set: &BitboardSet, locked: i32) -> Score {
+    let knights = set.count(Piece::Knight) as i32;
+    let bishops = set.count(Piece::Bishop) as i32;
+    let pawns = set.count(Piece::Pawn) as i32;
+
+    let mut score = Score::ZERO;
+    if bishops >= 2 {
+        score += BISHOP_PAIR;
+    }
+    score += KNIGHT_PER_PAWN * (knights * (pawns - PAWN_BASELINE));
+    score += KNIGHT_PER_LOCKED_PAWN * (knights * locked);
+    score += BISHOP_PER_LOCKED_PAWN * (bishops * locked);
+    score
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    fn imbalance_of(fen: &str) -> Result<Score, FenParseError> {
+        let (pos, _) = Position::from_fen(fen)?;
+        Ok(imbalance(&pos))
+    }
+
+    #[test]
+    fn bishop_pair() -> Result<(), FenParseError> {
+        assert_eq!(imbalance_of("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1")?, BISHOP_PAIR);
+        assert_eq!(imbalance_of("2b1kb2/8/8/8/8/8/8/2B1KB2 w - - 0 1")?, Score::ZERO);
+        assert_eq!(imbalance_of("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1")?, Score::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn knights_like_pawns() -> Result<(), FenParseError> {
+        // Seven pawns each, knight against bishop, nothing locked
+        let score = imbalance_of("4kb2/pppppppp/8/8/8/8/PPPPPPP1/4KN2 w - - 0 1")?;
+        assert_eq!(score, KNIGHT_PER_PAWN * 2);
+        Ok(())
+    }
+
+    #[test]
+    fn closed_center_favors_knights() -> Result<(), FenParseError> {
+        let open = imbalance_of("4kb2/8/8/3p4/8/3P4/8/4KN2 w - - 0 1")?;
+        let closed = imbalance_of("4kb2/8/8/3p4/3P4/8/8/4KN2 w - - 0 1")?;
+        assert_eq!(closed - open, KNIGHT_PER_LOCKED_PAWN - BISHOP_PER_LOCKED_PAWN);
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use crate::constants::{
+    board::{A1, A8, H1, H8, LIGHT_SQUARES},
+    distance::{CENTER_DISTANCE, DISTANCE, MANHATTAN_DISTANCE},
+};
+use crate::core::{bitboard::{Bitboard, BitboardSet}, piece::Piece, position::Position};
+
+// Enough to mate with (a rook), or to win the defender's last piece (KQ vs KR)
+const WINNING_EDGE: i32 = 400;
+
+const PUSH_TO_EDGE: i32 = 10;  // per step of the losing king away from the centre
+const KINGS_CLOSER: i32 = 4;   // per step the kings are closer than the full board apart
+const BISHOP_CORNER: i32 = 20;  // KBN vs K, per step of the losing king towards a corner of the bishop's color
+
+// Without pawns on the board and with a decisive material edge, the static eval alone
+// gives no idea of how to make progress. Drive the losing king to the edge, and bring
+// the winning one next to it. From White's point of view, in centipawns
+pub fn mop_up(pos: &Position, material: i32) -> i32 {
+    if pos.w.pawns | pos.b.pawns != 0 || material.abs() < WINNING_EDGE {
+        return 0;
+    }
+
+    let (strong, weak) = if material > 0 { (&pos.w, &pos.b) } else { (&pos.b, &pos.w) };
+    let weak_king = weak.king.lsb().index();
+    let kings = MANHATTAN_DISTANCE[strong.king.lsb().index()][weak_king] as i32;
+
+    let edge = if is_kbn_vs_k(strong, weak) {
+        BISHOP_CORNER * (7 - bishop_corner_distance(strong.bishops, weak_king))
+    } else {
+        PUSH_TO_EDGE * CENTER_DISTANCE[weak_king] as i32
+    };
+    let bonus = edge + KINGS_CLOSER * (14 - kings);
+
+    if material > 0 { bonus } else { -bonus }
+}
+
+fn is_kbn_vs_k(strong: &BitboardSet, weak: &BitboardSet) -> bool {
+    weak.count_all() == 1 &&
+    strong.count_all() == 3 &&
+    strong.count(Piece::Bishop) == 1 &&
+    strong.count(Piece::Knight) == 1
+}
+
+// Only the corners the bishop can reach can be mated in, the other two are
+// as bad a place to push the king to as the centre
+fn bishop_corner_distance(bishop: Bitboard, king: usize) -> i32 {
+    let corners = if bishop & LIGHT_SQUARES != 0 { [H1, A8] } else { [A1, H8] };
+    corners.iter()
+        .map(|&c| DISTANCE[king][c.index()] as i32)
+        .min()
+        .unwrap()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    fn mop(fen: &str) -> Result<i32, FenParseError> {
+        let (pos, _) = Position::from_fen(fen)?;
+        let material = pos.material.score;
+        Ok(mop_up(&pos, material))
+    }
+
+    #[test]
+    fn corner_and_close_kings() -> Result<(), FenParseError> {
+        let centre = mop("8/8/8/3k4/8/8/8/1Q2K3 w - - 0 1")?;
+        let corner = mop("7k/8/8/8/8/8/8/1Q2K3 w - - 0 1")?;
+        let closer = mop("7k/8/5K2/8/8/8/8/1Q6 w - - 0 1")?;
+        assert!(corner > centre);
+        assert!(closer > corner);
+        Ok(())
+    }
+
+    #[test]
+    fn symmetric() -> Result<(), FenParseError> {
+        assert_eq!(mop("7k/8/8/8/8/8/8/R3K3 w - - 0 1")?, -mop("r3k3/8/8/8/8/8/8/7K b - - 0 1")?);
+        Ok(())
+    }
+
+    #[test]
+    fn kbn_right_corner() -> Result<(), FenParseError> {
+        // Light-squared bishop, h1 and a8 are the mating corners
+        let right = mop("k7/8/1K6/8/8/8/8/3BN3 w - - 0 1")?;
+        let wrong = mop("8/8/8/8/8/1K6/8/k2BN3 w - - 0 1")?;
+        let centre = mop("8/8/8/3k4/8/1K6/8/3BN3 w - - 0 1")?;
+        assert!(right > centre);
+        assert!(centre > wrong);
+        Ok(())
+    }
+
+    #[test]
+    fn only_without_pawns_and_when_winning() -> Result<(), FenParseError> {
+        assert_eq!(mop("7k/8/8/8/8/8/P7/1Q2K3 w - - 0 1")?, 0);
+        assert_eq!(mop("7k/8/8/8/8/8/8/N3K3 w - - 0 1")?, 0);
+        assert_eq!(mop("7k/8/8/8/8/8/8/RN2K1nr w - - 0 1")?, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::constants::{
+    attacks::{PAWN_ATTACKS_BLACK, PAWN_ATTACKS_WHITE},
+    board::{FILE_C, FILE_D, FILE_E, FILE_F, RANK},
+    masks::{PAWN_ATTACK_SPAN_BLACK, PAWN_ATTACK_SPAN_WHITE},
+};
+use crate::core::{bitboard::Bitboard, player::Player, position::Position};
+use super::{s, Score};
+
+const OUTPOST:         Score = s(20, 12);
+const CENTRAL_OUTPOST: Score = s(15, 5);  // on top of `OUTPOST`, on the c-f files
+
+const CENTRAL_FILES: u64 = FILE_C | FILE_D | FILE_E | FILE_F;
+
+// From White's point of view
+pub fn knight_outposts(pos: &Position) -> Score {
+    let [white, black] = knight_outposts_by_side(pos);
+    white - black
+}
+
+// Indexed by `Player::index()`
+pub(super) fn knight_outposts_by_side(pos: &Position) -> [Score; 2] {
+    [side_knight_outposts(pos, Player::White), side_knight_outposts(pos, Player::Black)]
+}
+
+fn side_knight_outposts(pos: &Position, player: Player) -> Score {
+    // `supporters`: where our pawns would have to stand to defend the square,
+    // the same squares an enemy pawn on it would attack
+    let (own, enemy, zone, span, supporters) = match player {
+        Player::White => (&pos.w, &pos.b, RANK[4] | RANK[5] | RANK[6], &PAWN_ATTACK_SPAN_WHITE, &PAWN_ATTACKS_BLACK),
+        Player::Black => (&pos.b, &pos.w, RANK[3] | RANK[4] | RANK[5], &PAWN_ATTACK_SPAN_BLACK, &PAWN_ATTACKS_WHITE),
+    };
+    let mut score = Score::ZERO;
+
+    for square in own.knights & zone {
+        let sq = square.index();
+        if supporters[sq] & own.pawns == 0 || span[sq] & enemy.pawns != 0 {
+            continue;
+        }
+
+        score += OUTPOST;
+        if Bitboard(CENTRAL_FILES).contains(square) {
+            score += CENTRAL_OUTPOST;
+        }
+    }
+
+    score
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    fn outposts(fen: &str) -> Result<Score, FenParseError> {
+        let (pos, _) = Position::from_fen(fen)?;
+        Ok(knight_outposts(&pos))
+    }
+
+    #[test]
+    fn supported_and_safe() -> Result<(), FenParseError> {
+        // Ne5 defended by d4, no black pawns on the d and f files
+        assert_eq!(outposts("6k1/p7/8/4N3/3P4/8/8/6K1 w - - 0 1")?, OUTPOST + CENTRAL_OUTPOST);
+        // The same on the edge
+        assert_eq!(outposts("6k1/p7/8/7N/6P1/8/8/6K1 w - - 0 1")?, OUTPOST);
+        // And for Black, Nd4 defended by e5
+        assert_eq!(outposts("6k1/8/8/4p3/3n4/8/P7/6K1 w - - 0 1")?, -(OUTPOST + CENTRAL_OUTPOST));
+        Ok(())
+    }
+
+    #[test]
+    fn not_an_outpost() -> Result<(), FenParseError> {
+        // Not supported
+        assert_eq!(outposts("6k1/p7/8/4N3/8/8/8/6K1 w - - 0 1")?, Score::ZERO);
+        // f7 can still kick it
+        assert_eq!(outposts("6k1/5p2/8/4N3/3P4/8/8/6K1 w - - 0 1")?, Score::ZERO);
+        // Too far back
+        assert_eq!(outposts("6k1/p7/8/8/8/4N3/3P4/6K1 w - - 0 1")?, Score::ZERO);
+        Ok(())
+    }
+}
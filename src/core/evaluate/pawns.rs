@@ -0,0 +1,182 @@
+use crate::constants::{attacks::*, board::FILE, masks::*};
+use crate::core::{bitboard::Bitboard, player::Player, position::Position};
+use super::{s, Score};
+
+const DOUBLED:  Score = s(-10, -25);  // per extra pawn on the file
+const ISOLATED: Score = s(-12, -18);
+const BACKWARD: Score = s(-8, -12);
+
+// By the rank relative to the pawn's owner, 0-indexed
+const PASSED: [Score; 8] = [
+    Score::ZERO, s(5, 10), s(10, 15), s(15, 30), s(30, 55), s(50, 90), s(80, 140), Score::ZERO,
+];
+
+const PAWN_TABLE_SIZE: usize = 1 << 14;  // entries, a power of two
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PawnEntry {
+    pub key: u64,
+    pub score: Score,      // from White's point of view
+    pub passed: [Bitboard; 2],  // passed pawns, indexed by `Player::index()`
+}
+
+// Pawn formations repeat all the time during the search, so their evaluation is cached by `pawn_hash`
+#[derive(Debug, Default, Clone)]
+pub struct PawnTable {
+    entries: Vec<PawnEntry>,  // allocated on the first probe, so idle `Game`s stay cheap to create and clone
+}
+
+impl PawnTable {
+    pub fn probe(&mut self, pos: &Position) -> PawnEntry {
+        if self.entries.is_empty() {
+            self.entries = vec![PawnEntry::default(); PAWN_TABLE_SIZE];
+        }
+
+        let index = pos.pawn_hash as usize & (PAWN_TABLE_SIZE - 1);
+        if self.entries[index].key == pos.pawn_hash {
+            return self.entries[index];
+        }
+
+        let entry = evaluate_pawns(pos);
+        self.entries[index] = entry;
+        entry
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(PawnEntry::default());
+    }
+}
+
+pub fn evaluate_pawns(pos: &Position) -> PawnEntry {
+    let (white_score, white_passed) = side_pawn_structure(pos.w.pawns, pos.b.pawns, Player::White);
+    let (black_score, black_passed) = side_pawn_structure(pos.b.pawns, pos.w.pawns, Player::Black);
+    PawnEntry {
+        key: pos.pawn_hash,
+        score: white_score - black_score,
+        passed: [white_passed, black_passed],
+    }
+}
+
+// Indexed by `Player::index()`, for `trace`. The search goes through `PawnTable`
+pub(super) fn pawn_structure_by_side(pos: &Position) -> [Score; 2] {
+    [
+        side_pawn_structure(pos.w.pawns, pos.b.pawns, Player::White).0,
+        side_pawn_structure(pos.b.pawns, pos.w.pawns, Player::Black).0,
+    ]
+}
+
+// Returns (score, passed pawns)
+fn side_pawn_structure(own: Bitboard, enemy: Bitboard, player: Player) -> (Score, Bitboard) {
+    let mut score = Score::ZERO;
+    let mut passed = Bitboard::EMPTY;
+
+    for file in &FILE[1..] {
+        let count = (own & *file).count_ones() as i32;
+        if count > 1 {
+            score += DOUBLED * (count - 1);
+        }
+    }
+
+    for square in own {
+        let sq = square.index();
+        let neighbours = own & ADJACENT_FILES[sq];
+
+        if neighbours.is_empty() {
+            score += ISOLATED;
+        } else if is_backward(sq, neighbours, enemy, player) {
+            score += BACKWARD;
+        }
+
+        let (passed_mask, relative_rank) = match player {
+            Player::White => (PASSED_PAWN_MASKS_WHITE[sq], sq / 8),
+            Player::Black => (PASSED_PAWN_MASKS_BLACK[sq], 7 - sq / 8),
+        };
+        if (enemy & passed_mask).is_empty() {
+            score += PASSED[relative_rank];
+            passed = passed.set_bit(square);
+        }
+    }
+
+    (score, passed)
+}
+
+// No friendly pawn beside or behind can ever defend it,
+// and the square in front of it is controlled by an enemy pawn
+fn is_backward(sq: usize, neighbours: Bitboard, enemy: Bitboard, player: Player) -> bool {
+    let (forward, stop_attackers) = match player {
+        Player::White => (FORWARD_RANKS_WHITE[sq], PAWN_ATTACKS_WHITE[sq + 8]),
+        Player::Black => (FORWARD_RANKS_BLACK[sq], PAWN_ATTACKS_BLACK[sq - 8]),
+    };
+    (neighbours & !forward).is_empty() && !(enemy & stop_attackers).is_empty()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::board::E5, core::position::FenParseError};
+
+    // Passed pawns are left out, the fixtures below are about the weaknesses
+    fn white_pawns(fen: &str) -> Result<Score, FenParseError> {
+        let (pos, _) = Position::from_fen(fen)?;
+        let (score, passed) = side_pawn_structure(pos.w.pawns, pos.b.pawns, Player::White);
+        let passed_bonus = passed.squares().fold(Score::ZERO, |bonus, sq| bonus + PASSED[sq.rank().index()]);
+        Ok(score - passed_bonus)
+    }
+
+    #[test]
+    fn doubled() -> Result<(), FenParseError> {
+        assert_eq!(white_pawns("4k3/8/8/8/3P4/3P4/2P1P3/4K3 w - - 0 1")?, DOUBLED);
+        assert_eq!(white_pawns("4k3/8/8/3P4/3P4/3P4/2P1P3/4K3 w - - 0 1")?, DOUBLED * 2);
+        Ok(())
+    }
+
+    #[test]
+    fn isolated() -> Result<(), FenParseError> {
+        assert_eq!(white_pawns("4k3/8/8/8/8/8/P1P1P3/4K3 w - - 0 1")?, ISOLATED * 3);
+        assert_eq!(white_pawns("4k3/8/8/8/8/8/PP6/4K3 w - - 0 1")?, Score::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn backward() -> Result<(), FenParseError> {
+        // d3 is left behind by c4 and e4, and d4 is covered by the c5 pawn
+        assert_eq!(white_pawns("4k3/8/8/2p5/2P1P3/3P4/8/4K3 w - - 0 1")?, BACKWARD);
+        // Without the black pawn d3 can advance safely
+        assert_eq!(white_pawns("4k3/8/8/8/2P1P3/3P4/8/4K3 w - - 0 1")?, Score::ZERO);
+        // Mirrored for Black
+        let (pos, _) = Position::from_fen("4k3/8/3p4/2p1p3/2P5/8/8/4K3 w - - 0 1")?;
+        // e5 happens to be passed
+        assert_eq!(side_pawn_structure(pos.b.pawns, pos.w.pawns, Player::Black), (BACKWARD + PASSED[3], Bitboard::from_square(E5)));
+        Ok(())
+    }
+
+    #[test]
+    fn passed() -> Result<(), FenParseError> {
+        // a5 is passed, c4 is stopped by d5's control of c4's path, h2 is blocked
+        let (pos, _) = Position::from_fen("4k3/7p/8/P2p4/2P5/8/7P/4K3 w - - 0 1")?;
+        let entry = evaluate_pawns(&pos);
+        assert_eq!(entry.passed[Player::White.index()], 1 << 32);
+        assert_eq!(entry.passed[Player::Black.index()], 0);
+
+        // A pawn on the 7th is worth more than on the 5th
+        let (far, _) = Position::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1")?;
+        let (near, _) = Position::from_fen("4k3/8/8/P7/8/8/8/4K3 w - - 0 1")?;
+        assert!(evaluate_pawns(&far).score.eg > evaluate_pawns(&near).score.eg);
+        Ok(())
+    }
+
+    #[test]
+    fn pawn_table() -> Result<(), FenParseError> {
+        let mut table = PawnTable::default();
+        let (pos, _) = Position::from_fen("4k3/7p/8/P2p4/2P5/8/7P/4K3 w - - 0 1")?;
+        assert_eq!(table.probe(&pos), evaluate_pawns(&pos));
+        assert_eq!(table.probe(&pos), evaluate_pawns(&pos));  // hit
+
+        // Same pawns, different pieces: same entry
+        let (other, _) = Position::from_fen("3qk3/7p/8/P2p4/2P5/8/7P/4K1N1 b - - 0 1")?;
+        assert_eq!(other.pawn_hash, pos.pawn_hash);
+        assert_eq!(table.probe(&other), evaluate_pawns(&pos));
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use crate::constants::board::{FILE, RANK};
+use crate::core::{bitboard::Bitboard, movegen::rook_attacks, player::Player, position::Position};
+use super::{s, Score};
+
+const OPEN_FILE:      Score = s(25, 10);  // no pawns at all
+const SEMI_OPEN_FILE: Score = s(12, 8);   // only enemy pawns
+const SEVENTH_RANK:   Score = s(15, 30);
+const CONNECTED:      Score = s(10, 5);
+
+// From White's point of view
+pub fn rook_placement(pos: &Position) -> Score {
+    let [white, black] = rook_placement_by_side(pos);
+    white - black
+}
+
+// Indexed by `Player::index()`
+pub(super) fn rook_placement_by_side(pos: &Position) -> [Score; 2] {
+    [side_rook_placement(pos, Player::White), side_rook_placement(pos, Player::Black)]
+}
+
+fn side_rook_placement(pos: &Position, player: Player) -> Score {
+    let (own, enemy, seventh, eighth) = match player {
+        Player::White => (&pos.w, &pos.b, RANK[7], RANK[8]),
+        Player::Black => (&pos.b, &pos.w, RANK[2], RANK[1]),
+    };
+    let mut score = Score::ZERO;
+
+    // The rooks not looked at yet, above this one
+    let mut rooks = own.rooks;
+    while !rooks.is_empty() {
+        let sq = rooks.pop_lsb();
+        let file = FILE[sq.file().index() + 1];
+
+        if ((own.pawns | enemy.pawns) & file).is_empty() {
+            score += OPEN_FILE;
+        } else if (own.pawns & file).is_empty() {
+            score += SEMI_OPEN_FILE;
+        }
+
+        // Only worth it if there is something to attack there, or the enemy king is cut off
+        if Bitboard(seventh).contains(sq) && (enemy.pawns & seventh != 0 || enemy.king & eighth != 0) {
+            score += SEVENTH_RANK;
+        }
+
+        // Every connected pair counted once, from the lower rook
+        score += CONNECTED * (rook_attacks(pos, sq, Bitboard::EMPTY) & rooks).count_ones() as i32;
+    }
+
+    score
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    fn white_rooks(fen: &str) -> Result<Score, FenParseError> {
+        let (pos, _) = Position::from_fen(fen)?;
+        Ok(side_rook_placement(&pos, Player::White))
+    }
+
+    #[test]
+    fn files() -> Result<(), FenParseError> {
+        assert_eq!(white_rooks("4k3/p7/8/8/8/8/P7/R3K3 w - - 0 1")?, Score::ZERO);
+        assert_eq!(white_rooks("4k3/p7/8/8/8/8/8/R3K3 w - - 0 1")?, SEMI_OPEN_FILE);
+        assert_eq!(white_rooks("4k3/8/8/8/8/8/8/R3K3 w - - 0 1")?, OPEN_FILE);
+        Ok(())
+    }
+
+    #[test]
+    fn seventh_rank() -> Result<(), FenParseError> {
+        assert_eq!(white_rooks("6k1/R4ppp/8/8/8/8/PP6/4K3 w - - 0 1")?, SEVENTH_RANK);
+        // Nothing to do on the 7th
+        assert_eq!(white_rooks("8/R7/5k2/8/8/8/PP6/4K3 w - - 0 1")?, Score::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn connected() -> Result<(), FenParseError> {
+        assert_eq!(white_rooks("4k3/pppppppp/8/8/8/8/PPPPPPPP/R4RK1 w - - 0 1")?, CONNECTED);
+        assert_eq!(white_rooks("4k3/pppppppp/8/8/8/8/PPPPPPPP/R2Q1RK1 w - - 0 1")?, Score::ZERO);
+        // Doubled on an open file
+        assert_eq!(white_rooks("4k3/8/8/8/8/8/R7/R3K3 w - - 0 1")?, OPEN_FILE * 2 + CONNECTED);
+        // Two pairs, whichever of the rooks comes first
+        assert_eq!(white_rooks("1k6/8/8/8/4R3/8/8/R3R1K1 w - - 0 1")?, OPEN_FILE * 3 + CONNECTED * 2);
+        assert_eq!(white_rooks("6k1/8/8/8/3R4/8/8/1K1R3R w - - 0 1")?, OPEN_FILE * 3 + CONNECTED * 2);
+        Ok(())
+    }
+
+    #[test]
+    fn black_is_mirrored() -> Result<(), FenParseError> {
+        let (pos, _) = Position::from_fen("6k1/R4ppp/8/8/8/8/PP6/4K1R1 w - - 0 1")?;
+        let mirrored = pos.mirror();
+        assert_eq!(side_rook_placement(&mirrored, Player::Black), side_rook_placement(&pos, Player::White));
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use crate::constants::{
+    board::{A1, A8, FILE_A, FILE_H, H1, H8, LIGHT_SQUARES},
+    distance::DISTANCE,
+};
+use crate::core::{bitboard::BitboardSet, player::Player, position::Position};
+
+// The eval is multiplied by `scale / SCALE_NORMAL`
+pub const SCALE_NORMAL: i32 = 64;
+const OPPOSITE_BISHOPS: i32 = 24;  // with at most `OPPOSITE_BISHOPS_PAWNS` extra pawns
+const ROOK_PAWN_UP:     i32 = 40;
+const WRONG_ROOK_PAWN:  i32 = 0;   // a dead draw
+
+const OPPOSITE_BISHOPS_PAWNS: u32 = 2;
+
+// How much of `white_eval` (from White's point of view) the side it favours can actually
+// hope to convert. Endgames that are known to be hard to win get pulled towards zero
+pub fn scale_factor(pos: &Position, white_eval: i32) -> i32 {
+    // Nobody to favour, and nothing to scale
+    if white_eval == 0 {
+        return SCALE_NORMAL;
+    }
+    let (strong, weak, strong_player) = if white_eval >= 0 {
+        (&pos.w, &pos.b, Player::White)
+    } else {
+        (&pos.b, &pos.w, Player::Black)
+    };
+
+    if is_wrong_rook_pawn(strong, weak, strong_player) {
+        return WRONG_ROOK_PAWN;
+    }
+
+    let extra_pawns = strong.pawns.count_ones().saturating_sub(weak.pawns.count_ones());
+
+    if only_bishops(strong) && only_bishops(weak) &&
+       strong.bishops.count_ones() == 1 && weak.bishops.count_ones() == 1 &&
+       (strong.bishops & LIGHT_SQUARES == 0) != (weak.bishops & LIGHT_SQUARES == 0) &&
+       extra_pawns <= OPPOSITE_BISHOPS_PAWNS
+    {
+        return OPPOSITE_BISHOPS;
+    }
+
+    if only_rooks(strong) && only_rooks(weak) &&
+       strong.rooks.count_ones() == 1 && weak.rooks.count_ones() == 1 &&
+       extra_pawns == 1
+    {
+        return ROOK_PAWN_UP;
+    }
+
+    SCALE_NORMAL
+}
+
+fn only_bishops(side: &BitboardSet) -> bool {
+    side.knights | side.rooks | side.queens == 0
+}
+
+fn only_rooks(side: &BitboardSet) -> bool {
+    side.knights | side.bishops | side.queens == 0
+}
+
+// Bishop and rook pawns against a lone king. If the bishop doesn't control the promotion
+// square and the defending king gets there first, there is no way to dislodge it
+fn is_wrong_rook_pawn(strong: &BitboardSet, weak: &BitboardSet, player: Player) -> bool {
+    if weak.count_all() != 1 || strong.pawns == 0 || strong.bishops.count_ones() != 1 ||
+       strong.knights | strong.rooks | strong.queens != 0
+    {
+        return false;
+    }
+
+    let (a_promotion, h_promotion) = match player {
+        Player::White => (A8, H8),
+        Player::Black => (A1, H1),
+    };
+    let promotion = if strong.pawns & !FILE_A == 0 {
+        a_promotion
+    } else if strong.pawns & !FILE_H == 0 {
+        h_promotion
+    } else {
+        return false;
+    };
+
+    let bishop_light = strong.bishops & LIGHT_SQUARES != 0;
+    bishop_light != promotion.is_light() && DISTANCE[weak.king.lsb().index()][promotion.index()] <= 1
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    // Scaled for whoever is better, which is decided by `white_eval`'s sign
+    fn scale(fen: &str, white_eval: i32) -> Result<i32, FenParseError> {
+        let (pos, _) = Position::from_fen(fen)?;
+        Ok(scale_factor(&pos, white_eval))
+    }
+
+    #[test]
+    fn opposite_bishops() -> Result<(), FenParseError> {
+        assert_eq!(scale("4k3/5p2/8/4b3/8/8/3PPP2/4KB2 w - - 0 1", 100)?, OPPOSITE_BISHOPS);
+        // Same-colored bishops
+        assert_eq!(scale("4k3/5p2/8/3b4/8/8/3PPP2/4KB2 w - - 0 1", 100)?, SCALE_NORMAL);
+        // Too many extra pawns
+        assert_eq!(scale("4k3/8/8/4b3/8/8/2PPPP2/4KB2 w - - 0 1", 100)?, SCALE_NORMAL);
+        // Other pieces still on the board
+        assert_eq!(scale("4k3/5p2/8/4b3/8/8/3PPP2/3RKB2 w - - 0 1", 100)?, SCALE_NORMAL);
+        Ok(())
+    }
+
+    #[test]
+    fn rook_ending_pawn_up() -> Result<(), FenParseError> {
+        assert_eq!(scale("4k3/r4pp1/8/8/8/8/5PPP/4K2R w - - 0 1", 100)?, ROOK_PAWN_UP);
+        // Black is better here, same thing
+        assert_eq!(scale("r3k3/5ppp/8/8/8/8/5PP1/4K2R w - - 0 1", -100)?, ROOK_PAWN_UP);
+        assert_eq!(scale("4k3/r5p1/8/8/8/8/5PPP/4K2R w - - 0 1", 100)?, SCALE_NORMAL);
+        // Even, so neither side is the one a pawn up
+        assert_eq!(scale("r3k3/5ppp/8/8/8/8/5PP1/4K2R w - - 0 1", 0)?, SCALE_NORMAL);
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_rook_pawn() -> Result<(), FenParseError> {
+        // h8 is dark, the bishop is light
+        assert_eq!(scale("7k/8/8/8/7P/8/8/3BK3 w - - 0 1", 400)?, WRONG_ROOK_PAWN);
+        // The right bishop
+        assert_eq!(scale("7k/8/8/8/7P/8/8/2B1K3 w - - 0 1", 400)?, SCALE_NORMAL);
+        // The king is too far away
+        assert_eq!(scale("8/8/8/4k3/7P/8/8/3BK3 w - - 0 1", 400)?, SCALE_NORMAL);
+        // For Black, h1 is light and the bishop on d8 is dark
+        assert_eq!(scale("3bk3/8/8/8/7p/8/8/6K1 w - - 0 1", -400)?, WRONG_ROOK_PAWN);
+        Ok(())
+    }
+}
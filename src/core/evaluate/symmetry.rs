@@ -0,0 +1,111 @@
+// The evaluation has to be color blind: with the colors swapped (`Position::mirror`) it is the same
+// position for the side to move, and so it is with the queenside and the kingside swapped. A term
+// that tells them apart has a bug for one of the colors, or one of the wings
+use std::fmt;
+use crate::core::position::Position;
+use super::trace::{trace, EvalTrace};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymmetryError {
+    pub transform: &'static str,      // "mirrored" or "flipped"
+    pub eval: i32,
+    pub transformed_eval: i32,
+    pub terms: Vec<&'static str>,     // the ones that changed, mop-up and scaling included
+}
+
+impl fmt::Display for SymmetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} instead of {}", self.transform, self.transformed_eval, self.eval)?;
+        if !self.terms.is_empty() {
+            write!(f, ", off in {}", self.terms.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// Evaluates `pos` mirrored and flipped, and names the terms that came out different
+pub fn check_symmetry(pos: &Position) -> Result<(), SymmetryError> {
+    let original = trace(pos);
+    compare("mirrored", &original, &trace(&pos.mirror()), true)?;
+    compare("flipped", &original, &trace(&pos.flip_horizontal()), false)
+}
+
+fn compare(transform: &'static str, original: &EvalTrace, transformed: &EvalTrace, swapped: bool) -> Result<(), SymmetryError> {
+    let mut terms: Vec<&'static str> = original.terms.iter().zip(&transformed.terms)
+        .filter(|(a, b)| match swapped {
+            true  => a.sides != [b.sides[1], b.sides[0]],
+            false => a.sides != b.sides,
+        })
+        .map(|(a, _)| a.name)
+        .collect();
+    // From White's point of view, so it changes sign with the colors
+    let mop_up = if swapped { -transformed.mop_up } else { transformed.mop_up };
+    if original.mop_up != mop_up {
+        terms.push("Mop-up");
+    }
+    if original.scale != transformed.scale {
+        terms.push("Scale");
+    }
+
+    if terms.is_empty() && original.eval() == transformed.eval() {
+        return Ok(());
+    }
+    Err(SymmetryError { transform, eval: original.eval(), transformed_eval: transformed.eval(), terms })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{position::FenParseError, rules::properties::random_games};
+
+    const CORPUS: [&str; 14] = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        "4k3/pp4pp/2p5/8/8/1P3P2/P1P3P1/4K3 b - - 0 1",
+        "8/8/8/4k3/8/8/8/KR6 w - - 0 1",                         // mop-up
+        "8/8/4kb2/8/3B1P2/5K2/8/8 w - - 0 1",                    // opposite bishops
+        "8/5k2/8/8/8/8/3N4/4K3 b - - 0 1",                       // a knight can't win
+        "2r3k1/5ppp/8/3N4/8/8/5PPP/3R2K1 w - - 0 1",             // knight outpost
+        "6k1/3R1ppp/8/8/8/8/5PPP/6K1 b - - 0 1",                 // rook on the seventh
+        "r1bq1rk1/pp3ppp/2n1pn2/3p4/1bPP4/2NBPN2/PP3PPP/R2QK2R w KQ - 0 8",
+        "1k6/1pp5/8/2PP4/8/8/6pp/6K1 w - - 0 1",                 // passed pawns both ways
+    ];
+
+    #[test]
+    fn corpus_is_symmetric() -> Result<(), FenParseError> {
+        for fen in CORPUS {
+            let (pos, _) = Position::from_fen(fen)?;
+            assert_eq!(check_symmetry(&pos), Ok(()), "{}", fen);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn random_games_are_symmetric() -> Result<(), FenParseError> {
+        random_games(|_, m, after| {
+            assert_eq!(check_symmetry(after), Ok(()), "after {}, {}", m, after.to_fen(1));
+        })
+    }
+
+    #[test]
+    fn names_the_broken_term() -> Result<(), FenParseError> {
+        let (pos, _) = Position::from_fen(CORPUS[1])?;
+        let mut original = trace(&pos);
+        let mirrored = trace(&pos.mirror());
+        assert_eq!(compare("mirrored", &original, &mirrored, true), Ok(()));
+
+        // As if the pawn structure were scored a little differently for White
+        original.terms[1].sides[0].mg += 10;
+        original.white += 10;
+        let error = compare("mirrored", &original, &mirrored, true).unwrap_err();
+        assert_eq!(error.terms, ["Pawns"]);
+        assert_eq!(error.transformed_eval + 10, error.eval);
+        assert!(error.to_string().ends_with("off in Pawns"));
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::fmt;
+use crate::core::{bitboard::BitboardSet, piece::Piece, player::Player, position::Position};
+use super::{
+    game_phase, imbalance, mopup, outposts, pawns, rooks, scaling, tropism,
+    s, Score, MAX_PHASE, TEMPO,
+};
+
+// One evaluation term, for each side on its own. Both are from the side's own point of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TermTrace {
+    pub name: &'static str,
+    pub sides: [Score; 2],  // indexed by `Player::index()`
+}
+
+impl TermTrace {
+    // From White's point of view
+    pub fn total(&self) -> Score {
+        self.sides[0] - self.sides[1]
+    }
+}
+
+// `evaluate`, taken apart. In centipawns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalTrace {
+    pub terms: Vec<TermTrace>,
+    pub phase: i32,
+    pub mop_up: i32,  // from White's point of view
+    pub scale: i32,   // out of `scaling::SCALE_NORMAL`
+    pub white: i32,   // everything but the tempo, from White's point of view
+    pub player_to_move: Player,
+}
+
+impl EvalTrace {
+    // What `evaluate` returns, from the point of view of the side to move
+    pub fn eval(&self) -> i32 {
+        let relative = match self.player_to_move {
+            Player::White => self.white,
+            Player::Black => -self.white,
+        };
+        relative + TEMPO.get()
+    }
+}
+
+fn side_material(set: &BitboardSet) -> Score {
+    let cp = Piece::all_variants()
+        .iter()
+        .filter(|&&piece| piece != Piece::King)
+        .map(|&piece| piece.value() * set.count(piece) as i32)
+        .sum();
+    s(cp, cp)
+}
+
+pub fn trace(pos: &Position) -> EvalTrace {
+    let terms = vec![
+        TermTrace { name: "Material",  sides: [side_material(&pos.w), side_material(&pos.b)] },
+        TermTrace { name: "Pawns",     sides: pawns::pawn_structure_by_side(pos) },
+        TermTrace { name: "Imbalance", sides: imbalance::imbalance_by_side(pos) },
+        TermTrace { name: "Rooks",     sides: rooks::rook_placement_by_side(pos) },
+        TermTrace { name: "Tropism",   sides: tropism::king_tropism_by_side(pos) },
+        TermTrace { name: "Outposts",  sides: outposts::knight_outposts_by_side(pos) },
+    ];
+
+    // The same steps as `evaluate_with_pawns`, material is not tapered
+    let phase = game_phase(pos);
+    let material = pos.material.score;
+    let score = terms[1..].iter().fold(Score::ZERO, |sum, term| sum + term.total());
+    let mop_up = mopup::mop_up(pos, material);
+    let white = material + score.taper(phase) + mop_up;
+    let scale = scaling::scale_factor(pos, white);
+    let white = white * scale / scaling::SCALE_NORMAL;
+
+    EvalTrace { terms, phase, mop_up, scale, white, player_to_move: pos.player_to_move }
+}
+
+impl fmt::Display for EvalTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let score = |score: Score| format!("{:>6} {:>6}", score.mg, score.eg);
+
+        writeln!(f, "      Term |     White     |     Black     |     Total")?;
+        writeln!(f, "           |     MG     EG |     MG     EG |     MG     EG")?;
+        writeln!(f, "-----------+---------------+---------------+--------------")?;
+        for term in &self.terms {
+            writeln!(
+                f,
+                "{:>10} | {} | {} | {}",
+                term.name,
+                score(term.sides[0]),
+                score(term.sides[1]),
+                score(term.total())
+            )?;
+        }
+        writeln!(f, "-----------+---------------+---------------+--------------")?;
+        writeln!(f)?;
+        writeln!(f, "Phase: {}/{}", self.phase, MAX_PHASE)?;
+        writeln!(f, "Mop-up: {}", self.mop_up)?;
+        writeln!(f, "Scale: {}/{}", self.scale, scaling::SCALE_NORMAL)?;
+        writeln!(f, "Tempo: {}", TEMPO.get())?;
+        writeln!(f, "Evaluation: {} (White's point of view)", self.white)?;
+        write!(f, "Evaluation: {} ({:?} to move)", self.eval(), self.player_to_move)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{evaluate::evaluate, position::FenParseError};
+
+    #[test]
+    fn adds_up_to_evaluate() -> Result<(), FenParseError> {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R b KQkq - 0 1",
+            "8/8/8/4k3/8/8/8/KR6 w - - 0 1",          // mop-up
+            "8/8/4kb2/8/3B1P2/5K2/8/8 w - - 0 1",     // opposite bishops
+        ] {
+            let (pos, _) = Position::from_fen(fen)?;
+            assert_eq!(trace(&pos).eval(), evaluate(&pos), "{}", fen);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn sides() -> Result<(), FenParseError> {
+        let (pos, _) = Position::from_fen("4k3/8/8/8/8/8/PPP5/R3K3 w - - 0 1")?;
+        let trace = trace(&pos);
+        assert_eq!(trace.terms[0].sides, [s(800, 800), Score::ZERO]);
+        assert_eq!(trace.terms[0].total(), s(800, 800));
+        assert!(trace.to_string().contains("Material"));
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use crate::constants::distance::DISTANCE;
+use crate::core::{bitboard::BitboardSet, piece::Piece, position::Position, square::Square};
+use super::{s, Score, tune::*};
+
+// Per step closer to the enemy king than the far side of the board, (midgame, endgame).
+// Mostly a midgame term: attackers matter while there is a king to attack
+static TROPISM: [(Piece, &Tunable, &Tunable); 4] = [
+    (Piece::Queen,  &TROPISM_QUEEN_MG,  &TROPISM_QUEEN_EG),
+    (Piece::Knight, &TROPISM_KNIGHT_MG, &TROPISM_KNIGHT_EG),
+    (Piece::Rook,   &TROPISM_ROOK_MG,   &TROPISM_ROOK_EG),
+    (Piece::Bishop, &TROPISM_BISHOP_MG, &TROPISM_BISHOP_EG),
+];
+
+// From White's point of view
+pub fn king_tropism(pos: &Position) -> Score {
+    let [white, black] = king_tropism_by_side(pos);
+    white - black
+}
+
+// Indexed by `Player::index()`
+pub(super) fn king_tropism_by_side(pos: &Position) -> [Score; 2] {
+    [side_tropism(&pos.w, pos.b.king.lsb()), side_tropism(&pos.b, pos.w.king.lsb())]
+}
+
+fn side_tropism(own: &BitboardSet, enemy_king: Square) -> Score {
+    let mut score = Score::ZERO;
+    for (piece, mg, eg) in TROPISM {
+        let weight = s(mg.get(), eg.get());
+        for sq in *own.piece_to_bb(piece) {
+            let closeness = 7 - DISTANCE[sq.index()][enemy_king.index()] as i32;
+            score += weight * closeness;
+        }
+    }
+    score
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    fn tropism(fen: &str) -> Result<Score, FenParseError> {
+        let (pos, _) = Position::from_fen(fen)?;
+        Ok(king_tropism(&pos))
+    }
+
+    #[test]
+    fn closer_is_better() -> Result<(), FenParseError> {
+        let far = tropism("7k/8/8/8/8/8/8/N3K3 w - - 0 1")?;
+        let near = tropism("7k/8/5N2/8/8/8/8/4K3 w - - 0 1")?;
+        assert!(near.mg > far.mg);
+        assert_eq!(near - far, s(3, 1) * 5);
+        Ok(())
+    }
+
+    #[test]
+    fn queens_count_most() -> Result<(), FenParseError> {
+        let queen = tropism("6k1/8/5Q2/8/8/8/8/4K3 b - - 0 1")?;
+        let bishop = tropism("6k1/8/5B2/8/8/8/8/4K3 b - - 0 1")?;
+        assert!(queen.mg > bishop.mg);
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// The evaluation weights an external tuner (SPSA through OpenBench or chess-tuning-tools) may set.
+// Plain constants in a normal build. With the `tune` feature they are read at run time instead,
+// and the UCI loop announces every one of them as a spin option
+#[cfg(feature = "tune")]
+use std::sync::atomic::{AtomicI32, Ordering};
+
+pub struct Tunable {
+    pub name: &'static str,  // of the UCI option
+    pub default: i32,
+    pub min: i32,
+    pub max: i32,
+    #[cfg(feature = "tune")]
+    value: AtomicI32,
+}
+
+impl Tunable {
+    const fn new(name: &'static str, default: i32, min: i32, max: i32) -> Self {
+        Tunable {
+            name,
+            default,
+            min,
+            max,
+            #[cfg(feature = "tune")]
+            value: AtomicI32::new(default),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self) -> i32 {
+        #[cfg(feature = "tune")]
+        return self.value.load(Ordering::Relaxed);
+        #[cfg(not(feature = "tune"))]
+        self.default
+    }
+
+    // Clamped to `min..=max`
+    #[cfg(feature = "tune")]
+    pub fn set(&self, value: i32) {
+        self.value.store(value.clamp(self.min, self.max), Ordering::Relaxed);
+    }
+}
+
+// See `Piece::value`
+pub static PAWN_VALUE:   Tunable = Tunable::new("PawnValue",   100,  50,  200);
+pub static KNIGHT_VALUE: Tunable = Tunable::new("KnightValue", 300, 150,  500);
+pub static BISHOP_VALUE: Tunable = Tunable::new("BishopValue", 330, 150,  500);
+pub static ROOK_VALUE:   Tunable = Tunable::new("RookValue",   500, 300,  800);
+pub static QUEEN_VALUE:  Tunable = Tunable::new("QueenValue",  900, 600, 1400);
+
+// Having the move is worth something, and it keeps odd and even depths closer together
+pub static TEMPO: Tunable = Tunable::new("Tempo", 15, 0, 50);
+
+// King safety, see `tropism.rs`
+pub static TROPISM_QUEEN_MG:  Tunable = Tunable::new("TropismQueenMg",  5, 0, 20);
+pub static TROPISM_QUEEN_EG:  Tunable = Tunable::new("TropismQueenEg",  2, 0, 20);
+pub static TROPISM_KNIGHT_MG: Tunable = Tunable::new("TropismKnightMg", 3, 0, 20);
+pub static TROPISM_KNIGHT_EG: Tunable = Tunable::new("TropismKnightEg", 1, 0, 20);
+pub static TROPISM_ROOK_MG:   Tunable = Tunable::new("TropismRookMg",   2, 0, 20);
+pub static TROPISM_ROOK_EG:   Tunable = Tunable::new("TropismRookEg",   1, 0, 20);
+pub static TROPISM_BISHOP_MG: Tunable = Tunable::new("TropismBishopMg", 1, 0, 20);
+pub static TROPISM_BISHOP_EG: Tunable = Tunable::new("TropismBishopEg", 0, 0, 20);
+
+// In the order they are announced
+pub static TUNABLES: [&Tunable; 14] = [
+    &PAWN_VALUE, &KNIGHT_VALUE, &BISHOP_VALUE, &ROOK_VALUE, &QUEEN_VALUE,
+    &TEMPO,
+    &TROPISM_QUEEN_MG,  &TROPISM_QUEEN_EG,
+    &TROPISM_KNIGHT_MG, &TROPISM_KNIGHT_EG,
+    &TROPISM_ROOK_MG,   &TROPISM_ROOK_EG,
+    &TROPISM_BISHOP_MG, &TROPISM_BISHOP_EG,
+];
+
+// Case-insensitive, like UCI option names
+pub fn find(name: &str) -> Option<&'static Tunable> {
+    TUNABLES.iter().copied().find(|t| t.name.eq_ignore_ascii_case(name))
+}
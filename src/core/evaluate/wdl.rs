@@ -0,0 +1,116 @@
+// Win/draw/loss chances for a score, for `UCI_ShowWDL`. The win chance is a logistic curve in
+// the score: its center and its steepness depend on the material left, the same model as
+// Stockfish's
+use crate::constants::CHECKMATE_EVAL;
+use crate::core::{piece::Piece, player::Player, position::Position};
+
+// Cubics in the material left (see `material_count`, over 58), Stockfish's fit:
+// the score the win chance is 50% at, and how quickly it rises around it
+const CENTER: [f64; 4] = [-185.71965483, 504.85014385, -438.58295743, 474.04604627];
+const SPREAD: [f64; 4] = [89.23542728, -137.02141296, 73.28669021, 47.53376190];
+const MATERIAL_MIN: i32 = 17;
+const MATERIAL_MAX: i32 = 78;
+const MATERIAL_NORM: f64 = 58.;
+
+// Permille, from the point of view of the side to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wdl {
+    pub win: u32,
+    pub draw: u32,
+    pub loss: u32,
+}
+
+// Both sides' material in pawns, the kings left out
+pub fn material_count(pos: &Position) -> i32 {
+    Piece::all_variants().iter().map(|&piece| {
+        let pawns = match piece {
+            Piece::Pawn                   => 1,
+            Piece::Knight | Piece::Bishop => 3,
+            Piece::Rook                   => 5,
+            Piece::Queen                  => 9,
+            Piece::King                   => 0,
+        };
+        pawns * (pos.material.count(Player::White, piece) + pos.material.count(Player::Black, piece)) as i32
+    }).sum()
+}
+
+fn cubic(coefficients: &[f64; 4], x: f64) -> f64 {
+    coefficients.iter().fold(0., |acc, c| acc * x + c)
+}
+
+// Permille. `score` in centipawns, the model's units are scaled so that a pawn up with
+// 58 pawns' worth of material on the board wins half the time
+fn win_rate(score: i32, material: i32) -> u32 {
+    let m = material.clamp(MATERIAL_MIN, MATERIAL_MAX) as f64 / MATERIAL_NORM;
+    let units_per_cp = cubic(&CENTER, 1.) / 100.;
+    let center = cubic(&CENTER, m);
+    let spread = cubic(&SPREAD, m);
+    let x = score as f64 * units_per_cp;
+    (1000. / (1. + ((center - x) / spread).exp())).round() as u32
+}
+
+pub fn wdl(score: i32, pos: &Position) -> Wdl {
+    if score.abs() > CHECKMATE_EVAL - 1000 {
+        return match score > 0 {
+            true  => Wdl { win: 1000, draw: 0, loss: 0 },
+            false => Wdl { win: 0, draw: 0, loss: 1000 },
+        };
+    }
+
+    let material = material_count(pos);
+    let win = win_rate(score, material);
+    let loss = win_rate(-score, material);
+    Wdl { win, draw: 1000 - win - loss, loss }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wdl_at(fen: &str, score: i32) -> Wdl {
+        let (pos, _) = Position::from_fen(fen).unwrap();
+        wdl(score, &pos)
+    }
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const ROOK_ENDGAME: &str = "8/5pk1/6p1/8/8/6P1/5PK1/3R4 w - - 0 1";
+
+    #[test]
+    fn material() {
+        assert_eq!(material_count(&Position::from_fen(START).unwrap().0), 78);
+        assert_eq!(material_count(&Position::from_fen(ROOK_ENDGAME).unwrap().0), 9);
+    }
+
+    #[test]
+    fn sums_to_a_thousand() {
+        for score in [-2000, -300, -50, 0, 30, 100, 450, 5000] {
+            for fen in [START, ROOK_ENDGAME] {
+                let wdl = wdl_at(fen, score);
+                assert_eq!(wdl.win + wdl.draw + wdl.loss, 1000, "{} {}", fen, score);
+            }
+        }
+    }
+
+    #[test]
+    fn shape() {
+        let even = wdl_at(START, 0);
+        assert_eq!(even.win, even.loss);
+        assert!(even.draw > 900);
+
+        // Symmetric, and monotonic in the score
+        let ahead = wdl_at(START, 150);
+        let behind = wdl_at(START, -150);
+        assert_eq!((ahead.win, ahead.loss), (behind.loss, behind.win));
+        assert!(ahead.win > wdl_at(START, 100).win);
+
+        // The same edge means something else with fewer pieces on the board
+        assert_ne!(wdl_at(ROOK_ENDGAME, 80), wdl_at(START, 80));
+    }
+
+    #[test]
+    fn mate() {
+        assert_eq!(wdl_at(START, CHECKMATE_EVAL - 5), Wdl { win: 1000, draw: 0, loss: 0 });
+        assert_eq!(wdl_at(START, -CHECKMATE_EVAL + 2), Wdl { win: 0, draw: 0, loss: 1000 });
+    }
+}
@@ -1,15 +1,17 @@
-use std::{
-    cmp::{max, min},
-    sync::{atomic::{AtomicBool, Ordering}, Arc},
-    time::{Duration, Instant}
-};
+use std::cmp::max;
 use crate::constants::*;
 use crate::core::{
+    bitboard::Bitboard,
     chess_move::*,
-    evaluate::evaluate,
-    movegen::pseudo_moves,
+    evaluate::{evaluate_cached, EvalTables},
+    movegen::{self, has_legal_move, is_single_reply, pseudo_moves, pseudo_moves_into},
+    opening::{self, Opening},
+    outcome::*,
     player::Player,
     position::*,
+    search::SearchControl,
+    tt::{Bound, TranspositionTable},
+    zobrist::ZobristHash,
     rules::{
         make::*,
         unmake::*,
@@ -18,227 +20,508 @@ use crate::core::{
     }
 };
 
+// Internal iterative reduction, see `negamax`
+const IIR_MIN_DEPTH: usize = 4;
+
 #[derive(Clone)]
 pub struct Game {
     pub position: Position,
     pub undos: Vec<UndoData>,
-    pub halfmove_clock: usize,
+    // The hashes of the positions before this one, oldest first. The ones the GUI sent as moves
+    // included: `position ... moves ...` replays them
+    pub hash_history: Vec<ZobristHash>,
+    pub fullmove_number: usize,
+    pub eval_tables: EvalTables,
+}
+
+// Asked to take back more moves than there are in the game
+#[derive(Debug, PartialEq, Eq)]
+pub struct TakebackError {
+    pub requested: usize,
+    pub played: usize,
 }
 
 impl Default for Game {
     fn default() -> Self {
-        let undos = Vec::with_capacity(GAME_HISTORY_CAPACITY);
-        let position = Position::default();
-        Game { position, undos, halfmove_clock: 0 }
+        Game::new(Position::default())
+    }
+}
+
+// The board the way `settings` say, and what else there is to know about the game
+pub struct GameDisplay<'a> {
+    game: &'a Game,
+    settings: DisplaySettings,
+}
+
+impl std::fmt::Display for GameDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let game = self.game;
+        let checker_squares: Vec<_> = checkers(&game.position).squares().map(|sq| sq.to_string()).collect();
+
+        writeln!(f, "{}", game.position.display(self.settings))?;
+        writeln!(f, "Fen: {}", game.to_fen())?;
+        writeln!(f, "Key: {:016X}", game.position.zobrist_hash)?;
+        writeln!(f, "Halfmove clock: {}", game.position.halfmove_clock)?;
+        writeln!(f, "Checkers: {}", checker_squares.join(" "))?;
+        write!(f, "Legal moves: {}", game.legal_moves().count())
+    }
+}
+
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display(DisplaySettings::default()).fmt(f)
     }
 }
 
 impl Game {
     pub fn new(pos: Position) -> Game {
-        let undos = Vec::with_capacity(GAME_HISTORY_CAPACITY);
-        Game { position: pos, undos, halfmove_clock: 0 }
+        Game::with_fullmove_number(pos, 1)
     }
 
     pub fn from_fen(fen: &str) -> Result<Game, FenParseError> {
-        let (position, clock) = Position::from_fen(fen)?;
-        let undos = Vec::with_capacity(GAME_HISTORY_CAPACITY);
-        Ok(Game { position, undos, halfmove_clock: clock })
+        let (position, fullmove_number) = Position::from_fen(fen)?;
+        Ok(Game::with_fullmove_number(position, fullmove_number))
+    }
+
+    fn with_fullmove_number(position: Position, fullmove_number: usize) -> Game {
+        Game {
+            position,
+            undos: Vec::with_capacity(GAME_HISTORY_CAPACITY),
+            hash_history: Vec::with_capacity(GAME_HISTORY_CAPACITY),
+            fullmove_number,
+            eval_tables: EvalTables::default(),
+        }
+    }
+
+    pub fn to_fen(&self) -> String {
+        self.position.to_fen(self.fullmove_number)
+    }
+
+    // What `d` shows
+    pub fn display(&self, settings: DisplaySettings) -> GameDisplay<'_> {
+        GameDisplay { game: self, settings }
     }
 
     pub fn pseudo_moves(&self) -> Vec<Move> {
         pseudo_moves(&self.position)
     }
 
+    // Only the moves that do not leave the king in check. Checked as the iterator gets to them,
+    // so stopping at the first one costs a single legality check
+    pub fn legal_moves(&self) -> impl Iterator<Item = Move> + '_ {
+        let pos = &self.position;
+        pseudo_moves(pos).into_iter().filter(move |m| movegen::leaves_king_safe(pos, m))
+    }
+
+    // `Move::null()` passes the turn, which is legal unless in check
     pub fn try_to_make_move(&mut self, m: &Move) -> bool {
-        let mut clock = self.halfmove_clock;
-        let undo = make_move(&mut self.position, m, &mut clock);
+        let checkers = checkers(&self.position);
+        let undo = if m.is_null() {
+            if !checkers.is_empty() {
+                return false;
+            }
+            make_null_move(&mut self.position)
+        } else {
+            match self.make_if_legal(m, checkers) {
+                Some(undo) => undo,
+                None => return false,
+            }
+        };
+
+        self.hash_history.push(undo.zobrist_hash);
+        self.undos.push(undo);
+
+        // The fullmove number is incremented after Black's move
+        if self.position.player_to_move == Player::White {
+            self.fullmove_number += 1;
+        }
+
+        true
+    }
+
+    // On the position alone, the history is up to the caller. None, with the position as it was,
+    // if the move leaves the king in check. `checkers` are the pieces giving check before the move
+    fn make_if_legal(&mut self, m: &Move, checkers: Bitboard) -> Option<UndoData> {
+        let could_expose_king = movegen::could_expose_king(&self.position, m, checkers);
+        let undo = make_move(&mut self.position, m);
 
         // Check legality of a move (is player that made the move still in check?)
         // Using `.opposite()` because the flag was already flipped in `make_move`
-        if is_king_in_check(&self.position, self.position.player_to_move.opposite()) {
-            unmake_move(&mut self.position, undo, &mut clock);
-            return false;
+        if could_expose_king && is_king_in_check(&self.position, self.position.player_to_move.opposite()) {
+            unmake_move(&mut self.position, undo);
+            return None;
         }
+        Some(undo)
+    }
 
-        self.undos.push(undo);
-        self.halfmove_clock = clock;
+    // Panics if no move was played, `try_unmake_move` does not
+    pub fn unmake_move(&mut self) {
+        let undo = self.undos.pop().unwrap();
+        match undo.move_to_undo().is_null() {
+            true  => unmake_null_move(&mut self.position, undo),
+            false => unmake_move(&mut self.position, undo),
+        }
+        self.hash_history.pop();
 
-        true
+        if self.position.player_to_move == Player::Black {
+            self.fullmove_number -= 1;
+        }
     }
 
-    pub fn unmake_move(&mut self) {
-        let mut clock = self.halfmove_clock;
-        unmake_move(&mut self.position, self.undos.pop().unwrap(), &mut clock);
-        self.halfmove_clock = clock;
+    // Returns the move taken back
+    pub fn try_unmake_move(&mut self) -> Result<Move, TakebackError> {
+        let m = self.undos.last().ok_or(TakebackError { requested: 1, played: 0 })?.move_to_undo();
+        self.unmake_move();
+        Ok(m)
+    }
+
+    // Takes back the last `n` moves, or none of them if there are fewer
+    pub fn undo(&mut self, n: usize) -> Result<(), TakebackError> {
+        if n > self.ply() {
+            return Err(TakebackError { requested: n, played: self.ply() });
+        }
+        for _ in 0..n {
+            self.unmake_move();
+        }
+        Ok(())
     }
 
-    // UTTERLY INSANE IMPLEMENTATION that works and seems to be fast enough
+    // The number of moves played since the game was set up, all of which can be taken back
+    pub fn ply(&self) -> usize {
+        self.undos.len()
+    }
+
+    // Oldest first
+    pub fn moves_played(&self) -> Vec<Move> {
+        self.undos.iter().map(|undo| undo.move_to_undo()).collect()
+    }
+
+    pub fn moves_played_uci(&self) -> Vec<String> {
+        self.undos.iter().map(|undo| undo.move_to_undo().to_string()).collect()
+    }
+
+    pub fn moves_played_san(&self) -> Vec<String> {
+        let mut game = self.initial();
+        self.moves_played().iter().map(|m| {
+            let san = m.to_san(&game.position);
+            game.try_to_make_move(m);
+            san
+        }).collect()
+    }
+
+    // The game as it was set up, before any of the moves played
+    pub fn initial(&self) -> Game {
+        let mut game = self.clone();
+        while game.try_unmake_move().is_ok() {}
+        game
+    }
+
+    // Pseudo-legal and does not leave the king in check
+    pub fn is_legal(&self, m: &Move) -> bool {
+        movegen::is_legal(&self.position, m)
+    }
+
+    pub fn is_legal_uci(&self, uci: &str) -> bool {
+        Move::from_uci(&self.position, uci).is_ok()
+    }
+
+    // "0000" passes the turn
     pub fn try_to_make_uci_move(&mut self, uci: &str) -> bool {
-        let moves = self.pseudo_moves();
-        for m in &moves {
-            if m.to_string() == uci {
-                return self.try_to_make_move(m);
+        if uci == "0000" {
+            return self.try_to_make_move(&Move::null());
+        }
+        match Move::from_uci(&self.position, uci) {
+            Ok(m) => self.try_to_make_move(&m),
+            Err(_) => false,
+        }
+    }
+
+    // The most specific named opening the game has passed through, so the name
+    // stays available after the game leaves the book
+    pub fn opening(&self) -> Option<Opening> {
+        std::iter::once(self.position.zobrist_hash)
+            .chain(self.undos.iter().rev().map(|undo| undo.zobrist_hash))
+            .find_map(opening::lookup)
+    }
+
+    // "1. e4 e5 2. Nf3", or "3... Kh7 4. Qg8#" with Black to move. The moves have to be
+    // legal one after another from here
+    pub fn san_line(&self, moves: &[Move]) -> String {
+        let mut game = self.clone();
+        let mut tokens = Vec::new();
+        for (i, m) in moves.iter().enumerate() {
+            match game.position.player_to_move {
+                Player::White => tokens.push(format!("{}.", game.fullmove_number)),
+                Player::Black if i == 0 => tokens.push(format!("{}...", game.fullmove_number)),
+                Player::Black => {}
             }
+            tokens.push(m.to_san(&game.position));
+            game.try_to_make_move(m);
         }
-        false
+        tokens.join(" ")
     }
 
-    fn is_threefold_repetition(&self) -> bool {
+    // Checkmate and stalemate take precedence over the other draw rules
+    pub fn outcome(&self) -> Outcome {
+        let player = self.position.player_to_move;
+        if !has_legal_move(&self.position) {
+            return if is_king_in_check(&self.position, player) {
+                Outcome::win(player.opposite(), WinReason::Checkmate)
+            } else {
+                Outcome::Draw(DrawReason::Stalemate)
+            };
+        }
+
+        if self.is_threefold_repetition() {
+            Outcome::Draw(DrawReason::ThreefoldRepetition)
+        } else if self.is_fifty_move_rule() {
+            Outcome::Draw(DrawReason::FiftyMoveRule)
+        } else if self.is_insufficient_material() {
+            Outcome::Draw(DrawReason::InsufficientMaterial)
+        } else {
+            Outcome::Ongoing
+        }
+    }
+
+    // How many plies back each earlier occurrence of this position is, nearest first.
+    // Only positions with the same side to move, and none before the last capture or pawn move
+    fn repetition_distances(&self) -> impl Iterator<Item = usize> + '_ {
         let current_hash = self.position.zobrist_hash;
-        let mut count = 1;
-        for undo in self.undos.iter().rev() {
-            if undo.zobrist_hash == current_hash {
-                count += 1;
-                if count == 3 {
-                    return true;
-                }
-            }
+        let reversible = self.position.halfmove_clock.min(self.hash_history.len());
+        (2..=reversible)
+            .step_by(2)
+            .filter(move |&plies| self.hash_history[self.hash_history.len() - plies] == current_hash)
+    }
+
+    fn is_threefold_repetition(&self) -> bool {
+        self.repetition_distances().nth(1).is_some()
+    }
+
+    // In the search a position that comes back once is a draw already, if it came back within
+    // the tree: whatever the players could do about it, they could do the first time around.
+    // Against the game played before the root it takes the full three
+    fn is_repetition(&self, ply: usize) -> bool {
+        let mut distances = self.repetition_distances();
+        match distances.next() {
+            Some(plies) if plies <= ply => true,
+            Some(_) => distances.next().is_some(),
+            None => false,
         }
-        false
     }
 
     fn is_fifty_move_rule(&self) -> bool {
-        self.halfmove_clock >= 100
+        self.position.halfmove_clock >= 100
     }
 
     fn is_insufficient_material(&self) -> bool {
         is_insufficient_material(&self.position)
     }
 
+    // Negamax: every score is from the point of view of the side to move.
     // Returns (best_move, best_eval, pv, unwind)
     // PV is REVERSED (leaf -> root), reverse it when printing to get normal root -> leaf
-    fn minimax_alphabeta(
+    fn negamax(
         &mut self,
         depth: usize,
+        ply: usize,
         mut alpha: i32,
-        mut beta: i32,
-        maximize: bool,
-        stop_flag: &Arc<AtomicBool>,
-        start_time: Instant,
-        time_limit: Option<Duration>,
-        nodes: &mut u64,
+        beta: i32,
+        ctrl: &mut SearchControl,
+        tt: &TranspositionTable,
     ) -> (Option<Move>, i32, Vec<Move>, bool) {
-        *nodes += 1;
+        ctrl.nodes += 1;
 
-        if self.is_threefold_repetition() ||
+        // The root has to come up with a move even if the game is drawn already
+        if ply > 0 && (
+            self.is_repetition(ply) ||
             self.is_fifty_move_rule() ||
-            self.is_insufficient_material() {
+            self.is_insufficient_material()
+        ) {
             return (None, DRAW_EVAL, Vec::new(), false);
         }
 
+        // Unwind the search if `stop_flag` was set or some limit was hit.
+        // Checked before the leaves too, so the node limit is exact
+        if ctrl.should_unwind() {
+            return (None, evaluate_cached(&self.position, &mut self.eval_tables), Vec::new(), true);
+        }
+
         if depth == 0 {
-            return (None, evaluate(&self.position), Vec::new(), false);
+            ctrl.stats.leaf_nodes += 1;
+            return (None, evaluate_cached(&self.position, &mut self.eval_tables), Vec::new(), false);
         }
 
-        // Unwind the search if `stop_flag` was set or time is over
-        // Check every 1024 nodes, because it is time-expensive
-        if *nodes % 1024 == 0 {
-            if stop_flag.load(Ordering::Relaxed) {
-                return (None, evaluate(&self.position), Vec::new(), true);
+        // The root always searches, it has to come up with a move and a PV
+        let key = self.position.zobrist_hash;
+        let entry = tt.probe(key);
+        ctrl.stats.tt_probes += 1;
+        ctrl.stats.tt_hits += entry.is_some() as u64;
+        if ply > 0
+            && let Some(entry) = entry
+            && entry.depth as usize >= depth {
+            let score = entry.score(ply);
+            let cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            if cutoff {
+                ctrl.stats.tt_cutoffs += 1;
+                return (entry.best_move, score, Vec::new(), false);
             }
+        }
 
-            if let Some(tl) = time_limit {
-                if start_time.elapsed() >= tl {
-                    return (None, evaluate(&self.position), Vec::new(), true);
-                }
-            }
+        // Worked out once for the node: the legality of every move, the extension and the mate all need it
+        let checkers = checkers(&self.position);
+
+        // The move that was best the last time goes first
+        let mut moves = ctrl.take_move_list(ply);
+        pseudo_moves_into(&self.position, &mut moves);
+        let mut tt_move = None;
+        if let Some(m) = entry.and_then(|e| e.best_move)
+            && let Some(i) = moves.iter().position(|&pm| pm == m) {
+            moves.swap(0, i);
+            tt_move = Some(m);
         }
+        // At the root: whether the previous iteration's best move is done with in this one
+        let mut previous_best_searched = tt_move.is_none();
+        // Nothing stored for this node: the moves go in blind order, and most of them would be searched
+        // in vain. A ply less is cheap, and leaves a move in the table for when it comes back deeper
+        let depth = match ply > 0 && tt_move.is_none() && depth >= IIR_MIN_DEPTH {
+            true  => depth - 1,
+            false => depth,
+        };
+        // A forced move costs no depth, forcing lines get searched to the end that much sooner.
+        // The root has nothing to decide either way
+        let extension = (ply > 0 && is_single_reply(&self.position, &moves, checkers)) as usize;
 
-        let moves = self.pseudo_moves();
-        let mut best_eval = if maximize { i32::MIN } else { i32::MAX };
-        let mut best_move = None;
+        let original_alpha = alpha;
+        let mut best_eval = -EVAL_INFINITY;
+        let mut best_move: Option<&Move> = None;
         let mut best_pv = None;
-        let mut found_legal_move = false;
+        let mut legal_moves_searched = 0;
+        let mut skipped_root_move = false;
 
         for m in &moves {
-            let legal = self.try_to_make_move(m);
-            if !legal {
+            if ply == 0 && ctrl.excluded_root_moves.contains(m) {
                 continue;
             }
+            // The undo data stays here, on the stack, `undos` is for the moves of the game.
+            // The hash goes into the history all the same, for the repetitions
+            let Some(undo) = self.make_if_legal(m, checkers) else {
+                continue;
+            };
+            // The child probes the table first thing, its entry loads while the move is bookkept
+            tt.prefetch(self.position.zobrist_hash);
+            self.hash_history.push(undo.zobrist_hash);
 
-            found_legal_move = true;
-            let (_best_response, eval, mut child_pv, unwind) = self.minimax_alphabeta(
-                depth - 1,
-                alpha,
-                beta,
-                !maximize,
-                stop_flag,
-                start_time,
-                time_limit,
-                nodes
+            legal_moves_searched += 1;
+            if ply == 0 {
+                ctrl.start_root_move(depth, m, legal_moves_searched);
+            }
+            let (_best_response, eval, mut child_pv, unwind) = self.negamax(
+                depth - 1 + extension,
+                ply + 1,
+                -beta,
+                -alpha,
+                ctrl,
+                tt,
             );
-            self.unmake_move();
+            let eval = -eval;
+            unmake_move(&mut self.position, undo);
+            self.hash_history.pop();
+
+            // A root move that ran out of its node budget is skipped, not the whole search
+            if ply == 0 && ctrl.finish_root_move() && !ctrl.is_stopped() {
+                ctrl.stats.skipped_root_moves += 1;
+                skipped_root_move = true;
+                continue;
+            }
             if unwind {
+                // The root moves finished before the stop are a complete result on their own.
+                // Once the old best move is among them, the best of them is at least as good a pick
+                if ply == 0 && previous_best_searched
+                    && let Some(pv) = best_pv {
+                    return (best_move.copied(), best_eval, pv, true);
+                }
                 return (None, best_eval, Vec::new(), true);
             }
+            if Some(*m) == tt_move {
+                previous_best_searched = true;
+            }
+            if ply == 0 {
+                ctrl.score_root_move(eval, eval > alpha);
+            }
 
-            let is_better = if maximize {
-                eval > best_eval
-            } else {
-                eval < best_eval
-            };
-
-            if is_better {
+            if eval > best_eval {
                 best_eval = eval;
                 best_move = Some(m);
                 child_pv.push(*m);
                 best_pv = Some(child_pv);
             }
 
-            if maximize {
-                alpha = max(alpha, eval);
-            } else {
-                beta = min(beta, eval);
-            }
-
-            if beta <= alpha {
+            alpha = max(alpha, eval);
+            if alpha >= beta {
+                ctrl.stats.beta_cutoffs += 1;
+                ctrl.stats.first_move_cutoffs += (legal_moves_searched == 1) as u64;
                 break;
             }
         }
 
-        if !found_legal_move {
-            // Checkmate
-            if is_king_in_check(&self.position, self.position.player_to_move) {
-                // losing sooner is worse
-                let eval = match self.position.player_to_move {
-                    Player::White => -CHECKMATE_EVAL + depth as i32,
-                    Player::Black =>  CHECKMATE_EVAL - depth as i32,
-                };
-                return (None, eval, Vec::new(), false);
+        let best_move = best_move.copied();
+        ctrl.put_move_list(ply, moves);
+
+        if legal_moves_searched == 0 {
+            // Checkmate, getting mated later is better
+            if !checkers.is_empty() {
+                return (None, -CHECKMATE_EVAL + ply as i32, Vec::new(), false);
             } else {  // Draw
                 return (None, DRAW_EVAL, Vec::new(), false);
             }
         }
 
-        (best_move.copied(), best_eval, best_pv.unwrap(), false)
+        // A root move that was skipped or left out could have been better
+        if !skipped_root_move && (ply > 0 || ctrl.excluded_root_moves.is_empty()) {
+            let bound = if best_eval <= original_alpha {
+                Bound::Upper
+            } else if best_eval >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+            tt.store(key, depth, ply, best_eval, bound, best_move);
+        }
+
+        // Only possible at the root, if every move ran out of its node budget
+        match best_pv {
+            Some(pv) => (best_move, best_eval, pv, false),
+            None => (None, best_eval, Vec::new(), true),
+        }
     }
 
     // Returns (best_move, best_score, nodes, pv, unwind)
+    // `best_score` is from the point of view of the side to move.
+    // On unwind, `best_move` is the best of the root moves searched completely, if there is
+    // one to trust (see `negamax`), and None otherwise
+    // `nodes` is the number of nodes searched in this call, `ctrl.nodes` accumulates
     pub fn find_best_move(
         &mut self,
         depth: usize,
-        stop_flag: &Arc<AtomicBool>,
-        start_time: Instant,
-        time_limit: Option<Duration>
+        ctrl: &mut SearchControl,
+        tt: &TranspositionTable,
     ) -> (Option<Move>, i32, u64, Vec<Move>, bool) {
-        let maximize = match self.position.player_to_move {
-            Player::White => true,
-            Player::Black => false,
-        };
-        let mut nodes = 0;
+        let nodes_before = ctrl.nodes;
 
-        let (best_move, best_eval, pv, unwind) = self.minimax_alphabeta(
+        let (best_move, best_eval, pv, unwind) = self.negamax(
             depth,  // NOT depth-1 here! compare the outputs of `go depth 1`
-            i32::MIN,
-            i32::MAX,
-            maximize,
-            stop_flag,
-            start_time,
-            time_limit,
-            &mut nodes
+            0,
+            -EVAL_INFINITY,
+            EVAL_INFINITY,
+            ctrl,
+            tt,
         );
 
-        (best_move, best_eval, nodes, pv, unwind)
+        (best_move, best_eval, ctrl.nodes - nodes_before, pv, unwind)
     }
 }
 
@@ -265,16 +548,283 @@ mod tests {
             game.try_to_make_move(&m4);
         }
 
-        assert_eq!(game.is_threefold_repetition(), true);
+        assert!(game.is_threefold_repetition());
+        assert_eq!(game.outcome(), Outcome::Draw(DrawReason::ThreefoldRepetition));
         Ok(())
     }
 
+    #[test]
+    fn repetition_in_search() {
+        let mut game = Game::default();
+        play(&mut game, "g1f3 g8f6 f3g1 f6g8");
+        // Back where the game started, 4 plies ago: a draw if the search went there itself
+        assert!(!game.is_threefold_repetition());
+        assert!(game.is_repetition(4));
+        assert!(!game.is_repetition(3));
+
+        play(&mut game, "g1f3 g8f6 f3g1 f6g8");
+        assert!(game.is_threefold_repetition());
+        assert!(game.is_repetition(1));
+
+        // Nothing before a pawn move can come back
+        play(&mut game, "e2e4 g8f6 g1f3 f6g8 f3g1");
+        assert_eq!(game.hash_history.len(), 13);
+        assert!(game.is_repetition(4));
+        assert!(!game.is_repetition(3));
+        game.unmake_move();
+        assert_eq!(game.hash_history.len(), 12);
+    }
+
     #[test]
     fn fifty_move_rule() -> Result<(), FenParseError> {
         let mut game = Game::from_fen("8/3k4/1n6/8/8/5N2/3K4/8 w - - 99 1")?;
         let m = Move::new(board::F3, board::G5, Piece::Knight, false);
         game.try_to_make_move(&m);
-        assert_eq!(game.halfmove_clock, 100);
+        assert_eq!(game.position.halfmove_clock, 100);
+        assert_eq!(game.outcome(), Outcome::Draw(DrawReason::FiftyMoveRule));
+        Ok(())
+    }
+
+    #[test]
+    fn fullmove_number() -> Result<(), FenParseError> {
+        let mut game = Game::default();
+        play(&mut game, "e2e4");
+        assert_eq!(game.fullmove_number, 1);
+        play(&mut game, "c7c5");
+        assert_eq!(game.fullmove_number, 2);
+        assert_eq!(game.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+        game.unmake_move();
+        assert_eq!(game.fullmove_number, 1);
+
+        let fen = "8/8/1k1p4/2p5/6P1/7P/5K2/8 b - - 39 100";
+        let mut game = Game::from_fen(fen)?;
+        assert_eq!(game.to_fen(), fen);
+        play(&mut game, "b6b5");
+        assert_eq!(game.to_fen(), "8/8/3p4/1kp5/6P1/7P/5K2/8 w - - 40 101");
+        game.unmake_move();
+        assert_eq!(game.to_fen(), fen);
+        Ok(())
+    }
+
+    #[test]
+    fn outcome() -> Result<(), FenParseError> {
+        let mut game = Game::default();
+        assert_eq!(game.outcome(), Outcome::Ongoing);
+        play(&mut game, "f2f3 e7e5 g2g4 d8h4");
+        assert_eq!(game.outcome(), Outcome::BlackWins(WinReason::Checkmate));
+        assert_eq!(game.outcome().to_string(), "0-1 (checkmate)");
+
+        let game = Game::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1")?;
+        assert_eq!(game.outcome(), Outcome::Draw(DrawReason::Stalemate));
+
+        let game = Game::from_fen("8/8/3k4/8/8/1K3B2/8/8 b - - 0 1")?;
+        assert_eq!(game.outcome(), Outcome::Draw(DrawReason::InsufficientMaterial));
+
+        // Mate on the move that hits the fifty-move limit still counts
+        let mut game = Game::from_fen("7k/8/6K1/8/8/8/8/R7 w - - 99 80")?;
+        play(&mut game, "a1a8");
+        assert_eq!(game.outcome(), Outcome::WhiteWins(WinReason::Checkmate));
+        Ok(())
+    }
+
+    #[test]
+    fn is_legal() -> Result<(), FenParseError> {
+        let game = Game::default();
+        assert!(game.is_legal(&Move::pawn(board::E2, board::E4, false, None, false)));
+        assert!(game.is_legal(&Move::new(board::G1, board::F3, Piece::Knight, false)));
+        assert!(!game.is_legal(&Move::new(board::G1, board::E2, Piece::Knight, false)));  // own piece
+        assert!(!game.is_legal(&Move::new(board::G1, board::F3, Piece::Bishop, false)));  // wrong piece
+        assert!(!game.is_legal(&Move::new(board::G1, board::F3, Piece::Knight, true)));   // not a capture
+        assert!(!game.is_legal(&Move::pawn(board::E7, board::E5, false, None, false)));   // not our turn
+        assert!(!game.is_legal(&Move::new(board::F1, board::C4, Piece::Bishop, false)));  // blocked
+        assert!(!game.is_legal(&Move::castling(Player::White, CastlingSide::KingSide)));
+
+        // En passant, castling through check, pinned knight
+        let game = Game::from_fen("r3k2r/8/8/3pP3/2b5/8/8/R3K2R w KQkq d6 0 1")?;
+        assert!(game.is_legal(&Move::pawn(board::E5, board::D6, true, None, true)));
+        assert!(!game.is_legal(&Move::pawn(board::E5, board::D6, true, None, false)));
+        assert!(game.is_legal(&Move::castling(Player::White, CastlingSide::QueenSide)));
+        assert!(!game.is_legal(&Move::castling(Player::White, CastlingSide::KingSide)));
+        let game = Game::from_fen("4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1")?;
+        assert!(!game.is_legal(&Move::new(board::E2, board::C3, Piece::Knight, false)));
+        Ok(())
+    }
+
+    #[test]
+    fn is_legal_uci() -> Result<(), FenParseError> {
+        let game = Game::default();
+        assert!(game.is_legal_uci("e2e4"));
+        assert!(game.is_legal_uci("b1c3"));
+        assert!(!game.is_legal_uci("e2e5"));
+        assert!(!game.is_legal_uci("e7e5"));
+        for garbage in ["", "e2", "e2e4qq", "e2e9", "E2E4", "e2e4k", "zzzz", "é2e4"] {
+            assert!(!game.is_legal_uci(garbage), "{}", garbage);
+        }
+
+        let game = Game::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1")?;
+        assert!(game.is_legal_uci("e1g1"));
+        assert!(game.is_legal_uci("e1c1"));
+        assert!(game.is_legal_uci("b7a8q"));
+        assert!(game.is_legal_uci("b7b8n"));
+        assert!(!game.is_legal_uci("b7b8"));
+        assert!(!game.is_legal_uci("a1a2q"));
+        Ok(())
+    }
+
+    #[test]
+    fn mate_in_one_is_side_relative() -> Result<(), FenParseError> {
+        use crate::core::search::{SearchControl, SearchLimits};
+        use std::sync::{atomic::AtomicBool, Arc};
+        let ctrl = || SearchControl::new(SearchLimits::infinite(), Arc::new(AtomicBool::new(false)));
+
+        // Black mates with Qh4#
+        let mut game = Game::default();
+        play(&mut game, "f2f3 e7e5 g2g4");
+        let (m, eval, _, _, _) = game.find_best_move(3, &mut ctrl(), &TranspositionTable::default());
+        assert_eq!(m.map(|m| m.to_string()).as_deref(), Some("d8h4"));
+        assert_eq!(eval, CHECKMATE_EVAL - 1);
+
+        // White mates with Ra8#, and a deeper search reports the same distance to mate
+        let mut game = Game::from_fen("7k/8/6K1/8/8/8/8/R7 w - - 0 1")?;
+        for depth in [2, 4] {
+            let (m, eval, _, _, _) = game.find_best_move(depth, &mut ctrl(), &TranspositionTable::default());
+            assert_eq!(m.map(|m| m.to_string()).as_deref(), Some("a1a8"));
+            assert_eq!(eval, CHECKMATE_EVAL - 1);
+        }
+
+        // The side getting mated sees the negated score
+        play(&mut game, "g6f6");
+        let (_, eval, _, _, _) = game.find_best_move(3, &mut ctrl(), &TranspositionTable::default());
+        assert!(eval < 0);
+        Ok(())
+    }
+
+    #[test]
+    fn single_reply_extension() -> Result<(), FenParseError> {
+        use crate::core::search::{SearchControl, SearchLimits};
+        use std::sync::{atomic::AtomicBool, Arc};
+        let mut ctrl = SearchControl::new(SearchLimits::infinite(), Arc::new(AtomicBool::new(false)));
+
+        // 1. Re8+ Rxe8 2. Rxe8#, the forced recapture makes it a mate in 2 at depth 3
+        let mut game = Game::from_fen("3r2k1/5ppp/8/8/8/8/4RPPP/4R1K1 w - - 0 1")?;
+        let (m, eval, _, pv, _) = game.find_best_move(3, &mut ctrl, &TranspositionTable::default());
+        assert_eq!(m.map(|m| m.to_string()).as_deref(), Some("e2e8"));
+        assert_eq!(eval, CHECKMATE_EVAL - 3);
+        assert_eq!(pv.len(), 3);
+        Ok(())
+    }
+
+    fn play(game: &mut Game, moves: &str) {
+        for m in moves.split_whitespace() {
+            assert!(game.try_to_make_uci_move(m));
+        }
+    }
+
+    #[test]
+    fn opening_name_najdorf() {
+        let mut game = Game::default();
+        assert_eq!(game.opening(), None);
+        play(&mut game, "e2e4 c7c5 g1f3 d7d6 d2d4 c5d4 f3d4 g8f6 b1c3 a7a6");
+        assert_eq!(game.opening().map(|o| o.to_string()).as_deref(), Some("B90 Sicilian, Najdorf"));
+    }
+
+    #[test]
+    fn opening_name_out_of_book() {
+        let mut game = Game::default();
+        play(&mut game, "e2e4 e7e5 g1f3 b8c6 f1b5 a7a6 h2h3 h7h6");
+        assert_eq!(game.opening().map(|o| o.to_string()).as_deref(), Some("C60 Ruy Lopez"));
+    }
+
+    #[test]
+    fn opening_name_transposition() {
+        let mut game = Game::default();
+        play(&mut game, "c2c4 g7g6 d2d4 g8f6");
+        assert_eq!(game.opening().map(|o| o.to_string()).as_deref(), Some("E60 King's Indian Defense"));
+    }
+
+    #[test]
+    fn san_line() {
+        let mut game = Game::default();
+        let moves: Vec<Move> = ["e2e4", "e7e5", "d1h5", "b8c6", "f1c4", "g8f6", "h5f7"].iter().map(|uci| {
+            let m = Move::from_uci(&game.position, uci).unwrap();
+            game.try_to_make_move(&m);
+            m
+        }).collect();
+        let start = Game::default();
+        assert_eq!(start.san_line(&moves), "1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7#");
+
+        let mut game = Game::default();
+        play(&mut game, "e2e4");
+        assert_eq!(game.san_line(&moves[1..2]), "1... e5");
+    }
+
+    #[test]
+    fn opening_name_after_fen() -> Result<(), FenParseError> {
+        let game = Game::from_fen("rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2")?;
+        assert_eq!(game.opening().map(|o| o.to_string()).as_deref(), Some("B20 Sicilian Defense"));
+        Ok(())
+    }
+
+    #[test]
+    fn display() -> Result<(), FenParseError> {
+        let fen = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3";
+        let shown = Game::from_fen(fen)?.to_string();
+        assert!(shown.contains(&format!("Fen: {}", fen)));
+        assert!(shown.contains("Halfmove clock: 1"));
+        assert!(shown.contains("Checkers: h4"));
+        assert!(shown.ends_with("Legal moves: 0"));
+        Ok(())
+    }
+
+    #[test]
+    fn legal_moves() -> Result<(), FenParseError> {
+        // The pawn is pinned, only the king moves
+        let game = Game::from_fen("7k/8/8/8/1b6/8/3P4/4K3 w - - 0 1")?;
+        let moves: Vec<String> = game.legal_moves().map(|m| m.to_string()).collect();
+        assert_eq!(moves, ["e1d1", "e1f1", "e1e2", "e1f2"]);
+        assert_eq!(game.legal_moves().collect::<Vec<_>>(), movegen::legal_moves(&game.position));
+        Ok(())
+    }
+
+    #[test]
+    fn navigation() -> Result<(), TakebackError> {
+        let mut game = Game::default();
+        for uci in ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"] {
+            assert!(game.try_to_make_uci_move(uci));
+        }
+        assert_eq!(game.ply(), 5);
+        assert_eq!(game.moves_played_uci(), ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"]);
+        assert_eq!(game.moves_played_san(), ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+
+        assert_eq!(game.undo(6), Err(TakebackError { requested: 6, played: 5 }));
+        assert_eq!(game.ply(), 5);
+        game.undo(2)?;
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+        assert_eq!(game.try_unmake_move()?.to_string(), "g1f3");
+        assert_eq!(game.initial().to_fen(), Game::default().to_fen());
+
+        game.undo(2)?;
+        assert_eq!(game.try_unmake_move(), Err(TakebackError { requested: 1, played: 0 }));
+        Ok(())
+    }
+
+    #[test]
+    fn null_move() -> Result<(), FenParseError> {
+        let mut game = Game::default();
+        for uci in ["e2e4", "0000", "d2d4"] {
+            assert!(game.try_to_make_uci_move(uci));
+        }
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/3PP3/8/PPP2PPP/RNBQKBNR b KQkq - 0 2");
+        assert_eq!(game.moves_played_uci(), ["e2e4", "0000", "d2d4"]);
+        assert_eq!(game.moves_played_san(), ["e4", "--", "d4"]);
+        game.undo(2).unwrap();
+        assert_eq!(game.to_fen(), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+        assert_eq!(game.position.zobrist_hash, Game::from_fen(&game.to_fen())?.position.zobrist_hash);
+
+        // Not out of check
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1")?;
+        assert!(!game.try_to_make_uci_move("0000"));
+        assert_eq!(game.ply(), 0);
         Ok(())
     }
 }
@@ -1,47 +1,191 @@
 use crate::{core::{
     chess_move::{CastlingSide::*, *},
     piece::Piece,
-    player::Player,
+    player::{Black, Player, Side, White},
+    bitboard::Bitboard,
     position::*,
-    rules::checks::is_square_attacked,
+    square::Square,
+    rules::{checks::{is_king_in_check, is_square_attacked}, make::make_move},
 }};
 use crate::utility::*;
-use crate::constants::{board::*, attacks::*, magics::*, masks::*, *};
+use crate::constants::{board::*, attacks::*, lines::LINE, magics::*, masks::*, *};
+
+pub mod reference;
+
+// Calls the `Side`-generic `f` for a color only known at runtime
+macro_rules! for_side {
+    ($player:expr, $f:ident($($arg:expr),*)) => {
+        match $player {
+            Player::White => $f::<White>($($arg),*),
+            Player::Black => $f::<Black>($($arg),*),
+        }
+    };
+}
+
+// Which way the pawns of a color go. `LEFT` and `RIGHT` are as the color itself sees the board
+trait PawnSide: Side {
+    const LEFT:       i8;
+    const FORWARD:    i8;
+    const RIGHT:      i8;
+    const START_RANK: u64;
+    const PROMO_RANK: u64;
+    const MASK_LEFT:  u64;  // the pawns that can capture to the left
+    const MASK_RIGHT: u64;
+}
+
+impl PawnSide for White {
+    const LEFT:       i8  = 7;
+    const FORWARD:    i8  = 8;
+    const RIGHT:      i8  = 9;
+    const START_RANK: u64 = RANK[2];
+    const PROMO_RANK: u64 = RANK[8];
+    const MASK_LEFT:  u64 = !FILE_A;
+    const MASK_RIGHT: u64 = !FILE_H;
+}
+
+impl PawnSide for Black {
+    const LEFT:       i8  = -7;
+    const FORWARD:    i8  = -8;
+    const RIGHT:      i8  = -9;
+    const START_RANK: u64 = RANK[7];
+    const PROMO_RANK: u64 = RANK[1];
+    const MASK_LEFT:  u64 = !FILE_H;
+    const MASK_RIGHT: u64 = !FILE_A;
+}
 
 pub fn pseudo_moves(pos: &Position) -> Vec<Move> {
-    let mut moves = Vec::with_capacity(MOVE_LIST_CAPACITY);
-    pseudo_pawn_moves(pos, &mut moves);
-    pseudo_moves_for_piece(pos, Piece::Knight, &mut moves);
-    pseudo_moves_for_piece(pos, Piece::Bishop, &mut moves);
-    pseudo_moves_for_piece(pos, Piece::Rook, &mut moves);
-    pseudo_moves_for_piece(pos, Piece::Queen, &mut moves);
-    pseudo_moves_for_piece(pos, Piece::King, &mut moves);
-    pseudo_castling_moves(pos, &mut moves);
+    let mut moves = Vec::new();
+    pseudo_moves_into(pos, &mut moves);
+    moves
+}
+
+// Replaces what is in `moves`, which keeps its allocation from one call to the next
+pub fn pseudo_moves_into(pos: &Position, moves: &mut Vec<Move>) {
+    moves.clear();
+    moves.reserve(MOVE_LIST_CAPACITY);
+    for_side!(pos.player_to_move, pseudo_moves_for(pos, moves))
+}
+
+fn pseudo_moves_for<S: PawnSide>(pos: &Position, moves: &mut Vec<Move>) {
+    pseudo_pawn_moves::<S>(pos, moves);
+    pseudo_moves_for_piece::<S>(pos, Piece::Knight, moves);
+    pseudo_moves_for_piece::<S>(pos, Piece::Bishop, moves);
+    pseudo_moves_for_piece::<S>(pos, Piece::Rook, moves);
+    pseudo_moves_for_piece::<S>(pos, Piece::Queen, moves);
+    pseudo_moves_for_piece::<S>(pos, Piece::King, moves);
+    pseudo_castling_moves::<S>(pos, moves);
+}
+
+// Slow, but does not need a `Game`. The search uses `Game::try_to_make_move` instead
+pub fn legal_moves(pos: &Position) -> Vec<Move> {
+    let mut moves = pseudo_moves(pos);
+    moves.retain(|m| leaves_king_safe(pos, m));
     moves
 }
 
-fn pseudo_castling_moves(pos: &Position, moves: &mut Vec<Move>) {
+// Exactly one of `moves`, the pseudo-legal moves of `pos`, is legal. Stops at the second legal one.
+// `checkers` are the pieces giving check in `pos`
+pub fn is_single_reply(pos: &Position, moves: &[Move], checkers: Bitboard) -> bool {
+    moves.iter()
+        .filter(|m| !could_expose_king(pos, m, checkers) || leaves_king_safe(pos, m))
+        .take(2)
+        .count() == 1
+}
+
+// Generates one piece type at a time and stops at the first legal move.
+// The king goes first, since it is the only piece that can answer a double check.
+// Castling is never tried: if it is legal, so is the king's step towards the rook
+pub fn has_legal_move(pos: &Position) -> bool {
+    for_side!(pos.player_to_move, has_legal_move_for(pos))
+}
+
+fn has_legal_move_for<S: PawnSide>(pos: &Position) -> bool {
+    let mut moves = Vec::with_capacity(MOVE_LIST_CAPACITY);
+    for piece in [Piece::King, Piece::Pawn, Piece::Knight, Piece::Bishop, Piece::Rook, Piece::Queen] {
+        moves.clear();
+        match piece {
+            Piece::Pawn => pseudo_pawn_moves::<S>(pos, &mut moves),
+            _ => pseudo_moves_for_piece::<S>(pos, piece, &mut moves),
+        }
+        if moves.iter().any(|m| leaves_king_safe(pos, m)) {
+            return true;
+        }
+    }
+    false
+}
+
+pub fn is_legal(pos: &Position, m: &Move) -> bool {
+    is_pseudo_legal(pos, m) && leaves_king_safe(pos, m)
+}
+
+// Verifies a move that did not come from the generator (user input, stored moves)
+// against this position, flags included, without generating the whole move list
+pub fn is_pseudo_legal(pos: &Position, m: &Move) -> bool {
+    for_side!(pos.player_to_move, is_pseudo_legal_for(pos, m))
+}
+
+fn is_pseudo_legal_for<S: PawnSide>(pos: &Position, m: &Move) -> bool {
+    if m.from.0 > 63 || m.to.0 > 63 {
+        return false;
+    }
+
+    if m.is_castling() {
+        let side = if m.kingside_castling { KingSide } else { QueenSide };
+        return *m == Move::castling(S::PLAYER, side) && can_castle::<S>(pos, side);
+    }
+
+    if pos.what(m.from) != Some((S::PLAYER, m.piece)) {
+        return false;
+    }
+
+    if m.piece == Piece::Pawn {
+        let mut moves = Vec::with_capacity(12);
+        pseudo_pawn_moves_from::<S>(pos, Bitboard::from_square(m.from), &mut moves);
+        return moves.contains(m);
+    }
+
+    let (my_set, enemy_set) = pos.perspective(S::PLAYER);
+    let attacks = attack_fn(m.piece)(pos, m.from, my_set.all);
+    let capture = enemy_set.all.contains(m.to);
+    attacks.contains(m.to) && *m == Move::new(m.from, m.to, m.piece, capture)
+}
+
+// Whether the pseudo-legal `m` needs `leaves_king_safe` at all. Out of check (no `checkers`), only
+// the king itself, an en passant capture (two pawns leave the rank) or a piece on a line through the
+// king, which could be pinned, can expose it
+pub(crate) fn could_expose_king(pos: &Position, m: &Move, checkers: Bitboard) -> bool {
+    let king = pos.perspective(pos.player_to_move).0.king.lsb();
+    !checkers.is_empty() || m.piece == Piece::King || m.en_passant || LINE[king.index()][m.from.index()] != 0
+}
+
+pub(crate) fn leaves_king_safe(pos: &Position, m: &Move) -> bool {
+    let mut after = *pos;
+    make_move(&mut after, m);
+    !is_king_in_check(&after, pos.player_to_move)
+}
+
+fn pseudo_castling_moves<S: Side>(pos: &Position, moves: &mut Vec<Move>) {
     for side in [KingSide, QueenSide] {
-        if can_castle(pos, side) {
-            moves.push(Move::castling(pos.player_to_move, side));
+        if can_castle::<S>(pos, side) {
+            moves.push(Move::castling(S::PLAYER, side));
         }
     }
 }
 
-fn can_castle(pos: &Position, side: CastlingSide) -> bool {
-    let king_bb = match pos.player_to_move {
-        Player::White => bit(4),
-        Player::Black => bit(60),
+fn can_castle<S: Side>(pos: &Position, side: CastlingSide) -> bool {
+    let king_bb = match S::PLAYER {
+        Player::White => Bitboard::from_square(E1),
+        Player::Black => Bitboard::from_square(E8),
     };
 
-    let mut mask = match (side, pos.player_to_move) {
+    let mut mask = Bitboard(match (side, S::PLAYER) {
         (KingSide,  Player::White) => sq_to_bb(&[E1, G1, F1]),
         (KingSide,  Player::Black) => sq_to_bb(&[E8, G8, F8]),
         (QueenSide, Player::White) => sq_to_bb(&[B1, C1, D1, E1]),
         (QueenSide, Player::Black) => sq_to_bb(&[B8, C8, D8, E8]),
-    };
+    });
 
-    let has_rights = match (side, pos.player_to_move) {
+    let has_rights = match (side, S::PLAYER) {
         (KingSide,  Player::White) => pos.castling.white_kingside,
         (KingSide,  Player::Black) => pos.castling.black_kingside,
         (QueenSide, Player::White) => pos.castling.white_queenside,
@@ -51,25 +195,20 @@ fn can_castle(pos: &Position, side: CastlingSide) -> bool {
     let is_empty = pos.occupied & mask == king_bb;
 
     if side == QueenSide {
-        pop_lsb(&mut mask);  // do not need this bit in the next check
+        mask.pop_lsb();  // do not need this bit in the next check
     }
 
     // Make sure the king doesn't pass through attacked squares
-    while mask != 0 {
-        let sq = pop_lsb(&mut mask);
-        if is_square_attacked(pos, sq.into(), pos.player_to_move.opposite()) {
-            return false;
-        }
-    };
+    if mask.squares().any(|sq| is_square_attacked(pos, sq, S::PLAYER.opposite())) {
+        return false;
+    }
 
     has_rights && is_empty
 }
 
-fn add_pawn_moves(moves: &mut Vec<Move>, to_mask: u64, offset: i8, capture: bool, promotion: bool, en_passant: bool) {
-    let mut bb = to_mask;
-    while bb != 0 {
-        let to = pop_lsb(&mut bb);
-        let from = (to as i8 - offset) as u8;
+fn add_pawn_moves(moves: &mut Vec<Move>, to_mask: Bitboard, offset: i8, capture: bool, promotion: bool, en_passant: bool) {
+    for to in to_mask {
+        let from = to.offset(-offset);
         if promotion {
             for promo in [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight] {
                 moves.push(Move::pawn(from, to, capture, Some(promo), en_passant));
@@ -82,110 +221,108 @@ fn add_pawn_moves(moves: &mut Vec<Move>, to_mask: u64, offset: i8, capture: bool
     }
 }
 
-fn pseudo_pawn_moves(pos: &Position, moves: &mut Vec<Move>) {
+fn pseudo_pawn_moves<S: PawnSide>(pos: &Position, moves: &mut Vec<Move>) {
+    pseudo_pawn_moves_from::<S>(pos, Bitboard::FULL, moves);
+}
+
+// Only for the pawns on `from_mask`
+fn pseudo_pawn_moves_from<S: PawnSide>(pos: &Position, from_mask: Bitboard, moves: &mut Vec<Move>) {
     let empty = !pos.occupied;
-    let en_passant_bb = pos.en_passant_square.map(bit).unwrap_or(0);
-    let (pawns, enemy, left_offset, forward_offset, right_offset,
-         start_rank, promo_rank, mask_right, mask_left) =
-        match pos.player_to_move {
-            Player::White => (
-                pos.w.pawns, pos.b.all, 7, 8, 9,
-                RANK[2], RANK[8], !FILE_H, !FILE_A,
-            ),
-            Player::Black => (
-                pos.b.pawns, pos.w.all, -7, -8, -9,
-                RANK[7], RANK[1], !FILE_A, !FILE_H,
-            )
-        };
-
-    let single = signed_shift(pawns, forward_offset) & empty;
-    let double = signed_shift(signed_shift(pawns & start_rank, forward_offset) & empty, forward_offset) & empty;
-    let left   = signed_shift(pawns & mask_left, left_offset) & enemy;
-    let right  = signed_shift(pawns & mask_right, right_offset) & enemy;
+    let en_passant_bb = pos.en_passant_square.map(Bitboard::from_square).unwrap_or_default();
+    let (my_set, enemy_set) = pos.perspective(S::PLAYER);
+    let enemy = enemy_set.all;
+
+    let pawns = my_set.pawns & from_mask;
+    let single = pawns.shift(S::FORWARD) & empty;
+    let double = ((pawns & S::START_RANK).shift(S::FORWARD) & empty).shift(S::FORWARD) & empty;
+    let left   = (pawns & S::MASK_LEFT).shift(S::LEFT) & enemy;
+    let right  = (pawns & S::MASK_RIGHT).shift(S::RIGHT) & enemy;
 
     // Handle promotions separately
-    let promo_push  = single & promo_rank;
-    let promo_left  = left & promo_rank;
-    let promo_right = right & promo_rank;
+    let promo_push  = single & S::PROMO_RANK;
+    let promo_left  = left & S::PROMO_RANK;
+    let promo_right = right & S::PROMO_RANK;
 
-    let non_promo_push  = single & !promo_rank;
-    let non_promo_left  = left & !promo_rank;
-    let non_promo_right = right & !promo_rank;
+    let non_promo_push  = single & !S::PROMO_RANK;
+    let non_promo_left  = left & !S::PROMO_RANK;
+    let non_promo_right = right & !S::PROMO_RANK;
 
     // En passant
-    let ep_left = signed_shift(pawns & mask_left, left_offset) & en_passant_bb;
-    let ep_right = signed_shift(pawns & mask_right, right_offset) & en_passant_bb;
+    let ep_left = (pawns & S::MASK_LEFT).shift(S::LEFT) & en_passant_bb;
+    let ep_right = (pawns & S::MASK_RIGHT).shift(S::RIGHT) & en_passant_bb;
 
-    add_pawn_moves(moves, non_promo_push,  forward_offset,     false, false, false);
-    add_pawn_moves(moves, double,          2 * forward_offset, false, false, false);
-    add_pawn_moves(moves, non_promo_left,  left_offset,        true,  false, false);
-    add_pawn_moves(moves, non_promo_right, right_offset,       true,  false, false);
+    add_pawn_moves(moves, non_promo_push,  S::FORWARD,     false, false, false);
+    add_pawn_moves(moves, double,          2 * S::FORWARD, false, false, false);
+    add_pawn_moves(moves, non_promo_left,  S::LEFT,        true,  false, false);
+    add_pawn_moves(moves, non_promo_right, S::RIGHT,       true,  false, false);
 
-    add_pawn_moves(moves, promo_push,  forward_offset, false, true, false);
-    add_pawn_moves(moves, promo_left,  left_offset,    true,  true, false);
-    add_pawn_moves(moves, promo_right, right_offset,   true,  true, false);
+    add_pawn_moves(moves, promo_push,  S::FORWARD, false, true, false);
+    add_pawn_moves(moves, promo_left,  S::LEFT,    true,  true, false);
+    add_pawn_moves(moves, promo_right, S::RIGHT,   true,  true, false);
 
-    add_pawn_moves(moves, ep_left,  left_offset,  true, false, true);
-    add_pawn_moves(moves, ep_right, right_offset, true, false, true);
+    add_pawn_moves(moves, ep_left,  S::LEFT,  true, false, true);
+    add_pawn_moves(moves, ep_right, S::RIGHT, true, false, true);
 }
 
-pub fn knight_attacks(_pos: &Position, sq: usize, friendly: u64) -> u64 {
-    KNIGHT_ATTACKS[sq] & !friendly
+pub fn knight_attacks(_pos: &Position, sq: Square, friendly: Bitboard) -> Bitboard {
+    KNIGHT_ATTACKS[sq.index()] & !friendly
 }
 
-pub fn bishop_attacks(pos: &Position, sq: usize, friendly: u64) -> u64 {
+pub fn bishop_attacks(pos: &Position, sq: Square, friendly: Bitboard) -> Bitboard {
+    let sq = sq.index();
     let mask = BISHOP_MASKS[sq];
     let magic = BISHOP_MAGICS[sq];
     let shift = BISHOP_MAGICS_SHIFT[sq];
-    let blockers = pos.occupied & mask;
+    let blockers = (pos.occupied & mask).0;
     let hash = (blockers.wrapping_mul(magic) >> shift) as usize;
     BISHOP_ATTACK_TABLES[sq][hash] & !friendly
 }
 
-pub fn rook_attacks(pos: &Position, sq: usize, friendly: u64) -> u64 {
+pub fn rook_attacks(pos: &Position, sq: Square, friendly: Bitboard) -> Bitboard {
+    let sq = sq.index();
     let mask = ROOK_MASKS[sq];
     let magic = ROOK_MAGICS[sq];
     let shift = ROOK_MAGICS_SHIFT[sq];
-    let blockers = pos.occupied & mask;
+    let blockers = (pos.occupied & mask).0;
     let hash = (blockers.wrapping_mul(magic) >> shift) as usize;
     ROOK_ATTACK_TABLES[sq][hash] & !friendly
 }
 
-pub fn queen_attacks(pos: &Position, sq: usize, friendly: u64) -> u64 {
+pub fn queen_attacks(pos: &Position, sq: Square, friendly: Bitboard) -> Bitboard {
     let rook_attacks = rook_attacks(pos, sq, friendly);
     let bishop_attacks = bishop_attacks(pos, sq, friendly);
     rook_attacks | bishop_attacks
 }
 
-pub fn king_attacks(_pos: &Position, sq: usize, friendly: u64) -> u64 {
-    KING_ATTACKS[sq] & !friendly
+pub fn king_attacks(_pos: &Position, sq: Square, friendly: Bitboard) -> Bitboard {
+    KING_ATTACKS[sq.index()] & !friendly
 }
 
-fn pseudo_moves_for_piece(pos: &Position, piece_type: Piece, moves: &mut Vec<Move>) {
-    let (my_set, enemy_set) = match pos.player_to_move {
-        Player::White => (&pos.w, &pos.b),
-        Player::Black => (&pos.b, &pos.w),
-    };
+type AttackFn = fn(&Position, Square, Bitboard) -> Bitboard;
+
+fn attack_fn(piece_type: Piece) -> AttackFn {
+    match piece_type {
+        Piece::Knight => knight_attacks,
+        Piece::Bishop => bishop_attacks,
+        Piece::Rook   => rook_attacks,
+        Piece::Queen  => queen_attacks,
+        Piece::King   => king_attacks,
+        Piece::Pawn   => panic!("aaaghhh")
+    }
+}
+
+fn pseudo_moves_for_piece<S: Side>(pos: &Position, piece_type: Piece, moves: &mut Vec<Move>) {
+    let (my_set, enemy_set) = pos.perspective(S::PLAYER);
     let friendly = my_set.all;
     let hostile = enemy_set.all;
 
-    type AttackFn = fn(&Position, usize, u64) -> u64;
-    let (mut pieces, attack_fn): (u64, AttackFn) = match piece_type {
-        Piece::Knight => (my_set.knights, knight_attacks),
-        Piece::Bishop => (my_set.bishops, bishop_attacks),
-        Piece::Rook   => (my_set.rooks, rook_attacks),
-        Piece::Queen  => (my_set.queens, queen_attacks),
-        Piece::King   => (my_set.king, king_attacks),
-        Piece::Pawn   => panic!("aaaghhh")
-    };
+    let pieces = *my_set.piece_to_bb(piece_type);
+    let attack_fn = attack_fn(piece_type);
 
-    while pieces != 0 {
-        let from = pop_lsb(&mut pieces) as usize;
-        let mut attacks = attack_fn(pos, from, friendly);
-        while attacks != 0 {
-            let to = pop_lsb(&mut attacks);
-            let capture = (bit(to) & hostile) > 0;
-            moves.push(Move::new(from as u8, to, piece_type, capture));
+    for from in pieces {
+        for to in attack_fn(pos, from, friendly) {
+            let capture = hostile.contains(to);
+            moves.push(Move::new(from, to, piece_type, capture));
         }
     }
 }
@@ -194,31 +331,100 @@ fn pseudo_moves_for_piece(pos: &Position, piece_type: Piece, moves: &mut Vec<Mov
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::rules::checks::checkers;
     use std::collections::HashSet;
 
+    #[test]
+    fn has_legal_move_matches_legal_moves() -> Result<(), FenParseError> {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",  // mate
+            "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",                                 // stalemate
+            "7k/8/8/8/8/1n6/8/K1q5 w - - 0 1",                                // double check
+            "4k3/8/8/8/8/8/4p3/4K3 w - - 0 1",                                // only the king moves
+            "7k/8/8/8/8/8/2q4P/K7 w - - 0 1",                                 // only the pawn moves
+        ] {
+            let (pos, _) = Position::from_fen(fen)?;
+            assert_eq!(has_legal_move(&pos), !legal_moves(&pos).is_empty(), "{}", fen);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn single_reply() -> Result<(), FenParseError> {
+        for (fen, single) in [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", false),
+            ("7k/8/8/8/8/8/8/K6q w - - 0 1", false),       // in check, Ka2 and Kb2
+            ("7k/8/8/8/8/8/6q1/K7 w - - 0 1", true),       // Kb1, the rest is covered
+            ("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1", false),     // stalemate, no reply at all
+        ] {
+            let (pos, _) = Position::from_fen(fen)?;
+            assert_eq!(is_single_reply(&pos, &pseudo_moves(&pos), checkers(&pos)), single, "{}", fen);
+            assert_eq!(legal_moves(&pos).len() == 1, single, "{}", fen);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn could_expose_king_misses_no_illegal_move() -> Result<(), FenParseError> {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "8/8/8/KPp4r/8/8/8/7k w - c6 0 1",                                   // en passant exposes the king
+            "4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1",                                 // pinned bishop
+            "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+        ] {
+            let (pos, _) = Position::from_fen(fen)?;
+            let checkers = checkers(&pos);
+            for m in pseudo_moves(&pos) {
+                if !could_expose_king(&pos, &m, checkers) {
+                    assert!(leaves_king_safe(&pos, &m), "{} {}", fen, m);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn is_pseudo_legal_accepts_generated_moves() -> Result<(), FenParseError> {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/Pp2P3/2N2Q1p/1PPBBPPP/R3K2R b KQkq a3 0 1",
+            "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ] {
+            let (pos, _) = Position::from_fen(fen)?;
+            let pseudo = pseudo_moves(&pos);
+            assert!(pseudo.iter().all(|m| is_pseudo_legal(&pos, m)), "{}", fen);
+            let legal: Vec<Move> = pseudo.into_iter().filter(|m| is_legal(&pos, m)).collect();
+            assert_eq!(legal, legal_moves(&pos), "{}", fen);
+        }
+        Ok(())
+    }
+
     #[test]
     fn pseudo_pawn_moves_start_position() {
         let pos = Position::start();
         let mut moves = Vec::new();
-        pseudo_pawn_moves(&pos, &mut moves);
+        pseudo_pawn_moves::<White>(&pos, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::pawn(8, 16, false, None, false),
-            Move::pawn(9, 17, false, None, false),
-            Move::pawn(10, 18, false, None, false),
-            Move::pawn(11, 19, false, None, false),
-            Move::pawn(12, 20, false, None, false),
-            Move::pawn(13, 21, false, None, false),
-            Move::pawn(14, 22, false, None, false),
-            Move::pawn(15, 23, false, None, false),
-            Move::pawn(8, 24, false, None, false),
-            Move::pawn(9, 25, false, None, false),
-            Move::pawn(10, 26, false, None, false),
-            Move::pawn(11, 27, false, None, false),
-            Move::pawn(12, 28, false, None, false),
-            Move::pawn(13, 29, false, None, false),
-            Move::pawn(14, 30, false, None, false),
-            Move::pawn(15, 31, false, None, false),
+            Move::pawn(A2, A3, false, None, false),
+            Move::pawn(B2, B3, false, None, false),
+            Move::pawn(C2, C3, false, None, false),
+            Move::pawn(D2, D3, false, None, false),
+            Move::pawn(E2, E3, false, None, false),
+            Move::pawn(F2, F3, false, None, false),
+            Move::pawn(G2, G3, false, None, false),
+            Move::pawn(H2, H3, false, None, false),
+            Move::pawn(A2, A4, false, None, false),
+            Move::pawn(B2, B4, false, None, false),
+            Move::pawn(C2, C4, false, None, false),
+            Move::pawn(D2, D4, false, None, false),
+            Move::pawn(E2, E4, false, None, false),
+            Move::pawn(F2, F4, false, None, false),
+            Move::pawn(G2, G4, false, None, false),
+            Move::pawn(H2, H4, false, None, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -229,40 +435,40 @@ mod tests {
     fn pseudo_pawn_moves_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/p1pp3p/BN6/3R4/1k2K3/8/1p3pp1/2Q1B3 b - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_pawn_moves(&pos, &mut moves);
+        pseudo_pawn_moves::<Black>(&pos, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::pawn(48, 41, true, None, false),
-            Move::pawn(50, 41, true, None, false),
-            Move::pawn(50, 42, false, None, false),
-            Move::pawn(50, 34, false, None, false),
-            Move::pawn(51, 43, false, None, false),
-            Move::pawn(55, 47, false, None, false),
-            Move::pawn(55, 39, false, None, false),
-
-            Move::pawn(9, 1, false, Some(Piece::Knight), false),
-            Move::pawn(9, 2, true, Some(Piece::Knight), false),
-            Move::pawn(13, 5, false, Some(Piece::Knight), false),
-            Move::pawn(13, 4, true, Some(Piece::Knight), false),
-            Move::pawn(14, 6, false, Some(Piece::Knight), false),
-
-            Move::pawn(9, 1, false, Some(Piece::Bishop), false),
-            Move::pawn(9, 2, true, Some(Piece::Bishop), false),
-            Move::pawn(13, 5, false, Some(Piece::Bishop), false),
-            Move::pawn(13, 4, true, Some(Piece::Bishop), false),
-            Move::pawn(14, 6, false, Some(Piece::Bishop), false),
-
-            Move::pawn(9, 1, false, Some(Piece::Rook), false),
-            Move::pawn(9, 2, true, Some(Piece::Rook), false),
-            Move::pawn(13, 5, false, Some(Piece::Rook), false),
-            Move::pawn(13, 4, true, Some(Piece::Rook), false),
-            Move::pawn(14, 6, false, Some(Piece::Rook), false),
-
-            Move::pawn(9, 1, false, Some(Piece::Queen), false),
-            Move::pawn(9, 2, true, Some(Piece::Queen), false),
-            Move::pawn(13, 5, false, Some(Piece::Queen), false),
-            Move::pawn(13, 4, true, Some(Piece::Queen), false),
-            Move::pawn(14, 6, false, Some(Piece::Queen), false),
+            Move::pawn(A7, B6, true, None, false),
+            Move::pawn(C7, B6, true, None, false),
+            Move::pawn(C7, C6, false, None, false),
+            Move::pawn(C7, C5, false, None, false),
+            Move::pawn(D7, D6, false, None, false),
+            Move::pawn(H7, H6, false, None, false),
+            Move::pawn(H7, H5, false, None, false),
+
+            Move::pawn(B2, B1, false, Some(Piece::Knight), false),
+            Move::pawn(B2, C1, true, Some(Piece::Knight), false),
+            Move::pawn(F2, F1, false, Some(Piece::Knight), false),
+            Move::pawn(F2, E1, true, Some(Piece::Knight), false),
+            Move::pawn(G2, G1, false, Some(Piece::Knight), false),
+
+            Move::pawn(B2, B1, false, Some(Piece::Bishop), false),
+            Move::pawn(B2, C1, true, Some(Piece::Bishop), false),
+            Move::pawn(F2, F1, false, Some(Piece::Bishop), false),
+            Move::pawn(F2, E1, true, Some(Piece::Bishop), false),
+            Move::pawn(G2, G1, false, Some(Piece::Bishop), false),
+
+            Move::pawn(B2, B1, false, Some(Piece::Rook), false),
+            Move::pawn(B2, C1, true, Some(Piece::Rook), false),
+            Move::pawn(F2, F1, false, Some(Piece::Rook), false),
+            Move::pawn(F2, E1, true, Some(Piece::Rook), false),
+            Move::pawn(G2, G1, false, Some(Piece::Rook), false),
+
+            Move::pawn(B2, B1, false, Some(Piece::Queen), false),
+            Move::pawn(B2, C1, true, Some(Piece::Queen), false),
+            Move::pawn(F2, F1, false, Some(Piece::Queen), false),
+            Move::pawn(F2, E1, true, Some(Piece::Queen), false),
+            Move::pawn(G2, G1, false, Some(Piece::Queen), false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -274,12 +480,12 @@ mod tests {
     fn pseudo_pawn_moves_en_passant() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/6k1/8/1pP1Pp2/8/8/5K2/8 w - b6 0 1")?;
         let mut moves = Vec::new();
-        pseudo_pawn_moves(&pos, &mut moves);
+        pseudo_pawn_moves::<White>(&pos, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::pawn(34, 42, false, None, false),
-            Move::pawn(34, 41, true, None, true),
-            Move::pawn(36, 44, false, None, false),
+            Move::pawn(C5, C6, false, None, false),
+            Move::pawn(C5, B6, true, None, true),
+            Move::pawn(E5, E6, false, None, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -291,13 +497,13 @@ mod tests {
     fn pseudo_knight_moves_start_position() {
         let pos = Position::start();
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Knight, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Knight, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(1, 16, Piece::Knight, false),
-            Move::new(1, 18, Piece::Knight, false),
-            Move::new(6, 21, Piece::Knight, false),
-            Move::new(6, 23, Piece::Knight, false),
+            Move::new(B1, A3, Piece::Knight, false),
+            Move::new(B1, C3, Piece::Knight, false),
+            Move::new(G1, F3, Piece::Knight, false),
+            Move::new(G1, H3, Piece::Knight, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -308,29 +514,29 @@ mod tests {
     fn pseudo_knight_moves_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/3nk3/1N3R2/3n2n1/3N4/8/3K1N2/1r6 w - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Knight, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Knight, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(13, 3, Piece::Knight, false),
-            Move::new(13, 7, Piece::Knight, false),
-            Move::new(13, 19, Piece::Knight, false),
-            Move::new(13, 23, Piece::Knight, false),
-            Move::new(13, 28, Piece::Knight, false),
-            Move::new(13, 30, Piece::Knight, false),
-            Move::new(27, 10, Piece::Knight, false),
-            Move::new(27, 12, Piece::Knight, false),
-            Move::new(27, 17, Piece::Knight, false),
-            Move::new(27, 21, Piece::Knight, false),
-            Move::new(27, 33, Piece::Knight, false),
-            Move::new(27, 37, Piece::Knight, false),
-            Move::new(27, 42, Piece::Knight, false),
-            Move::new(27, 44, Piece::Knight, false),
-            Move::new(41, 24, Piece::Knight, false),
-            Move::new(41, 26, Piece::Knight, false),
-            Move::new(41, 35, Piece::Knight, true),
-            Move::new(41, 51, Piece::Knight, true),
-            Move::new(41, 56, Piece::Knight, false),
-            Move::new(41, 58, Piece::Knight, false),
+            Move::new(F2, D1, Piece::Knight, false),
+            Move::new(F2, H1, Piece::Knight, false),
+            Move::new(F2, D3, Piece::Knight, false),
+            Move::new(F2, H3, Piece::Knight, false),
+            Move::new(F2, E4, Piece::Knight, false),
+            Move::new(F2, G4, Piece::Knight, false),
+            Move::new(D4, C2, Piece::Knight, false),
+            Move::new(D4, E2, Piece::Knight, false),
+            Move::new(D4, B3, Piece::Knight, false),
+            Move::new(D4, F3, Piece::Knight, false),
+            Move::new(D4, B5, Piece::Knight, false),
+            Move::new(D4, F5, Piece::Knight, false),
+            Move::new(D4, C6, Piece::Knight, false),
+            Move::new(D4, E6, Piece::Knight, false),
+            Move::new(B6, A4, Piece::Knight, false),
+            Move::new(B6, C4, Piece::Knight, false),
+            Move::new(B6, D5, Piece::Knight, true),
+            Move::new(B6, D7, Piece::Knight, true),
+            Move::new(B6, A8, Piece::Knight, false),
+            Move::new(B6, C8, Piece::Knight, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -342,7 +548,7 @@ mod tests {
     fn pseudo_king_moves_start_position() {
         let pos = Position::start();
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::King, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::King, &mut moves);
         assert_eq!(moves.len(), 0);
     }
 
@@ -350,16 +556,16 @@ mod tests {
     fn pseudo_king_moves_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/8/8/8/7P/6K1/1r6/k7 w - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::King, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::King, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(22, 13, Piece::King, false),
-            Move::new(22, 14, Piece::King, false),
-            Move::new(22, 15, Piece::King, false),
-            Move::new(22, 21, Piece::King, false),
-            Move::new(22, 23, Piece::King, false),
-            Move::new(22, 29, Piece::King, false),
-            Move::new(22, 30, Piece::King, false),
+            Move::new(G3, F2, Piece::King, false),
+            Move::new(G3, G2, Piece::King, false),
+            Move::new(G3, H2, Piece::King, false),
+            Move::new(G3, F3, Piece::King, false),
+            Move::new(G3, H3, Piece::King, false),
+            Move::new(G3, F4, Piece::King, false),
+            Move::new(G3, G4, Piece::King, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -371,7 +577,7 @@ mod tests {
     fn pseudo_rook_moves_start_position() {
         let pos = Position::start();
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Rook, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Rook, &mut moves);
         assert_eq!(moves.len(), 0);
     }
 
@@ -379,25 +585,25 @@ mod tests {
     fn pseudo_rook_moves_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/3k4/8/R3p3/6P1/1P6/3K2R1/8 w - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Rook, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Rook, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(32, 40, Piece::Rook, false),
-            Move::new(32, 48, Piece::Rook, false),
-            Move::new(32, 56, Piece::Rook, false),
-            Move::new(32, 24, Piece::Rook, false),
-            Move::new(32, 16, Piece::Rook, false),
-            Move::new(32, 8, Piece::Rook, false),
-            Move::new(32, 0, Piece::Rook, false),
-            Move::new(32, 33, Piece::Rook, false),
-            Move::new(32, 34, Piece::Rook, false),
-            Move::new(32, 35, Piece::Rook, false),
-            Move::new(32, 36, Piece::Rook, true),
-            Move::new(14, 6, Piece::Rook, false),
-            Move::new(14, 22, Piece::Rook, false),
-            Move::new(14, 15, Piece::Rook, false),
-            Move::new(14, 13, Piece::Rook, false),
-            Move::new(14, 12, Piece::Rook, false),
+            Move::new(A5, A6, Piece::Rook, false),
+            Move::new(A5, A7, Piece::Rook, false),
+            Move::new(A5, A8, Piece::Rook, false),
+            Move::new(A5, A4, Piece::Rook, false),
+            Move::new(A5, A3, Piece::Rook, false),
+            Move::new(A5, A2, Piece::Rook, false),
+            Move::new(A5, A1, Piece::Rook, false),
+            Move::new(A5, B5, Piece::Rook, false),
+            Move::new(A5, C5, Piece::Rook, false),
+            Move::new(A5, D5, Piece::Rook, false),
+            Move::new(A5, E5, Piece::Rook, true),
+            Move::new(G2, G1, Piece::Rook, false),
+            Move::new(G2, G3, Piece::Rook, false),
+            Move::new(G2, H2, Piece::Rook, false),
+            Move::new(G2, F2, Piece::Rook, false),
+            Move::new(G2, E2, Piece::Rook, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -409,7 +615,7 @@ mod tests {
     fn pseudo_bishop_moves_start_position() {
         let pos = Position::start();
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Bishop, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Bishop, &mut moves);
         assert_eq!(moves.len(), 0);
     }
 
@@ -417,41 +623,41 @@ mod tests {
     fn pseudo_bishop_moves_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/8/8/3b4/5P1b/1k6/3b3K/b7 b - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Bishop, &mut moves);
+        pseudo_moves_for_piece::<Black>(&pos, Piece::Bishop, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(0, 9, Piece::Bishop, false),
-            Move::new(0, 18, Piece::Bishop, false),
-            Move::new(0, 27, Piece::Bishop, false),
-            Move::new(0, 36, Piece::Bishop, false),
-            Move::new(0, 45, Piece::Bishop, false),
-            Move::new(0, 54, Piece::Bishop, false),
-            Move::new(0, 63, Piece::Bishop, false),
-            Move::new(11, 2, Piece::Bishop, false),
-            Move::new(11, 4, Piece::Bishop, false),
-            Move::new(11, 18, Piece::Bishop, false),
-            Move::new(11, 25, Piece::Bishop, false),
-            Move::new(11, 32, Piece::Bishop, false),
-            Move::new(11, 20, Piece::Bishop, false),
-            Move::new(11, 29, Piece::Bishop, true),
-            Move::new(31, 22, Piece::Bishop, false),
-            Move::new(31, 13, Piece::Bishop, false),
-            Move::new(31, 4, Piece::Bishop, false),
-            Move::new(31, 38, Piece::Bishop, false),
-            Move::new(31, 45, Piece::Bishop, false),
-            Move::new(31, 52, Piece::Bishop, false),
-            Move::new(31, 59, Piece::Bishop, false),
-            Move::new(35, 26, Piece::Bishop, false),
-            Move::new(35, 44, Piece::Bishop, false),
-            Move::new(35, 53, Piece::Bishop, false),
-            Move::new(35, 62, Piece::Bishop, false),
-            Move::new(35, 42, Piece::Bishop, false),
-            Move::new(35, 49, Piece::Bishop, false),
-            Move::new(35, 56, Piece::Bishop, false),
-            Move::new(35, 28, Piece::Bishop, false),
-            Move::new(35, 21, Piece::Bishop, false),
-            Move::new(35, 14, Piece::Bishop, false),
-            Move::new(35, 7, Piece::Bishop, false),
+            Move::new(A1, B2, Piece::Bishop, false),
+            Move::new(A1, C3, Piece::Bishop, false),
+            Move::new(A1, D4, Piece::Bishop, false),
+            Move::new(A1, E5, Piece::Bishop, false),
+            Move::new(A1, F6, Piece::Bishop, false),
+            Move::new(A1, G7, Piece::Bishop, false),
+            Move::new(A1, H8, Piece::Bishop, false),
+            Move::new(D2, C1, Piece::Bishop, false),
+            Move::new(D2, E1, Piece::Bishop, false),
+            Move::new(D2, C3, Piece::Bishop, false),
+            Move::new(D2, B4, Piece::Bishop, false),
+            Move::new(D2, A5, Piece::Bishop, false),
+            Move::new(D2, E3, Piece::Bishop, false),
+            Move::new(D2, F4, Piece::Bishop, true),
+            Move::new(H4, G3, Piece::Bishop, false),
+            Move::new(H4, F2, Piece::Bishop, false),
+            Move::new(H4, E1, Piece::Bishop, false),
+            Move::new(H4, G5, Piece::Bishop, false),
+            Move::new(H4, F6, Piece::Bishop, false),
+            Move::new(H4, E7, Piece::Bishop, false),
+            Move::new(H4, D8, Piece::Bishop, false),
+            Move::new(D5, C4, Piece::Bishop, false),
+            Move::new(D5, E6, Piece::Bishop, false),
+            Move::new(D5, F7, Piece::Bishop, false),
+            Move::new(D5, G8, Piece::Bishop, false),
+            Move::new(D5, C6, Piece::Bishop, false),
+            Move::new(D5, B7, Piece::Bishop, false),
+            Move::new(D5, A8, Piece::Bishop, false),
+            Move::new(D5, E4, Piece::Bishop, false),
+            Move::new(D5, F3, Piece::Bishop, false),
+            Move::new(D5, G2, Piece::Bishop, false),
+            Move::new(D5, H1, Piece::Bishop, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -463,13 +669,13 @@ mod tests {
     fn pseudo_bishop_moves_blocking_friendly() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("1k3K2/8/1P3P2/8/3B4/8/1P3P2/8 w - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Bishop, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Bishop, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(27, 18, Piece::Bishop, false),
-            Move::new(27, 20, Piece::Bishop, false),
-            Move::new(27, 34, Piece::Bishop, false),
-            Move::new(27, 36, Piece::Bishop, false),
+            Move::new(D4, C3, Piece::Bishop, false),
+            Move::new(D4, E3, Piece::Bishop, false),
+            Move::new(D4, C5, Piece::Bishop, false),
+            Move::new(D4, E5, Piece::Bishop, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -481,17 +687,17 @@ mod tests {
     fn pseudo_bishop_moves_blocking_hostile() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("1k3K2/8/1p3p2/8/3B4/8/1p3p2/8 w - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Bishop, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Bishop, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(27, 18, Piece::Bishop, false),
-            Move::new(27, 9, Piece::Bishop, true),
-            Move::new(27, 20, Piece::Bishop, false),
-            Move::new(27, 13, Piece::Bishop, true),
-            Move::new(27, 34, Piece::Bishop, false),
-            Move::new(27, 41, Piece::Bishop, true),
-            Move::new(27, 36, Piece::Bishop, false),
-            Move::new(27, 45, Piece::Bishop, true),
+            Move::new(D4, C3, Piece::Bishop, false),
+            Move::new(D4, B2, Piece::Bishop, true),
+            Move::new(D4, E3, Piece::Bishop, false),
+            Move::new(D4, F2, Piece::Bishop, true),
+            Move::new(D4, C5, Piece::Bishop, false),
+            Move::new(D4, B6, Piece::Bishop, true),
+            Move::new(D4, E5, Piece::Bishop, false),
+            Move::new(D4, F6, Piece::Bishop, true),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -503,7 +709,7 @@ mod tests {
     fn pseudo_queen_moves_start_position() {
         let pos = Position::start();
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Queen, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Queen, &mut moves);
         assert_eq!(moves.len(), 0);
     }
 
@@ -511,29 +717,29 @@ mod tests {
     fn pseudo_queen_moves_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/k3b3/2r5/8/4Q1N1/8/2K5/8 w - - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_moves_for_piece(&pos, Piece::Queen, &mut moves);
+        pseudo_moves_for_piece::<White>(&pos, Piece::Queen, &mut moves);
 
         let expected: HashSet<Move> = [
-            Move::new(28, 4, Piece::Queen, false),
-            Move::new(28, 7, Piece::Queen, false),
-            Move::new(28, 12, Piece::Queen, false),
-            Move::new(28, 14, Piece::Queen, false),
-            Move::new(28, 19, Piece::Queen, false),
-            Move::new(28, 20, Piece::Queen, false),
-            Move::new(28, 21, Piece::Queen, false),
-            Move::new(28, 24, Piece::Queen, false),
-            Move::new(28, 25, Piece::Queen, false),
-            Move::new(28, 26, Piece::Queen, false),
-            Move::new(28, 27, Piece::Queen, false),
-            Move::new(28, 29, Piece::Queen, false),
-            Move::new(28, 35, Piece::Queen, false),
-            Move::new(28, 36, Piece::Queen, false),
-            Move::new(28, 37, Piece::Queen, false),
-            Move::new(28, 42, Piece::Queen, true),
-            Move::new(28, 44, Piece::Queen, false),
-            Move::new(28, 46, Piece::Queen, false),
-            Move::new(28, 52, Piece::Queen, true),
-            Move::new(28, 55, Piece::Queen, false),
+            Move::new(E4, E1, Piece::Queen, false),
+            Move::new(E4, H1, Piece::Queen, false),
+            Move::new(E4, E2, Piece::Queen, false),
+            Move::new(E4, G2, Piece::Queen, false),
+            Move::new(E4, D3, Piece::Queen, false),
+            Move::new(E4, E3, Piece::Queen, false),
+            Move::new(E4, F3, Piece::Queen, false),
+            Move::new(E4, A4, Piece::Queen, false),
+            Move::new(E4, B4, Piece::Queen, false),
+            Move::new(E4, C4, Piece::Queen, false),
+            Move::new(E4, D4, Piece::Queen, false),
+            Move::new(E4, F4, Piece::Queen, false),
+            Move::new(E4, D5, Piece::Queen, false),
+            Move::new(E4, E5, Piece::Queen, false),
+            Move::new(E4, F5, Piece::Queen, false),
+            Move::new(E4, C6, Piece::Queen, true),
+            Move::new(E4, E6, Piece::Queen, false),
+            Move::new(E4, G6, Piece::Queen, false),
+            Move::new(E4, E7, Piece::Queen, true),
+            Move::new(E4, H7, Piece::Queen, false),
         ].into();
 
         let moves_set: HashSet<Move> = moves.into_iter().collect();
@@ -545,7 +751,7 @@ mod tests {
     fn pseudo_castling_moves_midgame1() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("rnb1k1nr/pppp1ppp/3b1q2/4p3/2BPP3/2P2N2/PP3PPP/RNBQK2R w KQkq - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_castling_moves(&pos, &mut moves);
+        pseudo_castling_moves::<White>(&pos, &mut moves);
         assert_eq!(moves.len(), 1);
         assert_eq!(moves[0], Move::castling(Player::White, CastlingSide::KingSide));
         Ok(())
@@ -555,7 +761,7 @@ mod tests {
     fn pseudo_castling_moves_midgame2() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("r3kbnr/ppp2ppp/2np2b1/4p2q/4P3/5PP1/PPPP3P/RNBQKBNR b KQkq - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_castling_moves(&pos, &mut moves);
+        pseudo_castling_moves::<Black>(&pos, &mut moves);
         assert_eq!(moves.len(), 1);
         assert_eq!(moves[0], Move::castling(Player::Black, CastlingSide::QueenSide));
         Ok(())
@@ -565,7 +771,7 @@ mod tests {
     fn pseudo_castling_moves_should_generate_nothing() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("r3kbnr/ppp2ppp/2np2b1/4p2q/4P3/3P1PPB/PPP4P/RNBQK1NR b KQkq - 0 1")?;
         let mut moves = Vec::new();
-        pseudo_castling_moves(&pos, &mut moves);
+        pseudo_castling_moves::<Black>(&pos, &mut moves);
         assert_eq!(moves.len(), 0);
         Ok(())
     }
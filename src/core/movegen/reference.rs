@@ -0,0 +1,206 @@
+// A slow move generator to check the fast one against. It walks the board one square at a time
+// from the mailbox: no bitboards, no magics, no attack tables
+use std::collections::HashSet;
+use crate::core::{
+    chess_move::{CastlingSide, Move},
+    movegen::legal_moves,
+    piece::Piece,
+    player::Player,
+    position::Position,
+    rules::make::make_move,
+    square::{File, Rank, Square},
+};
+
+const KNIGHT_STEPS: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+const KING_STEPS:   [(i8, i8); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const ROOK_DIRECTIONS:   [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+// Panics if the fast generator and the reference disagree on the legal moves of `pos`, or if the
+// fast one has a move twice. Called at every perft node with the `verify` feature
+pub fn verify_movegen(pos: &Position) {
+    let generated = legal_moves(pos);
+    let fast: HashSet<Move> = generated.iter().copied().collect();
+    let reference: HashSet<Move> = reference_legal_moves(pos).into_iter().collect();
+    let list = |moves: Vec<&Move>| moves.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(" ");
+    assert!(fast == reference, "move generation, {}: missing [{}], extra [{}]", pos.to_fen(1),
+        list(reference.difference(&fast).collect()), list(fast.difference(&reference).collect()));
+    assert_eq!(generated.len(), fast.len(), "move generation, {}: duplicate moves", pos.to_fen(1));
+}
+
+pub fn reference_legal_moves(pos: &Position) -> Vec<Move> {
+    let player = pos.player_to_move;
+    let mut moves = Vec::new();
+    for from in Square::all() {
+        match pos.what(from) {
+            Some((owner, Piece::Pawn)) if owner == player => pawn_moves(pos, from, &mut moves),
+            Some((owner, piece)) if owner == player => piece_moves(pos, from, piece, &mut moves),
+            _ => {}
+        }
+    }
+    castling_moves(pos, &mut moves);
+
+    moves.retain(|m| {
+        let mut after = *pos;
+        make_move(&mut after, m);
+        Square::all()
+            .filter(|&sq| after.what(sq) == Some((player, Piece::King)))
+            .all(|king| !is_attacked(&after, king, player.opposite()))
+    });
+    moves
+}
+
+// `sq` moved by (files, ranks), None off the board
+fn step(sq: Square, (files, ranks): (i8, i8)) -> Option<Square> {
+    let file = sq.file().0 as i8 + files;
+    let rank = sq.rank().0 as i8 + ranks;
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then(|| Square::new(File(file as u8), Rank(rank as u8)))
+}
+
+// The squares a slider on `sq` reaches in one direction, up to and including the first piece
+fn ray(pos: &Position, sq: Square, direction: (i8, i8)) -> Vec<Square> {
+    let mut squares = Vec::new();
+    let mut current = sq;
+    while let Some(next) = step(current, direction) {
+        squares.push(next);
+        if pos.what(next).is_some() {
+            break;
+        }
+        current = next;
+    }
+    squares
+}
+
+fn is_attacked(pos: &Position, sq: Square, by: Player) -> bool {
+    let holds = |sq: Option<Square>, pieces: &[Piece]| {
+        sq.and_then(|sq| pos.what(sq)).is_some_and(|(owner, piece)| owner == by && pieces.contains(&piece))
+    };
+    // The pawns attack forward, so an attacking pawn is one rank behind `sq` as `by` sees the board
+    let behind = match by {
+        Player::White => -1,
+        Player::Black => 1,
+    };
+    KNIGHT_STEPS.iter().any(|&d| holds(step(sq, d), &[Piece::Knight]))
+        || KING_STEPS.iter().any(|&d| holds(step(sq, d), &[Piece::King]))
+        || [-1, 1].iter().any(|&files| holds(step(sq, (files, behind)), &[Piece::Pawn]))
+        || BISHOP_DIRECTIONS.iter().any(|&d| holds(ray(pos, sq, d).last().copied(), &[Piece::Bishop, Piece::Queen]))
+        || ROOK_DIRECTIONS.iter().any(|&d| holds(ray(pos, sq, d).last().copied(), &[Piece::Rook, Piece::Queen]))
+}
+
+fn piece_moves(pos: &Position, from: Square, piece: Piece, moves: &mut Vec<Move>) {
+    let targets: Vec<Square> = match piece {
+        Piece::Knight => KNIGHT_STEPS.iter().filter_map(|&d| step(from, d)).collect(),
+        Piece::King   => KING_STEPS.iter().filter_map(|&d| step(from, d)).collect(),
+        Piece::Bishop => BISHOP_DIRECTIONS.iter().flat_map(|&d| ray(pos, from, d)).collect(),
+        Piece::Rook   => ROOK_DIRECTIONS.iter().flat_map(|&d| ray(pos, from, d)).collect(),
+        _ => BISHOP_DIRECTIONS.iter().chain(&ROOK_DIRECTIONS).flat_map(|&d| ray(pos, from, d)).collect(),
+    };
+    for to in targets {
+        match pos.what(to) {
+            None => moves.push(Move::new(from, to, piece, false)),
+            Some((owner, _)) if owner != pos.player_to_move => moves.push(Move::new(from, to, piece, true)),
+            _ => {}
+        }
+    }
+}
+
+fn pawn_moves(pos: &Position, from: Square, moves: &mut Vec<Move>) {
+    let player = pos.player_to_move;
+    let (forward, start_rank, last_rank) = match player {
+        Player::White => (1, 1, 7),
+        Player::Black => (-1, 6, 0),
+    };
+    let mut add = |to: Square, capture: bool, en_passant: bool| {
+        if to.rank().0 == last_rank {
+            for promotion in [Piece::Queen, Piece::Rook, Piece::Bishop, Piece::Knight] {
+                moves.push(Move::pawn(from, to, capture, Some(promotion), false));
+            }
+        } else {
+            moves.push(Move::pawn(from, to, capture, None, en_passant));
+        }
+    };
+
+    if let Some(to) = step(from, (0, forward))
+        && pos.what(to).is_none() {
+        add(to, false, false);
+        if from.rank().0 == start_rank
+            && let Some(to) = step(to, (0, forward))
+            && pos.what(to).is_none() {
+            add(to, false, false);
+        }
+    }
+    for files in [-1, 1] {
+        let Some(to) = step(from, (files, forward)) else {
+            continue;
+        };
+        match pos.what(to) {
+            Some((owner, _)) if owner != player => add(to, true, false),
+            None if pos.en_passant_square == Some(to) => add(to, true, true),
+            _ => {}
+        }
+    }
+}
+
+fn castling_moves(pos: &Position, moves: &mut Vec<Move>) {
+    let player = pos.player_to_move;
+    let (rank, kingside, queenside) = match player {
+        Player::White => (0, pos.castling.white_kingside, pos.castling.white_queenside),
+        Player::Black => (7, pos.castling.black_kingside, pos.castling.black_queenside),
+    };
+    let sq = |file: u8| Square::new(File(file), Rank(rank));
+    if pos.what(sq(4)) != Some((player, Piece::King)) {
+        return;
+    }
+
+    // The squares between the king and the rook have to be empty, and the king may not
+    // start on, pass through or land on an attacked square
+    for (side, rights, rook, between, path) in [
+        (CastlingSide::KingSide,  kingside,  7, &[5, 6][..],    [4, 5, 6]),
+        (CastlingSide::QueenSide, queenside, 0, &[1, 2, 3][..], [4, 3, 2]),
+    ] {
+        if rights
+            && pos.what(sq(rook)) == Some((player, Piece::Rook))
+            && between.iter().all(|&file| pos.what(sq(file)).is_none())
+            && path.iter().all(|&file| !is_attacked(pos, sq(file), player.opposite())) {
+            moves.push(Move::castling(player, side));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{position::FenParseError, rules::properties::random_games};
+
+    fn reference_perft(pos: &Position, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        reference_legal_moves(pos).iter().map(|m| {
+            let mut after = *pos;
+            make_move(&mut after, m);
+            reference_perft(&after, depth - 1)
+        }).sum()
+    }
+
+    #[test]
+    // https://www.chessprogramming.org/Perft_Results
+    fn reference_perft_results() -> Result<(), FenParseError> {
+        for (fen, depth, nodes) in [
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", 3, 8_902),
+            ("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1", 2, 2_039),
+            ("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 3, 2_812),
+            ("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1", 3, 9_467),
+        ] {
+            let (pos, _) = Position::from_fen(fen)?;
+            assert_eq!(reference_perft(&pos, depth), nodes, "{}", fen);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fast_generator_matches_on_random_games() -> Result<(), FenParseError> {
+        random_games(|_, _, after| verify_movegen(after))
+    }
+}
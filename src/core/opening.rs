@@ -0,0 +1,62 @@
+use std::{collections::HashMap, sync::OnceLock};
+use crate::constants::openings::OPENINGS;
+use crate::core::{game::Game, zobrist::ZobristHash};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Opening {
+    pub eco: &'static str,
+    pub name: &'static str,
+}
+
+impl std::fmt::Display for Opening {
+    // "B90 Sicilian, Najdorf"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.eco, self.name)
+    }
+}
+
+static OPENINGS_BY_HASH: OnceLock<HashMap<ZobristHash, Opening>> = OnceLock::new();
+
+// Replays every line of the embedded table once, on the first lookup
+fn openings_by_hash() -> &'static HashMap<ZobristHash, Opening> {
+    OPENINGS_BY_HASH.get_or_init(|| {
+        let mut map = HashMap::with_capacity(OPENINGS.len());
+        for &(eco, name, moves) in OPENINGS {
+            let mut game = Game::default();
+            for m in moves.split_whitespace() {
+                let ok = game.try_to_make_uci_move(m);
+                assert!(ok, "Illegal move {} in opening {} {}", m, eco, name);
+            }
+            map.entry(game.position.zobrist_hash).or_insert(Opening { eco, name });
+        }
+        map
+    })
+}
+
+pub fn lookup(hash: ZobristHash) -> Option<Opening> {
+    openings_by_hash().get(&hash).copied()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_line_is_legal() {
+        // Panics on the first illegal move in the table
+        assert!(!openings_by_hash().is_empty());
+    }
+
+    #[test]
+    fn start_position_is_not_an_opening() {
+        let game = Game::default();
+        assert_eq!(lookup(game.position.zobrist_hash), None);
+    }
+
+    #[test]
+    fn display() {
+        let opening = Opening { eco: "B90", name: "Sicilian, Najdorf" };
+        assert_eq!(opening.to_string(), "B90 Sicilian, Najdorf");
+    }
+}
@@ -0,0 +1,69 @@
+use crate::core::player::Player;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinReason {
+    Checkmate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    Stalemate,
+    FiftyMoveRule,
+    ThreefoldRepetition,
+    InsufficientMaterial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Ongoing,
+    WhiteWins(WinReason),
+    BlackWins(WinReason),
+    Draw(DrawReason),
+}
+
+impl Outcome {
+    pub fn win(winner: Player, reason: WinReason) -> Outcome {
+        match winner {
+            Player::White => Outcome::WhiteWins(reason),
+            Player::Black => Outcome::BlackWins(reason),
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        *self != Outcome::Ongoing
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            Outcome::WhiteWins(_) => Some(Player::White),
+            Outcome::BlackWins(_) => Some(Player::Black),
+            _ => None,
+        }
+    }
+
+    // PGN result tag: "1-0", "0-1", "1/2-1/2" or "*"
+    pub fn result(&self) -> &'static str {
+        match self {
+            Outcome::Ongoing      => "*",
+            Outcome::WhiteWins(_) => "1-0",
+            Outcome::BlackWins(_) => "0-1",
+            Outcome::Draw(_)      => "1/2-1/2",
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    // "1-0 (checkmate)", "1/2-1/2 (threefold repetition)"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            Outcome::Ongoing => return write!(f, "*"),
+            Outcome::WhiteWins(WinReason::Checkmate) |
+            Outcome::BlackWins(WinReason::Checkmate)         => "checkmate",
+            Outcome::Draw(DrawReason::Stalemate)             => "stalemate",
+            Outcome::Draw(DrawReason::FiftyMoveRule)         => "fifty-move rule",
+            Outcome::Draw(DrawReason::ThreefoldRepetition)   => "threefold repetition",
+            Outcome::Draw(DrawReason::InsufficientMaterial)  => "insufficient material",
+        };
+        write!(f, "{} ({})", self.result(), reason)
+    }
+}
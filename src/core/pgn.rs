@@ -0,0 +1,241 @@
+// Reading and writing PGN. Only the main line is read, comments, variations and NAGs
+// are skipped
+use std::fmt::Write;
+use crate::core::{chess_move::Move, game::Game, player::Player, position::FenParseError};
+
+const LINE_WIDTH: usize = 80;
+const RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,  // in the order they were read
+    pub moves: Vec<Move>,             // up to the first one that could not be read
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+    }
+
+    // The `FEN` tag, or the starting position
+    pub fn start(&self) -> Result<Game, FenParseError> {
+        match self.tag("FEN") {
+            Some(fen) => Game::from_fen(fen),
+            None => Ok(Game::default()),
+        }
+    }
+}
+
+// A move as it is written out, with its annotations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnMove {
+    pub m: Move,
+    pub suffix: Option<&'static str>,  // "!", "?!", "??"...
+    pub comment: Option<String>,
+}
+
+impl From<Move> for PgnMove {
+    fn from(m: Move) -> Self {
+        PgnMove { m, suffix: None, comment: None }
+    }
+}
+
+// `ECO` and `Opening` of the last named opening `game` went through, unless `tags` has them already
+pub fn add_opening_tags(tags: &mut Vec<(&str, String)>, game: &Game) {
+    let Some(opening) = game.opening() else { return };
+    for (name, value) in [("ECO", opening.eco), ("Opening", opening.name)] {
+        if !tags.iter().any(|(n, _)| *n == name) {
+            tags.push((name, value.to_string()));
+        }
+    }
+}
+
+// `[Name "Value"]`
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((name.to_string(), value.replace("\\\"", "\"").replace("\\\\", "\\")))
+}
+
+fn is_move_token(token: &str) -> bool {
+    !(token.is_empty() ||
+      token.chars().all(|c| c.is_ascii_digit()) ||
+      token.starts_with('$') ||
+      RESULTS.contains(&token))
+}
+
+pub fn parse_pgn(text: &str) -> Vec<PgnGame> {
+    let mut games = Vec::new();
+    let mut pgn = PgnGame::default();
+    let mut game: Option<Game> = None;  // where the main line has got to, None if it can't go on
+    let mut in_movetext = false;
+    let mut depth = 0;                  // of comments and variations
+
+    for line in text.lines() {
+        let line = line.trim();
+        if depth == 0 && line.starts_with('[') {
+            // Tags after movetext start the next game
+            if in_movetext {
+                games.push(std::mem::take(&mut pgn));
+                in_movetext = false;
+            }
+            if let Some(tag) = parse_tag(line) {
+                pgn.tags.push(tag);
+            }
+            continue;
+        }
+        if depth == 0 && (line.is_empty() || line.starts_with('%')) {
+            continue;
+        }
+
+        if !in_movetext {
+            in_movetext = true;
+            game = pgn.start().ok();
+        }
+        let mut token = String::new();
+        for c in line.chars().chain(std::iter::once(' ')) {
+            match c {
+                '{' | '(' => depth += 1,
+                '}' | ')' => depth -= 1,
+                _ if depth > 0 => {}
+                _ if c.is_whitespace() => {
+                    // "1.e4" and "1...e5" are single tokens
+                    let san = token.rsplit('.').next().unwrap_or("");
+                    if is_move_token(san) {
+                        let m = game.as_ref().and_then(|g| Move::from_san(&g.position, san).ok());
+                        match (m, &mut game) {
+                            (Some(m), Some(g)) => {
+                                g.try_to_make_move(&m);
+                                pgn.moves.push(m);
+                            }
+                            _ => game = None,
+                        }
+                    }
+                    token.clear();
+                }
+                _ => token.push(c),
+            }
+        }
+    }
+    if in_movetext || !pgn.tags.is_empty() {
+        games.push(pgn);
+    }
+    games
+}
+
+// The tags, the movetext wrapped at 80 columns and an empty line.
+// `moves` are played from `start`, which should match the `FEN` tag if there is one
+pub fn write_pgn(tags: &[(&str, String)], start: &Game, moves: &[PgnMove], result: &str) -> String {
+    let mut pgn = String::new();
+    for (name, value) in tags {
+        writeln!(pgn, "[{} \"{}\"]", name, value.replace('\\', "\\\\").replace('"', "\\\"")).unwrap();
+    }
+    pgn.push('\n');
+
+    let mut game = start.clone();
+    let mut tokens = Vec::new();
+    for (i, pgn_move) in moves.iter().enumerate() {
+        // The move number stays on the same line as its move
+        let number = match game.position.player_to_move {
+            Player::White => format!("{}. ", game.fullmove_number),
+            Player::Black if i == 0 => format!("{}... ", game.fullmove_number),
+            Player::Black => String::new(),
+        };
+        tokens.push(format!("{}{}{}", number, pgn_move.m.to_san(&game.position), pgn_move.suffix.unwrap_or("")));
+        if let Some(comment) = &pgn_move.comment {
+            tokens.push(format!("{{{}}}", comment));
+        }
+        game.try_to_make_move(&pgn_move.m);
+    }
+    tokens.push(result.to_string());
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+            writeln!(pgn, "{}", line).unwrap();
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    writeln!(pgn, "{}", line).unwrap();
+    pgn.push('\n');
+    pgn
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PGN: &str = r#"[Event "Casual \"game\""]
+[White "A"]
+[Black "B"]
+[Result "1-0"]
+
+1. e4 e5 {the usual} 2. Qh5 (2. Nf3 Nc6) Nc6 3.Bc4 Nf6?? $4
+4. Qxf7# 1-0
+
+[Event "Endgame"]
+[FEN "8/8/4k3/8/8/4K3/4P3/8 w - - 0 1"]
+
+1. Kd3 Kd5 2. e4+ Kc5 *
+"#;
+
+    #[test]
+    fn read() {
+        let games = parse_pgn(PGN);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("Event"), Some("Casual \"game\""));
+        assert_eq!(games[0].tag("Result"), Some("1-0"));
+        let start = games[0].start().unwrap();
+        assert_eq!(start.san_line(&games[0].moves), "1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7#");
+
+        let start = games[1].start().unwrap();
+        assert_eq!(start.san_line(&games[1].moves), "1. Kd3 Kd5 2. e4+ Kc5");
+    }
+
+    #[test]
+    fn stops_at_a_bad_move() {
+        let games = parse_pgn("1. e4 e5 2. Ke3 Nc6 *");
+        assert_eq!(games[0].moves.len(), 2);
+    }
+
+    #[test]
+    fn round_trip() {
+        let games = parse_pgn(PGN);
+        let start = games[0].start().unwrap();
+        let mut moves: Vec<PgnMove> = games[0].moves.iter().map(|&m| m.into()).collect();
+        moves[5].suffix = Some("??");
+        moves[5].comment = Some("Blunder".to_string());
+        let tags = [("Event", "Casual \"game\"".to_string()), ("Result", "1-0".to_string())];
+        let written = write_pgn(&tags, &start, &moves, "1-0");
+        assert!(written.contains("3. Bc4 Nf6?? {Blunder} 4. Qxf7# 1-0"));
+
+        let read = parse_pgn(&written);
+        assert_eq!(read[0].moves, games[0].moves);
+        assert_eq!(read[0].tag("Event"), Some("Casual \"game\""));
+    }
+
+    #[test]
+    fn opening_tags() {
+        let mut game = Game::default();
+        let mut tags = vec![("Event", "?".to_string())];
+        add_opening_tags(&mut tags, &game);
+        assert_eq!(tags.len(), 1);
+
+        for m in ["e2e4", "c7c5", "g1f3", "d7d6", "d2d4", "c5d4", "f3d4", "g8f6", "b1c3", "a7a6", "c1e3"] {
+            assert!(game.try_to_make_uci_move(m));
+        }
+        add_opening_tags(&mut tags, &game);
+        assert_eq!(tags[1..], [("ECO", "B90".to_string()), ("Opening", "Sicilian, Najdorf".to_string())]);
+
+        // The game's own tags stay
+        let mut tags = vec![("ECO", "B99".to_string())];
+        add_opening_tags(&mut tags, &game);
+        assert_eq!(tags, [("ECO", "B99".to_string()), ("Opening", "Sicilian, Najdorf".to_string())]);
+    }
+}
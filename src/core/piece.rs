@@ -1,3 +1,6 @@
+use crate::core::evaluate::tune::{BISHOP_VALUE, KNIGHT_VALUE, PAWN_VALUE, QUEEN_VALUE, ROOK_VALUE};
+use crate::core::player::Player;
+
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum Piece {
     Pawn = 0,
@@ -20,6 +23,36 @@ impl Piece {
         }
     }
 
+    // The chess symbols, ♔ to ♙ for White and ♚ to ♟ for Black
+    pub fn to_unicode(&self, player: Player) -> char {
+        match (player, self) {
+            (Player::White, Piece::Pawn)   => '♙',
+            (Player::White, Piece::Knight) => '♘',
+            (Player::White, Piece::Bishop) => '♗',
+            (Player::White, Piece::Rook)   => '♖',
+            (Player::White, Piece::Queen)  => '♕',
+            (Player::White, Piece::King)   => '♔',
+            (Player::Black, Piece::Pawn)   => '♟',
+            (Player::Black, Piece::Knight) => '♞',
+            (Player::Black, Piece::Bishop) => '♝',
+            (Player::Black, Piece::Rook)   => '♜',
+            (Player::Black, Piece::Queen)  => '♛',
+            (Player::Black, Piece::King)   => '♚',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Piece> {
+        match c {
+            'p' => Some(Piece::Pawn),
+            'n' => Some(Piece::Knight),
+            'b' => Some(Piece::Bishop),
+            'r' => Some(Piece::Rook),
+            'q' => Some(Piece::Queen),
+            'k' => Some(Piece::King),
+            _ => None,
+        }
+    }
+
     pub fn all_variants() -> [Piece; 6] {
         [Piece::Pawn, Piece::Knight, Piece::Bishop,
          Piece::Rook, Piece::Queen,  Piece::King]
@@ -28,11 +61,11 @@ impl Piece {
     // Used in evaluation function
     pub fn value(&self) -> i32 {
         match self {
-            Piece::Pawn   => 100,
-            Piece::Knight => 300,
-            Piece::Bishop => 330,
-            Piece::Rook   => 500,
-            Piece::Queen  => 900,
+            Piece::Pawn   => PAWN_VALUE.get(),
+            Piece::Knight => KNIGHT_VALUE.get(),
+            Piece::Bishop => BISHOP_VALUE.get(),
+            Piece::Rook   => ROOK_VALUE.get(),
+            Piece::Queen  => QUEEN_VALUE.get(),
             Piece::King   => 100_000,
         }
     }
@@ -18,3 +18,20 @@ impl Player {
         }
     }
 }
+
+// `Player` known at compile time. Code generic over `Side` is compiled once for each color,
+// so the per-color branches in the hot paths fold away
+pub trait Side {
+    const PLAYER: Player;
+}
+
+pub struct White;
+pub struct Black;
+
+impl Side for White {
+    const PLAYER: Player = Player::White;
+}
+
+impl Side for Black {
+    const PLAYER: Player = Player::Black;
+}
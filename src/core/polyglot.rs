@@ -0,0 +1,92 @@
+use crate::constants::{attacks::{PAWN_ATTACKS_BLACK, PAWN_ATTACKS_WHITE}, polyglot::POLYGLOT_RANDOM};
+use crate::core::{piece::Piece, player::Player, position::Position};
+
+// Offsets into `POLYGLOT_RANDOM`, after the 12 * 64 piece keys
+const CASTLING: usize = 768;
+const EN_PASSANT: usize = 772;
+const TURN: usize = 780;
+
+// Polyglot orders the pieces black pawn, white pawn, black knight, ..., white king
+fn piece_kind(piece: Piece, player: Player) -> usize {
+    let white = (player == Player::White) as usize;
+    match piece {
+        Piece::Pawn   => white,
+        Piece::Knight => 2 + white,
+        Piece::Bishop => 4 + white,
+        Piece::Rook   => 6 + white,
+        Piece::Queen  => 8 + white,
+        Piece::King   => 10 + white,
+    }
+}
+
+// The key Polyglot books are indexed by. Unlike `zobrist_hash`, the en passant file
+// only counts when a pawn of the side to move can actually capture there
+pub fn polyglot_key(pos: &Position) -> u64 {
+    let mut key = 0;
+
+    for (player, set) in [(Player::White, &pos.w), (Player::Black, &pos.b)] {
+        for piece in Piece::all_variants() {
+            for sq in *set.piece_to_bb(piece) {
+                key ^= POLYGLOT_RANDOM[64 * piece_kind(piece, player) + sq.index()];
+            }
+        }
+    }
+
+    let castling = &pos.castling;
+    for (i, right) in [
+        castling.white_kingside,
+        castling.white_queenside,
+        castling.black_kingside,
+        castling.black_queenside,
+    ].into_iter().enumerate() {
+        if right {
+            key ^= POLYGLOT_RANDOM[CASTLING + i];
+        }
+    }
+
+    if let Some(ep) = pos.en_passant_square {
+        // The squares a capturing pawn would stand on are the ones an enemy pawn on `ep` attacks
+        let capturers = match pos.player_to_move {
+            Player::White => PAWN_ATTACKS_BLACK[ep.index()] & pos.w.pawns,
+            Player::Black => PAWN_ATTACKS_WHITE[ep.index()] & pos.b.pawns,
+        };
+        if capturers != 0 {
+            key ^= POLYGLOT_RANDOM[EN_PASSANT + ep.file().index()];
+        }
+    }
+
+    if pos.player_to_move == Player::White {
+        key ^= POLYGLOT_RANDOM[TURN];
+    }
+
+    key
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::game::Game;
+
+    // The test positions from the Polyglot book format description
+    #[test]
+    fn reference_keys() {
+        for (moves, key) in [
+            ("",                                    0x463B96181691FC9C),
+            ("e2e4",                                0x823C9B50FD114196),
+            ("e2e4 d7d5",                           0x0756B94461C50FB0),
+            ("e2e4 d7d5 e4e5",                      0x662FAFB965DB29D4),
+            ("e2e4 d7d5 e4e5 f7f5",                 0x22A48B5A8E47FF78),
+            ("e2e4 d7d5 e4e5 f7f5 e1e2",            0x652A607CA3F242C1),
+            ("e2e4 d7d5 e4e5 f7f5 e1e2 e8f7",       0x00FDD303C946BDD9),
+            ("a2a4 b7b5 h2h4 b5b4 c2c4",            0x3C8123EA7B067637),
+            ("a2a4 b7b5 h2h4 b5b4 c2c4 b4c3 a1a3",  0x5C3F9B829B279560),
+        ] {
+            let mut game = Game::default();
+            for m in moves.split_whitespace() {
+                assert!(game.try_to_make_uci_move(m));
+            }
+            assert_eq!(polyglot_key(&game.position), key, "{}", moves);
+        }
+    }
+}
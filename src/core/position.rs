@@ -1,10 +1,13 @@
-use crate::utility::*;
+use crate::constants::{attacks::{PAWN_ATTACKS_BLACK, PAWN_ATTACKS_WHITE}, board::*};
 use crate::core::{
     bitboard::*,
     player::Player,
     chess_move::*,
     piece::Piece,
-    zobrist::zobrist_hash,
+    zobrist::{pawn_hash, zobrist_hash},
+    rules::checks::is_king_in_check,
+    evaluate::phase_weight,
+    square::{File, Rank, Square},
 };
 
 /// Uses [Little-Endian Rank-File Mapping](https://www.chessprogramming.org/Square_Mapping_Considerations#Little-Endian_Rank-File_Mapping)
@@ -12,14 +15,66 @@ use crate::core::{
 pub struct Position {
     pub w: BitboardSet,
     pub b: BitboardSet,
-    pub occupied: u64,
+    pub occupied: Bitboard,
     pub player_to_move: Player,
-    pub en_passant_square: Option<u8>,
+    pub en_passant_square: Option<Square>,
     pub castling: CastlingRights,
+    pub halfmove_clock: usize,  // plies since the last capture or pawn move
     pub zobrist_hash: u64,
+    pub pawn_hash: u64,  // Zobrist over the pawns only, keys the pawn hash table
+    // Redundant square -> piece lookup, kept in sync with the bitboards by make/unmake
+    pub mailbox: [Option<(Player, Piece)>; 64],
+    pub material: Material,
 }
 
-#[derive(Debug)]
+// Piece counts and what they are worth, kept up to date by make/unmake so that
+// the evaluation and the draw rules don't have to count bits
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct Material {
+    counts: [[u8; 6]; 2],  // [player][piece]
+    pub score: i32,  // White's minus Black's, `Piece::value` each
+    pub phase: i32,  // both sides' `phase_weight`s, not capped at `MAX_PHASE`
+}
+
+impl Material {
+    // Counts the bitboards, only needed when a position is built from scratch
+    pub fn of(pos: &Position) -> Material {
+        let mut material = Material::default();
+        for (player, set) in [(Player::White, &pos.w), (Player::Black, &pos.b)] {
+            for piece in Piece::all_variants() {
+                for _ in 0..set.count(piece) {
+                    material.add(player, piece);
+                }
+            }
+        }
+        material
+    }
+
+    pub fn count(&self, player: Player, piece: Piece) -> u32 {
+        self.counts[player.index()][piece.index()] as u32
+    }
+
+    pub fn add(&mut self, player: Player, piece: Piece) {
+        self.counts[player.index()][piece.index()] += 1;
+        self.score += Self::sign(player) * piece.value();
+        self.phase += phase_weight(piece);
+    }
+
+    pub fn remove(&mut self, player: Player, piece: Piece) {
+        self.counts[player.index()][piece.index()] -= 1;
+        self.score -= Self::sign(player) * piece.value();
+        self.phase -= phase_weight(piece);
+    }
+
+    fn sign(player: Player) -> i32 {
+        match player {
+            Player::White => 1,
+            Player::Black => -1,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub enum FenParseError {
     BadFieldCount,
     InvalidPieceChar(char),
@@ -30,6 +85,20 @@ pub enum FenParseError {
     InvalidEnPassant(String),
     InvalidHalfmove(String),
     InvalidFullmove(String),
+    IllegalPosition(PositionError),
+}
+
+// Syntactically correct, but impossible positions
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PositionError {
+    MissingKing(Player),
+    TooManyKings(Player),
+    TooManyPawns(Player),
+    PawnOnBackRank,
+    OverlappingPieces,
+    OpponentInCheck,
+    InvalidEnPassant(Square),
+    InvalidCastlingRights,
 }
 
 impl Default for Position {
@@ -38,84 +107,110 @@ impl Default for Position {
     }
 }
 
-impl std::fmt::Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut board = [['.'; 8]; 8];
+// How the board is drawn, see `Position::display`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DisplaySettings {
+    pub unicode: bool,  // ♔ and ♚ instead of K and k
+    pub flipped: bool,  // from Black's side, the 8th rank at the bottom
+}
 
-        for rank in 0..8 {
-            for file in 0..8 {
-                let sq_idx = rank * 8 + file;
-                let what = self.what(sq_idx);
-
-                if let Some((player, piece)) = what {
-                    let uppercase = player == Player::White;
-                    let letter = piece.to_char();
-
-                    board[7 - rank as usize][file as usize] = if uppercase {
-                        letter.to_ascii_uppercase()
-                    } else {
-                        letter
-                    };
-                }
-            }
-        }
+// A position drawn the way `settings` say
+pub struct PositionDisplay<'a> {
+    pos: &'a Position,
+    settings: DisplaySettings,
+}
 
-        for (rank, row) in board.iter().enumerate() {
-            write!(f, "{} ", 8 - rank)?;
-            for square in row {
+impl std::fmt::Display for PositionDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pos = self.pos;
+        // Top to bottom, left to right, as seen from the side at the bottom
+        let (ranks, files): (Vec<u8>, Vec<u8>) = match self.settings.flipped {
+            false => ((0..8).rev().collect(), (0..8).collect()),
+            true  => ((0..8).collect(), (0..8).rev().collect()),
+        };
+
+        for &rank in &ranks {
+            write!(f, "{} ", rank + 1)?;
+            for &file in &files {
+                let square = match pos.what(Square::new(File(file), Rank(rank))) {
+                    None => '.',
+                    Some((player, piece)) if self.settings.unicode => piece.to_unicode(player),
+                    Some((Player::White, piece)) => piece.to_char().to_ascii_uppercase(),
+                    Some((Player::Black, piece)) => piece.to_char(),
+                };
                 write!(f, "{} ", square)?;
             }
             writeln!(f)?;
         }
-        writeln!(f, "  a b c d e f g h")?;
-        if let Some(ep_sq) = self.en_passant_square {
-            writeln!(f, "En passant square: {:?}", square_idx_to_string(ep_sq))?;
+        write!(f, " ")?;
+        for &file in &files {
+            write!(f, " {}", File(file).to_char())?;
         }
-        writeln!(f, "Player to move: {:?}", self.player_to_move)?;
-        write!(f, "Castling rights: {}", self.castling)?;
+        writeln!(f)?;
+        if let Some(ep_sq) = pos.en_passant_square {
+            writeln!(f, "En passant square: {:?}", ep_sq.to_string())?;
+        }
+        writeln!(f, "Player to move: {:?}", pos.player_to_move)?;
+        write!(f, "Castling rights: {}", pos.castling)?;
         Ok(())
     }
 }
 
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display(DisplaySettings::default()).fmt(f)
+    }
+}
+
 impl Position {
     pub fn start() -> Self {
         let mut pos = Position {
             w: BitboardSet {
-                all:     0x000000000000FFFF,
-                pawns:   0x000000000000FF00,
-                knights: 0x0000000000000042,
-                bishops: 0x0000000000000024,
-                rooks:   0x0000000000000081,
-                queens:  0x0000000000000008,
-                king:    0x0000000000000010,
+                all:     Bitboard(0x000000000000FFFF),
+                pawns:   Bitboard(0x000000000000FF00),
+                knights: Bitboard(0x0000000000000042),
+                bishops: Bitboard(0x0000000000000024),
+                rooks:   Bitboard(0x0000000000000081),
+                queens:  Bitboard(0x0000000000000008),
+                king:    Bitboard(0x0000000000000010),
             },
             b: BitboardSet {
-                all:     0xFFFF000000000000,
-                pawns:   0x00FF000000000000,
-                knights: 0x4200000000000000,
-                bishops: 0x2400000000000000,
-                rooks:   0x8100000000000000,
-                queens:  0x0800000000000000,
-                king:    0x1000000000000000,
+                all:     Bitboard(0xFFFF000000000000),
+                pawns:   Bitboard(0x00FF000000000000),
+                knights: Bitboard(0x4200000000000000),
+                bishops: Bitboard(0x2400000000000000),
+                rooks:   Bitboard(0x8100000000000000),
+                queens:  Bitboard(0x0800000000000000),
+                king:    Bitboard(0x1000000000000000),
             },
-            occupied: 0xFFFF00000000FFFF,
+            occupied: Bitboard(0xFFFF00000000FFFF),
             player_to_move: Player::White,
             en_passant_square: None,
             castling: CastlingRights::default(),
+            halfmove_clock: 0,
             zobrist_hash: 0,
+            pawn_hash: 0,
+            mailbox: [None; 64],
+            material: Material::default(),
         };
-        pos.zobrist_hash = zobrist_hash(&pos);
+        pos.rebuild_incremental_state();
         pos
     }
 
-    fn validate_fen(fen: &str) -> Result<(), FenParseError> {
+    // The halfmove clock and the fullmove number are often left out (EPD, some GUIs),
+    // they default to "0" and "1" then
+    fn fen_fields(fen: &str) -> Result<[&str; 6], FenParseError> {
+        let mut fields = ["", "", "", "", "0", "1"];
         let parts: Vec<&str> = fen.split_whitespace().collect();
-        if parts.len() != 6 {
+        if !(4..=6).contains(&parts.len()) {
             return Err(FenParseError::BadFieldCount);
         }
+        fields[..parts.len()].copy_from_slice(&parts);
+        Ok(fields)
+    }
 
-        let (placement, side, castling, en_passant, halfmove, fullmove) =
-            (parts[0], parts[1], parts[2], parts[3], parts[4], parts[5]);
+    fn validate_fen(parts: &[&str; 6]) -> Result<(), FenParseError> {
+        let [placement, side, castling, en_passant, halfmove, fullmove] = *parts;
 
         // Validate placement
         let ranks: Vec<&str> = placement.split('/').collect();
@@ -146,7 +241,7 @@ impl Position {
             return Err(FenParseError::InvalidCastling(castling.into()));
         }
 
-        if en_passant != "-" && square_string_to_idx(en_passant).is_none() {
+        if en_passant != "-" && Square::parse(en_passant).is_none() {
             return Err(FenParseError::InvalidEnPassant(en_passant.into()));
         }
 
@@ -161,22 +256,23 @@ impl Position {
         Ok(())
     }
 
-    // Returns (position, halfmove_clock)
+    // Returns (position, fullmove_number)
     pub fn from_fen(fen: &str) -> Result<(Self, usize), FenParseError> {
-        Self::validate_fen(fen)?;
+        let parts = Self::fen_fields(fen)?;
+        Self::validate_fen(&parts)?;
 
         let mut w = BitboardSet::default();
         let mut b = BitboardSet::default();
 
-        let parts: Vec<&str> = fen.split_whitespace().collect();
         let board = parts[0];
         let side_to_move = parts[1];
         let castling = CastlingRights::from_string(parts[2]);
         let en_passant_square = match parts[3] {
             "-" => None,
-            _ => square_string_to_idx(parts[3])
+            _ => Square::parse(parts[3])
         };
         let halfmove_clock = parts[4].parse::<usize>().unwrap();
+        let fullmove_number = parts[5].parse::<usize>().unwrap();
 
         // Starting from the top-left, 0-indexed [0; 7]
         let mut rank = 7;
@@ -214,7 +310,7 @@ impl Position {
 
         w.update();
         b.update();
-        let occupied: u64 = w.all | b.all;
+        let occupied = w.all | b.all;
 
         let mut pos = Position {
             w, b, occupied,
@@ -225,10 +321,138 @@ impl Position {
             },
             en_passant_square,
             castling,
+            halfmove_clock,
             zobrist_hash: 0,
+            pawn_hash: 0,
+            mailbox: [None; 64],
+            material: Material::default(),
+        };
+        pos.rebuild_incremental_state();
+        pos.validate().map_err(FenParseError::IllegalPosition)?;
+
+        // The same as a position reached by a double push nobody can take, see `make_move`
+        if let Some(ep_sq) = pos.en_passant_square
+            && !pos.can_capture_en_passant(ep_sq, pos.player_to_move) {
+            pos.en_passant_square = None;
+            pos.rebuild_incremental_state();
+        }
+        Ok((pos, fullmove_number))
+    }
+
+    pub fn validate(&self) -> Result<(), PositionError> {
+        for (player, set) in [(Player::White, &self.w), (Player::Black, &self.b)] {
+            match set.king.count_ones() {
+                0 => return Err(PositionError::MissingKing(player)),
+                1 => {}
+                _ => return Err(PositionError::TooManyKings(player)),
+            }
+            if set.pawns.count_ones() > 8 {
+                return Err(PositionError::TooManyPawns(player));
+            }
+        }
+
+        if (self.w.pawns | self.b.pawns) & (RANK[1] | RANK[8]) != 0 {
+            return Err(PositionError::PawnOnBackRank);
+        }
+
+        let pieces = self.w.count_all() + self.b.count_all();
+        if self.occupied.count_ones() != pieces {
+            return Err(PositionError::OverlappingPieces);
+        }
+
+        if is_king_in_check(self, self.player_to_move.opposite()) {
+            return Err(PositionError::OpponentInCheck);
+        }
+
+        if let Some(ep_sq) = self.en_passant_square
+            && !self.is_en_passant_square_valid(ep_sq) {
+            return Err(PositionError::InvalidEnPassant(ep_sq));
+        }
+
+        if !self.are_castling_rights_valid() {
+            return Err(PositionError::InvalidCastlingRights);
+        }
+
+        Ok(())
+    }
+
+    // The square has to be right behind a pawn that has just made a double push
+    fn is_en_passant_square_valid(&self, ep_sq: Square) -> bool {
+        let (rank, pushed_pawns) = match self.player_to_move {
+            Player::White => (Rank(5), self.b.pawns),
+            Player::Black => (Rank(2), self.w.pawns),
+        };
+        if ep_sq.rank() != rank {
+            return false;
+        }
+        let (pushed_pawn_sq, start_sq) = match self.player_to_move {
+            Player::White => (ep_sq - 8, ep_sq + 8),
+            Player::Black => (ep_sq + 8, ep_sq - 8),
+        };
+        !self.occupied.contains(ep_sq) && !self.occupied.contains(start_sq) && pushed_pawns.contains(pushed_pawn_sq)
+    }
+
+    // Whether a pawn of `capturer` attacks `ep_sq`, legal or not
+    pub fn can_capture_en_passant(&self, ep_sq: Square, capturer: Player) -> bool {
+        let (attackers, pawns) = match capturer {
+            Player::White => (PAWN_ATTACKS_BLACK[ep_sq.index()], self.w.pawns),
+            Player::Black => (PAWN_ATTACKS_WHITE[ep_sq.index()], self.b.pawns),
         };
-        pos.zobrist_hash = zobrist_hash(&pos);
-        Ok((pos, halfmove_clock))
+        attackers & pawns != 0
+    }
+
+    // Every castling right requires the king and the rook on their initial squares
+    fn are_castling_rights_valid(&self) -> bool {
+        let rights = [
+            (self.castling.white_kingside,  self.w, E1, H1),
+            (self.castling.white_queenside, self.w, E1, A1),
+            (self.castling.black_kingside,  self.b, E8, H8),
+            (self.castling.black_queenside, self.b, E8, A8),
+        ];
+        rights.iter().all(|&(right, set, king_sq, rook_sq)| {
+            !right || (set.king.contains(king_sq) && set.rooks.contains(rook_sq))
+        })
+    }
+
+    // The fullmove number is not a part of `Position`, so it has to be supplied
+    pub fn to_fen(&self, fullmove_number: usize) -> String {
+        let mut placement = String::new();
+        for rank in (0..8).rev() {
+            let mut empty = 0;
+            for file in 0..8 {
+                match self.what(Square::new(File(file), Rank(rank))) {
+                    Some((player, piece)) => {
+                        if empty > 0 {
+                            placement.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        let letter = piece.to_char();
+                        placement.push(match player {
+                            Player::White => letter.to_ascii_uppercase(),
+                            Player::Black => letter,
+                        });
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                placement.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                placement.push('/');
+            }
+        }
+
+        let side = match self.player_to_move {
+            Player::White => "w",
+            Player::Black => "b",
+        };
+        let en_passant = match self.en_passant_square {
+            Some(sq) => sq.to_string(),
+            None => "-".to_string(),
+        };
+
+        format!("{} {} {} {} {} {}", placement, side, self.castling, en_passant, self.halfmove_clock, fullmove_number)
     }
 
     // Mutate fields `w`, `b` and `occupied` so they are correct
@@ -238,35 +462,99 @@ impl Position {
         self.occupied = self.w.all | self.b.all;
     }
 
-    pub fn what(&self, sq_idx: u8) -> Option<(Player, Piece)> {
-        let bb = bit(sq_idx);
-
-        if self.w.pawns & bb != 0 {
-            Some((Player::White, Piece::Pawn))
-        } else if self.w.knights & bb != 0 {
-            Some((Player::White, Piece::Knight))
-        } else if self.w.bishops & bb != 0 {
-            Some((Player::White, Piece::Bishop))
-        } else if self.w.rooks & bb != 0 {
-            Some((Player::White, Piece::Rook))
-        } else if self.w.queens & bb != 0 {
-            Some((Player::White, Piece::Queen))
-        } else if self.w.king & bb != 0 {
-            Some((Player::White, Piece::King))
-        } else if self.b.pawns & bb != 0 {
-            Some((Player::Black, Piece::Pawn))
-        } else if self.b.knights & bb != 0 {
-            Some((Player::Black, Piece::Knight))
-        } else if self.b.bishops & bb != 0 {
-            Some((Player::Black, Piece::Bishop))
-        } else if self.b.rooks & bb != 0 {
-            Some((Player::Black, Piece::Rook))
-        } else if self.b.queens & bb != 0 {
-            Some((Player::Black, Piece::Queen))
-        } else if self.b.king & bb != 0 {
-            Some((Player::Black, Piece::King))
-        } else {
-            None
+    // `to_string` draws the board in ASCII with White at the bottom, this can do otherwise
+    pub fn display(&self, settings: DisplaySettings) -> PositionDisplay<'_> {
+        PositionDisplay { pos: self, settings }
+    }
+
+    pub fn what(&self, sq: Square) -> Option<(Player, Piece)> {
+        self.mailbox[sq.index()]
+    }
+
+    // Everything make/unmake keeps up to date besides the bitboards, from the bitboards
+    fn rebuild_incremental_state(&mut self) {
+        self.mailbox = self.build_mailbox();
+        self.zobrist_hash = zobrist_hash(self);
+        self.pawn_hash = pawn_hash(self);
+        self.material = Material::of(self);
+    }
+
+    // Panics if anything make/unmake keeps up to date has drifted from the piece bitboards.
+    // Called after every make/unmake with the `verify` feature
+    pub fn verify_incremental_state(&self) {
+        let mut expected = *self;
+        expected.update();
+        expected.rebuild_incremental_state();
+        assert_eq!((self.w.all, self.b.all, self.occupied), (expected.w.all, expected.b.all, expected.occupied), "occupancy, {}", self.to_fen(1));
+        assert_eq!(self.zobrist_hash, expected.zobrist_hash, "Zobrist hash, {}", self.to_fen(1));
+        assert_eq!(self.pawn_hash, expected.pawn_hash, "pawn hash, {}", self.to_fen(1));
+        assert_eq!(self.mailbox, expected.mailbox, "mailbox, {}", self.to_fen(1));
+        assert_eq!(self.material, expected.material, "material, {}", self.to_fen(1));
+    }
+
+    // Scans the bitboards, only needed when a position is built from scratch
+    pub fn build_mailbox(&self) -> [Option<(Player, Piece)>; 64] {
+        let mut mailbox = [None; 64];
+        for (sq, entry) in Square::all().zip(mailbox.iter_mut()) {
+            *entry = self.w.what(sq).map(|piece| (Player::White, piece))
+                .or_else(|| self.b.what(sq).map(|piece| (Player::Black, piece)));
+        }
+        mailbox
+    }
+
+    // Swaps the colors and flips the board vertically: the evaluation of the
+    // mirrored position has to be exactly the negated evaluation of the original
+    pub fn mirror(&self) -> Self {
+        let mut pos = Position {
+            w: self.b.map(Bitboard::flip_vertical),
+            b: self.w.map(Bitboard::flip_vertical),
+            occupied: self.occupied.flip_vertical(),
+            player_to_move: self.player_to_move.opposite(),
+            en_passant_square: self.en_passant_square.map(Square::flip_vertical),
+            castling: self.castling.swapped(),
+            halfmove_clock: self.halfmove_clock,
+            zobrist_hash: 0,
+            pawn_hash: 0,
+            mailbox: [None; 64],
+            material: Material::default(),
+        };
+        pos.rebuild_incremental_state();
+        pos
+    }
+
+    // Flips the board vertically without swapping the colors
+    pub fn flip_vertical(&self) -> Self {
+        let mut pos = self.mirror();
+        std::mem::swap(&mut pos.w, &mut pos.b);
+        pos.player_to_move = self.player_to_move;
+        pos.castling = self.castling;
+        pos.rebuild_incremental_state();
+        pos
+    }
+
+    // Mirrors the files (a <-> h). Castling is not symmetric, so the rights are dropped
+    pub fn flip_horizontal(&self) -> Self {
+        let mut pos = Position {
+            w: self.w.map(Bitboard::flip_horizontal),
+            b: self.b.map(Bitboard::flip_horizontal),
+            occupied: self.occupied.flip_horizontal(),
+            player_to_move: self.player_to_move,
+            en_passant_square: self.en_passant_square.map(Square::flip_horizontal),
+            castling: CastlingRights::none(),
+            halfmove_clock: self.halfmove_clock,
+            zobrist_hash: 0,
+            pawn_hash: 0,
+            mailbox: [None; 64],
+            material: Material::default(),
+        };
+        pos.rebuild_incremental_state();
+        pos
+    }
+
+    pub fn perspective(&self, player: Player) -> (&BitboardSet, &BitboardSet) {
+        match player {
+            Player::White => (&self.w, &self.b),
+            Player::Black => (&self.b, &self.w),
         }
     }
 
@@ -282,6 +570,8 @@ impl Position {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::{movegen::pseudo_moves, rules::{make::make_move, unmake::unmake_move}};
+    use crate::utility::bit;
 
     #[test]
     fn fen_start() -> Result<(), FenParseError> {
@@ -306,37 +596,21 @@ mod tests {
     }
 
     #[test]
-    fn fen_empty() -> Result<(), FenParseError> {
-        let (pos, _) = Position::from_fen("8/8/8/8/8/8/8/8 b - - 0 1")?;
-        assert_eq!(pos.w.pawns,   0x0);
-        assert_eq!(pos.w.rooks,   0x0);
-        assert_eq!(pos.w.knights, 0x0);
-        assert_eq!(pos.w.bishops, 0x0);
-        assert_eq!(pos.w.queens,  0x0);
-        assert_eq!(pos.w.king,    0x0);
-        assert_eq!(pos.w.all,     0x0);
-        assert_eq!(pos.b.pawns,   0x0);
-        assert_eq!(pos.b.rooks,   0x0);
-        assert_eq!(pos.b.knights, 0x0);
-        assert_eq!(pos.b.bishops, 0x0);
-        assert_eq!(pos.b.queens,  0x0);
-        assert_eq!(pos.b.king,    0x0);
-        assert_eq!(pos.b.all,     0x0);
-        assert_eq!(pos.occupied,  0x0);
-        assert_eq!(pos.player_to_move, Player::Black);
-        Ok(())
+    fn fen_empty() {
+        let result = Position::from_fen("8/8/8/8/8/8/8/8 b - - 0 1");
+        assert!(matches!(result, Err(FenParseError::IllegalPosition(PositionError::MissingKing(Player::White)))));
     }
 
     #[test]
     fn fen_endgame() -> Result<(), FenParseError> {
-        let (pos, _) = Position::from_fen("4r3/2n5/8/6R1/3k4/8/1B6/4K3 w - - 0 1")?;
+        let (pos, _) = Position::from_fen("4r3/2n5/8/6R1/3k4/8/8/1B2K3 w - - 0 1")?;
         assert_eq!(pos.w.pawns,   0x0);
         assert_eq!(pos.w.rooks,   bit(38));
         assert_eq!(pos.w.knights, 0x0);
-        assert_eq!(pos.w.bishops, bit(9));
+        assert_eq!(pos.w.bishops, bit(1));
         assert_eq!(pos.w.queens,  0x0);
         assert_eq!(pos.w.king,    bit(4));
-        assert_eq!(pos.w.all,     bit(4) | bit(9) | bit(38));
+        assert_eq!(pos.w.all,     bit(1) | bit(4) | bit(38));
 
         assert_eq!(pos.b.pawns,   0x0);
         assert_eq!(pos.b.rooks,   bit(60));
@@ -346,8 +620,161 @@ mod tests {
         assert_eq!(pos.b.king,    bit(27));
         assert_eq!(pos.b.all,     bit(27) | bit(50) | bit(60));
 
-        assert_eq!(pos.occupied,  bit(4) | bit(9) | bit(27) | bit(38) | bit(50) | bit(60));
+        assert_eq!(pos.occupied,  bit(1) | bit(4) | bit(27) | bit(38) | bit(50) | bit(60));
         assert_eq!(pos.player_to_move, Player::White);
         Ok(())
     }
+
+    fn validate(fen: &str) -> Result<(), PositionError> {
+        match Position::from_fen(fen) {
+            Ok(_) => Ok(()),
+            Err(FenParseError::IllegalPosition(e)) => Err(e),
+            Err(e) => panic!("Unexpected FEN error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn validate_kings() {
+        assert_eq!(validate("8/8/8/8/8/8/8/4K3 w - - 0 1"), Err(PositionError::MissingKing(Player::Black)));
+        assert_eq!(validate("4k3/8/8/8/8/8/8/4K1K1 w - - 0 1"), Err(PositionError::TooManyKings(Player::White)));
+    }
+
+    #[test]
+    fn validate_pawns() {
+        assert_eq!(validate("4k3/8/8/8/8/8/8/P3K3 w - - 0 1"), Err(PositionError::PawnOnBackRank));
+        assert_eq!(validate("p3k3/8/8/8/8/8/8/4K3 w - - 0 1"), Err(PositionError::PawnOnBackRank));
+        assert_eq!(validate("4k3/8/8/8/8/P7/PPPPPPPP/4K3 w - - 0 1"), Err(PositionError::TooManyPawns(Player::White)));
+    }
+
+    #[test]
+    fn validate_opponent_in_check() {
+        assert_eq!(validate("4k3/8/8/8/8/8/8/4KR2 w - - 0 1"), Ok(()));
+        assert_eq!(validate("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1"), Err(PositionError::OpponentInCheck));
+    }
+
+    #[test]
+    fn validate_en_passant() {
+        assert_eq!(validate("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"), Ok(()));
+        assert_eq!(validate("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq d3 0 1"),
+                   Err(PositionError::InvalidEnPassant(D3)));
+        assert_eq!(validate("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e3 0 1"),
+                   Err(PositionError::InvalidEnPassant(E3)));
+    }
+
+    #[test]
+    fn validate_castling_rights() {
+        assert_eq!(validate("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"), Ok(()));
+        assert_eq!(validate("r3k2r/8/8/8/8/8/8/R3K1R1 w KQkq - 0 1"), Err(PositionError::InvalidCastlingRights));
+        assert_eq!(validate("r3k2r/8/8/8/8/8/8/R4K1R w Qkq - 0 1"), Err(PositionError::InvalidCastlingRights));
+    }
+
+    #[test]
+    fn mirror() -> Result<(), FenParseError> {
+        let (pos, _) = Position::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K1R1 w Qkq - 0 1")?;
+        let (expected, _) = Position::from_fen("r3k1r1/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R b KQq - 0 1")?;
+        assert_eq!(pos.mirror(), expected);
+        assert_eq!(pos.mirror().mirror(), pos);
+        assert_eq!(Position::start().mirror().zobrist_hash, zobrist_hash(&Position::start().mirror()));
+        Ok(())
+    }
+
+    #[test]
+    fn mirror_en_passant() -> Result<(), FenParseError> {
+        let (pos, _) = Position::from_fen("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 1")?;
+        let (expected, _) = Position::from_fen("rnbqkbnr/pppp1ppp/8/8/3Pp3/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 1")?;
+        assert_eq!(pos.mirror(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn flip() -> Result<(), FenParseError> {
+        let (pos, _) = Position::from_fen("8/2k5/8/4p3/8/1N6/6K1/8 b - - 0 1")?;
+        let (vertical, _) = Position::from_fen("8/6K1/1N6/8/4p3/8/2k5/8 b - - 0 1")?;
+        let (horizontal, _) = Position::from_fen("8/5k2/8/3p4/8/6N1/1K6/8 b - - 0 1")?;
+        assert_eq!(pos.flip_vertical(), vertical);
+        assert_eq!(pos.flip_horizontal(), horizontal);
+        assert_eq!(pos.flip_horizontal().flip_horizontal(), pos);
+        Ok(())
+    }
+
+    #[test]
+    fn fen_clocks() -> Result<(), FenParseError> {
+        let (pos, fullmove) = Position::from_fen("8/8/1k1p4/2p5/6P1/7P/5K2/8 b - - 39 100")?;
+        assert_eq!(pos.halfmove_clock, 39);
+        assert_eq!(fullmove, 100);
+        Ok(())
+    }
+
+    #[test]
+    fn fen_without_clocks() -> Result<(), FenParseError> {
+        let (pos, fullmove) = Position::from_fen("8/8/1k1p4/2p5/6P1/7P/5K2/8 b - -")?;
+        assert_eq!((pos.halfmove_clock, fullmove), (0, 1));
+        assert_eq!(pos.to_fen(fullmove), "8/8/1k1p4/2p5/6P1/7P/5K2/8 b - - 0 1");
+
+        let (pos, fullmove) = Position::from_fen("8/8/1k1p4/2p5/6P1/7P/5K2/8 b - - 7")?;
+        assert_eq!((pos.halfmove_clock, fullmove), (7, 1));
+
+        // The board part is still checked strictly
+        assert_eq!(Position::from_fen("8/8/1k1p4/2p5/6P1/7P/5K2 b - -").err(), Some(FenParseError::BadRankCount));
+        assert_eq!(Position::from_fen("8/8/1k1p4/2p5/6P1/7P/5K2/8 b -").err(), Some(FenParseError::BadFieldCount));
+        Ok(())
+    }
+
+    #[test]
+    fn fen_round_trip() -> Result<(), FenParseError> {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/8/1k1p4/2p5/6P1/7P/5K2/8 b - - 39 100",
+        ] {
+            let (pos, fullmove) = Position::from_fen(fen)?;
+            assert_eq!(pos.to_fen(fullmove), fen);
+        }
+        Ok(())
+    }
+
+    fn check_incremental_state(pos: &mut Position, depth: usize) {
+        pos.verify_incremental_state();
+        if depth == 0 {
+            return;
+        }
+        for m in pseudo_moves(pos) {
+            let undo = make_move(pos, &m);
+            check_incremental_state(pos, depth - 1);
+            unmake_move(pos, undo);
+        }
+        assert_eq!(pos.mailbox, pos.build_mailbox());
+    }
+
+    #[test]
+    fn incremental_state_stays_in_sync() -> Result<(), FenParseError> {
+        // Castling, en passant, promotions and captures of promoted pieces
+        let (mut kiwipete, _) = Position::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")?;
+        check_incremental_state(&mut kiwipete, 3);
+        let (mut promotions, _) = Position::from_fen("n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1")?;
+        check_incremental_state(&mut promotions, 3);
+        assert_eq!(kiwipete.mirror().mailbox, kiwipete.mirror().build_mailbox());
+        assert_eq!(kiwipete.flip_vertical().mailbox, kiwipete.flip_vertical().build_mailbox());
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Zobrist hash")]
+    fn verify_catches_a_stale_hash() {
+        let mut pos = Position::start();
+        pos.zobrist_hash ^= 1;
+        pos.verify_incremental_state();
+    }
+
+    #[test]
+    #[should_panic(expected = "occupancy")]
+    fn verify_catches_stale_occupancy() {
+        let mut pos = Position::start();
+        pos.w.pawns ^= bit(E2.0) | bit(E4.0);
+        pos.mailbox[E2.index()] = None;
+        pos.mailbox[E4.index()] = Some((Player::White, Piece::Pawn));
+        pos.verify_incremental_state();
+    }
 }
@@ -2,3 +2,5 @@ pub mod make;
 pub mod unmake;
 pub mod checks;
 pub mod draw;
+#[cfg(test)]
+pub(crate) mod properties;
@@ -1,32 +1,57 @@
 use crate::constants::attacks;
-use crate::utility::lsb;
 use crate::core::{
+    bitboard::{Bitboard, BitboardSet},
     position::*,
+    square::Square,
     movegen::*,
     player::Player,
 };
 
-pub fn is_square_attacked(pos: &Position, sq: usize, by_player: Player) -> bool {
-    let friend = match by_player {
-        Player::White => &pos.w,
-        Player::Black => &pos.b,
-    };
+// Checks the cheap leapers first and returns as soon as one of them answers
+pub fn is_square_attacked(pos: &Position, sq: Square, by_player: Player) -> bool {
+    let (friend, pawn) = attacker_side(pos, sq, by_player);
+    if !(pawn & friend.pawns).is_empty()
+        || !(knight_attacks(pos, sq, Bitboard::EMPTY) & friend.knights).is_empty()
+        || !(king_attacks(pos, sq, Bitboard::EMPTY) & friend.king).is_empty() {
+        return true;
+    }
+
+    let diagonal = friend.bishops | friend.queens;
+    if !diagonal.is_empty() && !(bishop_attacks(pos, sq, Bitboard::EMPTY) & diagonal).is_empty() {
+        return true;
+    }
+    let straight = friend.rooks | friend.queens;
+    !straight.is_empty() && !(rook_attacks(pos, sq, Bitboard::EMPTY) & straight).is_empty()
+}
+
+// Every piece of `by_player` that attacks `sq`
+pub fn attackers_to(pos: &Position, sq: Square, by_player: Player) -> Bitboard {
+    let (friend, pawn) = attacker_side(pos, sq, by_player);
+    // A queen attacks like a bishop and a rook, so it goes with both instead of getting its own lookup
+    pawn & friend.pawns |
+    knight_attacks(pos, sq, Bitboard::EMPTY) & friend.knights |
+    bishop_attacks(pos, sq, Bitboard::EMPTY) & (friend.bishops | friend.queens) |
+    rook_attacks  (pos, sq, Bitboard::EMPTY) & (friend.rooks   | friend.queens) |
+    king_attacks  (pos, sq, Bitboard::EMPTY) & friend.king
+}
+
+// The pieces of `by_player`, and the squares its pawns would have to be on to attack `sq`
+fn attacker_side(pos: &Position, sq: Square, by_player: Player) -> (&BitboardSet, Bitboard) {
+    // Reversing intentionally, questioning: "what could have attacked this square?"
+    match by_player {
+        Player::White => (&pos.w, Bitboard(attacks::PAWN_ATTACKS_BLACK[sq.index()])),
+        Player::Black => (&pos.b, Bitboard(attacks::PAWN_ATTACKS_WHITE[sq.index()])),
+    }
+}
 
-    // All the possible pieces' positions, which could attack this square
-    // reversing intentionally, questioning: "what could have attacked this square?"
-    let pawn = match by_player {
-        Player::White => attacks::PAWN_ATTACKS_BLACK[sq],
-        Player::Black => attacks::PAWN_ATTACKS_WHITE[sq],
+// The pieces giving check to the side to move
+pub fn checkers(pos: &Position) -> Bitboard {
+    let player = pos.player_to_move;
+    let king_bb = match player {
+        Player::White => pos.w.king,
+        Player::Black => pos.b.king,
     };
-    let knight = knight_attacks(pos, sq, 0x0);
-    let bishop = bishop_attacks(pos, sq, 0x0);
-    let rook   = rook_attacks  (pos, sq, 0x0);
-    let queen  = queen_attacks (pos, sq, 0x0);
-    let king   = king_attacks  (pos, sq, 0x0);
-
-    pawn   & friend.pawns   > 0 || knight & friend.knights > 0 ||
-    bishop & friend.bishops > 0 || rook   & friend.rooks   > 0 ||
-    queen  & friend.queens  > 0 || king   & friend.king    > 0
+    attackers_to(pos, king_bb.lsb(), player.opposite())
 }
 
 pub fn is_king_in_check(pos: &Position, player: Player) -> bool {
@@ -34,47 +59,100 @@ pub fn is_king_in_check(pos: &Position, player: Player) -> bool {
         Player::White => pos.w.king,
         Player::Black => pos.b.king,
     };
-    is_square_attacked(pos, lsb(king_bb).into(), player.opposite())
+    is_square_attacked(pos, king_bb.lsb(), player.opposite())
+}
+
+pub fn is_checkmate(pos: &Position) -> bool {
+    is_king_in_check(pos, pos.player_to_move) && !has_legal_move(pos)
+}
+
+pub fn is_stalemate(pos: &Position) -> bool {
+    !is_king_in_check(pos, pos.player_to_move) && !has_legal_move(pos)
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{constants::board::*, utility::bit};
 
     #[test]
     fn is_square_attacked_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("8/3r1k2/8/4N3/1Q5q/8/2K5/8 b - - 0 1")?;
-        assert_eq!(is_square_attacked(&pos, 53, Player::White), true);
-        assert_eq!(is_square_attacked(&pos, 51, Player::White), true);
-        assert_eq!(is_square_attacked(&pos, 20, Player::White), false);
-        assert_eq!(is_square_attacked(&pos, 25, Player::Black), true);
-        assert_eq!(is_square_attacked(&pos, 52, Player::Black), true);
-        assert_eq!(is_square_attacked(&pos, 10, Player::Black), false);
+        assert!(is_square_attacked(&pos, F7, Player::White));
+        assert!(is_square_attacked(&pos, D7, Player::White));
+        assert!(!is_square_attacked(&pos, E3, Player::White));
+        assert!(is_square_attacked(&pos, B4, Player::Black));
+        assert!(is_square_attacked(&pos, E7, Player::Black));
+        assert!(!is_square_attacked(&pos, C2, Player::Black));
         Ok(())
     }
 
     #[test]
     fn is_king_in_check_midgame_1() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("r1bqkb1r/ppp2ppp/5n2/1B4Q1/1n1P2N1/2N5/PPP2PPP/R1B1K2R b KQkq - 0 1")?;
-        assert_eq!(is_king_in_check(&pos, Player::White), false);
-        assert_eq!(is_king_in_check(&pos, Player::Black), true);
+        assert!(!is_king_in_check(&pos, Player::White));
+        assert!(is_king_in_check(&pos, Player::Black));
         Ok(())
     }
 
     #[test]
     fn is_king_in_check_midgame_2() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("r1bqk1nr/pppp2pp/2n5/1B2pp2/1b1PP3/5N2/PPP2PPP/RNBQK2R w KQkq - 0 1")?;
-        assert_eq!(is_king_in_check(&pos, Player::White), true);
-        assert_eq!(is_king_in_check(&pos, Player::Black), false);
+        assert!(is_king_in_check(&pos, Player::White));
+        assert!(!is_king_in_check(&pos, Player::Black));
         Ok(())
     }
 
     #[test]
     fn is_king_in_check_endgame() -> Result<(), FenParseError> {
         let (pos, _) = Position::from_fen("R6k/8/7K/8/8/1b6/8/8 b - - 0 1")?;
-        assert_eq!(is_king_in_check(&pos, Player::White), false);
-        assert_eq!(is_king_in_check(&pos, Player::Black), true);
+        assert!(!is_king_in_check(&pos, Player::White));
+        assert!(is_king_in_check(&pos, Player::Black));
+        Ok(())
+    }
+
+    #[test]
+    fn checkmate_and_stalemate() -> Result<(), FenParseError> {
+        let (mate, _) = Position::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3")?;
+        assert!(is_checkmate(&mate));
+        assert!(!is_stalemate(&mate));
+
+        let (stalemate, _) = Position::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1")?;
+        assert!(!is_checkmate(&stalemate));
+        assert!(is_stalemate(&stalemate));
+
+        // In check, but can run away
+        let (check, _) = Position::from_fen("R6k/8/7K/8/8/1b6/8/8 b - - 0 1")?;
+        assert!(!is_checkmate(&check));
+        assert!(!is_stalemate(&check));
+        Ok(())
+    }
+
+    #[test]
+    fn double_check() -> Result<(), FenParseError> {
+        // Discovered check from the rook, the knight checks too
+        let (pos, _) = Position::from_fen("4k3/8/3N4/8/8/8/8/4R1K1 b - - 0 1")?;
+        assert_eq!(checkers(&pos), bit(4) | bit(43));
+        let (pos, _) = Position::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1")?;
+        assert_eq!(checkers(&pos), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn is_square_attacked_matches_attackers_to() -> Result<(), FenParseError> {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/3r1k2/8/4N3/1Q5q/8/2K5/8 b - - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        ] {
+            let (pos, _) = Position::from_fen(fen)?;
+            for sq in Square::all() {
+                for player in [Player::White, Player::Black] {
+                    assert_eq!(is_square_attacked(&pos, sq, player), !attackers_to(&pos, sq, player).is_empty());
+                }
+            }
+        }
         Ok(())
     }
 }
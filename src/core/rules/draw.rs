@@ -1,45 +1,50 @@
-use crate::utility::{is_square_color_white, lsb};
+use crate::constants::board::{DARK_SQUARES, LIGHT_SQUARES};
 use crate::core::{
     position::*,
     piece::Piece,
+    player::Player,
 };
 
+// Dead positions by the FIDE rules: no sequence of legal moves leads to mate. K vs K, a single
+// minor piece against a lone king, and bishops that are all on squares of the same color
 pub fn is_insufficient_material(pos: &Position) -> bool {
-    let total_pieces = pos.w.count_all() + pos.b.count_all();
-
-    let w_bishops = pos.w.count(Piece::Bishop);
-    let w_knights = pos.w.count(Piece::Knight);
-    let b_bishops = pos.b.count(Piece::Bishop);
-    let b_knights = pos.b.count(Piece::Knight);
+    let (w, b) = (&pos.w, &pos.b);
+    if w.pawns | b.pawns | w.rooks | b.rooks | w.queens | b.queens != 0 {
+        return false;
+    }
 
-    // King vs King
-    if total_pieces == 2 {
+    // Only bishops (if any), and all of them on the same color. That includes K vs K
+    let bishops = w.bishops | b.bishops;
+    let knights = w.knights | b.knights;
+    if knights == 0 && (bishops & LIGHT_SQUARES == 0 || bishops & DARK_SQUARES == 0) {
         return true;
     }
+    bishops == 0 && knights.count_ones() == 1
+}
 
-    // King and single minor piece vs King
-    let white_minors = w_bishops + w_knights;
-    let black_minors = b_bishops + b_knights;
-
-    if total_pieces == 3 {
-        return (white_minors == 1 && black_minors == 0) ||
-               (white_minors == 0 && black_minors == 1);
+// The dead positions, plus those where a mate only exists with the loser's help: two knights
+// vs a lone king, and a knight against a minor piece. Nobody can force it there, so it is
+// a draw for adjudication, but not by the rules
+pub fn is_unwinnable_for_both(pos: &Position) -> bool {
+    if is_insufficient_material(pos) {
+        return true;
     }
-
-    // King + Bishop vs King + Bishop (same color bishops)
-    if total_pieces == 4
-        && w_bishops == 1
-        && b_bishops == 1
-        && w_knights == 0
-        && b_knights == 0
-    {
-        let wb_sq = lsb(pos.w.bishops);
-        let bb_sq = lsb(pos.b.bishops);
-        let same_color = is_square_color_white(wb_sq) == is_square_color_white(bb_sq);
-        return same_color;
+    let (w, b) = (&pos.w, &pos.b);
+    if w.pawns | b.pawns | w.rooks | b.rooks | w.queens | b.queens != 0 {
+        return false;
     }
 
-    false
+    let count = |player, piece| pos.material.count(player, piece);
+    let white_minors = count(Player::White, Piece::Bishop) + count(Player::White, Piece::Knight);
+    let black_minors = count(Player::Black, Piece::Bishop) + count(Player::Black, Piece::Knight);
+
+    match (white_minors, black_minors) {
+        // Opposite-colored bishops are left alone, a mate can still come out of a blunder there
+        (1, 1) => w.knights | b.knights != 0,
+        (2, 0) => count(Player::White, Piece::Knight) == 2,
+        (0, 2) => count(Player::Black, Piece::Knight) == 2,
+        _ => false,
+    }
 }
 
 
@@ -50,36 +55,93 @@ mod tests {
     #[test]
     fn test_insufficient_material_king_vs_king() {
         let (pos, _) = Position::from_fen("8/8/8/8/8/8/8/K2k4 w - - 0 1").unwrap();
-        assert_eq!(is_insufficient_material(&pos), true);
+        assert!(is_insufficient_material(&pos));
     }
 
     #[test]
     fn test_insufficient_material_king_and_bishop_vs_king() {
         let (pos, _) = Position::from_fen("8/8/8/1K2k3/8/8/5B2/8 w - - 0 1").unwrap();
-        assert_eq!(is_insufficient_material(&pos), true);
+        assert!(is_insufficient_material(&pos));
     }
 
     #[test]
     fn test_insufficient_material_king_and_knight_vs_king() {
         let (pos, _) = Position::from_fen("8/8/5N2/2K5/8/6k1/8/8 w - - 0 1").unwrap();
-        assert_eq!(is_insufficient_material(&pos), true);
+        assert!(is_insufficient_material(&pos));
     }
 
     #[test]
     fn test_insufficient_material_king_bishop_vs_king_bishop_same_color() {
         let (pos, _) = Position::from_fen("8/8/3k2b1/8/8/1K3B2/8/8 w - - 0 1").unwrap();
-        assert_eq!(is_insufficient_material(&pos), true);
+        assert!(is_insufficient_material(&pos));
     }
 
     #[test]
     fn test_sufficient_material_king_bishop_vs_king_bishop_opposite_color() {
         let (pos, _) = Position::from_fen("8/6b1/3k4/8/8/1K3B2/8/8 w - - 0 1").unwrap();
-        assert_eq!(is_insufficient_material(&pos), false);
+        assert!(!is_insufficient_material(&pos));
+    }
+
+    #[test]
+    fn test_helpmate_only_is_not_insufficient_material() {
+        // KNN vs K, N vs N, B vs N: mate is still possible if the loser helps
+        for fen in [
+            "8/8/3k4/8/8/1K1NN3/8/8 w - - 0 1",
+            "8/3nn3/3k4/8/8/1K6/8/8 w - - 0 1",
+            "8/5n2/3k4/8/8/1K3N2/8/8 w - - 0 1",
+            "8/5n2/3k4/8/8/1K3B2/8/8 w - - 0 1",
+        ] {
+            let (pos, _) = Position::from_fen(fen).unwrap();
+            assert!(!is_insufficient_material(&pos), "{}", fen);
+            assert!(is_unwinnable_for_both(&pos), "{}", fen);
+        }
+    }
+
+    #[test]
+    fn test_insufficient_material_same_color_bishops() {
+        // Any number of them, as long as they are all on the same color
+        let (pos, _) = Position::from_fen("8/8/3k2b1/8/2B5/1K3B2/8/8 w - - 0 1").unwrap();
+        assert!(is_insufficient_material(&pos));
+    }
+
+    #[test]
+    fn test_sufficient_material_mating_pieces() {
+        // KBN vs K
+        let (pos, _) = Position::from_fen("8/8/3k4/8/8/1K1BN3/8/8 w - - 0 1").unwrap();
+        assert!(!is_insufficient_material(&pos));
+        // Bishop pair
+        let (pos, _) = Position::from_fen("8/8/3k4/8/8/1K1BB3/8/8 w - - 0 1").unwrap();
+        assert!(!is_insufficient_material(&pos));
+        // Three knights
+        let (pos, _) = Position::from_fen("8/8/3k4/8/8/1KNNN3/8/8 w - - 0 1").unwrap();
+        assert!(!is_insufficient_material(&pos));
+        // KNN vs KN, the defender has more than a king to get in its own way
+        let (pos, _) = Position::from_fen("8/5n2/3k4/8/8/1K1NN3/8/8 w - - 0 1").unwrap();
+        assert!(!is_insufficient_material(&pos));
+        // Rook
+        let (pos, _) = Position::from_fen("8/8/3k4/8/8/1K3R2/8/8 w - - 0 1").unwrap();
+        assert!(!is_insufficient_material(&pos));
     }
 
     #[test]
     fn test_sufficient_material_pawn() {
         let (pos, _) = Position::from_fen("8/8/3k4/8/8/4P3/1K6/8 w - - 0 1").unwrap();
-        assert_eq!(is_insufficient_material(&pos), false);
+        assert!(!is_insufficient_material(&pos));
+    }
+
+    #[test]
+    fn test_unwinnable_for_both() {
+        // The dead positions are unwinnable too
+        let (pos, _) = Position::from_fen("8/8/3k2b1/8/2B5/1K3B2/8/8 w - - 0 1").unwrap();
+        assert!(is_unwinnable_for_both(&pos));
+        for fen in [
+            "8/6b1/3k4/8/8/1K3B2/8/8 w - - 0 1",   // opposite-colored bishops
+            "8/8/3k4/8/8/1K1BN3/8/8 w - - 0 1",    // KBN vs K
+            "8/5n2/3k4/8/8/1K1NN3/8/8 w - - 0 1",  // KNN vs KN
+            "8/8/3k4/8/8/4P3/1K6/8 w - - 0 1",
+        ] {
+            let (pos, _) = Position::from_fen(fen).unwrap();
+            assert!(!is_unwinnable_for_both(&pos), "{}", fen);
+        }
     }
 }
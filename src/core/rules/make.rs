@@ -1,5 +1,4 @@
 use crate::constants::{board, zobrist::*};
-use crate::utility::square_idx_to_coordinates;
 use crate::core::{
     position::*,
     bitboard::*,
@@ -7,21 +6,16 @@ use crate::core::{
     player::Player,
     piece::Piece,
     rules::unmake::UndoData,
+    square::Square,
 };
 
-pub fn make_move(pos: &mut Position, m: &Move, halfmove_clock: &mut usize) -> UndoData {
+pub fn make_move(pos: &mut Position, m: &Move) -> UndoData {
     let who_made_move = pos.player_to_move;
 
-    let mut undo = UndoData {
-        move_to_undo: *m,
-        captured_piece: None,
-        castling: pos.castling,
-        en_passant_square: pos.en_passant_square,
-        halfmove_clock: *halfmove_clock,
-        zobrist_hash: pos.zobrist_hash,
-    };
+    let mut undo = UndoData::new(pos, m);
 
     update_en_passant_square(pos, m);
+    pos.halfmove_clock += 1;
 
     if m.is_castling() {
         handle_castling(pos, m, who_made_move);
@@ -29,8 +23,13 @@ pub fn make_move(pos: &mut Position, m: &Move, halfmove_clock: &mut usize) -> Un
         update_castling_rights(&mut pos.castling, m, who_made_move);
 
         if m.piece == Piece::Pawn || m.capture {
-            *halfmove_clock = 0;
+            pos.halfmove_clock = 0;
+        }
+
+        if m.capture && !m.en_passant {
+            undo.captured_piece = pos.what(m.to).map(|(_, piece)| piece);
         }
+        update_mailbox(pos, m, who_made_move);
 
         // Borrow checker workaround
         let mut castling = pos.castling;
@@ -47,16 +46,35 @@ pub fn make_move(pos: &mut Position, m: &Move, halfmove_clock: &mut usize) -> Un
         if m.en_passant {
             handle_en_passant(hostile, m, &mut hash, who_made_move);
         } else if m.capture {
-            undo.captured_piece = hostile.what(m.to);
             handle_capture(hostile, m, &mut hash, &mut castling, who_made_move, undo.captured_piece.unwrap());
         }
 
         pos.castling = castling;
         pos.zobrist_hash = hash;
+        update_pawn_hash(pos, m, who_made_move, undo.captured_piece);
+        update_material(&mut pos.material, m, who_made_move, undo.captured_piece);
     }
 
-    update_castling_hash(pos, undo.castling);
-    finalize_move(pos, halfmove_clock);
+    update_castling_hash(pos, undo.castling());
+    finalize_move(pos);
+    #[cfg(feature = "verify")]
+    pos.verify_incremental_state();
+
+    undo
+}
+
+// Passes the turn: for null-move pruning and threat detection. Not legal when in check
+pub fn make_null_move(pos: &mut Position) -> UndoData {
+    let undo = UndoData::new(pos, &Move::null());
+
+    if let Some(ep_sq) = pos.en_passant_square.take() {
+        en_passant_hash(&mut pos.zobrist_hash, ep_sq);
+    }
+    pos.halfmove_clock += 1;
+    pos.player_to_move = pos.player_to_move.opposite();
+    pos.zobrist_hash ^= ZOBRIST_SIDE_BLACK;
+    #[cfg(feature = "verify")]
+    pos.verify_incremental_state();
 
     undo
 }
@@ -66,8 +84,10 @@ fn update_en_passant_square(new: &mut Position, m: &Move) {
         en_passant_hash(&mut new.zobrist_hash, prev_ep_sq);
     }
 
-    new.en_passant_square = if m.double_push {
-        let new_ep_sq = (m.from + m.to) / 2;
+    // Only if a pawn can take en passant: otherwise the position is the same as one without the
+    // square for the repetition rules (and for other engines' hashes, Polyglot's included)
+    let new_ep_sq = Square((m.from.0 + m.to.0) / 2);
+    new.en_passant_square = if m.double_push && new.can_capture_en_passant(new_ep_sq, new.player_to_move.opposite()) {
         en_passant_hash(&mut new.zobrist_hash, new_ep_sq);
         Some(new_ep_sq)
     } else {
@@ -91,6 +111,11 @@ fn handle_castling(new: &mut Position, m: &Move, who_made_move: Player) {
     friendly.king = friendly.king.unset_bit(m.from).set_bit(m.to);
     friendly.rooks = friendly.rooks.unset_bit(rook_from).set_bit(rook_to);
 
+    new.mailbox[m.from.index()] = None;
+    new.mailbox[rook_from.index()] = None;
+    new.mailbox[m.to.index()] = Some((who_made_move, Piece::King));
+    new.mailbox[rook_to.index()] = Some((who_made_move, Piece::Rook));
+
     let rook_move = Move::new(rook_from, rook_to, Piece::Rook, false);
 
     apply_move_hash(&mut new.zobrist_hash, m, who_made_move);
@@ -99,8 +124,22 @@ fn handle_castling(new: &mut Position, m: &Move, who_made_move: Player) {
     new.castling.reset(who_made_move);
 }
 
-fn toggle_piece_hash(hash: &mut u64, piece: Piece, player: Player, sq: u8) {
-    *hash ^= ZOBRIST_PIECE[piece.index()][player.index()][sq as usize];
+// Castling is handled in `handle_castling`
+fn update_mailbox(new: &mut Position, m: &Move, who_made_move: Player) {
+    new.mailbox[m.from.index()] = None;
+    new.mailbox[m.to.index()] = Some((who_made_move, m.promotion.unwrap_or(m.piece)));
+
+    if m.en_passant {
+        let captured_pawn_sq = match who_made_move {
+            Player::White => m.to - 8,
+            Player::Black => m.to + 8,
+        };
+        new.mailbox[captured_pawn_sq.index()] = None;
+    }
+}
+
+fn toggle_piece_hash(hash: &mut u64, piece: Piece, player: Player, sq: Square) {
+    *hash ^= ZOBRIST_PIECE[piece.index()][player.index()][sq.index()];
 }
 
 fn apply_move_hash(hash: &mut u64, m: &Move, player: Player) {
@@ -108,9 +147,8 @@ fn apply_move_hash(hash: &mut u64, m: &Move, player: Player) {
     toggle_piece_hash(hash, m.piece, player, m.to);
 }
 
-fn en_passant_hash(hash: &mut u64, ep_sq: u8) {
-    let (file, _) = square_idx_to_coordinates(ep_sq);
-    *hash ^= ZOBRIST_EN_PASSANT_FILE[file as usize];
+fn en_passant_hash(hash: &mut u64, ep_sq: Square) {
+    *hash ^= ZOBRIST_EN_PASSANT_FILE[ep_sq.file().index()];
 }
 
 fn handle_promotion(
@@ -174,6 +212,38 @@ fn handle_capture(
     }
 }
 
+fn update_pawn_hash(new: &mut Position, m: &Move, who_made_move: Player, captured: Option<Piece>) {
+    if m.piece == Piece::Pawn {
+        toggle_piece_hash(&mut new.pawn_hash, Piece::Pawn, who_made_move, m.from);
+        if m.promotion.is_none() {
+            toggle_piece_hash(&mut new.pawn_hash, Piece::Pawn, who_made_move, m.to);
+        }
+    }
+
+    if m.en_passant {
+        let captured_pawn_sq = match who_made_move {
+            Player::White => m.to - 8,
+            Player::Black => m.to + 8,
+        };
+        toggle_piece_hash(&mut new.pawn_hash, Piece::Pawn, who_made_move.opposite(), captured_pawn_sq);
+    } else if captured == Some(Piece::Pawn) {
+        toggle_piece_hash(&mut new.pawn_hash, Piece::Pawn, who_made_move.opposite(), m.to);
+    }
+}
+
+fn update_material(material: &mut Material, m: &Move, who_made_move: Player, captured: Option<Piece>) {
+    if let Some(promotion_piece) = m.promotion {
+        material.remove(who_made_move, Piece::Pawn);
+        material.add(who_made_move, promotion_piece);
+    }
+
+    if m.en_passant {
+        material.remove(who_made_move.opposite(), Piece::Pawn);
+    } else if let Some(captured) = captured {
+        material.remove(who_made_move.opposite(), captured);
+    }
+}
+
 fn update_castling_rights(castling: &mut CastlingRights, m: &Move, who_made_move: Player) {
     match m.piece {
         Piece::King => castling.reset(who_made_move),
@@ -193,25 +263,23 @@ fn update_castling_hash(new: &mut Position, old_castling: CastlingRights) {
     new.zobrist_hash ^= ZOBRIST_CASTLING[new.castling.encode() as usize];
 }
 
-fn finalize_move(new: &mut Position, halfmove_clock: &mut usize) {
+fn finalize_move(new: &mut Position) {
     new.update();
     new.player_to_move = new.player_to_move.opposite();
     new.zobrist_hash ^= ZOBRIST_SIDE_BLACK;
-    *halfmove_clock += 1;
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utility::bit;
+    use crate::{constants::board::*, core::zobrist::zobrist_hash, utility::bit};
 
     #[test]
     fn make_move_knight() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/1k6/3r4/8/4N3/8/1K6/8 w - - 0 1")?;
-        let m = Move::new(28, 43, Piece::Knight, true);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        let m = Move::new(E4, D6, Piece::Knight, true);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.w.king, bit(9));
         assert_eq!(pos.w.knights, bit(43));
@@ -228,9 +296,8 @@ mod tests {
     #[test]
     fn make_move_rook() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/8/8/5r2/8/1k6/5Q2/1K6 b - - 0 1")?;
-        let m = Move::new(37, 13, Piece::Rook, true);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        let m = Move::new(F5, F2, Piece::Rook, true);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.w.king, bit(1));
         assert_eq!(pos.w.queens, 0x0);
@@ -245,9 +312,8 @@ mod tests {
     #[test]
     fn make_move_king() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/5kq1/1R6/8/3K4/8/8/8 w - - 0 1")?;
-        let m = Move::new(27, 35, Piece::King, false);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        let m = Move::new(D4, D5, Piece::King, false);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.w.rooks, bit(41));
         assert_eq!(pos.w.king, bit(35));
@@ -262,9 +328,8 @@ mod tests {
     #[test]
     fn make_move_bishop() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/2k5/8/4K3/1r6/8/3B4/8 w - - 0 1")?;
-        let m = Move::new(11, 25, Piece::Bishop, true);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        let m = Move::new(D2, B4, Piece::Bishop, true);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.w.king, bit(36));
         assert_eq!(pos.w.bishops, bit(25));
@@ -279,9 +344,8 @@ mod tests {
     #[test]
     fn make_move_queen() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/8/1kq5/8/5K2/2R5/8/8 b - - 0 1")?;
-        let m = Move::new(42, 18, Piece::Queen, true);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        let m = Move::new(C6, C3, Piece::Queen, true);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.w.king, bit(29));
         assert_eq!(pos.w.rooks, 0x0);
@@ -297,8 +361,7 @@ mod tests {
     fn make_move_white_kingside_castling() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("rn1qkbnr/ppp2ppp/3p4/4p3/2B1P1b1/5N2/PPPP1PPP/RNBQK2R w KQkq - 2 4")?;
         let m = Move::castling(Player::White, CastlingSide::KingSide);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.w.all, pos.w.all & !(bit(4) | bit(7)) | bit(5) | bit(6));
         assert_eq!(pos.occupied, pos.occupied & !(bit(4) | bit(7)) | bit(5) | bit(6));
@@ -312,8 +375,7 @@ mod tests {
     fn make_move_black_kingside_castling() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("rnbqk2r/pppp1ppp/5n2/2b1p3/4P3/3PBN2/PPP2PPP/RN1QKB1R b KQkq - 4 4")?;
         let m = Move::castling(Player::Black, CastlingSide::KingSide);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.b.all, pos.b.all & !(bit(60) | bit(63)) | bit(61) | bit(62));
         assert_eq!(pos.occupied, pos.occupied & !(bit(60) | bit(63)) | bit(61) | bit(62));
@@ -327,8 +389,7 @@ mod tests {
     fn make_move_white_queenside_castling() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("rn2k1nr/ppp2ppp/3pbq2/2b1p2Q/4P3/2NPB3/PPP2PPP/R3KBNR w KQkq - 4 6")?;
         let m = Move::castling(Player::White, CastlingSide::QueenSide);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.w.all, pos.w.all & !(bit(0) | bit(4)) | bit(2) | bit(3));
         assert_eq!(pos.occupied, pos.occupied & !(bit(0) | bit(4)) | bit(2) | bit(3));
@@ -342,8 +403,7 @@ mod tests {
     fn make_move_black_queenside_castling() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("r3kbnr/ppp2ppp/2npbq2/4p1N1/4P3/2NPB3/PPP2PPP/R2QKB1R b KQkq - 7 6")?;
         let m = Move::castling(Player::Black, CastlingSide::QueenSide);
-        let mut clock = 0;
-        make_move(&mut pos, &m, &mut clock);
+        make_move(&mut pos, &m);
 
         assert_eq!(pos.b.all, pos.b.all & !(bit(56) | bit(60)) | bit(58) | bit(59));
         assert_eq!(pos.occupied, pos.occupied & !(bit(56) | bit(60)) | bit(58) | bit(59));
@@ -353,11 +413,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn make_move_halfmove_clock() -> Result<(), FenParseError> {
+        let (mut pos, _) = Position::from_fen("8/1k6/3r4/8/4N3/8/1K4P1/8 w - - 10 1")?;
+        make_move(&mut pos, &Move::new(board::B2, board::C2, Piece::King, false));
+        assert_eq!(pos.halfmove_clock, 11);
+        make_move(&mut pos, &Move::new(board::B7, board::C7, Piece::King, false));
+        assert_eq!(pos.halfmove_clock, 12);
+        make_move(&mut pos, &Move::pawn(board::G2, board::G4, false, None, false));
+        assert_eq!(pos.halfmove_clock, 0);
+        make_move(&mut pos, &Move::new(board::D6, board::D2, Piece::Rook, false));
+        assert_eq!(pos.halfmove_clock, 1);
+        make_move(&mut pos, &Move::new(board::C2, board::D2, Piece::King, true));
+        assert_eq!(pos.halfmove_clock, 0);
+        Ok(())
+    }
+
+    #[test]
+    // The reset has to come after the increment, or a capture or a pawn move leaves the clock at 1
+    fn make_move_halfmove_clock_reset_last() -> Result<(), FenParseError> {
+        let (mut pos, _) = Position::from_fen("1r5k/P7/8/8/8/8/8/R3K3 w Q - 30 40")?;
+        make_move(&mut pos, &Move::castling(Player::White, CastlingSide::QueenSide));
+        assert_eq!(pos.halfmove_clock, 31);
+        make_move(&mut pos, &Move::new(board::H8, board::G8, Piece::King, false));
+        assert_eq!(pos.halfmove_clock, 32);
+        make_move(&mut pos, &Move::pawn(board::A7, board::B8, true, Some(Piece::Queen), false));
+        assert_eq!(pos.halfmove_clock, 0);
+
+        let (mut pos, _) = Position::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 7 20")?;
+        make_move(&mut pos, &Move::pawn(board::E5, board::D6, true, None, true));
+        assert_eq!(pos.halfmove_clock, 0);
+        Ok(())
+    }
+
     #[test]
     fn zobrist_hash_piece_movement() -> Result<(), FenParseError> {
         let mut pos = Position::start();
-        let mut clock = 0;
-        make_move(&mut pos, &Move::pawn(board::E2, board::E3, false, None, false), &mut clock);
+        make_move(&mut pos, &Move::pawn(board::E2, board::E3, false, None, false));
         let (after, _) = Position::from_fen("rnbqkbnr/pppppppp/8/8/8/4P3/PPPP1PPP/RNBQKBNR b KQkq - 0 1")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -366,8 +458,7 @@ mod tests {
     #[test]
     fn zobrist_hash_piece_movement_en_passant() -> Result<(), FenParseError> {
         let mut pos = Position::start();
-        let mut clock = 0;
-        make_move(&mut pos, &Move::pawn(board::E2, board::E4, false, None, false), &mut clock);
+        make_move(&mut pos, &Move::pawn(board::E2, board::E4, false, None, false));
         let (after, _) = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -375,34 +466,43 @@ mod tests {
 
     #[test]
     fn zobrist_hash_piece_movement_en_passant_update() -> Result<(), FenParseError> {
-        let mut pos = Position::start();
-        let mut clock = 0;
-
-        let e4 = Move::pawn(board::E2, board::E4, false, None, false);
-        let d5 = Move::pawn(board::D7, board::D5, false, None, false);
-
-        make_move(&mut pos, &e4, &mut clock);
-        let after_e4 = pos;
-
-        make_move(&mut pos, &d5, &mut clock);
-        let after_d5 = pos;
+        let (mut pos, _) = Position::from_fen("rnbqkbnr/ppp1pppp/8/3P4/3p4/8/PPP1PPPP/RNBQKBNR w KQkq - 0 1")?;
+
+        make_move(&mut pos, &Move::pawn(board::E2, board::E4, false, None, false));
+        let (after_e4, _) = Position::from_fen("rnbqkbnr/ppp1pppp/8/3P4/3pP3/8/PPP2PPP/RNBQKBNR b KQkq e3 0 1")?;
+        assert_eq!(pos.en_passant_square, Some(board::E3));
+        assert_eq!(pos.zobrist_hash, after_e4.zobrist_hash);
+
+        // The old square goes, the new one comes
+        make_move(&mut pos, &Move::pawn(board::C7, board::C5, false, None, false));
+        let (after_c5, _) = Position::from_fen("rnbqkbnr/pp2pppp/8/2pP4/3pP3/8/PPP2PPP/RNBQKBNR w KQkq c6 0 2")?;
+        assert_eq!(pos.en_passant_square, Some(board::C6));
+        assert_eq!(pos.zobrist_hash, after_c5.zobrist_hash);
+        Ok(())
+    }
 
-        let x = after_e4.zobrist_hash ^ ZOBRIST_PIECE[Piece::Pawn.index()][Player::White.index()][board::E4 as usize]
-                                      ^ ZOBRIST_PIECE[Piece::Pawn.index()][Player::Black.index()][board::D7 as usize]
-                                      ^ ZOBRIST_EN_PASSANT_FILE[4];
-        let y = after_d5.zobrist_hash ^ ZOBRIST_PIECE[Piece::Pawn.index()][Player::White.index()][board::E4 as usize]
-                                      ^ ZOBRIST_PIECE[Piece::Pawn.index()][Player::Black.index()][board::D5 as usize]
-                                      ^ ZOBRIST_SIDE_BLACK
-                                      ^ ZOBRIST_EN_PASSANT_FILE[3];
-        assert_eq!(x, y);
+    #[test]
+    fn en_passant_square_only_if_it_can_be_taken() -> Result<(), FenParseError> {
+        let mut after_e4 = Position::start();
+        make_move(&mut after_e4, &Move::pawn(board::E2, board::E4, false, None, false));
+        assert_eq!(after_e4.en_passant_square, None);
+        assert_eq!(after_e4.zobrist_hash, zobrist_hash(&after_e4));
+
+        // A pawn next to it on the other side of the board does not count
+        let (mut pos, _) = Position::from_fen("4k3/8/8/8/7p/8/P7/4K3 w - - 0 1")?;
+        make_move(&mut pos, &Move::pawn(board::A2, board::A4, false, None, false));
+        assert_eq!(pos.en_passant_square, None);
+
+        // A FEN with a square nobody can take on is read as one without
+        let (from_fen, _) = Position::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")?;
+        assert_eq!(from_fen, after_e4);
         Ok(())
     }
 
     #[test]
     fn zobrist_hash_piece_capture() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/1k6/4r3/1K1P4/8/8/8/8 w - - 0 1")?;
-        let mut clock = 0;
-        make_move(&mut pos, &Move::pawn(board::D5, board::E6, true, None, false), &mut clock);
+        make_move(&mut pos, &Move::pawn(board::D5, board::E6, true, None, false));
         let (after, _) = Position::from_fen("8/1k6/4P3/1K6/8/8/8/8 b - - 0 1")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -411,8 +511,7 @@ mod tests {
     #[test]
     fn zobrist_hash_piece_capture_en_passant() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/6k1/1p6/2pP4/8/8/2P3K1/8 w - c6 0 1")?;
-        let mut clock = 0;
-        make_move(&mut pos, &Move::pawn(board::D5, board::C6, true, None, true), &mut clock);
+        make_move(&mut pos, &Move::pawn(board::D5, board::C6, true, None, true));
         let (after, _) = Position::from_fen("8/6k1/1pP5/8/8/8/2P3K1/8 b - - 0 1")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -421,8 +520,7 @@ mod tests {
     #[test]
     fn zobrist_hash_pawn_promotion() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("8/2P5/8/8/8/1r6/4k1K1/8 w - - 0 1")?;
-        let mut clock = 0;
-        make_move(&mut pos, &Move::pawn(board::C7, board::C8, false, Some(Piece::Queen), false), &mut clock);
+        make_move(&mut pos, &Move::pawn(board::C7, board::C8, false, Some(Piece::Queen), false));
         let (after, _) = Position::from_fen("2Q5/8/8/8/8/1r6/4k1K1/8 b - - 0 1")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -431,8 +529,7 @@ mod tests {
     #[test]
     fn zobrist_hash_castling() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("r1b1kbnr/pppp1ppp/2n2q2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4")?;
-        let mut clock = 0;
-        make_move(&mut pos, &Move::castling(Player::White, CastlingSide::KingSide), &mut clock);
+        make_move(&mut pos, &Move::castling(Player::White, CastlingSide::KingSide));
         let (after, _) = Position::from_fen("r1b1kbnr/pppp1ppp/2n2q2/4p3/2B1P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 5 4")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -441,8 +538,7 @@ mod tests {
     #[test]
     fn zobrist_hash_castling_revoked_rook_move() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("r1b1kbnr/pppp1ppp/2n2q2/4p3/2B1P3/2N2N2/PPPP1PPP/R1BQK2R b KQkq - 0 1")?;
-        let mut clock = 0;
-        make_move(&mut pos, &Move::new(board::A8, board::B8, Piece::Rook, false), &mut clock);
+        make_move(&mut pos, &Move::new(board::A8, board::B8, Piece::Rook, false));
         let (after, _) = Position::from_fen("1rb1kbnr/pppp1ppp/2n2q2/4p3/2B1P3/2N2N2/PPPP1PPP/R1BQK2R w KQk - 1 2")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -451,8 +547,7 @@ mod tests {
     #[test]
     fn zobrist_hash_castling_revoked_king_move() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("r1b1kbnr/pppp1ppp/2n2q2/4p3/2B1P3/2N2N2/PPPP1PPP/R1BQK2R b KQkq - 0 1")?;
-        let mut clock = 0;
-        make_move(&mut pos, &Move::new(board::E8, board::E7, Piece::King, false), &mut clock);
+        make_move(&mut pos, &Move::new(board::E8, board::E7, Piece::King, false));
         let (after, _) = Position::from_fen("r1b2bnr/ppppkppp/2n2q2/4p3/2B1P3/2N2N2/PPPP1PPP/R1BQK2R w KQ - 1 2")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
@@ -461,10 +556,19 @@ mod tests {
     #[test]
     fn zobrist_hash_castling_revoked_rook_capture() -> Result<(), FenParseError> {
         let (mut pos, _) = Position::from_fen("r1b1kbnr/ppp2ppp/1Nn2q2/4p3/2BpP3/5N2/PPPP1PPP/R1BQK2R w KQkq - 0 4")?;
-        let mut clock = 0;
-        make_move(&mut pos, &Move::new(board::B6, board::A8, Piece::Knight, true), &mut clock);
+        make_move(&mut pos, &Move::new(board::B6, board::A8, Piece::Knight, true));
         let (after, _) = Position::from_fen("N1b1kbnr/ppp2ppp/2n2q2/4p3/2BpP3/5N2/PPPP1PPP/R1BQK2R b KQk - 0 4")?;
         assert_eq!(pos.zobrist_hash, after.zobrist_hash);
         Ok(())
     }
+
+    #[test]
+    fn zobrist_hash_null_move() -> Result<(), FenParseError> {
+        let (mut pos, _) = Position::from_fen("8/6k1/1p6/2pP4/8/8/2P3K1/8 w - c6 0 1")?;
+        make_null_move(&mut pos);
+        let (after, _) = Position::from_fen("8/6k1/1p6/2pP4/8/8/2P3K1/8 b - - 1 1")?;
+        assert_eq!(pos, after);
+        assert_eq!(Move::null().to_string(), "0000");
+        Ok(())
+    }
 }
@@ -0,0 +1,91 @@
+// Invariants of make/unmake, checked along random games: whatever the moves, unmaking gives the
+// position back, and what make keeps up to date incrementally matches a recomputation.
+// The move generator and the evaluation check theirs along the same games
+use rand::seq::IndexedRandom;
+use crate::core::{
+    chess_move::Move,
+    movegen::legal_moves,
+    position::{FenParseError, Position},
+    rules::{checks::is_king_in_check, make::*, unmake::*},
+    zobrist::{pawn_hash, zobrist_hash},
+};
+
+const GAMES: usize = 20;
+const PLIES: usize = 120;
+
+const START_FENS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+];
+
+// Plays `GAMES` random games of up to `PLIES` from every start position, calls `check` with the
+// position before and after every move, then unmakes the whole game and expects the start back
+pub(crate) fn random_games(mut check: impl FnMut(&Position, &Move, &Position)) -> Result<(), FenParseError> {
+    let mut rng = rand::rng();
+    for fen in START_FENS {
+        let (start, _) = Position::from_fen(fen)?;
+        for _ in 0..GAMES {
+            let mut pos = start;
+            let mut undos = Vec::new();
+            for _ in 0..PLIES {
+                let Some(m) = legal_moves(&pos).choose(&mut rng).copied() else {
+                    break;
+                };
+                let before = pos;
+                undos.push(make_move(&mut pos, &m));
+                check(&before, &m, &pos);
+            }
+            while let Some(undo) = undos.pop() {
+                unmake_move(&mut pos, undo);
+            }
+            assert_eq!(pos, start, "unmaking a whole game, {}", fen);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn unmake_restores_the_position() -> Result<(), FenParseError> {
+    random_games(|before, m, after| {
+        let mut pos = *after;
+        let undo = make_null_move(&mut pos);
+        unmake_null_move(&mut pos, undo);
+        assert_eq!(pos, *after, "null move, {}", after.to_fen(1));
+
+        let mut pos = *before;
+        let undo = make_move(&mut pos, m);
+        unmake_move(&mut pos, undo);
+        assert_eq!(pos, *before, "{} in {}", m, before.to_fen(1));
+    })
+}
+
+#[test]
+fn incremental_hashes_match_recomputation() -> Result<(), FenParseError> {
+    random_games(|before, m, after| {
+        assert_eq!(after.zobrist_hash, zobrist_hash(after), "Zobrist hash after {} in {}", m, before.to_fen(1));
+        assert_eq!(after.pawn_hash, pawn_hash(after), "pawn hash after {} in {}", m, before.to_fen(1));
+
+        if !is_king_in_check(after, after.player_to_move) {
+            let mut pos = *after;
+            make_null_move(&mut pos);
+            assert_eq!(pos.zobrist_hash, zobrist_hash(&pos), "Zobrist hash after a null move in {}", after.to_fen(1));
+        }
+    })
+}
+
+#[test]
+fn occupancy_matches_the_pieces() -> Result<(), FenParseError> {
+    random_games(|before, m, after| {
+        let (w, b) = (&after.w, &after.b);
+        let context = || format!("after {} in {}", m, before.to_fen(1));
+        assert_eq!(after.occupied, w.all | b.all, "{}", context());
+        assert!((w.all & b.all).is_empty(), "{}", context());
+        for set in [w, b] {
+            assert_eq!(set.all, set.pawns | set.knights | set.bishops | set.rooks | set.queens | set.king, "{}", context());
+            assert_eq!(set.king.count_ones(), 1, "{}", context());
+        }
+    })
+}
@@ -5,27 +5,65 @@ use crate::core::{
     chess_move::*,
     player::Player,
     piece::Piece,
+    square::Square,
 };
 
-#[derive(Clone)]
+// What `unmake_move` can not work out from the move alone, packed: the search keeps one per ply
+// on its stack. The material is not in here, unmaking the move puts it back
+#[derive(Clone, Copy)]
 pub struct UndoData {
-    pub move_to_undo: Move,
-    pub captured_piece: Option<Piece>,
-    pub castling: CastlingRights,
-    pub en_passant_square: Option<u8>,
-    pub halfmove_clock: usize,
     pub zobrist_hash: u64,
+    pub pawn_hash: u64,
+    packed_move: u32,  // `Move::pack`
+    halfmove_clock: u16,
+    pub en_passant_square: Option<Square>,
+    pub captured_piece: Option<Piece>,
+    castling: u8,  // `CastlingRights::encode`
 }
 
-pub fn unmake_move(pos: &mut Position, undo: UndoData, halfmove_clock: &mut usize) {
+impl UndoData {
+    // `pos` as it is before `m`, the captured piece is up to the caller
+    pub(crate) fn new(pos: &Position, m: &Move) -> UndoData {
+        UndoData {
+            zobrist_hash: pos.zobrist_hash,
+            pawn_hash: pos.pawn_hash,
+            packed_move: m.pack(),
+            // Past the fifty-move rule the exact count makes no difference
+            halfmove_clock: pos.halfmove_clock.min(u16::MAX as usize) as u16,
+            en_passant_square: pos.en_passant_square,
+            captured_piece: None,
+            castling: pos.castling.encode(),
+        }
+    }
+
+    pub fn move_to_undo(&self) -> Move {
+        Move::unpack(self.packed_move)
+    }
+
+    pub fn castling(&self) -> CastlingRights {
+        CastlingRights::decode(self.castling)
+    }
+
+    pub fn halfmove_clock(&self) -> usize {
+        self.halfmove_clock as usize
+    }
+}
+
+pub fn unmake_move(pos: &mut Position, undo: UndoData) {
     let who_moved = pos.player_to_move.opposite();
-    let m = undo.move_to_undo;
+    let m = undo.move_to_undo();
 
-    pos.castling = undo.castling;
+    pos.castling = undo.castling();
     pos.en_passant_square = undo.en_passant_square;
     pos.zobrist_hash = undo.zobrist_hash;
-    *halfmove_clock = undo.halfmove_clock;
+    pos.pawn_hash = undo.pawn_hash;
+    pos.halfmove_clock = undo.halfmove_clock();
     pos.player_to_move = who_moved;
+    restore_material(&mut pos.material, &m, who_moved, undo.captured_piece);
+
+    if !m.is_castling() {
+        restore_mailbox(pos, &m, who_moved, undo.captured_piece);
+    }
 
     let (friendly, hostile) = pos.perspective_mut(who_moved);
 
@@ -46,9 +84,23 @@ pub fn unmake_move(pos: &mut Position, undo: UndoData, halfmove_clock: &mut usiz
     }
 
     pos.update();
+    #[cfg(feature = "verify")]
+    pos.verify_incremental_state();
+}
+
+pub fn unmake_null_move(pos: &mut Position, undo: UndoData) {
+    pos.en_passant_square = undo.en_passant_square;
+    pos.zobrist_hash = undo.zobrist_hash;
+    pos.halfmove_clock = undo.halfmove_clock();
+    pos.player_to_move = pos.player_to_move.opposite();
+    #[cfg(feature = "verify")]
+    pos.verify_incremental_state();
 }
 
 fn undo_castling(pos: &mut Position, m: &Move, who: Player) {
+    pos.mailbox[m.to.index()] = None;
+    pos.mailbox[m.from.index()] = Some((who, Piece::King));
+
     let (friendly, _) = pos.perspective_mut(who);
 
     let (rook_from, rook_to) = match (who, m.kingside_castling, m.queenside_castling) {
@@ -64,6 +116,36 @@ fn undo_castling(pos: &mut Position, m: &Move, who: Player) {
 
     friendly.unset_bit(rook_to);
     friendly.set_bit(rook_from, Piece::Rook);
+
+    pos.mailbox[rook_to.index()] = None;
+    pos.mailbox[rook_from.index()] = Some((who, Piece::Rook));
+}
+
+// `make::update_material` backwards
+fn restore_material(material: &mut Material, m: &Move, who: Player, captured: Option<Piece>) {
+    if let Some(promotion_piece) = m.promotion {
+        material.remove(who, promotion_piece);
+        material.add(who, Piece::Pawn);
+    }
+
+    if m.en_passant {
+        material.add(who.opposite(), Piece::Pawn);
+    } else if let Some(captured) = captured {
+        material.add(who.opposite(), captured);
+    }
+}
+
+fn restore_mailbox(pos: &mut Position, m: &Move, who: Player, captured: Option<Piece>) {
+    pos.mailbox[m.from.index()] = Some((who, m.piece));
+    pos.mailbox[m.to.index()] = captured.map(|piece| (who.opposite(), piece));
+
+    if m.en_passant {
+        let sq = match who {
+            Player::White => m.to - 8,
+            Player::Black => m.to + 8,
+        };
+        pos.mailbox[sq.index()] = Some((who.opposite(), Piece::Pawn));
+    }
 }
 
 fn undo_promotion(friendly: &mut BitboardSet, m: &Move) {
@@ -92,97 +174,123 @@ fn undo_en_passant(hostile: &mut BitboardSet, m: &Move, who: Player) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::rules::make::make_move;
+    use crate::core::rules::make::{make_move, make_null_move};
+
+    #[test]
+    fn undo_data_size() {
+        assert!(std::mem::size_of::<UndoData>() <= 32);
+    }
 
     #[test]
     fn unmake_move_normal_move() {
-        let (mut pos, mut clock) = Position::from_fen("8/3r4/2k5/8/5R2/2K5/8/8 w - - 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("8/3r4/2k5/8/5R2/2K5/8/8 w - - 0 1").unwrap();
         let save = pos;
         let m = Move::new(board::F4, board::F8, Piece::Rook, false);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_capture() {
-        let (mut pos, mut clock) = Position::from_fen("2b5/5k2/8/4n3/8/6B1/1K6/8 w - - 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("2b5/5k2/8/4n3/8/6B1/1K6/8 w - - 0 1").unwrap();
         let save = pos;
         let m = Move::new(board::G3, board::E5, Piece::Bishop, true);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_promotion() {
-        let (mut pos, mut clock) = Position::from_fen("8/2P5/5k2/1K6/8/8/8/8 w - - 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("8/2P5/5k2/1K6/8/8/8/8 w - - 0 1").unwrap();
         let save = pos;
         let m = Move::pawn(board::C7, board::C8, false, Some(Piece::Queen), false);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_en_passant() {
-        let (mut pos, mut clock) = Position::from_fen("8/8/5k2/1KPp4/8/8/8/8 w - d6 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("8/8/5k2/1KPp4/8/8/8/8 w - d6 0 1").unwrap();
         let save = pos;
         let m = Move::pawn(board::C5, board::D6, true, None, true);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_castling() {
-        let (mut pos, mut clock) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/8/P4PPP/2B1K2R w K - 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/8/P4PPP/2B1K2R w K - 0 1").unwrap();
         let save = pos;
         let m = Move::castling(Player::White, CastlingSide::KingSide);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_castling_rights_rook_move() {
-        let (mut pos, mut clock) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/8/P4PPP/2B1K2R w K - 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/8/P4PPP/2B1K2R w K - 0 1").unwrap();
         let save = pos;
         let m = Move::new(board::H1, board::F1, Piece::Rook, false);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_castling_rights_king_move() {
-        let (mut pos, mut clock) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/8/P4PPP/2B1K2R w K - 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/8/P4PPP/2B1K2R w K - 0 1").unwrap();
         let save = pos;
         let m = Move::new(board::E1, board::D2, Piece::King, false);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_castling_rights_rook_capture() {
-        let (mut pos, mut clock) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/6n1/P4PPP/2B1K2R b K - 0 1").unwrap();
+        let (mut pos, _) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/6n1/P4PPP/2B1K2R b K - 0 1").unwrap();
         let save = pos;
         let m = Move::new(board::G3, board::H1, Piece::Knight, true);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
         assert_eq!(pos, save);
     }
 
     #[test]
     fn unmake_move_castling_rights_clock() {
-        let (mut pos, mut clock) = Position::from_fen("8/8/1k1p4/2p5/6P1/7P/5K2/8 b - - 39 100").unwrap();
+        let (mut pos, _) = Position::from_fen("8/8/1k1p4/2p5/6P1/7P/5K2/8 b - - 39 100").unwrap();
         let save = pos;
-        let save_clock = clock;
         let m = Move::new(board::B6, board::B5, Piece::King, false);
-        let undo = make_move(&mut pos, &m, &mut clock);
-        unmake_move(&mut pos, undo, &mut clock);
+        let undo = make_move(&mut pos, &m);
+        unmake_move(&mut pos, undo);
+        assert_eq!(pos, save);
+        assert_eq!(pos.halfmove_clock, 39);
+    }
+
+    #[test]
+    fn unmake_move_null_move() {
+        let (mut pos, _) = Position::from_fen("5b2/1q1pp2p/5k2/8/6Q1/8/P4PPP/2B1K2R b K - 7 30").unwrap();
+        let save = pos;
+        let undo = make_null_move(&mut pos);
+        assert_eq!(pos.player_to_move, Player::White);
+        assert_eq!(pos.halfmove_clock, 8);
+        assert_ne!(pos.zobrist_hash, save.zobrist_hash);
+        unmake_null_move(&mut pos, undo);
+        assert_eq!(pos, save);
+    }
+
+    #[test]
+    fn unmake_move_null_move_en_passant() {
+        let (mut pos, _) = Position::from_fen("8/8/5k2/1KPp4/8/8/8/8 w - d6 0 1").unwrap();
+        let save = pos;
+        let undo = make_null_move(&mut pos);
+        assert_eq!(pos.en_passant_square, None);
+        unmake_null_move(&mut pos, undo);
         assert_eq!(pos, save);
-        assert_eq!(clock, save_clock);
     }
 }
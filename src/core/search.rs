@@ -0,0 +1,610 @@
+use std::{
+    sync::{atomic::{AtomicBool, Ordering}, Arc},
+    time::Duration,
+};
+use crate::constants::MAX_DEPTH;
+use crate::core::{chess_move::Move, clock::Instant, game::Game, tt::TranspositionTable};
+use time::TimeManager;
+
+pub mod time;
+pub mod mate;
+pub mod strength;
+pub mod skill;
+
+// Every limit is optional, the search stops at whichever is hit first.
+// No limits at all means an infinite search, which only `stop_flag` can end
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<usize>,
+    pub movetime: Option<Duration>,
+    pub nodes: Option<u64>,
+    // Caps the nodes spent on a single root move within one iteration,
+    // so one pathological line can not starve the others
+    pub nodes_per_root_move: Option<u64>,
+    // Not a limit: how many of the best root moves get a line of their own, one if None
+    pub multi_pv: Option<usize>,
+    // Not a limit either: the search's clock counts nodes, this many to the millisecond, instead
+    // of time. The same search then stops at the same node, and reports the same times, every run
+    pub nodestime: Option<u64>,
+}
+
+impl SearchLimits {
+    pub fn infinite() -> Self {
+        SearchLimits::default()
+    }
+
+    pub fn depth(depth: usize) -> Self {
+        SearchLimits { depth: Some(depth), ..Default::default() }
+    }
+
+    pub fn movetime(movetime: Duration) -> Self {
+        SearchLimits { movetime: Some(movetime), ..Default::default() }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        SearchLimits { nodes: Some(nodes), ..Default::default() }
+    }
+}
+
+// Why a search ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Depth,     // `limits.depth`, or `MAX_DEPTH`
+    Nodes,
+    Movetime,  // `limits.movetime`, the hard time limit of a game on the clock included
+    SoftTime,  // the time manager would rather not start another iteration
+    Stopped,   // `stop_flag`
+}
+
+// Counted as the search goes, for `debug on` and `stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub tt_probes: u64,
+    pub tt_hits: u64,
+    pub tt_cutoffs: u64,
+    pub beta_cutoffs: u64,
+    pub first_move_cutoffs: u64,  // the beta cutoffs by the first legal move, a measure of move ordering
+    pub skipped_root_moves: u64,  // ran out of `limits.nodes_per_root_move`
+    pub leaf_nodes: u64,          // evaluated at the horizon, the nodes a quiescence search would take over
+}
+
+// One root move, over every iteration so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootMoveStats {
+    pub m: Move,
+    pub nodes: u64,
+    // Of the last time it was searched to the end, None if it never was. Only the best
+    // move's is exact, the others' are upper bounds
+    pub score: Option<i32>,
+    // The times it beat the best move so far, what would fail high against a null window
+    pub fail_highs: u64,
+}
+
+// Root moves are only announced in searches long enough for somebody to be watching
+const CURRMOVE_DELAY: Duration = Duration::from_secs(3);
+
+// The stop token of a single search: owns the node counter and decides when to unwind
+pub struct SearchControl<'a> {
+    pub limits: SearchLimits,
+    pub nodes: u64,
+    pub stats: SearchStats,
+    stop_flag: Arc<AtomicBool>,
+    start_time: Instant,
+    stop_reason: Option<StopReason>,
+    root_move_node_limit: u64,
+    // Left out at the root, they have their MultiPV lines already
+    pub(crate) excluded_root_moves: Vec<Move>,
+    pub root_moves: Vec<RootMoveStats>,  // in the order they were first searched
+    current_root_move: (usize, u64),     // the index in `root_moves`, `nodes` when it started
+    // One per ply, reused from node to node: the search allocates no move lists once it is deep enough
+    move_lists: Vec<Vec<Move>>,
+    report: Option<&'a mut dyn SearchReport>,
+}
+
+impl<'a> SearchControl<'a> {
+    pub fn new(limits: SearchLimits, stop_flag: Arc<AtomicBool>) -> Self {
+        SearchControl {
+            limits,
+            nodes: 0,
+            stats: SearchStats::default(),
+            stop_flag,
+            start_time: Instant::now(),
+            stop_reason: None,
+            root_move_node_limit: u64::MAX,
+            excluded_root_moves: Vec::new(),
+            root_moves: Vec::new(),
+            current_root_move: (0, 0),
+            move_lists: Vec::new(),
+            report: None,
+        }
+    }
+
+    pub fn with_report(mut self, report: &'a mut dyn SearchReport) -> Self {
+        self.report = Some(report);
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.limits.nodestime {
+            Some(nodes_per_ms) => Duration::from_micros(self.nodes * 1000 / nodes_per_ms.max(1)),
+            None => self.start_time.elapsed(),
+        }
+    }
+
+    // The whole search has to unwind (as opposed to a single root move)
+    pub fn is_stopped(&self) -> bool {
+        self.stop_reason.is_some()
+    }
+
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    // Called once per node, after incrementing `nodes`
+    pub fn should_unwind(&mut self) -> bool {
+        if self.is_stopped() {
+            return true;
+        }
+
+        if let Some(limit) = self.limits.nodes
+            && self.nodes >= limit {
+            self.stop_reason = Some(StopReason::Nodes);
+        }
+
+        // Check every 1024 nodes, because it is time-expensive
+        if self.nodes.is_multiple_of(1024) {
+            if self.stop_flag.load(Ordering::Relaxed) {
+                self.stop_reason = Some(StopReason::Stopped);
+            }
+
+            if let Some(movetime) = self.limits.movetime
+                && self.elapsed() >= movetime {
+                self.stop_reason = Some(StopReason::Movetime);
+            }
+        }
+
+        self.is_stopped() || self.nodes >= self.root_move_node_limit
+    }
+
+    // `number` counts the legal root moves from 1
+    pub(crate) fn start_root_move(&mut self, depth: usize, m: &Move, number: usize) {
+        if let Some(budget) = self.limits.nodes_per_root_move {
+            self.root_move_node_limit = self.nodes.saturating_add(budget);
+        }
+        let index = match self.root_moves.iter().position(|r| r.m == *m) {
+            Some(index) => index,
+            None => {
+                self.root_moves.push(RootMoveStats { m: *m, nodes: 0, score: None, fail_highs: 0 });
+                self.root_moves.len() - 1
+            }
+        };
+        self.current_root_move = (index, self.nodes);
+        let announce = self.elapsed() >= CURRMOVE_DELAY;
+        if let Some(report) = &mut self.report
+            && announce {
+            report.root_move(&CurrMove { depth, m: *m, number });
+        }
+    }
+
+    // Returns true if the root move ran out of its node budget, so its score is not reliable
+    pub(crate) fn finish_root_move(&mut self) -> bool {
+        let (index, nodes_before) = self.current_root_move;
+        self.root_moves[index].nodes += self.nodes - nodes_before;
+        let exhausted = self.nodes >= self.root_move_node_limit;
+        self.root_move_node_limit = u64::MAX;
+        exhausted
+    }
+
+    // The move list of `ply`, empty or with what was there last, until `put_move_list` gives it back.
+    // A node that unwinds keeps it, the next search starts with a new one
+    pub(crate) fn take_move_list(&mut self, ply: usize) -> Vec<Move> {
+        if ply >= self.move_lists.len() {
+            self.move_lists.resize_with(ply + 1, Vec::new);
+        }
+        std::mem::take(&mut self.move_lists[ply])
+    }
+
+    pub(crate) fn put_move_list(&mut self, ply: usize, moves: Vec<Move>) {
+        self.move_lists[ply] = moves;
+    }
+
+    // The root move that was just finished got to the end, with `score`
+    pub(crate) fn score_root_move(&mut self, score: i32, fail_high: bool) {
+        let stats = &mut self.root_moves[self.current_root_move.0];
+        stats.score = Some(score);
+        stats.fail_highs += fail_high as u64;
+    }
+}
+
+
+// What a search iteration found, handed to the caller as the search goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchInfo {
+    pub depth: usize,
+    pub multipv: usize,  // 1 for the best line, 2 for the next best...
+    pub score: i32,      // from the point of view of the side to move
+    pub pv: Vec<Move>,   // root -> leaf
+    pub nodes: u64,      // in this iteration
+    pub time: Duration,  // of this iteration
+    pub hashfull: usize, // permille
+}
+
+// The root move the search is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrMove {
+    pub depth: usize,
+    pub m: Move,
+    pub number: usize,  // from 1
+}
+
+// Whoever is watching the search. A closure only hears about the iterations
+pub trait SearchReport {
+    fn iteration(&mut self, info: &SearchInfo);
+
+    fn root_move(&mut self, _current: &CurrMove) {}
+}
+
+impl<F: FnMut(&SearchInfo)> SearchReport for F {
+    fn iteration(&mut self, info: &SearchInfo) {
+        self(info)
+    }
+}
+
+// One of the MultiPV lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootLine {
+    pub score: i32,     // from the point of view of the side to move
+    pub pv: Vec<Move>,  // root -> leaf, never empty
+}
+
+// How the whole search went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,  // None if there is no legal move
+    pub lines: Vec<RootLine>,     // of the deepest iteration that got through as many of them
+    pub stop_reason: StopReason,
+    pub nodes: u64,
+    pub time: Duration,
+    pub stats: SearchStats,
+    pub root_moves: Vec<RootMoveStats>,
+}
+
+// Searches 1, 2, 3... plies deep until `limits` or `time_manager` say to stop, or `stop_flag` is set.
+// `report` hears about every iteration that has a move to show, the interrupted one included
+pub fn iterative_deepening(
+    game: &mut Game,
+    limits: SearchLimits,
+    mut time_manager: Option<TimeManager>,
+    stop_flag: Arc<AtomicBool>,
+    tt: &mut TranspositionTable,
+    mut report: impl SearchReport,
+) -> SearchResult {
+    tt.new_search();  // what earlier searches stored is the first to be replaced
+    let mut last_move = None;
+    let mut lines = Vec::new();
+    let mut ctrl = SearchControl::new(limits, stop_flag).with_report(&mut report);
+    let mut stop_reason = StopReason::Depth;
+    let multi_pv = limits.multi_pv.unwrap_or(1).clamp(1, game.legal_moves().count().max(1));
+
+    for depth in 1..=MAX_DEPTH {
+        if let Some(d) = limits.depth
+            && depth > d {
+            break;
+        }
+
+        let time_before = ctrl.elapsed();
+        let nodes_before = ctrl.nodes;
+        let mut best = (None, 0);
+        let mut iteration_lines = Vec::new();
+        let mut unwind = false;
+
+        // The best move, then the best of the rest, and so on
+        ctrl.excluded_root_moves.clear();
+        for multipv in 1..=multi_pv {
+            let (m, score, _, mut pv, line_unwind) = game.find_best_move(depth, &mut ctrl, tt);
+            if multipv == 1 {
+                best = (m, score);
+            }
+
+            // An interrupted line still counts if it has a move to show for itself
+            if let Some(m) = m {
+                pv.reverse();
+                let info = SearchInfo {
+                    depth,
+                    multipv,
+                    score,
+                    pv: pv.clone(),
+                    nodes: ctrl.nodes - nodes_before,
+                    time: ctrl.elapsed() - time_before,
+                    hashfull: tt.hashfull(),
+                };
+                if let Some(report) = &mut ctrl.report {
+                    report.iteration(&info);
+                }
+                ctrl.excluded_root_moves.push(m);
+                iteration_lines.push(RootLine { score, pv });
+            }
+            if line_unwind {
+                unwind = true;
+                break;
+            }
+        }
+        ctrl.excluded_root_moves.clear();
+
+        if best.0.is_some() {
+            last_move = best.0;
+        }
+        if iteration_lines.len() >= lines.len() {
+            lines = iteration_lines;
+        }
+        let (m, score) = best;
+        if unwind {
+            // Nothing stopped it if every root move ran out of its node budget, the next iteration is no better off
+            stop_reason = ctrl.stop_reason().unwrap_or(StopReason::Nodes);
+            break;
+        }
+
+        if let Some(limit) = limits.movetime
+            && ctrl.elapsed() >= limit {
+            stop_reason = StopReason::Movetime;
+            break;
+        }
+
+        if let Some(tm) = &mut time_manager {
+            tm.update(m, score);
+            if tm.should_stop(ctrl.elapsed()) {
+                stop_reason = StopReason::SoftTime;
+                break;
+            }
+        }
+    }
+
+    SearchResult {
+        best_move: last_move,
+        lines,
+        stop_reason,
+        nodes: ctrl.nodes,
+        time: ctrl.elapsed(),
+        stats: ctrl.stats,
+        root_moves: ctrl.root_moves,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{board::*, CHECKMATE_EVAL, MOVE_LIST_CAPACITY};
+    use crate::core::piece::Piece;
+
+    fn control(limits: SearchLimits) -> SearchControl<'static> {
+        SearchControl::new(limits, Arc::new(AtomicBool::new(false)))
+    }
+
+    #[test]
+    fn node_limit() {
+        let mut game = Game::default();
+        let mut ctrl = control(SearchLimits::nodes(5_000));
+        let (_, _, _, _, unwind) = game.find_best_move(10, &mut ctrl, &TranspositionTable::default());
+        assert!(unwind);
+        assert_eq!(ctrl.stop_reason(), Some(StopReason::Nodes));
+        assert_eq!(ctrl.nodes, 5_000);
+
+        // Counted at the leaves too, where a depth 1 search has all of its nodes but the root
+        let mut ctrl = control(SearchLimits::nodes(10));
+        let (_, _, _, _, unwind) = game.find_best_move(1, &mut ctrl, &TranspositionTable::default());
+        assert!(unwind);
+        assert_eq!(ctrl.nodes, 10);
+    }
+
+    #[test]
+    fn stop_flag() {
+        let mut game = Game::default();
+        let mut ctrl = control(SearchLimits::infinite());
+        ctrl.stop_flag.store(true, Ordering::Relaxed);
+        let (_, _, _, _, unwind) = game.find_best_move(10, &mut ctrl, &TranspositionTable::default());
+        assert!(unwind);
+        assert_eq!(ctrl.stop_reason(), Some(StopReason::Stopped));
+    }
+
+    #[test]
+    fn move_lists_are_reused() {
+        let mut game = Game::default();
+        let mut ctrl = control(SearchLimits::infinite());
+        game.find_best_move(3, &mut ctrl, &TranspositionTable::default());
+        // The leaves generate no moves
+        assert_eq!(ctrl.move_lists.len(), 3);
+        assert!(ctrl.move_lists.iter().all(|l| l.capacity() >= MOVE_LIST_CAPACITY));
+    }
+
+    // Only hears about the root moves
+    struct CurrMoves<'a>(&'a mut Vec<CurrMove>);
+
+    impl SearchReport for CurrMoves<'_> {
+        fn iteration(&mut self, _: &SearchInfo) {}
+
+        fn root_move(&mut self, current: &CurrMove) {
+            self.0.push(*current);
+        }
+    }
+
+    #[test]
+    fn currmove_after_a_delay() {
+        // A node a millisecond, so the delay is 3000 nodes in
+        let limits = |nodes| SearchLimits { nodes: Some(nodes), nodestime: Some(1), ..Default::default() };
+        let m = Move::new(E2, E4, Piece::Pawn, false);
+        let mut reported = Vec::new();
+        let mut report = CurrMoves(&mut reported);
+        let mut ctrl = SearchControl::new(limits(10_000), Arc::new(AtomicBool::new(false))).with_report(&mut report);
+        ctrl.nodes = 2_999;
+        ctrl.start_root_move(5, &m, 1);
+        ctrl.finish_root_move();
+        ctrl.nodes = 3_000;
+        ctrl.start_root_move(5, &m, 2);
+        drop(ctrl);
+        assert_eq!(reported, [CurrMove { depth: 5, m, number: 2 }]);
+
+        let search = |nodes| {
+            let mut reported = Vec::new();
+            let mut tt = TranspositionTable::new(1);
+            iterative_deepening(&mut Game::default(), limits(nodes), None, Arc::new(AtomicBool::new(false)), &mut tt, CurrMoves(&mut reported));
+            reported
+        };
+        assert_eq!(search(2_999), []);
+        let reported = search(20_000);
+        assert!(!reported.is_empty());
+        assert!(reported.iter().all(|r| r.number >= 1 && r.number <= 20));
+        assert_eq!(search(20_000), reported);
+    }
+
+    #[test]
+    fn nodestime() {
+        let limits = SearchLimits { movetime: Some(Duration::from_millis(50)), nodestime: Some(100), ..Default::default() };
+        let search = || {
+            let mut infos = Vec::new();
+            let mut tt = TranspositionTable::new(1);
+            let result = iterative_deepening(&mut Game::default(), limits, None, Arc::new(AtomicBool::new(false)), &mut tt, |i: &SearchInfo| infos.push(i.clone()));
+            (result, infos)
+        };
+        let (result, infos) = search();
+        assert_eq!(result.stop_reason, StopReason::Movetime);
+        // Checked every 1024 nodes
+        assert_eq!(result.nodes, 5_120);
+        assert_eq!(result.time, Duration::from_micros(51_200));
+        assert_eq!(search(), (result, infos));
+    }
+
+    #[test]
+    fn nodes_per_root_move() {
+        let budget = 300;
+        let mut game = Game::default();
+        let mut ctrl = control(SearchLimits { nodes_per_root_move: Some(budget), ..Default::default() });
+        let (best_move, _, nodes, _, unwind) = game.find_best_move(4, &mut ctrl, &TranspositionTable::default());
+
+        // 20 root moves, every one of them is cut, but the search goes on
+        assert!(nodes <= 1 + 20 * budget);
+        assert!(!ctrl.is_stopped());
+        assert!(unwind == best_move.is_none());
+    }
+
+    #[test]
+    fn nodes_per_root_move_large_budget_is_transparent() {
+        let mut game = Game::default();
+        let (expected, expected_eval, expected_nodes, _, _) = game.find_best_move(3, &mut control(SearchLimits::infinite()), &TranspositionTable::default());
+        let mut ctrl = control(SearchLimits { nodes_per_root_move: Some(1_000_000), ..Default::default() });
+        let (best_move, eval, nodes, _, unwind) = game.find_best_move(3, &mut ctrl, &TranspositionTable::default());
+        assert!(!unwind);
+        assert_eq!(best_move, expected);
+        assert_eq!(eval, expected_eval);
+        assert_eq!(nodes, expected_nodes);
+    }
+
+    #[test]
+    fn transposition_table_is_reused() {
+        let mut game = Game::default();
+        let tt = TranspositionTable::default();
+        let (first_move, _, first_nodes, _, _) = game.find_best_move(4, &mut control(SearchLimits::infinite()), &tt);
+        let (second_move, _, second_nodes, _, _) = game.find_best_move(4, &mut control(SearchLimits::infinite()), &tt);
+        assert_eq!(first_move, second_move);
+        assert!(second_nodes * 10 < first_nodes);
+    }
+
+    #[test]
+    fn partial_iteration_keeps_finished_root_moves() {
+        let mut game = Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1").unwrap();
+        let tt = TranspositionTable::default();
+        let (previous, _, _, _, _) = game.find_best_move(3, &mut control(SearchLimits::infinite()), &tt);
+
+        // How long the next iteration takes in full
+        let (_, _, full, _, _) = game.find_best_move(4, &mut control(SearchLimits::infinite()), &tt.clone());
+
+        // Stopped right away, nothing to show
+        let (m, _, _, _, unwind) = game.find_best_move(4, &mut control(SearchLimits::nodes(10)), &tt.clone());
+        assert!(unwind);
+        assert_eq!(m, None);
+
+        // Stopped halfway, the old best move is done with and the best one so far is reported
+        let (m, _, _, pv, unwind) = game.find_best_move(4, &mut control(SearchLimits::nodes(full / 2)), &tt.clone());
+        assert!(unwind);
+        assert!(m.is_some() && previous.is_some());
+        assert_eq!(pv.last().copied(), m);
+    }
+
+    #[test]
+    fn drawn_root_still_has_a_move() {
+        let mut game = Game::from_fen("8/8/8/4k3/8/8/8/4K3 w - - 0 1").unwrap();
+        let (m, eval, _, _, unwind) = game.find_best_move(2, &mut control(SearchLimits::infinite()), &TranspositionTable::default());
+        assert!(!unwind);
+        assert!(m.is_some());
+        assert_eq!(eval, 0);
+    }
+
+    #[test]
+    fn iterative_deepening_reports() {
+        let mut game = Game::default();
+        let mut infos = Vec::new();
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let result = iterative_deepening(
+            &mut game,
+            SearchLimits::depth(3),
+            None,
+            stop_flag,
+            &mut TranspositionTable::default(),
+            |info: &SearchInfo| infos.push(info.clone()),
+        );
+
+        assert_eq!(infos.iter().map(|i| i.depth).collect::<Vec<_>>(), [1, 2, 3]);
+        let last = infos.last().unwrap();
+        assert_eq!(last.pv.first().copied(), result.best_move);
+        assert_eq!(last.pv.len(), 3);
+        assert!(infos.iter().all(|i| i.nodes > 0));
+        assert_eq!(result.stop_reason, StopReason::Depth);
+        assert_eq!(result.nodes, infos.iter().map(|i| i.nodes).sum::<u64>());
+        assert!(result.stats.beta_cutoffs >= result.stats.first_move_cutoffs);
+        assert!(result.stats.tt_probes >= result.stats.tt_hits && result.stats.tt_hits > 0);
+        assert!(result.stats.leaf_nodes > 0 && result.stats.leaf_nodes < result.nodes);
+    }
+
+    #[test]
+    fn root_move_stats() {
+        let mut game = Game::default();
+        let mut tt = TranspositionTable::new(1);
+        let result = iterative_deepening(&mut game, SearchLimits::depth(4), None, Arc::new(AtomicBool::new(false)), &mut tt, |_: &SearchInfo| {});
+        assert_eq!(result.root_moves.len(), 20);
+        // Every node but the root's own
+        assert_eq!(result.root_moves.iter().map(|r| r.nodes).sum::<u64>(), result.nodes - 4);
+        let best = result.root_moves.iter().find(|r| Some(r.m) == result.best_move).unwrap();
+        assert_eq!(best.score, Some(result.lines[0].score));
+        assert!(best.fail_highs >= 1);
+    }
+
+    #[test]
+    fn multi_pv() {
+        let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/5PPP/R2R2K1 w - - 0 1").unwrap();
+        let mut infos = Vec::new();
+        let limits = SearchLimits { depth: Some(3), multi_pv: Some(3), ..Default::default() };
+        let result = iterative_deepening(
+            &mut game,
+            limits,
+            None,
+            Arc::new(AtomicBool::new(false)),
+            &mut TranspositionTable::default(),
+            |info: &SearchInfo| infos.push(info.clone()),
+        );
+
+        assert_eq!(infos.iter().map(|i| (i.depth, i.multipv)).collect::<Vec<_>>(),
+                   [(1, 1), (1, 2), (1, 3), (2, 1), (2, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+        // Ra8# and Rd8# first, then something that does not mate
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.lines[0].pv[0], result.best_move.unwrap());
+        assert!(result.lines[..2].iter().all(|line| line.score > CHECKMATE_EVAL - 1000));
+        assert!(result.lines[2].score < CHECKMATE_EVAL - 1000);
+        let first_moves: Vec<Move> = result.lines.iter().map(|line| line.pv[0]).collect();
+        assert!(first_moves.iter().all(|m| first_moves.iter().filter(|&other| other == m).count() == 1));
+
+        // No more lines than legal moves
+        let mut game = Game::from_fen("7k/8/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        let limits = SearchLimits { depth: Some(2), multi_pv: Some(10), ..Default::default() };
+        let result = iterative_deepening(&mut game, limits, None, Arc::new(AtomicBool::new(false)), &mut TranspositionTable::default(), |_: &SearchInfo| {});
+        assert_eq!(result.lines.len(), 3);
+    }
+}
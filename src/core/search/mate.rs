@@ -0,0 +1,212 @@
+// Proves or disproves a forced mate by trying every move, independent of the search and
+// its evaluation, so it can be trusted to check the search's mate scores. Like in composed
+// problems, repetitions and the fifty-move rule are not considered
+use std::collections::HashMap;
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+use crate::core::{
+    chess_move::Move,
+    game::Game,
+    movegen::{has_legal_move, legal_moves},
+    rules::checks::is_king_in_check,
+    zobrist::ZobristHash,
+};
+
+// A first move that forces mate, and how it goes against the most stubborn defense
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyLine {
+    pub mate_in: usize,
+    pub moves: Vec<Move>,
+}
+
+impl KeyLine {
+    pub fn key(&self) -> Move {
+        self.moves[0]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MateSolution {
+    pub key_lines: Vec<KeyLine>,  // quickest mates first, empty if there is no mate in N
+    pub nodes: u64,
+    pub stopped: bool,  // by `stop_flag`, before every first move was tried
+}
+
+struct Solver {
+    // (position, moves): whether the side to move mates within that many moves
+    known: HashMap<(ZobristHash, usize), bool>,
+    nodes: u64,
+    stop_flag: Arc<AtomicBool>,
+    stopped: bool,  // from then on nothing mates, and nothing found is to be trusted
+}
+
+impl Solver {
+    fn new(stop_flag: Arc<AtomicBool>) -> Self {
+        Solver { known: HashMap::new(), nodes: 0, stop_flag, stopped: false }
+    }
+
+    // Can the side to move force mate within `n` moves?
+    fn mates_within(&mut self, game: &mut Game, n: usize) -> bool {
+        if n == 0 {
+            return false;
+        }
+        let key = (game.position.zobrist_hash, n);
+        if let Some(&known) = self.known.get(&key) {
+            return known;
+        }
+        let result = legal_moves(&game.position).iter().any(|m| self.mates_after(game, m, n));
+        if !self.stopped {
+            self.known.insert(key, result);
+        }
+        result
+    }
+
+    // Does `m` force mate within `n` moves, itself included?
+    fn mates_after(&mut self, game: &mut Game, m: &Move, n: usize) -> bool {
+        if self.stopped {
+            return false;
+        }
+        self.nodes += 1;
+        // Every 1024 nodes, like the search
+        if self.nodes.is_multiple_of(1024) && self.stop_flag.load(Ordering::Relaxed) {
+            self.stopped = true;
+            return false;
+        }
+        game.try_to_make_move(m);
+        let in_check = is_king_in_check(&game.position, game.position.player_to_move);
+        let result = if n == 1 && !in_check {
+            false  // the last move has to give check
+        } else if !has_legal_move(&game.position) {
+            in_check
+        } else if n == 1 {
+            false
+        } else {
+            legal_moves(&game.position).iter().all(|reply| {
+                game.try_to_make_move(reply);
+                let mates = self.mates_within(game, n - 1);
+                game.unmake_move();
+                mates
+            })
+        };
+        game.unmake_move();
+        result
+    }
+
+    fn mate_distance(&mut self, game: &mut Game, m: &Move, n: usize) -> Option<usize> {
+        (1..=n).find(|&k| self.mates_after(game, m, k))
+    }
+
+    // `m` mates in exactly `k`. The defense that holds out longest, then the quickest mate
+    // against it, and so on. Cut short once stopped
+    fn line(&mut self, game: &mut Game, m: Move, k: usize) -> Vec<Move> {
+        let mut line = vec![m];
+        game.try_to_make_move(&m);
+        if k > 1 {
+            let mut defense = None;
+            for reply in legal_moves(&game.position) {
+                game.try_to_make_move(&reply);
+                let holds = (1..k).find(|&j| self.mates_within(game, j));
+                game.unmake_move();
+                let Some(holds) = holds else { break };  // stopped
+                if defense.is_none_or(|(_, longest)| holds > longest) {
+                    defense = Some((reply, holds));
+                }
+            }
+
+            if let Some((reply, holds)) = defense {
+                game.try_to_make_move(&reply);
+                let next = legal_moves(&game.position)
+                    .into_iter()
+                    .find(|m| self.mates_after(game, m, holds));
+                line.push(reply);
+                if let Some(next) = next {
+                    line.extend(self.line(game, next, holds));
+                }
+                game.unmake_move();
+            }
+        }
+        game.unmake_move();
+        line
+    }
+}
+
+// Every first move that mates in `n` moves or fewer, or those found until `stop_flag` is set
+pub fn solve_mate(game: &mut Game, n: usize, stop_flag: Arc<AtomicBool>) -> MateSolution {
+    let mut solver = Solver::new(stop_flag);
+    let mut key_lines = Vec::new();
+    for m in legal_moves(&game.position) {
+        let mate_in = solver.mate_distance(game, &m, n);
+        let line = mate_in.map(|k| KeyLine { mate_in: k, moves: solver.line(game, m, k) });
+        // The move being tried when it stopped is left out, it may mate quicker or not at all
+        if solver.stopped {
+            break;
+        }
+        key_lines.extend(line);
+    }
+    key_lines.sort_by_key(|line| line.mate_in);
+    MateSolution { key_lines, nodes: solver.nodes, stopped: solver.stopped }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::position::FenParseError;
+
+    fn solve(fen: &str, n: usize) -> Result<(Game, MateSolution), FenParseError> {
+        let mut game = Game::from_fen(fen)?;
+        let solution = solve_mate(&mut game, n, Arc::new(AtomicBool::new(false)));
+        Ok((game, solution))
+    }
+
+    fn keys(game: &Game, solution: &MateSolution) -> Vec<String> {
+        solution.key_lines.iter().map(|line| line.key().to_san(&game.position)).collect()
+    }
+
+    #[test]
+    fn mate_in_one() -> Result<(), FenParseError> {
+        let (game, solution) = solve("6k1/5ppp/8/8/8/8/5PPP/R2R2K1 w - - 0 1", 1)?;
+        assert_eq!(keys(&game, &solution), ["Ra8#", "Rd8#"]);
+        assert!(solution.key_lines.iter().all(|line| line.mate_in == 1 && line.moves.len() == 1));
+        Ok(())
+    }
+
+    #[test]
+    fn mate_in_two() -> Result<(), FenParseError> {
+        // Morphy: 1. Ra6! bxa6 2. b7#
+        let fen = "kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1";
+        let (_, solution) = solve(fen, 1)?;
+        assert!(solution.key_lines.is_empty());
+
+        let (game, solution) = solve(fen, 2)?;
+        assert_eq!(keys(&game, &solution), ["Ra6"]);
+        assert_eq!(game.san_line(&solution.key_lines[0].moves), "1. Ra6 bxa6 2. b7#");
+        Ok(())
+    }
+
+    #[test]
+    fn stalemate_is_not_mate() -> Result<(), FenParseError> {
+        // Qg6 stalemates, Qg7 mates
+        let (game, solution) = solve("7k/5K2/8/6Q1/8/8/8/8 w - - 0 1", 1)?;
+        assert!(keys(&game, &solution).iter().all(|key| key.ends_with('#')));
+        assert!(!keys(&game, &solution).contains(&"Qg6".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn no_mate() -> Result<(), FenParseError> {
+        let (_, solution) = solve("4k3/8/8/8/8/8/8/4K3 w - - 0 1", 3)?;
+        assert!(solution.key_lines.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn stop_flag() {
+        // Stopped at the first check, with no first move tried all the way
+        let mut game = Game::default();
+        let solution = solve_mate(&mut game, 3, Arc::new(AtomicBool::new(true)));
+        assert!(solution.stopped);
+        assert_eq!(solution.nodes, 1024);
+        assert!(solution.key_lines.is_empty());
+        assert_eq!(game.position, Game::default().position);
+    }
+}
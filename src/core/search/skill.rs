@@ -0,0 +1,97 @@
+// Playing weaker on purpose, for `Skill Level`: a shallower search with a few MultiPV lines,
+// and a pick among them that gets more random the lower the level. The same idea as Stockfish's
+use rand::Rng;
+use crate::core::{chess_move::Move, search::{RootLine, SearchLimits}};
+
+pub const MAX_SKILL_LEVEL: usize = 20;
+
+// The lines to choose from, whatever MultiPV the GUI asked for
+const CANDIDATES: usize = 4;
+// The most the spread of the lines adds to the random part of a push
+const PAWN_CP: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Skill {
+    pub level: usize,  // 0..=MAX_SKILL_LEVEL, full strength at the top
+}
+
+impl Skill {
+    pub fn new(level: usize) -> Skill {
+        Skill { level: level.min(MAX_SKILL_LEVEL) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.level < MAX_SKILL_LEVEL
+    }
+
+    // `limits` searching `1 + level` plies at most, with at least `CANDIDATES` lines
+    pub fn limit(&self, limits: SearchLimits) -> SearchLimits {
+        if !self.is_enabled() {
+            return limits;
+        }
+        SearchLimits {
+            depth: Some(limits.depth.map_or(1 + self.level, |d| d.min(1 + self.level))),
+            multi_pv: Some(limits.multi_pv.unwrap_or(1).max(CANDIDATES)),
+            ..limits
+        }
+    }
+
+    // Every line gets a push: a share of how far it is behind the best one, plus a random part,
+    // both larger the lower the level. The line that comes out on top is played
+    pub fn pick_move(&self, lines: &[RootLine], rng: &mut impl Rng) -> Option<Move> {
+        let top = lines.iter().map(|line| line.score).max()?;
+        let worst = lines.iter().map(|line| line.score).min()?;
+        let weakness = 120 - 2 * self.level as i64;
+        let delta = (top as i64 - worst as i64).min(PAWN_CP as i64);
+
+        lines.iter()
+            .max_by_key(|line| {
+                let behind = top as i64 - line.score as i64;
+                let push = (weakness * behind + delta * rng.random_range(0..weakness)) / 128;
+                line.score as i64 + push
+            })
+            .map(|line| line.pv[0])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::game::Game;
+
+    fn lines(scores: &[(&str, i32)]) -> Vec<RootLine> {
+        let game = Game::default();
+        scores.iter()
+            .map(|&(uci, score)| RootLine { score, pv: vec![Move::from_uci(&game.position, uci).unwrap()] })
+            .collect()
+    }
+
+    #[test]
+    fn limit() {
+        let limits = Skill::new(3).limit(SearchLimits::depth(10));
+        assert_eq!((limits.depth, limits.multi_pv), (Some(4), Some(CANDIDATES)));
+        let full = Skill::new(MAX_SKILL_LEVEL + 5);
+        assert!(!full.is_enabled());
+        assert_eq!(full.limit(SearchLimits::depth(10)), SearchLimits::depth(10));
+    }
+
+    #[test]
+    fn low_levels_vary() {
+        let lines = lines(&[("e2e4", 30), ("d2d4", 25), ("g1f3", 20), ("c2c4", 15)]);
+        let mut rng = rand::rng();
+        let picked: Vec<Move> = (0..50).map(|_| Skill::new(0).pick_move(&lines, &mut rng).unwrap()).collect();
+        assert!(picked.iter().any(|&m| m != picked[0]));
+    }
+
+    #[test]
+    fn blunders_stay_out_of_reach() {
+        // A queen behind: at level 10 the push can not make up for it
+        let lines = lines(&[("e2e4", 50), ("d2d4", 40), ("g2g4", -900)]);
+        let mut rng = rand::rng();
+        for _ in 0..50 {
+            assert_ne!(Skill::new(10).pick_move(&lines, &mut rng).unwrap().to_string(), "g2g4");
+        }
+        assert_eq!(Skill::new(0).pick_move(&[], &mut rng), None);
+    }
+}
@@ -0,0 +1,149 @@
+// Playing weaker on purpose, for `UCI_LimitStrength`: a shallower and smaller search, and a
+// move picked from the root moves with random noise added to their scores, the more of it
+// the lower the Elo
+use std::sync::{atomic::AtomicBool, Arc};
+use rand::Rng;
+use crate::constants::{CHECKMATE_EVAL, DRAW_EVAL};
+use crate::core::{
+    chess_move::Move,
+    game::Game,
+    movegen::legal_moves,
+    outcome::Outcome,
+    search::{SearchControl, SearchLimits},
+    tt::TranspositionTable,
+};
+
+pub const MIN_ELO: u32 = 600;
+pub const MAX_ELO: u32 = 2400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Strength {
+    pub depth: usize,
+    pub nodes: u64,     // for the search, and again for scoring the root moves
+    pub noise_cp: i32,  // up to this much is added to every root move's score
+}
+
+impl Strength {
+    // Roughly calibrated against human play: 600 looks one ply ahead and often misses a piece,
+    // 2400 is the full engine at depth 7
+    pub fn from_elo(elo: u32) -> Strength {
+        let steps = elo.clamp(MIN_ELO, MAX_ELO) - MIN_ELO;
+        Strength {
+            depth: 1 + steps as usize / 300,
+            nodes: 1000 << (steps / 150),
+            noise_cp: ((MAX_ELO - MIN_ELO - steps) / 3) as i32,
+        }
+    }
+
+    // `limits` with the depth and nodes capped, the time limits stay as they are
+    pub fn cap(&self, limits: SearchLimits) -> SearchLimits {
+        SearchLimits {
+            depth: Some(limits.depth.map_or(self.depth, |d| d.min(self.depth))),
+            nodes: Some(limits.nodes.map_or(self.nodes, |n| n.min(self.nodes))),
+            ..limits
+        }
+    }
+
+    // Every root move searched `depth - 1` plies deep after it is made, then the best of them once
+    // the noise is added. `best` is what the search came up with, and the fallback if the node
+    // budget runs out before every move has a score
+    pub fn pick_move(
+        &self,
+        game: &mut Game,
+        best: Move,
+        stop_flag: Arc<AtomicBool>,
+        tt: &mut TranspositionTable,
+        rng: &mut impl Rng,
+    ) -> Move {
+        if self.noise_cp == 0 {
+            return best;
+        }
+
+        let mut ctrl = SearchControl::new(SearchLimits::nodes(self.nodes), stop_flag);
+        let mut picked = (best, i32::MIN);
+        for m in legal_moves(&game.position) {
+            game.try_to_make_move(&m);
+            let score = match game.outcome() {
+                Outcome::Ongoing => -game.find_best_move(self.depth - 1, &mut ctrl, tt).1,
+                Outcome::Draw(_) => DRAW_EVAL,
+                _ => CHECKMATE_EVAL,
+            };
+            game.unmake_move();
+            if ctrl.is_stopped() {
+                return best;
+            }
+
+            let noisy = score.saturating_add(rng.random_range(0..=self.noise_cp));
+            if noisy > picked.1 {
+                picked = (m, noisy);
+            }
+        }
+        picked.0
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_elo() {
+        let weakest = Strength::from_elo(0);
+        assert_eq!(weakest, Strength::from_elo(MIN_ELO));
+        assert_eq!(weakest.depth, 1);
+
+        let strongest = Strength::from_elo(MAX_ELO);
+        assert_eq!(strongest.noise_cp, 0);
+        for elo in (MIN_ELO..MAX_ELO).step_by(100) {
+            let (weaker, stronger) = (Strength::from_elo(elo), Strength::from_elo(elo + 100));
+            assert!(weaker.depth <= stronger.depth);
+            assert!(weaker.nodes <= stronger.nodes);
+            assert!(weaker.noise_cp > stronger.noise_cp);
+        }
+    }
+
+    #[test]
+    fn cap() {
+        let strength = Strength::from_elo(1500);
+        let capped = strength.cap(SearchLimits::depth(20));
+        assert_eq!((capped.depth, capped.nodes), (Some(strength.depth), Some(strength.nodes)));
+        assert_eq!(strength.cap(SearchLimits::depth(1)).depth, Some(1));
+        let movetime = SearchLimits::movetime(std::time::Duration::from_millis(100));
+        assert_eq!(strength.cap(movetime).movetime, movetime.movetime);
+    }
+
+    #[test]
+    fn weak_moves_are_legal_and_varied() {
+        let mut game = Game::default();
+        let best = Move::from_uci(&game.position, "e2e4").unwrap();
+        let strength = Strength::from_elo(MIN_ELO);
+        let mut tt = TranspositionTable::new(1);
+        let mut rng = rand::rng();
+        let picked: Vec<Move> = (0..20)
+            .map(|_| strength.pick_move(&mut game, best, Arc::new(AtomicBool::new(false)), &mut tt, &mut rng))
+            .collect();
+        assert!(picked.iter().all(|m| legal_moves(&game.position).contains(m)));
+        assert!(picked.iter().any(|&m| m != picked[0]));
+    }
+
+    #[test]
+    fn mate_in_one_is_never_missed() {
+        let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let best = Move::from_uci(&game.position, "a1a8").unwrap();
+        let mut tt = TranspositionTable::new(1);
+        for _ in 0..10 {
+            let m = Strength::from_elo(MIN_ELO).pick_move(&mut game, best, Arc::new(AtomicBool::new(false)), &mut tt, &mut rand::rng());
+            assert_eq!(m, best);
+        }
+    }
+
+    #[test]
+    fn full_strength_keeps_the_best_move() {
+        let mut game = Game::default();
+        let best = Move::from_uci(&game.position, "d2d4").unwrap();
+        let strength = Strength::from_elo(MAX_ELO);
+        let m = strength.pick_move(&mut game, best, Arc::new(AtomicBool::new(false)), &mut TranspositionTable::new(1), &mut rand::rng());
+        assert_eq!(m, best);
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Duration;
+use crate::core::chess_move::Move;
+
+// Never spend more than this fraction of what is left on the clock, whatever the increment
+const MAX_CLOCK_FRACTION: usize = 2;
+// The hard limit is at most this many soft limits
+const HARD_TO_SOFT: usize = 5;
+const MIN_MOVETIME_MS: usize = 1;
+
+// Moves still to be played are guessed from the game phase: with all the pieces
+// on the board the game goes on for a while, in an endgame it is close to the end
+const MIN_MOVES_TO_GO: usize = 20;
+
+// An iteration that changed its mind about the best move, or saw the score drop,
+// makes the next one worth waiting for. A best move that stays put makes it less so
+const MAX_STABILITY: u32 = 5;
+const SCORE_DROP: i32 = 30;  // centipawns
+
+// Time control for a game on the clock. The hard limit aborts the search in the middle of an
+// iteration, the soft one only keeps a new iteration from starting, and moves with the search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeManager {
+    soft_ms: usize,
+    hard_ms: usize,
+    best_move: Option<Move>,
+    stable_iterations: u32,   // in a row, with the same best move
+    best_move_changes: f64,   // decaying, halved every iteration
+    score: Option<i32>,
+    score_dropped: bool,
+}
+
+impl TimeManager {
+    // `time` and `inc` are our side's clock, `overhead` is lost to the GUI and the network.
+    // `phase` is the game phase, as `evaluate::game_phase` computes it. All in milliseconds
+    pub fn new(time: usize, inc: usize, overhead: usize, phase: i32) -> Self {
+        let moves_to_go = MIN_MOVES_TO_GO + phase.max(0) as usize;
+        let usable = time.saturating_sub(overhead);
+
+        let soft = (time / moves_to_go + inc * 8 / 10)  // use 80% of the increment
+            .saturating_sub(overhead);
+        let hard = (usable / MAX_CLOCK_FRACTION)
+            .min(soft * HARD_TO_SOFT)
+            .max(MIN_MOVETIME_MS);
+
+        TimeManager {
+            soft_ms: soft.clamp(MIN_MOVETIME_MS, hard),
+            hard_ms: hard,
+            best_move: None,
+            stable_iterations: 0,
+            best_move_changes: 0.,
+            score: None,
+            score_dropped: false,
+        }
+    }
+
+    pub fn hard_limit(&self) -> Duration {
+        Duration::from_millis(self.hard_ms as u64)
+    }
+
+    // The base soft limit scaled by how settled the search looks
+    pub fn soft_limit(&self) -> Duration {
+        let stability = 1.25 - 0.1 * self.stable_iterations.min(MAX_STABILITY) as f64;
+        let instability = 1. + 0.5 * self.best_move_changes;
+        let score = if self.score_dropped { 1.25 } else { 1. };
+
+        let ms = (self.soft_ms as f64 * stability * instability * score) as usize;
+        Duration::from_millis(ms.min(self.hard_ms) as u64)
+    }
+
+    // Called after every completed iteration
+    pub fn update(&mut self, best_move: Option<Move>, score: i32) {
+        self.best_move_changes /= 2.;
+        if best_move == self.best_move {
+            self.stable_iterations += 1;
+        } else {
+            if self.best_move.is_some() {
+                self.best_move_changes += 1.;
+            }
+            self.stable_iterations = 0;
+        }
+        self.best_move = best_move;
+
+        self.score_dropped = self.score.is_some_and(|s| s - score > SCORE_DROP);
+        self.score = Some(score);
+    }
+
+    // Whether another iteration is worth starting
+    pub fn should_stop(&self, elapsed: Duration) -> bool {
+        elapsed >= self.soft_limit()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::board::{D2, D4, E2, E4}, core::{evaluate::MAX_PHASE, piece::Piece}};
+
+    fn ms(d: Duration) -> usize {
+        d.as_millis() as usize
+    }
+
+    #[test]
+    fn limits_from_clock() {
+        // Opening: a long game ahead
+        let opening = TimeManager::new(60_000, 0, 0, MAX_PHASE);
+        assert_eq!(opening.soft_ms, 60_000 / (MIN_MOVES_TO_GO + MAX_PHASE as usize));
+        assert_eq!(ms(opening.hard_limit()), opening.soft_ms * HARD_TO_SOFT);
+        // Endgame: more per move
+        let endgame = TimeManager::new(60_000, 0, 0, 0);
+        assert_eq!(endgame.soft_ms, 3_000);
+        // The overhead is taken off
+        let lagging = TimeManager::new(60_000, 0, 100, 0);
+        assert_eq!(lagging.soft_ms, 2_900);
+    }
+
+    #[test]
+    fn never_flags() {
+        // A huge increment doesn't matter with almost nothing left on the clock
+        let tm = TimeManager::new(500, 2_000, 0, 0);
+        assert_eq!(ms(tm.hard_limit()), 250);
+        assert!(ms(tm.soft_limit()) <= 250);
+        let tm = TimeManager::new(500, 2_000, 100, 0);
+        assert_eq!(ms(tm.hard_limit()), 200);
+        // Less left than the overhead, move right away
+        let tm = TimeManager::new(50, 0, 100, 0);
+        assert_eq!(ms(tm.hard_limit()), MIN_MOVETIME_MS);
+        assert_eq!(ms(tm.soft_limit()), MIN_MOVETIME_MS);
+    }
+
+    #[test]
+    fn stability() {
+        let a = Some(Move::new(E2, E4, Piece::Pawn, false));
+        let b = Some(Move::new(D2, D4, Piece::Pawn, false));
+        let base = TimeManager::new(60_000, 0, 0, 0);
+
+        // One move dominates: less time
+        let mut stable = base;
+        for _ in 0..6 {
+            stable.update(a, 20);
+        }
+        assert!(stable.soft_limit() < base.soft_limit());
+
+        // The best move keeps changing: more time, but never past the hard limit
+        let mut unstable = base;
+        for i in 0..6 {
+            unstable.update(if i % 2 == 0 { a } else { b }, 20);
+        }
+        assert!(unstable.soft_limit() > base.soft_limit());
+        assert!(unstable.soft_limit() <= unstable.hard_limit());
+
+        // The score drops with the same move
+        let mut dropped = base;
+        dropped.update(a, 50);
+        let before = dropped.soft_limit();
+        dropped.update(a, -50);
+        assert!(dropped.soft_limit() > before);
+    }
+}
@@ -0,0 +1,165 @@
+use std::{fmt, ops::{Add, Sub}};
+
+// Little-Endian Rank-File Mapping: a1 = 0, b1 = 1, ..., h8 = 63
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(pub u8);
+
+// 0 is the a-file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct File(pub u8);
+
+// 0 is the first rank
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rank(pub u8);
+
+impl Square {
+    pub const fn new(file: File, rank: Rank) -> Self {
+        Square(rank.0 * 8 + file.0)
+    }
+
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square)
+    }
+
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub const fn file(self) -> File {
+        File(self.0 % 8)
+    }
+
+    pub const fn rank(self) -> Rank {
+        Rank(self.0 / 8)
+    }
+
+    // `Add` and `Sub` with a signed step
+    pub const fn offset(self, delta: i8) -> Self {
+        Square(self.0.wrapping_add_signed(delta))
+    }
+
+    // a1 <-> a8
+    pub const fn flip_vertical(self) -> Self {
+        Square(self.0 ^ 56)
+    }
+
+    // a1 <-> h1
+    pub const fn flip_horizontal(self) -> Self {
+        Square(self.0 ^ 7)
+    }
+
+    // h1 is light, a1 is dark
+    pub const fn is_light(self) -> bool {
+        (self.0 % 8 + self.0 / 8) % 2 == 1
+    }
+
+    // "e4" and the like
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut chars = s.chars();
+        let (Some(file @ 'a'..='h'), Some(rank @ '1'..='8'), None) = (chars.next(), chars.next(), chars.next()) else {
+            return None;
+        };
+        Some(Square::new(File(file as u8 - b'a'), Rank(rank as u8 - b'1')))
+    }
+}
+
+impl File {
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'a' + self.0) as char
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        matches!(c, 'a'..='h').then(|| File(c as u8 - b'a'))
+    }
+}
+
+impl Rank {
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'1' + self.0) as char
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        matches!(c, '1'..='8').then(|| Rank(c as u8 - b'1'))
+    }
+
+    // The same rank from the other side of the board
+    pub const fn flip(self) -> Self {
+        Rank(7 - self.0)
+    }
+}
+
+// Steps along the board: +1 is one file to the right, +8 is one rank up. Nothing wraps
+// around the edges, the caller has to know the step stays on the board
+impl Add<u8> for Square {
+    type Output = Square;
+    fn add(self, rhs: u8) -> Square {
+        Square(self.0 + rhs)
+    }
+}
+
+impl Sub<u8> for Square {
+    type Output = Square;
+    fn sub(self, rhs: u8) -> Square {
+        Square(self.0 - rhs)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::board::*;
+
+    #[test]
+    fn coordinates() {
+        assert_eq!(E4.file(), File(4));
+        assert_eq!(E4.rank(), Rank(3));
+        assert_eq!(Square::new(File(4), Rank(3)), E4);
+        assert_eq!(E2 + 16, E4);
+        assert_eq!(E4 - 1, D4);
+        assert_eq!(E4.offset(-9), D3);
+        assert_eq!(E2.flip_vertical(), E7);
+        assert_eq!(A1.flip_horizontal(), H1);
+        assert!(H1.is_light() && !A1.is_light());
+        assert_eq!(Square::all().count(), 64);
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(A1.to_string(), "a1");
+        assert_eq!(H8.to_string(), "h8");
+        assert_eq!(D4.to_string(), "d4");
+        assert_eq!(Square::parse("e4"), Some(E4));
+        assert_eq!(Square::parse("i4"), None);
+        assert_eq!(Square::parse("e9"), None);
+        assert_eq!(Square::parse("e44"), None);
+        assert_eq!(File::from_char('c'), Some(File(2)));
+        assert_eq!(Rank::from_char('0'), None);
+    }
+}
@@ -0,0 +1,537 @@
+use std::io::{self, Read, Write};
+use std::mem::size_of;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::constants::CHECKMATE_EVAL;
+use crate::core::{chess_move::Move, zobrist::ZobristHash};
+
+// Scores this close to `CHECKMATE_EVAL` are mates, their distance is counted from the root
+const MATE_THRESHOLD: i32 = CHECKMATE_EVAL - 1000;
+
+// Entries sampled for `hashfull`
+const HASHFULL_SAMPLE: usize = 1000;
+
+// Entries a position can go into, the ones of a bucket are next to each other in memory
+const BUCKET_SIZE: usize = 4;
+// How many plies of depth a search's worth of age outweighs when picking an entry to replace
+const AGE_WEIGHT: i32 = 8;
+
+// The start of a saved table, bump the version whenever the layout of an entry or the Zobrist keys change
+const FILE_MAGIC: &[u8; 8] = b"CHSTT\0\0\x02";
+
+// What the stored score says about the real one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    #[default]
+    Exact,
+    Lower,  // failed high, the score is at least this
+    Upper,  // failed low, the score is at most this
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TTEntry {
+    pub key: ZobristHash,  // 0 = empty
+    pub best_move: Option<Move>,
+    score: i32,  // mate scores relative to this node, see `score`
+    pub depth: u8,
+    pub bound: Bound,
+    generation: u8,  // of the search that stored it
+}
+
+impl TTEntry {
+    // The score as seen from the root, `ply` moves above this position
+    pub fn score(&self, ply: usize) -> i32 {
+        if self.score > MATE_THRESHOLD {
+            self.score - ply as i32
+        } else if self.score < -MATE_THRESHOLD {
+            self.score + ply as i32
+        } else {
+            self.score
+        }
+    }
+
+    // Score, depth, bound and generation
+    fn pack_data(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        self.score as u32 as u64 | (self.depth as u64) << 32 | bound << 40 | (self.generation as u64) << 48
+    }
+
+    // Bit 0 says whether there is a move at all, `Move::pack` above it
+    fn pack_move(&self) -> u64 {
+        self.best_move.map_or(0, |m| 1 | (m.pack() as u64) << 1)
+    }
+
+    // The words may come from a saved file: a move that doesn't unpack is no move
+    fn unpack(key: ZobristHash, data: u64, packed_move: u64) -> TTEntry {
+        let best_move = (packed_move & 1 == 1).then(|| Move::try_unpack((packed_move >> 1) as u32)).flatten();
+        let bound = match (data >> 40) & 0b11 {
+            0 => Bound::Exact,
+            1 => Bound::Lower,
+            _ => Bound::Upper,
+        };
+        TTEntry {
+            key,
+            best_move,
+            score: data as u32 as i32,
+            depth: (data >> 32) as u8,
+            bound,
+            generation: (data >> 48) as u8,
+        }
+    }
+}
+
+// An entry as it is stored: the key XORed with the other two words. Threads store and probe
+// without locking, and a slot written by two of them at once holds one's data and the other's
+// move. Its key then does not check out, and the probe misses
+#[derive(Debug, Default)]
+struct Slot {
+    check: AtomicU64,
+    data: AtomicU64,
+    packed_move: AtomicU64,
+}
+
+impl Slot {
+    // A torn entry comes out with a key that matches no position, 0 if the slot is empty
+    fn load(&self) -> TTEntry {
+        let data = self.data.load(Ordering::Relaxed);
+        let packed_move = self.packed_move.load(Ordering::Relaxed);
+        let key = self.check.load(Ordering::Relaxed) ^ data ^ packed_move;
+        TTEntry::unpack(key, data, packed_move)
+    }
+
+    fn save(&self, entry: &TTEntry) {
+        let (data, packed_move) = (entry.pack_data(), entry.pack_move());
+        self.check.store(entry.key ^ data ^ packed_move, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+        self.packed_move.store(packed_move, Ordering::Relaxed);
+    }
+}
+
+// Position hash -> the result of searching it. Survives between searches, sized in MB by the `Hash` option.
+// Every search is a generation of its own, entries from the earlier ones are the first to go.
+// Storing only takes `&self`, so search threads can share one table
+#[derive(Debug)]
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    pub fn new(mb: usize) -> Self {
+        let slots = (0..Self::capacity(mb)).map(|_| Slot::default()).collect();
+        TranspositionTable { slots, generation: 0 }
+    }
+
+    // The largest power of two number of buckets that fits into `mb`, in entries
+    fn capacity(mb: usize) -> usize {
+        let buckets = (mb.max(1) << 20) / (BUCKET_SIZE * size_of::<Slot>());
+        BUCKET_SIZE << buckets.ilog2()
+    }
+
+    // Drops everything stored, the memory is allocated by the time this returns
+    pub fn resize(&mut self, mb: usize) {
+        *self = TranspositionTable::new(mb);
+    }
+
+    pub fn clear(&mut self) {
+        for slot in &self.slots {
+            slot.save(&TTEntry::default());
+        }
+        self.generation = 0;
+    }
+
+    // Called once at the start of every search
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    // Searches since the entry was stored, wraps around after 256
+    fn age(&self, entry: &TTEntry) -> i32 {
+        self.generation.wrapping_sub(entry.generation) as i32
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    // The entries `key` can be in
+    fn bucket(&self, key: ZobristHash) -> &[Slot] {
+        let start = key as usize & (self.slots.len() - BUCKET_SIZE);
+        &self.slots[start..start + BUCKET_SIZE]
+    }
+
+    // Starts loading the bucket of `key` into the cache, so that it is there by the time it is probed.
+    // Only a hint, on other architectures than x86-64 it does nothing
+    #[inline]
+    pub fn prefetch(&self, key: ZobristHash) {
+        #[cfg(target_arch = "x86_64")]
+        {
+            use std::arch::x86_64::{_mm_prefetch, _MM_HINT_T0};
+            let bucket = self.bucket(key).as_ptr() as *const i8;
+            // A prefetch never faults, whatever the address
+            unsafe { _mm_prefetch::<_MM_HINT_T0>(bucket) };
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        let _ = key;
+    }
+
+    pub fn probe(&self, key: ZobristHash) -> Option<TTEntry> {
+        self.bucket(key).iter().map(Slot::load).find(|e| e.key == key && key != 0)
+    }
+
+    // `score` is from the point of view of the side to move in the position, found `ply` moves from the root.
+    // A shallower result for the same position only replaces a deeper one from an earlier search.
+    // A new position goes into an empty entry of its bucket, or else replaces the one that is the
+    // shallowest once the age is taken into account
+    pub fn store(&self, key: ZobristHash, depth: usize, ply: usize, score: i32, bound: Bound, best_move: Option<Move>) {
+        let bucket = self.bucket(key);
+        let entries: Vec<TTEntry> = bucket.iter().map(Slot::load).collect();
+        let index = match entries.iter().position(|e| e.key == key) {
+            Some(i) => i,
+            None => (0..BUCKET_SIZE).min_by_key(|&i| {
+                let e = &entries[i];
+                if e.key == 0 { i32::MIN } else { e.depth as i32 - AGE_WEIGHT * self.age(e) }
+            }).unwrap(),
+        };
+
+        let old = entries[index];
+        if old.key == key && old.depth as usize > depth && old.generation == self.generation {
+            return;
+        }
+
+        let score = if score > MATE_THRESHOLD {
+            score + ply as i32
+        } else if score < -MATE_THRESHOLD {
+            score - ply as i32
+        } else {
+            score
+        };
+        // Keep the old move if the new search didn't come up with one
+        let best_move = best_move.or(if old.key == key { old.best_move } else { None });
+
+        let depth = depth.min(u8::MAX as usize) as u8;
+        bucket[index].save(&TTEntry { key, best_move, score, depth, bound, generation: self.generation });
+    }
+
+    // Permille of the table in use by the current search, as UCI `hashfull` wants it
+    pub fn hashfull(&self) -> usize {
+        let sample = HASHFULL_SAMPLE.min(self.slots.len());
+        let used = self.slots[..sample].iter()
+            .map(Slot::load)
+            .filter(|e| e.key != 0 && e.generation == self.generation)
+            .count();
+        used * 1000 / sample
+    }
+}
+
+// Saving and loading, for analysis that goes on after the engine is restarted.
+// The file is the header, the entry count and the generation, then every slot as it is in memory.
+// Entries are checked against their key like always, a damaged one is a miss and nothing more
+impl TranspositionTable {
+    pub fn save(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(FILE_MAGIC)?;
+        w.write_all(&(self.slots.len() as u64).to_le_bytes())?;
+        w.write_all(&[self.generation])?;
+        for slot in &self.slots {
+            for word in [&slot.check, &slot.data, &slot.packed_move] {
+                w.write_all(&word.load(Ordering::Relaxed).to_le_bytes())?;
+            }
+        }
+        w.flush()
+    }
+
+    // The table comes back with the size it was saved with
+    pub fn load(r: &mut impl Read) -> io::Result<TranspositionTable> {
+        let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidData, what.to_string());
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != FILE_MAGIC {
+            return Err(invalid("not a saved transposition table"));
+        }
+        let len = read_u64(r)? as usize;
+        if !len.is_power_of_two() || len < BUCKET_SIZE {
+            return Err(invalid("bad entry count"));
+        }
+        let mut generation = [0];
+        r.read_exact(&mut generation)?;
+
+        // Grows as it is read, a cut off file fails before a bogus count gets allocated
+        let mut slots = Vec::new();
+        for _ in 0..len {
+            slots.push(Slot {
+                check: AtomicU64::new(read_u64(r)?),
+                data: AtomicU64::new(read_u64(r)?),
+                packed_move: AtomicU64::new(read_u64(r)?),
+            });
+        }
+        Ok(TranspositionTable { slots, generation: generation[0] })
+    }
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    r.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+impl Clone for TranspositionTable {
+    fn clone(&self) -> Self {
+        let slots = self.slots.iter().map(|slot| {
+            let copy = Slot::default();
+            copy.save(&slot.load());
+            copy
+        }).collect();
+        TranspositionTable { slots, generation: self.generation }
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        TranspositionTable::new(1)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::board::*, core::{piece::Piece, square::Square}};
+
+    #[test]
+    fn sizes() {
+        let tt = TranspositionTable::new(1);
+        assert!(tt.len().is_power_of_two() && tt.len() >= BUCKET_SIZE);
+        assert!(tt.len() * size_of::<Slot>() <= 1 << 20);
+        assert!(tt.len() * size_of::<Slot>() * 2 > 1 << 20);
+        let mut tt = TranspositionTable::new(16);
+        assert_eq!(tt.hashfull(), 0);
+        tt.resize(4);
+        assert_eq!(tt.len(), TranspositionTable::new(4).len());
+    }
+
+    #[test]
+    fn store_and_probe() {
+        let mut tt = TranspositionTable::new(1);
+        let m = Move::new(E2, E4, Piece::Pawn, false);
+        assert_eq!(tt.probe(42), None);
+
+        tt.store(42, 5, 0, 35, Bound::Lower, Some(m));
+        let entry = tt.probe(42).unwrap();
+        assert_eq!((entry.depth, entry.score(0), entry.bound, entry.best_move), (5, 35, Bound::Lower, Some(m)));
+
+        // Shallower results don't replace deeper ones
+        tt.store(42, 3, 0, -10, Bound::Exact, None);
+        assert_eq!(tt.probe(42).unwrap().score(0), 35);
+
+        // A deeper one does, and keeps the move
+        tt.store(42, 6, 0, -10, Bound::Upper, None);
+        let entry = tt.probe(42).unwrap();
+        assert_eq!((entry.score(0), entry.best_move), (-10, Some(m)));
+
+        // Different positions in the same bucket
+        let other = 42 + tt.len() as u64;
+        tt.store(other, 1, 0, 0, Bound::Exact, None);
+        assert!(tt.probe(42).is_some() && tt.probe(other).is_some());
+
+        tt.clear();
+        assert_eq!(tt.probe(other), None);
+    }
+
+    #[test]
+    fn replacement() {
+        let mut tt = TranspositionTable::new(1);
+        let len = tt.len() as u64;
+        let key = |i: u64| 42 + i * len;
+        for (i, depth) in [7, 2, 9, 5].into_iter().enumerate() {
+            tt.store(key(i as u64), depth, 0, 0, Bound::Exact, None);
+        }
+
+        // The bucket is full, the shallowest entry goes
+        tt.store(key(4), 3, 0, 0, Bound::Exact, None);
+        assert_eq!(tt.probe(key(1)), None);
+        assert!([0, 2, 3, 4].iter().all(|&i| tt.probe(key(i)).is_some()));
+
+        // A few searches later, the deep entries are stale and go first
+        for _ in 0..2 {
+            tt.new_search();
+        }
+        tt.store(key(5), 1, 0, 0, Bound::Exact, None);
+        tt.store(key(6), 1, 0, 0, Bound::Exact, None);
+        assert_eq!((tt.probe(key(3)), tt.probe(key(4))), (None, None));
+        assert!(tt.probe(key(0)).is_some() && tt.probe(key(2)).is_some());
+
+        // A shallower result replaces a deeper one from an earlier search
+        tt.store(key(2), 1, 0, 17, Bound::Exact, None);
+        assert_eq!(tt.probe(key(2)).unwrap().score(0), 17);
+    }
+
+    #[test]
+    fn mate_scores_are_relative_to_the_node() {
+        let tt = TranspositionTable::new(1);
+        // Mate in 3 plies from the root, found 2 plies deep: the node itself is 1 ply away from it
+        tt.store(7, 1, 2, CHECKMATE_EVAL - 3, Bound::Exact, None);
+        let entry = tt.probe(7).unwrap();
+        assert_eq!(entry.score(2), CHECKMATE_EVAL - 3);
+        // Reached 4 plies deep through another move order, the mate is 5 plies away now
+        assert_eq!(entry.score(4), CHECKMATE_EVAL - 5);
+        tt.store(8, 1, 2, -CHECKMATE_EVAL + 3, Bound::Exact, None);
+        assert_eq!(tt.probe(8).unwrap().score(4), -CHECKMATE_EVAL + 5);
+    }
+
+    #[test]
+    fn hashfull() {
+        let mut tt = TranspositionTable::new(1);
+        for key in 1..=(HASHFULL_SAMPLE as u64 / 2) {
+            tt.store(key, 1, 0, 0, Bound::Exact, None);
+        }
+        assert_eq!(tt.hashfull(), 500);
+
+        // Only the current search's entries count
+        tt.new_search();
+        assert_eq!(tt.hashfull(), 0);
+        tt.store(1, 1, 0, 0, Bound::Exact, None);
+        assert_eq!(tt.hashfull(), 1);
+    }
+
+    #[test]
+    fn moves_survive_packing() {
+        let tt = TranspositionTable::new(1);
+        let moves = [
+            Move::new(E2, E4, Piece::Pawn, false),
+            Move { promotion: Some(Piece::Knight), ..Move::new(E7, F8, Piece::Pawn, true) },
+            Move { en_passant: true, ..Move::new(E5, D6, Piece::Pawn, true) },
+            Move { double_push: true, ..Move::new(A2, A4, Piece::Pawn, false) },
+            Move { kingside_castling: true, ..Move::new(E1, G1, Piece::King, false) },
+            Move { queenside_castling: true, ..Move::new(E8, C8, Piece::King, false) },
+            Move { promotion: Some(Piece::Queen), ..Move::new(H7, H8, Piece::Pawn, false) },
+        ];
+        for (i, &m) in moves.iter().enumerate() {
+            let key = 1000 + i as u64;
+            tt.store(key, 3, 0, -CHECKMATE_EVAL + 7, Bound::Upper, Some(m));
+            let entry = tt.probe(key).unwrap();
+            assert_eq!((entry.best_move, entry.score(0), entry.bound, entry.depth), (Some(m), -CHECKMATE_EVAL + 7, Bound::Upper, 3));
+        }
+    }
+
+    #[test]
+    fn prefetch_is_only_a_hint() {
+        let tt = TranspositionTable::new(1);
+        tt.store(42, 5, 0, 35, Bound::Exact, None);
+        for key in [0, 42, u64::MAX] {
+            tt.prefetch(key);
+        }
+        assert_eq!(tt.probe(42).unwrap().score(0), 35);
+    }
+
+    #[test]
+    fn torn_entries_are_misses() {
+        let tt = TranspositionTable::new(1);
+        let len = tt.len() as u64;
+        tt.store(42, 5, 0, 35, Bound::Exact, Some(Move::new(E2, E4, Piece::Pawn, false)));
+        tt.store(42 + len, 9, 0, -80, Bound::Lower, Some(Move::new(G1, F3, Piece::Knight, false)));
+
+        // Another thread got halfway through storing the second one over the first one
+        let bucket = tt.bucket(42);
+        let other = bucket[1].load();
+        bucket[0].data.store(other.pack_data(), Ordering::Relaxed);
+        assert_eq!(tt.probe(42), None);
+        assert!(tt.probe(42 + len).is_some());
+
+        bucket[0].packed_move.store(other.pack_move(), Ordering::Relaxed);
+        assert_eq!(tt.probe(42), None);
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        // Every thread stores entries whose contents follow from the key, into the same few buckets.
+        // Whatever is found has to be one thread's entry in full
+        let tt = TranspositionTable::new(1);
+        let len = tt.len() as u64;
+        let m = |key: u64| Move::new(Square((key % 64) as u8), Square((key / 64 % 64) as u8), Piece::Rook, false);
+        std::thread::scope(|scope| {
+            for thread in 0..4u64 {
+                let tt = &tt;
+                scope.spawn(move || {
+                    for i in 0..20_000u64 {
+                        let key = 1 + (i * 7 + thread) % 64 * len;
+                        tt.store(key, (key % 50) as usize, 0, key as i32, Bound::Exact, Some(m(key)));
+                        let probed = 1 + (i * 13 + thread) % 64 * len;
+                        if let Some(entry) = tt.probe(probed) {
+                            assert_eq!(entry.score(0), probed as i32);
+                            assert_eq!(entry.depth as u64, probed % 50);
+                            assert_eq!(entry.best_move, Some(m(probed)));
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn save_and_load() {
+        let mut tt = TranspositionTable::new(1);
+        tt.new_search();
+        let m = Move::new(E2, E4, Piece::Pawn, false);
+        tt.store(42, 7, 0, -120, Bound::Lower, Some(m));
+        tt.store(43, 3, 1, CHECKMATE_EVAL - 4, Bound::Exact, None);
+
+        let mut file = Vec::new();
+        tt.save(&mut file).unwrap();
+        let loaded = TranspositionTable::load(&mut file.as_slice()).unwrap();
+        assert_eq!(loaded.len(), tt.len());
+        assert_eq!(loaded.probe(42), tt.probe(42));
+        assert_eq!(loaded.probe(43).unwrap().score(1), CHECKMATE_EVAL - 4);
+        assert_eq!(loaded.hashfull(), tt.hashfull());
+
+        // A flipped bit costs the entry it is in, and only that one
+        // 42 went into the first slot of its bucket, 40 (43 into the second one)
+        let data_of_42 = FILE_MAGIC.len() + 8 + 1 + 40 * size_of::<Slot>() + 8;
+        file[data_of_42] ^= 1;
+        let damaged = TranspositionTable::load(&mut file.as_slice()).unwrap();
+        assert_eq!(damaged.probe(42), None);
+        assert!(damaged.probe(43).is_some());
+    }
+
+    #[test]
+    fn load_corrupt_moves() {
+        let tt = TranspositionTable::new(1);
+        tt.store(42, 7, 0, -120, Bound::Lower, Some(Move::new(E2, E4, Piece::Pawn, false)));
+        let mut file = Vec::new();
+        tt.save(&mut file).unwrap();
+
+        // Piece bits of 7, then promotion bits of 7, with the key fixed up so that the entry checks out
+        let slot = FILE_MAGIC.len() + 8 + 1 + 40 * size_of::<Slot>();
+        let word = |file: &[u8], at: usize| u64::from_le_bytes(file[at..at + 8].try_into().unwrap());
+        for corrupt in [0b111 << 13, 0b111 << 17] {
+            let mut file = file.clone();
+            let packed_move = word(&file, slot + 16) | corrupt;
+            let check = word(&file, slot) ^ corrupt;
+            file[slot..slot + 8].copy_from_slice(&check.to_le_bytes());
+            file[slot + 16..slot + 24].copy_from_slice(&packed_move.to_le_bytes());
+
+            let loaded = TranspositionTable::load(&mut file.as_slice()).unwrap();
+            let entry = loaded.probe(42).unwrap();
+            assert_eq!((entry.best_move, entry.score(0), entry.depth), (None, -120, 7));
+            assert_eq!(loaded.hashfull(), tt.hashfull());
+        }
+    }
+
+    #[test]
+    fn load_errors() {
+        let mut file = Vec::new();
+        TranspositionTable::new(1).save(&mut file).unwrap();
+        let kind = |bytes: &[u8]| TranspositionTable::load(&mut &bytes[..]).unwrap_err().kind();
+        assert_eq!(kind(&file[..file.len() - 1]), io::ErrorKind::UnexpectedEof);
+        assert_eq!(kind(b"PGN [Event \"?\"]"), io::ErrorKind::InvalidData);
+        let mut bad_count = file.clone();
+        bad_count[8] = 3;
+        assert_eq!(kind(&bad_count), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,243 @@
+// A game with its alternatives kept: taking a move back and playing another one starts a
+// variation instead of losing the line that was there. For analysis, and PGN with variations
+use crate::core::{chess_move::Move, game::Game, player::Player};
+
+#[derive(Clone)]
+pub struct GameTree {
+    game: Game,        // at `current`
+    nodes: Vec<Node>,  // the root first
+    current: usize,
+}
+
+#[derive(Clone)]
+struct Node {
+    m: Option<Move>,      // the move that leads here, None for the root
+    parent: usize,
+    children: Vec<usize>, // the main line first
+}
+
+impl GameTree {
+    // `game` is the root, the moves played before it are not part of the tree
+    pub fn new(game: Game) -> Self {
+        GameTree {
+            game,
+            nodes: vec![Node { m: None, parent: 0, children: Vec::new() }],
+            current: 0,
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    // Follows the continuation if it is already in the tree, adds it as the last variation if not.
+    // False if the move is illegal
+    pub fn play(&mut self, m: &Move) -> bool {
+        if !self.game.is_legal(m) {
+            return false;
+        }
+        self.game.try_to_make_move(m);
+        self.current = match self.child(m) {
+            Some(child) => child,
+            None => {
+                self.nodes.push(Node { m: Some(*m), parent: self.current, children: Vec::new() });
+                let child = self.nodes.len() - 1;
+                self.nodes[self.current].children.push(child);
+                child
+            }
+        };
+        true
+    }
+
+    pub fn play_uci(&mut self, uci: &str) -> bool {
+        match Move::from_uci(&self.game.position, uci) {
+            Ok(m) => self.play(&m),
+            Err(_) => false,
+        }
+    }
+
+    // One move towards the root, keeping the line. None at the root
+    pub fn back(&mut self) -> Option<Move> {
+        let m = self.nodes[self.current].m?;
+        self.game.unmake_move();
+        self.current = self.nodes[self.current].parent;
+        Some(m)
+    }
+
+    // Along the main continuation. None at the end of the line
+    pub fn forward(&mut self) -> Option<Move> {
+        let m = self.variations().first().copied()?;
+        self.play(&m);
+        Some(m)
+    }
+
+    pub fn to_start(&mut self) {
+        while self.back().is_some() {}
+    }
+
+    pub fn to_end(&mut self) {
+        while self.forward().is_some() {}
+    }
+
+    // The continuations from here, the main one first
+    pub fn variations(&self) -> Vec<Move> {
+        self.nodes[self.current].children.iter().filter_map(|&child| self.nodes[child].m).collect()
+    }
+
+    // From the root to here
+    pub fn line(&self) -> Vec<Move> {
+        let mut line = Vec::new();
+        let mut node = self.current;
+        while let Some(m) = self.nodes[node].m {
+            line.push(m);
+            node = self.nodes[node].parent;
+        }
+        line.reverse();
+        line
+    }
+
+    // From the root, always along the first continuation
+    pub fn main_line(&self) -> Vec<Move> {
+        let mut line = Vec::new();
+        let mut node = 0;
+        while let Some(&child) = self.nodes[node].children.first() {
+            line.extend(self.nodes[child].m);
+            node = child;
+        }
+        line
+    }
+
+    // Makes every variation on the way from the root to here the main one at its branching point
+    pub fn promote(&mut self) {
+        let mut node = self.current;
+        while node != 0 {
+            let parent = self.nodes[node].parent;
+            let children = &mut self.nodes[parent].children;
+            let i = children.iter().position(|&c| c == node).unwrap();
+            children[..=i].rotate_right(1);
+            node = parent;
+        }
+    }
+
+    // Drops the continuation `m` from here, with everything after it. False if there is none
+    pub fn remove_variation(&mut self, m: &Move) -> bool {
+        let Some(child) = self.child(m) else { return false };
+        self.nodes[self.current].children.retain(|&c| c != child);
+        true
+    }
+
+    // "1. e4 e5 (1... c5 2. Nf3) 2. Nf3", the whole tree from the root in SAN
+    pub fn movetext(&self) -> String {
+        let mut game = self.game.clone();
+        for _ in self.line() {
+            game.unmake_move();
+        }
+        let mut tokens = Vec::new();
+        self.write_continuations(0, &mut game, true, &mut tokens);
+        tokens.join(" ")
+    }
+
+    fn child(&self, m: &Move) -> Option<usize> {
+        self.nodes[self.current].children.iter().copied().find(|&c| self.nodes[c].m == Some(*m))
+    }
+
+    // The main continuation of `node`, its alternatives in parentheses, then the rest of the main line
+    fn write_continuations(&self, node: usize, game: &mut Game, number_black: bool, tokens: &mut Vec<String>) {
+        let Some((&main, alternatives)) = self.nodes[node].children.split_first() else { return };
+        tokens.push(move_token(game, node_move(self, main), number_black));
+
+        for &alternative in alternatives {
+            let m = node_move(self, alternative);
+            let mut variation = vec![move_token(game, m, true)];
+            game.try_to_make_move(&m);
+            self.write_continuations(alternative, game, false, &mut variation);
+            game.unmake_move();
+            tokens.push(format!("({})", variation.join(" ")));
+        }
+
+        let m = node_move(self, main);
+        game.try_to_make_move(&m);
+        self.write_continuations(main, game, !alternatives.is_empty(), tokens);
+        game.unmake_move();
+    }
+}
+
+// Every node but the root has one
+fn node_move(tree: &GameTree, node: usize) -> Move {
+    tree.nodes[node].m.unwrap()
+}
+
+// "3. Nf3", or "3... Nf6" where Black's move needs its number
+fn move_token(game: &Game, m: Move, number_black: bool) -> String {
+    let number = match game.position.player_to_move {
+        Player::White => format!("{}. ", game.fullmove_number),
+        Player::Black if number_black => format!("{}... ", game.fullmove_number),
+        Player::Black => String::new(),
+    };
+    format!("{}{}", number, m.to_san(&game.position))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(tree: &mut GameTree, line: &str) {
+        for uci in line.split_whitespace() {
+            assert!(tree.play_uci(uci), "{}", uci);
+        }
+    }
+
+    fn uci(moves: &[Move]) -> Vec<String> {
+        moves.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn keeps_alternatives() {
+        let mut tree = GameTree::new(Game::default());
+        play(&mut tree, "e2e4 e7e5 g1f3");
+        tree.back();
+        tree.back();
+        play(&mut tree, "c7c5 g1f3");
+        assert_eq!(uci(&tree.line()), ["e2e4", "c7c5", "g1f3"]);
+        assert_eq!(uci(&tree.main_line()), ["e2e4", "e7e5", "g1f3"]);
+
+        tree.to_start();
+        assert_eq!(tree.game().to_fen(), Game::default().to_fen());
+        tree.forward();
+        assert_eq!(uci(&tree.variations()), ["e7e5", "c7c5"]);
+
+        // Playing a move that is already there follows it
+        play(&mut tree, "c7c5");
+        assert_eq!(uci(&tree.variations()), ["g1f3"]);
+        assert!(!tree.play_uci("e1e3"));
+    }
+
+    #[test]
+    fn promote_and_remove() {
+        let mut tree = GameTree::new(Game::default());
+        play(&mut tree, "e2e4 e7e5");
+        tree.back();
+        play(&mut tree, "c7c5");
+        tree.promote();
+        assert_eq!(uci(&tree.main_line()), ["e2e4", "c7c5"]);
+
+        tree.back();
+        assert!(tree.remove_variation(&Move::from_uci(&tree.game().position, "c7c5").unwrap()));
+        assert_eq!(uci(&tree.main_line()), ["e2e4", "e7e5"]);
+    }
+
+    #[test]
+    fn movetext() {
+        let mut tree = GameTree::new(Game::default());
+        play(&mut tree, "e2e4 e7e5 g1f3 b8c6");
+        tree.to_start();
+        tree.forward();
+        play(&mut tree, "c7c5 g1f3");
+        tree.back();
+        play(&mut tree, "b1c3");
+        tree.to_start();
+        play(&mut tree, "d2d4");
+        assert_eq!(tree.movetext(), "1. e4 (1. d4) 1... e5 (1... c5 2. Nf3 (2. Nc3)) 2. Nf3 Nc6");
+    }
+}
@@ -1,26 +1,24 @@
 use crate::constants::zobrist::*;
-use crate::core::{position::*, player::Player};
-use crate::utility::square_idx_to_coordinates;
+use crate::core::{position::*, piece::Piece, player::Player, square::Square};
 
 pub type ZobristHash = u64;
 
 pub fn zobrist_hash(pos: &Position) -> u64 {
     let mut hash: u64 = 0;
-    for sq_idx in 0..64 {
-        let what = pos.what(sq_idx);
+    for sq in Square::all() {
+        let what = pos.what(sq);
 
         if let Some((player, piece)) = what {
             let piece = piece.index();
             let color = player.index();
-            hash ^= ZOBRIST_PIECE[piece][color][sq_idx as usize];
+            hash ^= ZOBRIST_PIECE[piece][color][sq.index()];
         }
     }
 
     hash ^= ZOBRIST_CASTLING[pos.castling.encode() as usize];
 
-    if let Some(ep_sq_idx) = pos.en_passant_square {
-        let (file, _) = square_idx_to_coordinates(ep_sq_idx);
-        hash ^= ZOBRIST_EN_PASSANT_FILE[file as usize];
+    if let Some(ep_sq) = pos.en_passant_square {
+        hash ^= ZOBRIST_EN_PASSANT_FILE[ep_sq.file().index()];
     }
 
     if pos.player_to_move == Player::Black {
@@ -29,3 +27,13 @@ pub fn zobrist_hash(pos: &Position) -> u64 {
 
     hash
 }
+
+pub fn pawn_hash(pos: &Position) -> u64 {
+    let mut hash = 0;
+    for (player, pawns) in [(Player::White, pos.w.pawns), (Player::Black, pos.b.pawns)] {
+        for sq in pawns {
+            hash ^= ZOBRIST_PIECE[Piece::Pawn.index()][player.index()][sq.index()];
+        }
+    }
+    hash
+}
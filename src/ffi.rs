@@ -0,0 +1,144 @@
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    ptr,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+use crate::core::{
+    game::Game,
+    search::{iterative_deepening, SearchInfo, SearchLimits},
+    tt::TranspositionTable,
+};
+
+// `depth`, `score` in centipawns from the side to move's point of view, and the line as
+// space-separated UCI moves. `pv` is only valid during the call
+pub type InfoCallback = extern "C" fn(user_data: *mut c_void, depth: u32, score: i32, pv: *const c_char);
+
+// The engine for C and everything that can call C, see `include/chessica.h`. Opaque on that side
+pub struct Chessica {
+    game: Game,
+    tt: TranspositionTable,
+    best_move: Option<CString>,
+    info: Option<(InfoCallback, *mut c_void)>,
+}
+
+// Free it with `chessica_free`
+#[unsafe(no_mangle)]
+pub extern "C" fn chessica_new(hash_mb: usize) -> *mut Chessica {
+    Box::into_raw(Box::new(Chessica {
+        game: Game::default(),
+        tt: TranspositionTable::new(hash_mb),
+        best_move: None,
+        info: None,
+    }))
+}
+
+/// # Safety
+/// `engine` comes from `chessica_new` and is not used afterwards. Null is ignored
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn chessica_free(engine: *mut Chessica) {
+    if !engine.is_null() {
+        drop(unsafe { Box::from_raw(engine) });
+    }
+}
+
+/// # Safety
+/// `engine` comes from `chessica_new`, `fen` is a NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn chessica_set_fen(engine: *mut Chessica, fen: *const c_char) -> bool {
+    let engine = unsafe { &mut *engine };
+    let Ok(fen) = unsafe { CStr::from_ptr(fen) }.to_str() else { return false };
+    match Game::from_fen(fen) {
+        Ok(game) => {
+            engine.game = game;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// # Safety
+/// `engine` comes from `chessica_new`, `uci` is a NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn chessica_make_move(engine: *mut Chessica, uci: *const c_char) -> bool {
+    let engine = unsafe { &mut *engine };
+    let Ok(uci) = unsafe { CStr::from_ptr(uci) }.to_str() else { return false };
+    engine.game.try_to_make_uci_move(uci)
+}
+
+/// # Safety
+/// `engine` comes from `chessica_new`. `user_data` is handed to `callback` as it is, and has to stay
+/// valid for as long as searches run with it. A null `callback` turns the reports off
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn chessica_set_info_callback(
+    engine: *mut Chessica,
+    callback: Option<InfoCallback>,
+    user_data: *mut c_void,
+) {
+    let engine = unsafe { &mut *engine };
+    engine.info = callback.map(|callback| (callback, user_data));
+}
+
+/// # Safety
+/// `engine` comes from `chessica_new`. Blocks for about `movetime_ms`, and returns whether there
+/// was a move to find. The move itself is in `chessica_best_move`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn chessica_go_movetime(engine: *mut Chessica, movetime_ms: u64) -> bool {
+    let engine = unsafe { &mut *engine };
+    let info = engine.info;
+    let report = |info_line: &SearchInfo| {
+        if let Some((callback, user_data)) = info {
+            let pv: Vec<String> = info_line.pv.iter().map(|m| m.to_string()).collect();
+            let pv = CString::new(pv.join(" ")).unwrap();
+            callback(user_data, info_line.depth as u32, info_line.score, pv.as_ptr());
+        }
+    };
+
+    let limits = SearchLimits::movetime(Duration::from_millis(movetime_ms));
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let result = iterative_deepening(&mut engine.game, limits, None, stop_flag, &mut engine.tt, report);
+    engine.best_move = result.best_move.map(|m| CString::new(m.to_string()).unwrap());
+    engine.best_move.is_some()
+}
+
+/// # Safety
+/// `engine` comes from `chessica_new`. The move of the last search in UCI notation, null if it found
+/// none. Owned by the engine, valid until the next search or `chessica_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn chessica_best_move(engine: *const Chessica) -> *const c_char {
+    let engine = unsafe { &*engine };
+    engine.best_move.as_ref().map_or(ptr::null(), |m| m.as_ptr())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn count_iterations(user_data: *mut c_void, depth: u32, _score: i32, pv: *const c_char) {
+        let depths = unsafe { &mut *(user_data as *mut Vec<u32>) };
+        depths.push(depth);
+        assert!(!unsafe { CStr::from_ptr(pv) }.to_bytes().is_empty());
+    }
+
+    #[test]
+    fn search_through_ffi() {
+        let fen = CString::new("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let mut depths: Vec<u32> = Vec::new();
+        unsafe {
+            let engine = chessica_new(1);
+            assert!(chessica_best_move(engine).is_null());
+            assert!(!chessica_set_fen(engine, c"not a fen".as_ptr()));
+            assert!(chessica_set_fen(engine, fen.as_ptr()));
+            chessica_set_info_callback(engine, Some(count_iterations), &mut depths as *mut Vec<u32> as *mut c_void);
+            assert!(chessica_go_movetime(engine, 100));
+            assert_eq!(CStr::from_ptr(chessica_best_move(engine)).to_str(), Ok("a1a8"));
+
+            assert!(chessica_make_move(engine, c"a1a8".as_ptr()));
+            assert!(!chessica_go_movetime(engine, 100));  // mate
+            assert!(chessica_best_move(engine).is_null());
+            chessica_free(engine);
+        }
+        assert_eq!(depths.first(), Some(&1));
+    }
+}
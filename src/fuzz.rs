@@ -0,0 +1,94 @@
+// Entry points for the cargo-fuzz targets in `fuzz/`. Each takes whatever bytes the fuzzer comes up
+// with: the engine may reject them, but must not panic on any of them
+use std::sync::mpsc;
+use crate::core::{
+    game::Game,
+    movegen::legal_moves,
+    pgn::{parse_pgn, write_pgn, PgnMove},
+    position::Position,
+};
+use crate::uci::{debug, options::EngineOptions, parse::parse_go_params, position};
+
+// A FEN that is accepted has to be playable, and has to come back the same from `to_fen`
+pub fn fen(data: &[u8]) {
+    let Ok(fen) = std::str::from_utf8(data) else { return };
+    let Ok((pos, fullmove_number)) = Position::from_fen(fen) else { return };
+
+    let written = pos.to_fen(fullmove_number);
+    assert_eq!(Position::from_fen(&written), Ok((pos, fullmove_number)), "{:?} -> {:?}", fen, written);
+    for m in legal_moves(&pos) {
+        m.to_san(&pos);
+    }
+}
+
+// Lines of UCI input, the commands that only parse and set up: nothing here searches
+pub fn uci(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    let (out, _lines) = mpsc::channel();
+    let mut game = Game::default();
+    let mut options = EngineOptions::default();
+    for line in text.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.first() {
+            Some(&"position")  => position(&mut game, &tokens, &out),
+            Some(&"go")        => { parse_go_params(&tokens); }
+            Some(&"setoption") => { let _ = options.set(&tokens); }
+            Some(&"debug")     => debug(&mut options, &tokens, &out),
+            _ => {}
+        }
+    }
+}
+
+// Whatever games are read have to replay from their start, and write back out
+pub fn pgn(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else { return };
+    for pgn in parse_pgn(text) {
+        let Ok(start) = pgn.start() else { continue };
+        let mut game = start.clone();
+        for m in &pgn.moves {
+            assert!(game.try_to_make_move(m), "{} in {}", m, game.to_fen());
+        }
+        let tags: Vec<(&str, String)> = pgn.tags.iter().map(|(name, value)| (name.as_str(), value.clone())).collect();
+        let moves: Vec<PgnMove> = pgn.moves.iter().copied().map(PgnMove::from).collect();
+        write_pgn(&tags, &start, &moves, "*");
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_input() {
+        for input in [
+            "8/8/8/8/8/8/8/8 w - - 0 1",
+            "k7/8/8/8/8/8/8/K7 w - - 99999999999999999999 1",
+            "9/8/8/8/8/8/8/K7 w - - 0 1",
+            "k7/8/8/8/8/8/8/K7 w KQkq z9 0 1",
+            "k7/8/8/8/8/8/8/K7 w - - 0 1\u{e9}",
+        ] {
+            fen(input.as_bytes());
+        }
+        for input in [
+            "go depth 99999999999999999999 wtime -5",
+            "setoption name value\nsetoption name\nsetoption",
+            "setoption name Hash value 99999999999999999999",
+            "position fen\nposition startpos moves e2\nposition startpos moves e7e8q a1a1",
+            "position fen k7/8/8/8/8/8/8/K6r w - - 0 1 moves 0000",
+            "debug",
+        ] {
+            uci(input.as_bytes());
+        }
+        for input in [
+            "}}}}} [Event \"x\"]\n1. e4",
+            ")))\n\n\n[A \"b\"]",
+            "[FEN \"bad\"]\n1. e4",
+            "[A \"\\\"]\n[",
+            "1. O-O-O O-O e8=Q exf -- Z@e4 {",
+        ] {
+            pgn(input.as_bytes());
+        }
+        fen(&[0xff, 0xfe]);
+    }
+}
@@ -2,3 +2,41 @@ pub mod core;
 pub mod constants;
 pub mod utility;
 pub mod uci;
+pub mod prelude;
+pub mod ffi;
+
+// `core` is the engine itself. What a program using the crate needs most is re-exported here,
+// the rest is reachable through `core`'s modules
+
+// The board: `Game` keeps the move history (takebacks, repetitions), `Position` is a single position
+pub use crate::core::{
+    bitboard::Bitboard,
+    chess_move::Move,
+    game::{Game, TakebackError},
+    outcome::Outcome,
+    piece::Piece,
+    player::Player,
+    position::{FenParseError, Position},
+    square::Square,
+};
+
+// Move generation, for a bare `Position`. `Game::try_to_make_move` is the faster way to play moves
+pub use crate::core::movegen::{has_legal_move, is_legal, legal_moves};
+
+// Counting the leaf nodes of the move tree, to check move generation against known numbers
+pub use crate::core::perft::{perft, perft_divide, PERFT_INTERRUPTED};
+
+// Searching: `iterative_deepening` is the entry point, `evaluate` is the static eval
+// from the side to move's point of view
+pub use crate::core::{
+    evaluate::evaluate,
+    search::{iterative_deepening, SearchInfo, SearchLimits, SearchReport, SearchResult, StopReason},
+    tt::TranspositionTable,
+};
+
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
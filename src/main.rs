@@ -1,38 +1,126 @@
-use std::io::{self, BufRead, Write};
-use std::sync::{Arc, atomic::AtomicBool};
-use std::thread::JoinHandle;
+use std::env;
+use std::io::{self, BufRead};
+use std::sync::{atomic::{AtomicBool, Ordering}, mpsc::{self, Sender}, Arc};
+use std::thread;
+use std::time::Duration;
 
-use chess_engine::{core::game::Game, uci};
+use signal_hook::{consts::{SIGINT, SIGTERM}, flag};
+use chess_engine::uci::{cli, engine::EngineHandle, json::JsonWriter, writer::{spawn_writer, StdoutWriter}};
+
+const INTERRUPT_POLL: Duration = Duration::from_millis(10);
+
+enum Input {
+    Line(String),
+    End,  // of stdin, or Ctrl-C
+}
+
+// `--json` and `--config <file>`, before the command if there is one
+#[derive(Default)]
+struct Flags {
+    json: bool,
+    config: Option<String>,
+}
 
 fn main() {
-    let stdin = io::stdin();
-    let mut game = Game::default();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let flags = take_flags(&mut args);
+
+    // A single writer, so whatever the engine thread and this one say comes out in order
+    let (out, printer) = match flags.json {
+        true  => spawn_writer(JsonWriter(StdoutWriter)),
+        false => spawn_writer(StdoutWriter),
+    };
+
+    // `chess-engine bench [depth]`, as testing frameworks run it, `chess-engine perft 6 <fen>` and the like
+    let tokens: Vec<&str> = args.iter().map(String::as_str).collect();
+    if cli::run(&tokens, flags.config.as_deref(), &out) {
+        drop(out);
+        printer.join().unwrap();
+        return;
+    }
+
+    let engine = EngineHandle::spawn(out.clone());
+    if let Some(path) = &flags.config {
+        cli::apply_config(&engine, path, &out);
+    }
+
+    let (input, lines) = mpsc::channel();
+    read_stdin(input.clone());
+    watch_interrupts(input);
+
+    for line in lines {
+        let Input::Line(line) = line else { break };
+        if !engine.execute(line) {
+            break;
+        }
+    }
 
-    let mut stop_flag = Arc::new(AtomicBool::new(false));
-    let mut search_thread: Option<JoinHandle<()>> = None;
+    // Also on the end of input or Ctrl-C, the running search still gets to send its `bestmove`,
+    // and a command that is writing a file gets to finish it
+    engine.quit();
+    drop(out);
+    printer.join().unwrap();
+}
 
-    for line in stdin.lock().lines() {
-        let line = line.unwrap();
-        let tokens: Vec<&str> = line.split_whitespace().collect();
-        if tokens.is_empty() {
-            continue;
+fn take_flags(args: &mut Vec<String>) -> Flags {
+    let mut flags = Flags::default();
+    loop {
+        match args.first().map(String::as_str) {
+            Some("--json") => flags.json = true,
+            Some("--config") if args.len() > 1 => flags.config = Some(args.remove(1)),
+            _ => return flags,
         }
+        args.remove(0);
+    }
+}
 
-        match tokens[0] {
-            "uci"        => uci::uci(),
-            "isready"    => uci::isready(),
-            "ucinewgame" => uci::ucinewgame(&mut game),
-            "position"   => uci::position(&mut game, &tokens),
-            "go"         => uci::go(&mut game, &tokens, &mut stop_flag, &mut search_thread),
-            "stop"       => uci::stop_search(&mut stop_flag, &mut search_thread),
-            "quit" => {
-                uci::stop_search(&mut stop_flag, &mut search_thread);
-                break;
+fn read_stdin(input: Sender<Input>) {
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if input.send(Input::Line(line)).is_err() {
+                return;
             }
-            "d" => println!("{}", game.position),
-            _   => println!("info string Unknown command!")
         }
+        let _ = input.send(Input::End);
+    });
+}
+
+// The first Ctrl-C (or SIGTERM) ends the loop the same way `quit` does. A second one, if
+// quitting takes too long, kills the process right away
+fn watch_interrupts(input: Sender<Input>) {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM] {
+        let _ = flag::register_conditional_shutdown(signal, 130, Arc::clone(&interrupted));
+        let _ = flag::register(signal, Arc::clone(&interrupted));
+    }
+    thread::spawn(move || {
+        while !interrupted.load(Ordering::Relaxed) {
+            thread::sleep(INTERRUPT_POLL);
+        }
+        let _ = input.send(Input::End);
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(args: &[&str]) -> (bool, Option<String>, Vec<String>) {
+        let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let flags = take_flags(&mut args);
+        (flags.json, flags.config, args)
+    }
 
-        io::stdout().flush().unwrap();
+    #[test]
+    fn flags_before_the_command() {
+        assert_eq!(flags(&[]), (false, None, vec![]));
+        assert_eq!(flags(&["bench", "6"]), (false, None, vec!["bench".into(), "6".into()]));
+        assert_eq!(flags(&["--json", "--config", "a.toml", "run", "b.txt"]), (true, Some("a.toml".into()), vec!["run".into(), "b.txt".into()]));
+        assert_eq!(flags(&["--config", "a.toml", "--json"]), (true, Some("a.toml".into()), vec![]));
+        // Only before the command, and `--config` needs its file
+        assert_eq!(flags(&["run", "--json"]), (false, None, vec!["run".into(), "--json".into()]));
+        assert_eq!(flags(&["--config"]), (false, None, vec!["--config".into()]));
     }
 }
@@ -0,0 +1,31 @@
+// `use chess_engine::prelude::*;` brings in everything needed to set up a game, play moves and search
+pub use crate::{
+    Bitboard, FenParseError, Game, Move, Outcome, Piece, Player, Position, Square,
+    has_legal_move, is_legal, legal_moves,
+    perft, perft_divide, PERFT_INTERRUPTED,
+    evaluate, iterative_deepening, SearchInfo, SearchLimits, SearchReport, SearchResult, StopReason,
+    TranspositionTable,
+};
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    #[test]
+    fn plays_and_searches() -> Result<(), FenParseError> {
+        let mut game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1")?;
+        assert!(game.try_to_make_uci_move("a1a2"));
+        game.unmake_move();
+        assert_eq!(game.legal_moves().count(), 17);
+
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        assert_eq!(perft(&mut game, 1, &stop_flag), 17);
+
+        let mut tt = TranspositionTable::new(1);
+        let result = iterative_deepening(&mut game, SearchLimits::depth(2), None, stop_flag, &mut tt, |_: &SearchInfo| {});
+        assert_eq!(result.best_move, Move::from_uci(&game.position, "a1a8").ok());
+        Ok(())
+    }
+}
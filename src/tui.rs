@@ -0,0 +1,287 @@
+// `chess-engine tui [fen]`, behind the `tui` feature: the engine analyzes the position while the
+// terminal shows the board, the best lines, an eval graph of the game and how long it has been
+// thinking. Moves (e4 or e2e4) are typed in followed by Enter, `u` takes one back, `f` flips the
+// board and `q` quits
+use std::io::{self, BufRead, Write};
+use std::sync::{atomic::{AtomicBool, Ordering}, mpsc::{self, RecvTimeoutError, Sender}, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::constants::CHECKMATE_EVAL;
+use crate::core::{
+    chess_move::Move,
+    game::Game,
+    player::Player,
+    position::DisplaySettings,
+    search::{iterative_deepening, SearchInfo, SearchLimits},
+    tt::TranspositionTable,
+};
+use crate::uci::{cli::game_from, Output};
+
+const MULTI_PV: usize = 3;
+const HASH_MB: usize = 64;
+const REDRAW: Duration = Duration::from_millis(200);
+const PV_WIDTH: usize = 60;       // characters of a line shown
+const GRAPH_CLAMP: i32 = 500;     // centipawns at the top and the bottom of the graph
+const GRAPH_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+const BOARD: DisplaySettings = DisplaySettings { unicode: true, flipped: false };
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const HELP: &str = "Type a move (e4 or e2e4) and Enter, u to take one back, f to flip the board, q to quit";
+
+enum Event {
+    Info(usize, SearchInfo),  // of the search with this id
+    Input(String),
+}
+
+enum Action {
+    Play(Move),
+    Undo,
+    Quit,
+}
+
+// What is on the screen
+struct Tui {
+    game: Game,
+    lines: Vec<SearchInfo>,       // of the position on the board, by MultiPV number
+    evals: Vec<Option<i32>>,      // from White's point of view, one for every position before this one
+    status: String,
+    display: DisplaySettings,
+}
+
+// A search of the position on the board, on its own thread. Gives the table back once stopped
+struct Analysis {
+    stop_flag: Arc<AtomicBool>,
+    thread: Option<JoinHandle<TranspositionTable>>,  // None if the game is over
+    start: Instant,
+}
+
+pub fn run(fen_tokens: &[&str], out: &Output) {
+    let Some(game) = game_from(fen_tokens, out) else { return };
+    let mut tui = Tui { game, lines: Vec::new(), evals: Vec::new(), status: HELP.to_string(), display: BOARD };
+
+    let (events, received) = mpsc::channel();
+    read_input(events.clone());
+
+    let mut tt = TranspositionTable::new(HASH_MB);
+    for id in 1.. {
+        let mut analysis = Analysis::start(&tui.game, tt, id, events.clone());
+        tui.lines.clear();
+
+        let action = loop {
+            tui.draw(analysis.start.elapsed());
+            match received.recv_timeout(REDRAW) {
+                Ok(Event::Info(search, info)) if search == id => tui.update(info),
+                Ok(Event::Info(..)) | Err(RecvTimeoutError::Timeout) => {}
+                // Nothing to search again, the analysis goes on
+                Ok(Event::Input(line)) if matches!(line.trim(), "f" | "flip") => tui.display.flipped = !tui.display.flipped,
+                Ok(Event::Input(line)) => match tui.parse(&line) {
+                    Ok(action) => break action,
+                    Err(status) => tui.status = status,
+                },
+                Err(RecvTimeoutError::Disconnected) => break Action::Quit,
+            }
+        };
+
+        tt = analysis.stop();
+        match action {
+            Action::Play(m) => {
+                tui.evals.push(tui.white_eval());
+                tui.game.try_to_make_move(&m);
+                tui.status = HELP.to_string();
+            }
+            Action::Undo => {
+                tui.game.unmake_move();
+                tui.evals.pop();
+                tui.status = HELP.to_string();
+            }
+            Action::Quit => break,
+        }
+    }
+    println!();
+}
+
+// Lines typed in, and a `q` once there are no more
+fn read_input(events: Sender<Event>) {
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if events.send(Event::Input(line)).is_err() {
+                return;
+            }
+        }
+        let _ = events.send(Event::Input("q".to_string()));
+    });
+}
+
+impl Analysis {
+    fn start(game: &Game, mut tt: TranspositionTable, id: usize, events: Sender<Event>) -> Self {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let thread = (!game.outcome().is_over()).then(|| {
+            let mut game = game.clone();
+            let stop_flag = Arc::clone(&stop_flag);
+            thread::spawn(move || {
+                let limits = SearchLimits { multi_pv: Some(MULTI_PV), ..SearchLimits::infinite() };
+                let report = |info: &SearchInfo| {
+                    let _ = events.send(Event::Info(id, info.clone()));
+                };
+                iterative_deepening(&mut game, limits, None, stop_flag, &mut tt, report);
+                tt
+            })
+        });
+        Analysis { stop_flag, thread, start: Instant::now() }
+    }
+
+    fn stop(&mut self) -> TranspositionTable {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        match self.thread.take() {
+            Some(thread) => thread.join().unwrap(),
+            None => TranspositionTable::new(HASH_MB),
+        }
+    }
+}
+
+impl Tui {
+    fn update(&mut self, info: SearchInfo) {
+        let i = info.multipv - 1;
+        if i >= self.lines.len() {
+            self.lines.resize(i + 1, info.clone());
+        }
+        self.lines[i] = info;
+    }
+
+    fn parse(&self, line: &str) -> Result<Action, String> {
+        let pos = &self.game.position;
+        match line.trim() {
+            "q" | "quit" => Ok(Action::Quit),
+            "u" | "undo" if self.game.ply() == 0 => Err("Nothing to take back".to_string()),
+            "u" | "undo" => Ok(Action::Undo),
+            text => Move::from_uci(pos, text)
+                .or_else(|_| Move::from_san(pos, text).map_err(|_| ()))
+                .map(Action::Play)
+                .map_err(|_| format!("Not a legal move: {:?}. {}", text, HELP)),
+        }
+    }
+
+    // The best line's score, if there is one yet
+    fn white_eval(&self) -> Option<i32> {
+        let score = self.lines.first()?.score;
+        Some(match self.game.position.player_to_move {
+            Player::White => score,
+            Player::Black => -score,
+        })
+    }
+
+    fn draw(&self, elapsed: Duration) {
+        let mut stdout = io::stdout().lock();
+        let _ = write!(stdout, "{}{}", CLEAR_SCREEN, self.render(elapsed));
+        let _ = stdout.flush();
+    }
+
+    fn render(&self, elapsed: Duration) -> String {
+        let board = self.game.position.display(self.display).to_string();
+        let mut side = Vec::new();
+
+        let outcome = self.game.outcome();
+        if outcome.is_over() {
+            side.push(outcome.to_string());
+        } else if let Some(best) = self.lines.first() {
+            // The lines of an iteration count their nodes from its start, the last one has them all
+            let latest = self.lines.iter().max_by_key(|l| (l.depth, l.multipv)).unwrap_or(best);
+            side.push(format!(
+                "Depth {}   Nodes {}   {:.1} Mnps   {}:{:02}",
+                best.depth,
+                latest.nodes,
+                latest.nodes as f64 / latest.time.as_secs_f64().max(0.001) / 1e6,
+                elapsed.as_secs() / 60,
+                elapsed.as_secs() % 60,
+            ));
+        } else {
+            side.push("Thinking...".to_string());
+        }
+        side.push(String::new());
+        for line in &self.lines {
+            let pv: String = self.game.san_line(&line.pv).chars().take(PV_WIDTH).collect();
+            side.push(format!("{}. {:>7}  {}", line.multipv, score_string(line.score), pv));
+        }
+
+        let mut screen = String::new();
+        for (i, row) in board.lines().take(9).enumerate() {
+            screen += &format!("{:<20}{}\n", row, side.get(i).map(String::as_str).unwrap_or(""));
+        }
+
+        let mut evals = self.evals.clone();
+        evals.push(self.white_eval());
+        screen += &format!("\nEval  {}\n", eval_graph(&evals));
+        screen += &format!("Moves {}\n\n", self.game.moves_played_san().join(" "));
+        screen += &self.status;
+        screen += "\n> ";
+        screen
+    }
+}
+
+// "+0.35", "-1.20", "#3" for mate in 3, "#-2" for getting mated in 2
+fn score_string(score: i32) -> String {
+    if score.abs() > CHECKMATE_EVAL - 1000 {
+        let moves = ((CHECKMATE_EVAL - score.abs()) as f64 / 2.).ceil() as i32;
+        format!("#{}", if score > 0 { moves } else { -moves })
+    } else {
+        format!("{:+.2}", score as f64 / 100.)
+    }
+}
+
+// One bar per position, higher is better for White. A gap where there was no eval
+fn eval_graph(evals: &[Option<i32>]) -> String {
+    evals.iter().map(|eval| match eval {
+        Some(eval) => {
+            let clamped = eval.clamp(&-GRAPH_CLAMP, &GRAPH_CLAMP) + GRAPH_CLAMP;
+            let level = clamped as usize * (GRAPH_BARS.len() - 1) / (2 * GRAPH_CLAMP) as usize;
+            GRAPH_BARS[level]
+        }
+        None => ' ',
+    }).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_and_graph() {
+        assert_eq!(score_string(35), "+0.35");
+        assert_eq!(score_string(-120), "-1.20");
+        assert_eq!(score_string(CHECKMATE_EVAL - 5), "#3");
+        assert_eq!(score_string(-(CHECKMATE_EVAL - 2)), "#-1");
+        assert_eq!(eval_graph(&[Some(-900), Some(0), None, Some(500)]), "▁▄ █");
+    }
+
+    #[test]
+    fn render() {
+        let mut tui = Tui { game: Game::default(), lines: Vec::new(), evals: Vec::new(), status: HELP.to_string(), display: BOARD };
+        let Ok(Action::Play(m)) = tui.parse("e4") else { panic!("e4 is legal") };
+        tui.game.try_to_make_move(&m);
+        assert!(tui.parse("e5e4").is_err());
+        tui.update(SearchInfo {
+            depth: 7,
+            multipv: 1,
+            score: -20,
+            pv: vec![Move::from_uci(&tui.game.position, "e7e5").unwrap()],
+            nodes: 1000,
+            time: Duration::from_millis(10),
+            hashfull: 0,
+        });
+        let second = SearchInfo { multipv: 2, pv: vec![Move::from_uci(&tui.game.position, "c7c5").unwrap()], nodes: 1500, ..tui.lines[0].clone() };
+        tui.update(second);
+        let screen = tui.render(Duration::from_secs(75));
+        assert!(screen.contains("Depth 7   Nodes 1500 "));
+        assert!(screen.contains("1:15"));
+        assert!(screen.contains("1.   -0.20  1... e5"));
+        assert!(screen.contains("Moves e4"));
+        assert!(screen.contains("8 ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜"));
+
+        tui.display.flipped = true;
+        assert!(tui.render(Duration::ZERO).starts_with("1 ♖ ♘ ♗ ♔ ♕ ♗ ♘ ♖"));
+    }
+}
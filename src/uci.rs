@@ -1,304 +1,545 @@
-use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration};
-use std::thread::{self, JoinHandle};
-use std::time::Instant;
+use std::cmp::Reverse;
+use std::sync::{atomic::{AtomicBool, Ordering}, mpsc::Sender, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::{constants::{AUTHOR, CHECKMATE_EVAL, NAME}, core::{chess_move::Move, position::FenParseError}};
+use crate::{constants::{AUTHOR, CHECKMATE_EVAL, NAME}, core::chess_move::{Move, UciMoveParseError}};
 use crate::core::{
+    analysis::{annotate, review_game, summarize},
+    bench::bench as run_bench,
+    book::pick_book_move,
     game::Game,
+    movegen::has_legal_move,
+    position::{DisplaySettings, Position},
+    piece::Piece,
     player::Player,
+    rules::checks::is_king_in_check,
     perft::*,
+    pgn::{add_opening_tags, parse_pgn, write_pgn},
+    evaluate::{game_phase, symmetry::check_symmetry, trace::trace, wdl::wdl},
+    search::{mate::solve_mate, iterative_deepening, time::TimeManager, CurrMove, SearchInfo, SearchLimits, SearchReport, SearchResult},
+    tt::TranspositionTable,
 };
-
-pub fn uci() {
-    println!("id name {}", NAME);
-    println!("id author {}", AUTHOR);
-    println!("uciok");
-}
-
-pub fn isready() {
-    println!("readyok");
+use options::{all_options, EngineOptions};
+use parse::{parse_go_params, parse_position_params, GoParams, UciParseError};
+
+pub mod options;
+pub mod engine;
+pub mod cli;
+pub mod config;
+pub mod writer;
+pub mod json;
+pub mod log;
+pub(crate) mod parse;
+
+const BENCH_DEFAULT_DEPTH: usize = 5;
+const ANALYZE_DEFAULT_DEPTH: usize = 6;
+const ANALYZE_HASH_MB: usize = 16;
+
+// Every line the engine sends to the GUI goes through here, in order. See `writer::spawn_writer`
+pub type Output = Sender<String>;
+
+// Sending only fails once the receiving end is gone, at which point nobody is listening anyway
+fn send(out: &Output, line: impl Into<String>) {
+    let _ = out.send(line.into());
 }
 
-pub fn ucinewgame(game: &mut Game) {
-    *game = Game::default();
+pub fn uci(out: &Output) {
+    send(out, format!("id name {}", NAME));
+    send(out, format!("id author {}", AUTHOR));
+    for option in all_options() {
+        send(out, option.to_string());
+    }
+    send(out, "uciok");
 }
 
-pub fn stop_search(
-    stop_flag: &mut Arc<AtomicBool>,
-    search_thread: &mut Option<JoinHandle<()>>,
-) {
-    stop_flag.store(true, Ordering::Relaxed);
-    if let Some(handle) = search_thread.take() {
-        let _ = handle.join();
+pub fn position(game: &mut Game, tokens: &[&str], out: &Output) {
+    if let Err(e) = try_position(game, tokens) {
+        send(out, format!("info string Bad position command! {:?}", e));
     }
-    stop_flag.store(false, Ordering::Relaxed);
 }
 
-pub fn position(game: &mut Game, tokens: &[&str]) {
-    if tokens.len() < 2 {
-        return;
-    }
+// Stops at the first bad move, the ones after it were meant for another position
+fn try_position(game: &mut Game, tokens: &[&str]) -> Result<(), UciParseError> {
+    let params = parse_position_params(tokens)?;
+    *game = match params.fen {
+        Some(fen) => Game::from_fen(&fen).map_err(UciParseError::BadFen)?,
+        None => Game::default(),
+    };
 
-    let i;
-    match tokens[1] {
-        "fen" => {
-            if tokens.len() < 8 {
-                eprintln!("info string Bad FEN! {:?}", FenParseError::BadFieldCount);
-                return;
-            }
-            let fen = tokens[2..=7].join(" ");
-            match Game::from_fen(&fen) {
-                Ok(parsed) => {
-                    *game = parsed;
-                    i = 8;
-                }
-                Err(e) => {
-                    eprintln!("info string Bad FEN! {:?}", e);
-                    return;
-                }
-            }
+    for (i, mv) in params.moves.iter().enumerate() {
+        // "0000" passes the turn, analysis tools send it
+        let m = match *mv {
+            "0000" => Move::null(),
+            _ => Move::from_uci(&game.position, mv).map_err(|e| UciParseError::BadMove(i, e))?,
+        };
+        // Legal unless it is a null move in check, `from_uci` checked
+        if !game.try_to_make_move(&m) {
+            return Err(UciParseError::BadMove(i, UciMoveParseError::IllegalMove(mv.to_string())));
         }
-        "startpos" => {
-            *game = Game::default();
-            i = 2;
+    }
+    Ok(())
+}
+
+// Non-UCI: `d [unicode] [flip]`, the board (in chess symbols, from Black's side) and the rest of
+// what there is to know about the position
+pub fn display(game: &Game, tokens: &[&str], out: &Output) {
+    let mut settings = DisplaySettings::default();
+    for &token in tokens.get(1..).unwrap_or_default() {
+        match token {
+            "unicode" => settings.unicode = true,
+            "flip"    => settings.flipped = true,
+            _ => return send(out, format!("info string Bad d command! {:?}", token)),
         }
-        _ => return,
     }
+    send(out, game.display(settings).to_string());
+}
 
-    if tokens.get(i) == Some(&"moves") {
-        for mv in &tokens[i + 1..] {
-            let ok = game.try_to_make_uci_move(mv);
-            if !ok {
-                println!("info string Failed to execute move {}!", mv);
-            }
+// Non-UCI: `moves`, every legal move in UCI and in SAN, piece by piece. For when a move is not accepted
+pub fn moves(game: &Game, out: &Output) {
+    let mut moves: Vec<Move> = game.legal_moves().collect();
+    moves.sort_by_key(|m| m.to_string());
+    send(out, format!("Legal moves: {}", moves.len()));
+    for piece in Piece::all_variants() {
+        let listed: Vec<String> = moves.iter()
+            .filter(|m| m.piece == piece)
+            .map(|m| format!("{} ({})", m, m.to_san(&game.position)))
+            .collect();
+        if !listed.is_empty() {
+            send(out, format!("{:?}: {}", piece, listed.join(", ")));
         }
     }
 }
 
-#[derive(Debug)]
-struct GoParams {
-    perft:    Option<usize>,
-    movetime: Option<usize>,
-    depth:    Option<usize>,
-    infinite: bool,
-    wtime:    Option<usize>,
-    btime:    Option<usize>,
-    winc:     Option<usize>,
-    binc:     Option<usize>,
+// Non-UCI: `eval`, the evaluation term by term. One that tells the colors or the wings apart is a
+// bug, and gets pointed out
+pub fn eval(pos: &Position, out: &Output) {
+    send(out, trace(pos).to_string());
+    if let Err(e) = check_symmetry(pos) {
+        send(out, format!("Asymmetric evaluation! {}", e));
+    }
 }
 
-fn parse_go_params(tokens: &[&str]) -> GoParams {
-    let mut params = GoParams {
-        perft:    None,
-        movetime: None,
-        depth:    None,
-        infinite: false,
-        wtime:    None,
-        btime:    None,
-        winc:     None,
-        binc:     None,
+// Non-UCI: `bench [depth]`, blocks until done. Also run as `chess-engine bench [depth]`
+pub fn bench(tokens: &[&str], out: &Output) {
+    let depth = match tokens.get(1).map(|d| d.parse()) {
+        Some(Ok(depth)) => depth,
+        Some(Err(_)) => {
+            send(out, format!("info string Bad bench depth! {:?}", tokens[1]));
+            return;
+        }
+        None => BENCH_DEFAULT_DEPTH,
     };
-
-    // Treat `go` as `go infinite`
-    if tokens.len() == 1 {
-        params.infinite = true;
-        return params;
+    let reports = run_bench(SearchLimits::depth(depth));
+
+    for r in &reports {
+        send(out, format!(
+            "{:<10} positions {:>2} nodes {:>10} depth {:>5.2} time {:>6} ms nps {:>9}",
+            r.category.to_string(),
+            r.positions,
+            r.nodes,
+            r.average_depth(),
+            r.time.as_millis(),
+            r.nps()
+        ));
     }
 
-    let mut i = 1;  // skip the "go"
-    let parse = |target: &mut Option<usize>, i: &mut usize| {
-        if let Some(value) = tokens.get(*i + 1) {
-            *target = value.parse().ok();
-            *i += 1;
+    let nodes: u64 = reports.iter().map(|r| r.nodes).sum();
+    let time: Duration = reports.iter().map(|r| r.time).sum();
+    send(out, format!("Nodes searched: {}", nodes));
+    send(out, format!("Time: {:.3} sec", time.as_secs_f64()));
+    send(out, format!("Nodes per second: {:.0}", nodes as f64 / time.as_secs_f64()));
+    // The one line OpenBench and fastchess look for
+    send(out, format!("{} nodes {:.0} nps", nodes, nodes as f64 / time.as_secs_f64()));
+}
+
+// Non-UCI: `mate <n>`, every first move that forces mate in at most n moves, with the line
+// against the best defense. Blocks until done or stopped
+pub fn mate(game: &mut Game, tokens: &[&str], stop_flag: &Arc<AtomicBool>, out: &Output) {
+    let n = match tokens.get(1).map(|n| n.parse()) {
+        Some(Ok(n)) if n > 0 => n,
+        _ => {
+            send(out, format!("info string Bad mate command! {:?}", tokens.get(1)));
+            return;
         }
     };
 
-    while i < tokens.len() {
-        match tokens[i] {
-            "perft"    => parse(&mut params.perft,    &mut i),
-            "movetime" => parse(&mut params.movetime, &mut i),
-            "depth"    => parse(&mut params.depth,    &mut i),
-            "wtime"    => parse(&mut params.wtime,    &mut i),
-            "btime"    => parse(&mut params.btime,    &mut i),
-            "winc"     => parse(&mut params.winc,     &mut i),
-            "binc"     => parse(&mut params.binc,     &mut i),
-            "infinite" => params.infinite = true,
-            _ => {}
+    let start = Instant::now();
+    let solution = solve_mate(game, n, Arc::clone(stop_flag));
+    if solution.stopped {
+        send(out, format!("Stopped before trying every move, mate in {} not ruled out", n));
+    } else if solution.key_lines.is_empty() {
+        send(out, format!("No mate in {}", n));
+    }
+    for line in &solution.key_lines {
+        send(out, format!("Mate in {}: {}", line.mate_in, game.san_line(&line.moves)));
+    }
+    send(out, format!("Nodes: {}", solution.nodes));
+    send(out, format!("Time: {:.3} sec", start.elapsed().as_secs_f64()));
+}
+
+fn analyze_depth(token: Option<&&str>, out: &Output) -> Option<usize> {
+    match token.map(|d| d.parse()) {
+        Some(Ok(depth)) if depth > 0 => Some(depth),
+        None => Some(ANALYZE_DEFAULT_DEPTH),
+        _ => {
+            send(out, format!("info string Bad analyze depth! {:?}", token));
+            None
         }
-        i += 1;
     }
-    params
 }
 
-fn compute_movetime(game: &mut Game, wtime: usize, btime: usize, winc: usize, binc: usize) -> usize {
-    let (time, inc) = if game.position.player_to_move == Player::White {
-        (wtime, winc)
-    } else {
-        (btime, binc)
-    };
+// `tags` with the review's own added, and the moves annotated
+fn annotated_pgn(
+    mut tags: Vec<(&str, String)>,
+    start: &Game,
+    moves: &[Move],
+    result: &str,
+    depth: usize,
+    tt: &mut TranspositionTable,
+) -> String {
+    let reviews = review_game(start, moves, depth, tt);
+    tags.retain(|(name, _)| !["Annotator", "WhiteACPL", "BlackACPL"].contains(name));
+    tags.push(("Annotator", format!("{} depth {}", NAME, depth)));
+    tags.push(("WhiteACPL", format!("{:.0}", summarize(&reviews, Player::White).average_loss())));
+    tags.push(("BlackACPL", format!("{:.0}", summarize(&reviews, Player::Black).average_loss())));
+    let mut game = start.clone();
+    for m in moves {
+        game.try_to_make_move(m);
+    }
+    add_opening_tags(&mut tags, &game);
+    write_pgn(&tags, start, &annotate(start, &reviews), result)
+}
+
+// Non-UCI: `analyze [depth]`, the moves of the current game (`position ... moves ...`) as an
+// annotated PGN: every one of them against the engine's choice. Blocks until done
+pub fn analyze(game: &Game, tokens: &[&str], tt: &mut TranspositionTable, out: &Output) {
+    let Some(depth) = analyze_depth(tokens.get(1), out) else { return };
+
+    let moves = game.moves_played();
+    let start = game.initial();
+
+    let result = game.outcome().result();
+    let mut tags = vec![("Event", "?".to_string()), ("Result", result.to_string())];
+    if start.to_fen() != Game::default().to_fen() {
+        tags.push(("SetUp", "1".to_string()));
+        tags.push(("FEN", start.to_fen()));
+    }
+    for line in annotated_pgn(tags, &start, &moves, result, depth, tt).lines() {
+        send(out, line);
+    }
+}
 
-    let moves_remaining = 30;
-    let base_time = time / moves_remaining;
-    let inc_bonus = inc * 8 / 10;  // use 80% of the increment
+// `chess-engine analyze <file.pgn> [depth]`, every game in the file annotated
+pub fn analyze_file(tokens: &[&str], out: &Output) {
+    let Some(path) = tokens.get(1) else {
+        send(out, "info string Bad analyze command! No PGN file");
+        return;
+    };
+    let Some(depth) = analyze_depth(tokens.get(2), out) else { return };
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            send(out, format!("info string Can not read {}: {}", path, e));
+            return;
+        }
+    };
 
-    base_time + inc_bonus
+    let mut tt = TranspositionTable::new(ANALYZE_HASH_MB);
+    for game in parse_pgn(&text) {
+        let Ok(start) = game.start() else {
+            send(out, format!("info string Bad FEN tag! {:?}", game.tag("FEN")));
+            continue;
+        };
+        tt.clear();
+        let tags = game.tags.iter().map(|(name, value)| (name.as_str(), value.clone())).collect();
+        let result = game.tag("Result").unwrap_or("*");
+        for line in annotated_pgn(tags, &start, &game.moves, result, depth, &mut tt).lines() {
+            send(out, line);
+        }
+    }
 }
 
+// Runs the whole `go` command on the calling thread, up to and including `bestmove`.
+// Returns the search's result, None if there was no search
 pub fn go(
     game: &mut Game,
     tokens: &[&str],
-    stop_flag: &mut Arc<AtomicBool>,
-    search_thread: &mut Option<JoinHandle<()>>,
-) {
-    let params = parse_go_params(tokens);
-    stop_search(stop_flag, search_thread);
+    stop_flag: &Arc<AtomicBool>,
+    tt: &mut TranspositionTable,
+    options: &EngineOptions,
+    out: &Output,
+) -> Option<SearchResult> {
+    let (params, errors) = parse_go_params(tokens);
+    for e in errors {
+        send(out, format!("info string Ignoring go parameter! {:?}", e));
+    }
 
     if let Some(perft_depth) = params.perft {  // non-UCI compliant
-        go_perft(game, perft_depth, stop_flag, search_thread);
-    } else if let Some(movetime) = params.movetime {
-        go_movetime(game, Duration::from_millis(movetime.try_into().unwrap()), stop_flag, search_thread);
-    } else if let Some(depth) = params.depth {
-        go_depth(game, depth, stop_flag, search_thread);
-    } else if params.infinite {
-        go_infinite(game, stop_flag, search_thread);
-    } else if params.wtime.is_some() && params.btime.is_some() {
-        let wtime = params.wtime.unwrap();
-        let btime = params.btime.unwrap();
-        let winc = params.winc.unwrap_or(0);
-        let binc = params.binc.unwrap_or(0);
-        let ms = compute_movetime(game, wtime, btime, winc, binc);
-        println!("info string will search for {} ms", ms);
-        go_movetime(game, Duration::from_millis(ms.try_into().unwrap()), stop_flag, search_thread);
+        go_perft(game, perft_depth, stop_flag, out);
+        return None;
     }
-}
 
-fn go_perft(game: &mut Game, depth: usize, stop_flag: &mut Arc<AtomicBool>, search_thread: &mut Option<JoinHandle<()>>) {
-    let mut game_clone = game.clone();
-    let stop_flag_clone = Arc::clone(stop_flag);
-
-    *search_thread = Some(thread::spawn(move || {
-        let start = Instant::now();
-        let nodes = perft(&mut game_clone, depth, 0, &stop_flag_clone);
-        let duration = start.elapsed();
-        let seconds = duration.as_secs_f64();
-
-        if nodes == PERFT_INTERRUPTED {
-            println!("perft interrupted");
-        } else {
-            println!("Nodes searched: {}", nodes);
-            println!("Time: {:.3} sec", seconds);
-            println!("Nodes per second: {:.2}", nodes as f64 / seconds);
+    // Every limit given counts, the search stops at whichever is hit first. The clock only
+    // counts when there is no `infinite`
+    let mut time_manager = None;
+    if let Some((time, inc)) = clock(&params, game.position.player_to_move, out) {
+        let overhead = options.move_overhead_ms as usize;
+        let tm = TimeManager::new(time, inc, overhead, game_phase(&game.position));
+        if options.debug {
+            send(out, format!(
+                "info string will search for {} ms, at most {} ms",
+                tm.soft_limit().as_millis(),
+                tm.hard_limit().as_millis()
+            ));
         }
-    }));
-}
+        time_manager = Some(tm);
+    }
+    let movetime = params.movetime.map(|ms| Duration::from_millis(ms as u64));
+    let limits = SearchLimits {
+        depth: params.depth,
+        movetime: movetime.into_iter().chain(time_manager.map(|tm| tm.hard_limit())).min(),
+        nodes: params.nodes.map(|n| n as u64),
+        ..SearchLimits::infinite()
+    };
 
-fn print_uci_info(depth: usize, eval: i32, nodes: u64, pv: Vec<Move>, elapsed: Duration) {
-    let score = if eval.abs() > CHECKMATE_EVAL - 1000 {
-        let n_moves = ((CHECKMATE_EVAL - eval.abs()) as f64 / 2.).ceil();
-        let mate_in = if eval > 0 { n_moves } else { -n_moves };
-        format!("mate {}", mate_in)
+    let infinite = limits == SearchLimits::infinite();
+    let limits = SearchLimits {
+        multi_pv: Some(options.multi_pv),
+        nodestime: Some(options.nodestime).filter(|&n| n > 0),
+        ..limits
+    };
+    // Analysis is always at full strength
+    let strength = options.strength().filter(|_| !infinite);
+    let skill = options.skill().filter(|_| !infinite);
+    let limits = match strength {
+        Some(strength) => {
+            if options.debug {
+                send(out, format!(
+                    "info string playing at Elo {}: depth {}, {} nodes, {} cp of noise",
+                    options.elo,
+                    strength.depth,
+                    strength.nodes,
+                    strength.noise_cp
+                ));
+            }
+            strength.cap(limits)
+        }
+        None => limits,
+    };
+    let limits = skill.map_or(limits, |skill| skill.limit(limits));
+    // Analysis is never answered from the book
+    let book_move = match options.own_book && !infinite {
+        true  => pick_book_move(&game.position, &mut rand::rng()),
+        false => None,
+    };
+    let mut search = None;
+    let best_move = if let Some(m) = book_move {
+        send(out, "info string book move");
+        Some(m)
+    } else if has_legal_move(&game.position) {
+        let position = game.position;
+        let report = UciReport { out, multi_pv: options.multi_pv, wdl_of: options.show_wdl.then_some(&position) };
+        let result = iterative_deepening(game, limits, time_manager, Arc::clone(stop_flag), tt, report);
+        if options.debug {
+            for line in debug_lines(&result) {
+                send(out, line);
+            }
+        }
+        let best_move = match (result.best_move, strength, skill) {
+            (Some(best), Some(strength), _) => Some(strength.pick_move(game, best, Arc::clone(stop_flag), tt, &mut rand::rng())),
+            (Some(best), _, Some(skill)) => skill.pick_move(&result.lines, &mut rand::rng()).or(Some(best)),
+            (best, _, _) => best,
+        };
+        search = Some(result);
+        best_move
     } else {
-        format!("cp {}", eval)
+        // Nothing to search, report the final score right away
+        let in_check = is_king_in_check(&game.position, game.position.player_to_move);
+        send(out, format!("info depth 0 score {}", if in_check { "mate 0" } else { "cp 0" }));
+        None
     };
+    if infinite {
+        wait_for_stop(stop_flag);
+    }
+    send(out, best_move_line(best_move));
+    search
+}
 
-    print!(
-        "info depth {} score {} time {} nodes {} nps {} pv ",
-        depth,
-        score,
-        elapsed.as_millis(),
-        nodes,
-        (nodes as f64 / elapsed.as_secs_f64()).round()
-    );
-
-    for m in pv.iter().rev() {
-        print!("{} ", m);
+// Our side's time and increment. With only the other side's clock given, as some GUIs send in
+// odd states, that one stands in for ours: the clocks are rarely far apart
+fn clock(params: &GoParams, player: Player, out: &Output) -> Option<(usize, usize)> {
+    let (ours, theirs) = match player {
+        Player::White => ((params.wtime, params.winc), (params.btime, params.binc)),
+        Player::Black => ((params.btime, params.binc), (params.wtime, params.winc)),
+    };
+    match (params.infinite, ours, theirs) {
+        (true, _, _) => None,
+        (false, (Some(time), inc), _) => Some((time, inc.unwrap_or(0))),
+        (false, (None, _), (Some(time), inc)) => {
+            send(out, "info string No time for the side to move, using the other side's");
+            Some((time, inc.unwrap_or(0)))
+        }
+        (false, (None, _), (None, _)) => None,
     }
-    println!();
 }
 
-fn print_best_move(best_move: Option<Move>) {
-    if let Some(m) = best_move {
-        println!("bestmove {}", m);
+fn go_perft(game: &mut Game, depth: usize, stop_flag: &Arc<AtomicBool>, out: &Output) {
+    let start = Instant::now();
+    let nodes = perft_divide(game, depth, stop_flag, |m, branches| send(out, format!("{} {}", m, branches)));
+    let duration = start.elapsed();
+    let seconds = duration.as_secs_f64();
+
+    if nodes == PERFT_INTERRUPTED {
+        send(out, "perft interrupted");
     } else {
-        println!("bestmove 0000");
+        send(out, format!("Nodes searched: {}", nodes));
+        send(out, format!("Time: {:.3} sec", seconds));
+        send(out, format!("Nodes per second: {:.2}", nodes as f64 / seconds));
     }
 }
 
-fn iterative_deepening(
-    game: &mut Game,
-    stop_flag: Arc<AtomicBool>,
-    max_depth: Option<usize>,
-    time_limit: Option<Duration>,
-) -> Option<Move>
-{
-    let mut last_move = None;
-    let start = Instant::now();
+// Sends the search's progress on to the GUI. `Skill Level` may search more lines than the GUI asked for
+struct UciReport<'a> {
+    out: &'a Output,
+    multi_pv: usize,
+    wdl_of: Option<&'a Position>,  // the root, with `UCI_ShowWDL` on
+}
 
-    for depth in 1.. {
-        if let Some(d) = max_depth {
-            if depth > d {
-                break;
-            }
+impl SearchReport for UciReport<'_> {
+    fn iteration(&mut self, info: &SearchInfo) {
+        if info.multipv <= self.multi_pv {
+            send(self.out, info_line(info, self.wdl_of));
         }
+    }
 
-        let depth_start = Instant::now();
-        let (m, eval, nodes, pv, unwind) = game.find_best_move(
-            depth,
-            &stop_flag,
-            start,
-            time_limit
-        );
-        let elapsed = depth_start.elapsed();
-
-        if unwind {
-            break;
-        }
+    fn root_move(&mut self, current: &CurrMove) {
+        send(self.out, format!(
+            "info depth {} currmove {} currmovenumber {}",
+            current.depth,
+            current.m,
+            current.number
+        ));
+    }
+}
 
-        // Update the best move only if there was NO unwind (the depth was searched fully)
-        last_move = m;
-        print_uci_info(depth, eval, nodes, pv, elapsed);
+// "cp 35", or "mate -2" for getting mated in two moves
+fn uci_score(score: i32) -> String {
+    if score.abs() > CHECKMATE_EVAL - 1000 {
+        let n_moves = ((CHECKMATE_EVAL - score.abs()) as f64 / 2.).ceil();
+        let mate_in = if score > 0 { n_moves } else { -n_moves };
+        format!("mate {}", mate_in)
+    } else {
+        format!("cp {}", score)
+    }
+}
 
-        if let Some(limit) = time_limit {
-            if start.elapsed() >= limit {
-                break;
-            }
-        }
+fn info_line(info: &SearchInfo, wdl_of: Option<&Position>) -> String {
+    let mut score = uci_score(info.score);
+    if let Some(pos) = wdl_of {
+        let wdl = wdl(info.score, pos);
+        score += &format!(" wdl {} {} {}", wdl.win, wdl.draw, wdl.loss);
     }
 
-    last_move
+    let mut line = format!(
+        "info depth {} multipv {} score {} time {} nodes {} nps {} hashfull {} pv",
+        info.depth,
+        info.multipv,
+        score,
+        info.time.as_millis(),
+        info.nodes,
+        (info.nodes as f64 / info.time.as_secs_f64()).round(),
+        info.hashfull
+    );
+
+    for m in &info.pv {
+        line += &format!(" {}", m);
+    }
+    line
 }
 
-fn go_movetime(
-    game: &mut Game,
-    movetime: Duration,
-    stop_flag: &mut Arc<AtomicBool>,
-    search_thread: &mut Option<JoinHandle<()>>,
-) {
-    let mut game_clone = game.clone();
-    let stop_flag_clone = Arc::clone(stop_flag);
-
-    *search_thread = Some(thread::spawn(move || {
-        let best_move = iterative_deepening(&mut game_clone, stop_flag_clone, None, Some(movetime));
-        print_best_move(best_move);
-    }));
+// `debug on`: how the search went, and why it ended. Then where the nodes went, root move by root move
+fn debug_lines(result: &SearchResult) -> Vec<String> {
+    let stats = &result.stats;
+    let percent = |part: u64, whole: u64| if whole == 0 { 0. } else { 100. * part as f64 / whole as f64 };
+    let mut lines = vec![
+        format!(
+            "info string search stopped: {:?} after {} ms, {} nodes",
+            result.stop_reason,
+            result.time.as_millis(),
+            result.nodes
+        ),
+        format!(
+            "info string tt probes {} hits {} ({:.1}%) cutoffs {}",
+            stats.tt_probes,
+            stats.tt_hits,
+            percent(stats.tt_hits, stats.tt_probes),
+            stats.tt_cutoffs
+        ),
+        format!(
+            "info string beta cutoffs {} on the first move {:.1}% skipped root moves {}",
+            stats.beta_cutoffs,
+            percent(stats.first_move_cutoffs, stats.beta_cutoffs),
+            stats.skipped_root_moves
+        ),
+        format!(
+            "info string leaf nodes {} ({:.1}% of all)",
+            stats.leaf_nodes,
+            percent(stats.leaf_nodes, result.nodes)
+        ),
+        // So that nobody goes looking for the numbers of what the search doesn't do
+        "info string no null move pruning, no quiescence search: leaf nodes are where it would start".to_string(),
+        "info string root move      score       nodes   share  fail highs".to_string(),
+    ];
+
+    let mut root_moves = result.root_moves.clone();
+    root_moves.sort_by_key(|r| Reverse(r.nodes));
+    let root_nodes = root_moves.iter().map(|r| r.nodes).sum();
+    for r in root_moves {
+        lines.push(format!(
+            "info string {:<9} {:>9} {:>11} {:>6.1}% {:>11}",
+            r.m.to_string(),
+            r.score.map_or("-".to_string(), uci_score),
+            r.nodes,
+            percent(r.nodes, root_nodes),
+            r.fail_highs
+        ));
+    }
+    lines
 }
 
-fn go_depth(game: &mut Game, depth: usize, stop_flag: &mut Arc<AtomicBool>, search_thread: &mut Option<JoinHandle<()>>) {
-    let mut game_clone = game.clone();
-    let stop_flag_clone = Arc::clone(stop_flag);
+// Non-UCI: `stats`, the `debug on` lines of the last search, whether or not debug was on for it
+pub fn stats(last_search: Option<&SearchResult>, out: &Output) {
+    match last_search {
+        Some(result) => {
+            for line in debug_lines(result) {
+                send(out, line);
+            }
+        }
+        None => send(out, "info string No search to show the stats of"),
+    }
+}
 
-    *search_thread = Some(thread::spawn(move || {
-        let best_move = iterative_deepening(&mut game_clone, stop_flag_clone, Some(depth), None);
-        print_best_move(best_move);
-    }));
+// `debug [on | off]`
+pub fn debug(options: &mut EngineOptions, tokens: &[&str], out: &Output) {
+    match tokens.get(1) {
+        Some(&"on")  => options.debug = true,
+        Some(&"off") => options.debug = false,
+        _ => send(out, format!("info string Bad debug command! {:?}", tokens.get(1..).unwrap_or_default())),
+    }
 }
 
-fn go_infinite(game: &mut Game, stop_flag: &mut Arc<AtomicBool>, search_thread: &mut Option<JoinHandle<()>>) {
-    let mut game_clone = game.clone();
-    let stop_flag_clone = Arc::clone(stop_flag);
+// Without a legal move, "(none)" is what GUIs expect
+fn best_move_line(best_move: Option<Move>) -> String {
+    match best_move {
+        Some(m) => format!("bestmove {}", m),
+        None    => "bestmove (none)".to_string(),
+    }
+}
 
-    *search_thread = Some(thread::spawn(move || {
-        let best_move = iterative_deepening(&mut game_clone, stop_flag_clone, None, None);
-        print_best_move(best_move);
-    }));
+// `go infinite` may not send `bestmove` before `stop`, even if there is nothing more to search
+fn wait_for_stop(stop_flag: &AtomicBool) {
+    while !stop_flag.load(Ordering::Relaxed) {
+        thread::sleep(Duration::from_millis(1));
+    }
 }
@@ -0,0 +1,252 @@
+// `chess-engine <command> ...`: the commands that run once and exit, for scripts.
+// Without a command (or with `uci`) the engine reads UCI from stdin instead
+use std::sync::{atomic::AtomicBool, Arc};
+
+use crate::constants::NAME;
+use crate::core::{
+    book::pick_book_move,
+    game::Game,
+    pgn::{add_opening_tags, write_pgn, PgnMove},
+    search::{iterative_deepening, SearchInfo, SearchLimits},
+    tt::TranspositionTable,
+};
+use super::{analyze_file, bench, config, engine::EngineHandle, eval as eval_trace, go_perft, send, Output};
+
+const SELFPLAY_DEFAULT_GAMES: usize = 1;
+const SELFPLAY_DEFAULT_DEPTH: usize = 6;
+const SELFPLAY_HASH_MB: usize = 16;
+
+const USAGE: [&str; 11] = [
+    "Usage: chess-engine [--json] [--config <file>] [command]",
+    "  --json                     every line of output as a JSON object, `info` and `bestmove` field by field",
+    "  --config <file>            option values to start the UCI loop or `run` with, `Hash = 256` and the like",
+    "  uci                        the UCI loop on stdin (the default)",
+    "  run <file>                 the UCI commands in the file, each search finished before the next line",
+    "  bench [depth]              searches the bench positions, prints nodes and speed",
+    "  perft <depth> [fen]        counts the leaf nodes under every root move",
+    "  eval [fen]                 the static evaluation, term by term",
+    "  analyze <file.pgn> [depth] annotates every game in the file",
+    "  selfplay [games] [depth]   the engine against itself, the games as PGN",
+    "  tui [fen]                  analysis in the terminal (built with the `tui` feature)",
+];
+
+// True if `tokens` name a command that was run. The UCI loop is the caller's, and so is
+// `config_file` for it. The other commands that start an engine take it too, the rest refuse it
+pub fn run(tokens: &[&str], config_file: Option<&str>, out: &Output) -> bool {
+    match tokens.first() {
+        None | Some(&"uci") => return false,
+        Some(&"run")        => run_script(tokens, config_file, out),
+        Some(command) if config_file.is_some() => {
+            send(out, format!("--config only applies to the UCI loop and run, not to {}", command));
+        }
+        Some(&"bench")      => bench(tokens, out),
+        Some(&"perft")      => perft(tokens, out),
+        Some(&"eval")       => eval(tokens, out),
+        Some(&"analyze")    => analyze_file(tokens, out),
+        Some(&"selfplay")   => selfplay(tokens, out),
+        #[cfg(feature = "tui")]
+        Some(&"tui")        => crate::tui::run(&tokens[1..], out),
+        Some(_)             => USAGE.iter().for_each(|line| send(out, *line)),
+    }
+    true
+}
+
+// The FEN is whatever follows, quoted or not. The starting position without one
+pub(crate) fn game_from(fen_tokens: &[&str], out: &Output) -> Option<Game> {
+    if fen_tokens.is_empty() {
+        return Some(Game::default());
+    }
+    let fen = fen_tokens.join(" ");
+    match Game::from_fen(&fen) {
+        Ok(game) => Some(game),
+        Err(e) => {
+            send(out, format!("Bad FEN! {:?}", e));
+            None
+        }
+    }
+}
+
+fn perft(tokens: &[&str], out: &Output) {
+    let Some(Ok(depth)) = tokens.get(1).map(|d| d.parse()) else {
+        send(out, format!("Bad perft depth! {:?}", tokens.get(1)));
+        return;
+    };
+    let Some(mut game) = game_from(&tokens[2..], out) else { return };
+    go_perft(&mut game, depth, &Arc::new(AtomicBool::new(false)), out);
+}
+
+fn eval(tokens: &[&str], out: &Output) {
+    let Some(game) = game_from(&tokens[1..], out) else { return };
+    eval_trace(&game.position, out);
+}
+
+// `run <file>`. Unlike piping the file in, a `go` has sent its `bestmove` before the next line is read,
+// so `go depth 20` followed by `quit` searches to depth 20. Infinite searches are left for `stop` to end
+fn run_script(tokens: &[&str], config_file: Option<&str>, out: &Output) {
+    let Some(path) = tokens.get(1) else {
+        send(out, "Bad run command! No file");
+        return;
+    };
+    let script = match std::fs::read_to_string(path) {
+        Ok(script) => script,
+        Err(e) => {
+            send(out, format!("Can not read {}: {}", path, e));
+            return;
+        }
+    };
+
+    let engine = EngineHandle::spawn(out.clone());
+    if let Some(path) = config_file {
+        apply_config(&engine, path, out);
+    }
+    for line in script.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let waits = tokens.first() == Some(&"go") && !tokens.contains(&"infinite") && !tokens.contains(&"ponder");
+        if !engine.execute(line.to_string()) {
+            break;
+        }
+        if waits {
+            engine.wait_for_search();
+        }
+    }
+    engine.quit();
+}
+
+// As if the GUI had sent the `setoption`s, before anything it does send
+pub fn apply_config(engine: &EngineHandle, path: &str, out: &Output) {
+    match config::load(path) {
+        Ok(setoptions) => {
+            for line in setoptions {
+                engine.execute(line);
+            }
+        }
+        Err(e) => send(out, format!("info string Bad config file! {:?}", e)),
+    }
+}
+
+// `selfplay [games] [depth]`. Book moves while there are some, picked at random so the games differ
+fn selfplay(tokens: &[&str], out: &Output) {
+    let parse = |i: usize, default: usize| match tokens.get(i).map(|n| n.parse()) {
+        Some(Ok(n)) => Some(n),
+        Some(Err(_)) => {
+            send(out, format!("Bad selfplay parameter! {:?}", tokens[i]));
+            None
+        }
+        None => Some(default),
+    };
+    let (Some(games), Some(depth)) = (parse(1, SELFPLAY_DEFAULT_GAMES), parse(2, SELFPLAY_DEFAULT_DEPTH)) else {
+        return;
+    };
+
+    let mut tt = TranspositionTable::new(SELFPLAY_HASH_MB);
+    for round in 1..=games {
+        tt.clear();
+        let start = Game::default();
+        let mut game = start.clone();
+        while !game.outcome().is_over() {
+            let m = match pick_book_move(&game.position, &mut rand::rng()) {
+                Some(m) => m,
+                None => {
+                    let stop_flag = Arc::new(AtomicBool::new(false));
+                    let result = iterative_deepening(&mut game, SearchLimits::depth(depth), None, stop_flag, &mut tt, |_: &SearchInfo| {});
+                    result.best_move.unwrap()  // the game is not over
+                }
+            };
+            game.try_to_make_move(&m);
+        }
+
+        let result = game.outcome().result();
+        let mut tags = vec![
+            ("Event", format!("{} selfplay, depth {}", NAME, depth)),
+            ("Round", round.to_string()),
+            ("White", NAME.to_string()),
+            ("Black", NAME.to_string()),
+            ("Result", result.to_string()),
+        ];
+        add_opening_tags(&mut tags, &game);
+        let moves: Vec<PgnMove> = game.moves_played().into_iter().map(PgnMove::from).collect();
+        for line in write_pgn(&tags, &start, &moves, result).lines() {
+            send(out, line);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{self, Receiver};
+
+    fn run_command(command: &str) -> (bool, Vec<String>) {
+        let (out, lines): (Output, Receiver<String>) = mpsc::channel();
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        let ran = run(&tokens, None, &out);
+        drop(out);
+        (ran, lines.iter().collect())
+    }
+
+    #[test]
+    fn commands() {
+        assert!(!run_command("").0);
+        assert!(!run_command("uci").0);
+
+        let (ran, lines) = run_command("perft 2");
+        assert!(ran);
+        assert!(lines.contains(&"Nodes searched: 400".to_string()));
+        let (_, lines) = run_command("perft 1 rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1");
+        assert!(lines.contains(&"Nodes searched: 20".to_string()));
+
+        let (_, lines) = run_command("eval 4k3/8/8/8/8/8/8/4K2Q w - - 0 1");
+        assert!(lines.iter().any(|l| l.contains("Total")));
+        assert!(run_command("eval 4k3/8").1[0].starts_with("Bad FEN!"));
+
+        assert_eq!(run_command("help").1, USAGE);
+    }
+
+    #[test]
+    fn selfplay_finishes_games() {
+        let (_, lines) = run_command("selfplay 1 1");
+        assert!(lines.contains(&"[Round \"1\"]".to_string()));
+        let result = lines.iter().find(|l| l.starts_with("[Result")).unwrap();
+        assert!(["[Result \"1-0\"]", "[Result \"0-1\"]", "[Result \"1/2-1/2\"]"].contains(&result.as_str()));
+    }
+
+    #[test]
+    fn run_script_waits_for_go() {
+        let path = std::env::temp_dir().join(format!("chess-engine-script-{}.txt", std::process::id()));
+        std::fs::write(&path, "setoption name OwnBook value false\nposition startpos\ngo depth 4\nquit\nd\n").unwrap();
+        let (_, lines) = run_command(&format!("run {}", path.display()));
+        std::fs::remove_file(&path).unwrap();
+        assert!(lines.iter().any(|l| l.starts_with("info depth 4")));
+        assert!(lines.last().unwrap().starts_with("bestmove"));
+    }
+
+    // `command` with `--config` and a file holding `config`
+    fn run_with_config(command: &str, config: &str) -> Vec<String> {
+        let path = std::env::temp_dir().join(format!("chess-engine-config-{}-{}.toml", std::process::id(), command.len()));
+        std::fs::write(&path, config).unwrap();
+        let (out, lines): (Output, Receiver<String>) = mpsc::channel();
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        run(&tokens, path.to_str(), &out);
+        drop(out);
+        std::fs::remove_file(&path).unwrap();
+        lines.iter().collect()
+    }
+
+    #[test]
+    fn config_for_run() {
+        let script = std::env::temp_dir().join(format!("chess-engine-config-script-{}.txt", std::process::id()));
+        std::fs::write(&script, "position startpos\ngo depth 2\nquit\n").unwrap();
+        let run_script = format!("run {}", script.display());
+
+        let lines = run_with_config(&run_script, "OwnBook = false\nMultiPV = 2\n");
+        assert!(lines.iter().any(|l| l.starts_with("info depth 2 multipv 2")));
+        let lines = run_with_config(&run_script, "Nope = 1\n");
+        assert_eq!(lines[0], "info string Failed to set option! UnknownOption(\"Nope\")");
+        let lines = run_with_config(&run_script, "Hash = lots\n");
+        assert_eq!(lines[0], "info string Bad config file! BadLine(1, \"Hash = lots\")");
+        std::fs::remove_file(&script).unwrap();
+
+        assert_eq!(run_with_config("bench 1", "Hash = 16\n"), ["--config only applies to the UCI loop and run, not to bench"]);
+    }
+}
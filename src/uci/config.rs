@@ -0,0 +1,99 @@
+// `chess-engine --config chessica.toml`: option values to start the UCI loop with, so they don't
+// have to be set in every GUI. The file is the part of TOML that is needed for that, one option
+// per line, names with spaces in quotes (without escapes):
+//
+//     # Lots of memory on this machine
+//     Hash = 256
+//     "Move Overhead" = 50
+//     OwnBook = false
+//     HashFile = "/data/analysis.tt"
+use std::fs;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Unreadable(String),
+    BadLine(usize, String),  // numbered from 1
+}
+
+// The `setoption` commands the file at `path` stands for
+pub fn load(path: &str) -> Result<Vec<String>, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|e| ConfigError::Unreadable(format!("{}: {}", path, e)))?;
+    setoption_lines(&text)
+}
+
+// One per option, in the order they are in the file. Whether the options exist and the values
+// fit them is up to `setoption`
+pub fn setoption_lines(text: &str) -> Result<Vec<String>, ConfigError> {
+    let mut lines = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let bad_line = || ConfigError::BadLine(i + 1, line.to_string());
+        let (name, value) = line.split_once('=').ok_or_else(bad_line)?;
+        let name = unquote(name.trim()).ok_or_else(bad_line)?;
+        let value = value_of(value.trim()).ok_or_else(bad_line)?;
+        if name.is_empty() {
+            return Err(bad_line());
+        }
+        lines.push(format!("setoption name {} value {}", name, value));
+    }
+    Ok(lines)
+}
+
+// A quoted string without its quotes, a bare key as it is
+fn unquote(token: &str) -> Option<String> {
+    match token.strip_prefix('"') {
+        Some(quoted) => quoted.strip_suffix('"').map(str::to_string),
+        None if token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') => Some(token.to_string()),
+        None => None,
+    }
+}
+
+// Strings, integers and booleans, a comment after them is allowed
+fn value_of(token: &str) -> Option<String> {
+    if let Some(quoted) = token.strip_prefix('"') {
+        let (string, rest) = quoted.split_once('"')?;
+        let rest = rest.trim();
+        return (rest.is_empty() || rest.starts_with('#')).then(|| string.to_string());
+    }
+    let value = token.split('#').next().unwrap_or_default().trim();
+    match value {
+        "true" | "false" => Some(value.to_string()),
+        _ => value.parse::<i64>().ok().map(|n| n.to_string()),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options() {
+        let text = "\
+            # Lots of memory\n\
+            Hash = 256\n\
+            \n\
+            \"Move Overhead\" = 50  # for the slow GUI\n\
+            OwnBook = false\n\
+            HashFile = \"/data/long analysis.tt\"\n";
+        assert_eq!(setoption_lines(text), Ok(vec![
+            "setoption name Hash value 256".to_string(),
+            "setoption name Move Overhead value 50".to_string(),
+            "setoption name OwnBook value false".to_string(),
+            "setoption name HashFile value /data/long analysis.tt".to_string(),
+        ]));
+    }
+
+    #[test]
+    fn bad_lines() {
+        assert_eq!(setoption_lines("Hash 256"), Err(ConfigError::BadLine(1, "Hash 256".into())));
+        assert_eq!(setoption_lines("Hash = 1\nMove Overhead = 50"), Err(ConfigError::BadLine(2, "Move Overhead = 50".into())));
+        assert_eq!(setoption_lines("Hash = lots"), Err(ConfigError::BadLine(1, "Hash = lots".into())));
+        assert_eq!(setoption_lines("HashFile = \"a.tt"), Err(ConfigError::BadLine(1, "HashFile = \"a.tt".into())));
+        assert_eq!(setoption_lines("[engine]"), Err(ConfigError::BadLine(1, "[engine]".into())));
+        assert!(matches!(load("/nonexistent/chessica.toml"), Err(ConfigError::Unreadable(_))));
+    }
+}
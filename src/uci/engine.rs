@@ -0,0 +1,687 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc::{self, Receiver, Sender}, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::core::{game::Game, search::SearchResult, tt::TranspositionTable};
+#[cfg(feature = "tune")]
+use crate::core::position::Material;
+use super::{analyze, bench, debug, display, eval, go, mate, moves, position, send, stats, uci, Output};
+use super::log::DebugLog;
+use super::options::{parse_setoption, EngineOptions, CLEAR_HASH, DEBUG_LOG_FILE, HASH, LOAD_HASH, SAVE_HASH};
+
+// Everything the engine thread is asked to do, one command line each
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    NewGame,
+    Position(String),
+    SetOption(String),
+    Go(String),
+    IsReady,
+    Display(String),
+    Bench(String),
+    Debug(String),
+    Eval,
+    Mate(String),
+    Analyze(String),
+    Stats,
+    Moves,
+    Quit,
+}
+
+// The engine thread's state, kept from one command to the next
+struct Engine {
+    game: Game,
+    options: EngineOptions,
+    tt: TranspositionTable,
+    stop_flag: Arc<AtomicBool>,
+    busy: Arc<AtomicBool>,
+    setting_up: Arc<AtomicUsize>,
+    last_search: Option<SearchResult>,  // for `stats`
+    log: DebugLog,
+    out: Output,
+}
+
+// The other end of the engine thread. Commands are queued and handled in order,
+// `stop` is the exception: the engine can't read the queue while it is searching
+pub struct EngineHandle {
+    commands: Sender<Command>,
+    stop_flag: Arc<AtomicBool>,
+    busy: Arc<AtomicBool>,  // from the moment `go` or `mate` is sent until it is done
+    setting_up: Arc<AtomicUsize>,  // the table allocation, then every `is_setup` command sent and not done with yet
+    log: DebugLog,
+    out: Output,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl EngineHandle {
+    pub fn spawn(out: Output) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let options = EngineOptions::default();
+        let log = DebugLog::default();
+        let out = log.tee(out);
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let busy = Arc::new(AtomicBool::new(false));
+        // The table is allocated on the engine thread, `isready` waits for it
+        let setting_up = Arc::new(AtomicUsize::new(1));
+        let engine = {
+            let (stop_flag, busy, setting_up, log, out) = (stop_flag.clone(), busy.clone(), setting_up.clone(), log.clone(), out.clone());
+            move || Engine {
+                game: Game::default(),
+                // Allocating and zeroing the table takes a while for a big one, better not on the GUI's time
+                tt: TranspositionTable::new(options.hash_mb),
+                options,
+                stop_flag,
+                busy,
+                setting_up,
+                last_search: None,
+                log,
+                out,
+            }
+        };
+
+        EngineHandle {
+            commands,
+            stop_flag,
+            busy,
+            setting_up,
+            log,
+            out,
+            thread: Some(thread::spawn(move || engine().run(receiver))),
+        }
+    }
+
+    // One line of UCI input. False on `quit`
+    pub fn execute(&self, line: String) -> bool {
+        self.log.input(&line);
+        let Some(&command) = tokens(&line).first() else { return true };
+        match command {
+            "uci"        => uci(&self.out),
+            "isready"    => self.isready(),
+            "setoption"  => self.send(Command::SetOption(line)),
+            "ucinewgame" => self.send(Command::NewGame),
+            "position"   => self.send(Command::Position(line)),
+            "go"         => self.send(Command::Go(line)),
+            "stop"       => self.stop(),
+            "quit"       => return false,
+            "d"          => self.send(Command::Display(line)),
+            "eval"       => self.send(Command::Eval),
+            "bench"      => self.send(Command::Bench(line)),
+            "debug"      => self.send(Command::Debug(line)),
+            "mate"       => self.send(Command::Mate(line)),
+            "analyze"    => self.send(Command::Analyze(line)),
+            "stats"      => self.send(Command::Stats),
+            "moves"      => self.send(Command::Moves),
+            _            => send(&self.out, format!("info string Unknown command! {:?}", command)),
+        }
+        true
+    }
+
+    pub fn send(&self, command: Command) {
+        if let Command::Go(_) | Command::Mate(_) = command {
+            // A new search replaces the running one, which still gets to send its `bestmove`
+            self.stop();
+            self.wait_for_search();
+            self.stop_flag.store(false, Ordering::Relaxed);
+            self.busy.store(true, Ordering::Relaxed);
+        }
+        if is_setup(&command) {
+            self.setting_up.fetch_add(1, Ordering::Relaxed);
+        }
+        let _ = self.commands.send(command);
+    }
+
+    // Ends the running search, if there is one. Its `bestmove` follows shortly
+    pub fn stop(&self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::Relaxed)
+    }
+
+    // Until the running search, if there is one, has sent its `bestmove`
+    pub fn wait_for_search(&self) {
+        while self.is_busy() {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    // Answered right away during a search, the engine is alive if it is searching. Otherwise it
+    // only waits if the table is still being allocated or loaded, the other commands are quick
+    pub fn isready(&self) {
+        if self.is_busy() || self.setting_up.load(Ordering::Relaxed) == 0 {
+            send(&self.out, "readyok");
+        } else {
+            self.send(Command::IsReady);
+        }
+    }
+
+    // Stops the search, and returns once the engine thread is done
+    pub fn quit(mut self) {
+        self.stop();
+        self.send(Command::Quit);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Engine {
+    fn run(mut self, commands: Receiver<Command>) {
+        self.setting_up.fetch_sub(1, Ordering::Relaxed);  // the table is allocated
+        for command in commands {
+            let setup = is_setup(&command);
+            match command {
+                Command::NewGame         => self.new_game(),
+                Command::Position(line)  => position(&mut self.game, &tokens(&line), &self.out),
+                Command::SetOption(line) => self.set_option(&tokens(&line)),
+                Command::Go(line)        => self.go(&tokens(&line)),
+                Command::IsReady         => send(&self.out, "readyok"),
+                Command::Display(line)   => display(&self.game, &tokens(&line), &self.out),
+                Command::Eval            => eval(&self.game.position, &self.out),
+                Command::Bench(line)     => bench(&tokens(&line), &self.out),
+                Command::Debug(line)     => debug(&mut self.options, &tokens(&line), &self.out),
+                Command::Mate(line)      => self.mate(&tokens(&line)),
+                Command::Analyze(line)   => analyze(&self.game, &tokens(&line), &mut self.tt, &self.out),
+                Command::Stats           => stats(self.last_search.as_ref(), &self.out),
+                Command::Moves           => moves(&self.game, &self.out),
+                Command::Quit            => break,
+            }
+            if setup {
+                self.setting_up.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn go(&mut self, tokens: &[&str]) {
+        if let Some(result) = go(&mut self.game, tokens, &self.stop_flag, &mut self.tt, &self.options, &self.out) {
+            self.last_search = Some(result);
+        }
+        self.busy.store(false, Ordering::Relaxed);
+    }
+
+    fn mate(&mut self, tokens: &[&str]) {
+        mate(&mut self.game, tokens, &self.stop_flag, &self.out);
+        self.busy.store(false, Ordering::Relaxed);
+    }
+
+    fn set_option(&mut self, tokens: &[&str]) {
+        match self.options.set(tokens) {
+            Ok(option) if option.name == HASH => self.tt.resize(self.options.hash_mb),
+            Ok(option) if option.name == CLEAR_HASH => self.clear_hash(),
+            Ok(option) if option.name == SAVE_HASH => self.save_hash(),
+            Ok(option) if option.name == LOAD_HASH => self.load_hash(),
+            Ok(option) if option.name == DEBUG_LOG_FILE => self.open_log(),
+            #[cfg(feature = "tune")]
+            Ok(option) if crate::core::evaluate::tune::find(option.name).is_some() => self.retune(),
+            Ok(_) => {}
+            Err(e) => send(&self.out, format!("info string Failed to set option! {:?}", e)),
+        }
+    }
+
+    fn open_log(&self) {
+        let path = &self.options.debug_log_file;
+        if let Err(e) = self.log.open(path) {
+            send(&self.out, format!("info string Can not open the log file {}: {}", path, e));
+        }
+    }
+
+    // Nothing learned in the previous game is carried over into the next one
+    fn new_game(&mut self) {
+        self.game = Game::default();
+        self.clear_hash();
+    }
+
+    fn clear_hash(&mut self) {
+        self.tt.clear();
+        self.game.eval_tables.clear();
+    }
+
+    // The material score of the current position and the cached evals are out of date. See `tune.rs`
+    #[cfg(feature = "tune")]
+    fn retune(&mut self) {
+        self.game.position.material = Material::of(&self.game.position);
+        self.clear_hash();
+    }
+
+    fn save_hash(&self) {
+        let path = &self.options.hash_file;
+        let saved = File::create(path).and_then(|file| self.tt.save(&mut BufWriter::new(file)));
+        match saved {
+            Ok(()) => send(&self.out, format!("info string Saved the hash to {}", path)),
+            Err(e) => send(&self.out, format!("info string Can not save the hash to {}: {}", path, e)),
+        }
+    }
+
+    // The loaded table keeps the size it was saved with, until `Hash` is set again
+    fn load_hash(&mut self) {
+        let path = &self.options.hash_file;
+        match File::open(path).and_then(|file| TranspositionTable::load(&mut BufReader::new(file))) {
+            Ok(tt) => {
+                self.tt = tt;
+                send(&self.out, format!("info string Loaded the hash from {}", path));
+            }
+            Err(e) => send(&self.out, format!("info string Can not load the hash from {}: {}", path, e)),
+        }
+    }
+}
+
+fn tokens(line: &str) -> Vec<&str> {
+    line.split_whitespace().collect()
+}
+
+// The commands that take a while for a big table: `setoption name Hash` and `Load Hash from File`
+fn is_setup(command: &Command) -> bool {
+    let Command::SetOption(line) = command else { return false };
+    parse_setoption(&tokens(line)).is_ok_and(|(option, _)| [HASH, LOAD_HASH].contains(&option.name))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::RecvTimeoutError;
+    use crate::uci::writer::spawn_writer;
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    fn engine() -> (EngineHandle, Receiver<String>) {
+        let (captured, lines) = mpsc::channel();
+        let (out, _) = spawn_writer(captured);
+        (EngineHandle::spawn(out), lines)
+    }
+
+    // Every line up to and including the first one starting with `prefix`
+    fn read_until(lines: &Receiver<String>, prefix: &str) -> Vec<String> {
+        let mut read = Vec::new();
+        loop {
+            let line = lines.recv_timeout(TIMEOUT).expect("the engine went silent");
+            let done = line.starts_with(prefix);
+            read.push(line);
+            if done {
+                return read;
+            }
+        }
+    }
+
+    fn assert_silent(lines: &Receiver<String>) {
+        assert_eq!(lines.recv_timeout(Duration::from_millis(50)), Err(RecvTimeoutError::Timeout));
+    }
+
+    #[test]
+    fn go_depth() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::Position("position startpos moves e2e4".into()));
+        engine.send(Command::Go("go depth 3".into()));
+        let read = read_until(&lines, "bestmove");
+        assert_eq!(read.iter().filter(|l| l.starts_with("info depth")).count(), 3);
+        assert_silent(&lines);
+        engine.quit();
+    }
+
+    #[test]
+    fn go_combined_limits() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::Debug("debug on".into()));
+        engine.send(Command::Go("go depth 2 movetime 60000 nodes 100000000".into()));
+        let read = read_until(&lines, "bestmove");
+        assert!(read.iter().any(|l| l.starts_with("info string search stopped: Depth")));
+
+        engine.send(Command::Go("go depth 30 movetime 60000 nodes 3000".into()));
+        let read = read_until(&lines, "bestmove");
+        assert!(read.iter().any(|l| l.starts_with("info string search stopped: Nodes after") && l.ends_with(" 3000 nodes")));
+
+        engine.send(Command::Go("go wtime 60000 btime 60000 depth 30 movetime 50".into()));
+        let read = read_until(&lines, "bestmove");
+        assert!(read.iter().any(|l| l.starts_with("info string search stopped: Movetime")));
+        engine.quit();
+    }
+
+    #[test]
+    fn go_one_sided_clock() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::Go("go wtime 500".into()));
+        assert!(!read_until(&lines, "bestmove").iter().any(|l| l.starts_with("info string")));
+
+        // Black's clock for White's move
+        engine.send(Command::Go("go btime 500 binc 10".into()));
+        let read = read_until(&lines, "bestmove");
+        assert_eq!(read[0], "info string No time for the side to move, using the other side's");
+        engine.quit();
+    }
+
+    #[test]
+    fn book_move() {
+        let (engine, lines) = engine();
+        engine.send(Command::Position("position startpos moves e2e4".into()));
+        engine.send(Command::Go("go depth 3".into()));
+        let read = read_until(&lines, "bestmove");
+        assert_eq!(read[0], "info string book move");
+        assert_eq!(read.len(), 2);
+
+        // Analysis searches regardless
+        engine.send(Command::Go("go infinite".into()));
+        assert!(read_until(&lines, "info")[0].starts_with("info depth"));
+        engine.stop();
+        read_until(&lines, "bestmove");
+        engine.quit();
+    }
+
+    #[test]
+    fn multi_pv_and_skill_level() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::SetOption("setoption name MultiPV value 3".into()));
+        engine.send(Command::Go("go depth 2".into()));
+        let read = read_until(&lines, "bestmove");
+        let infos: Vec<&String> = read.iter().filter(|l| l.starts_with("info depth 2")).collect();
+        assert_eq!(infos.len(), 3);
+        assert!(infos[2].starts_with("info depth 2 multipv 3 score"));
+
+        // Searches more lines than it shows
+        engine.send(Command::SetOption("setoption name MultiPV value 1".into()));
+        engine.send(Command::SetOption("setoption name Skill Level value 0".into()));
+        engine.send(Command::Go("go depth 5".into()));
+        let read = read_until(&lines, "bestmove");
+        assert_eq!(read.iter().filter(|l| l.starts_with("info depth")).count(), 1);
+        assert!(read[0].starts_with("info depth 1 multipv 1 score"));
+        engine.quit();
+    }
+
+    #[test]
+    fn show_wdl() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::Go("go depth 1".into()));
+        assert!(!read_until(&lines, "bestmove")[0].contains(" wdl "));
+
+        engine.send(Command::SetOption("setoption name UCI_ShowWDL value true".into()));
+        engine.send(Command::Go("go depth 1".into()));
+        let info = read_until(&lines, "bestmove")[0].clone();
+        let tokens: Vec<&str> = info.split_whitespace().collect();
+        let at = tokens.iter().position(|&t| t == "wdl").unwrap();
+        assert_eq!(tokens[at - 3], "score");
+        let wdl: Vec<u32> = tokens[at + 1..at + 4].iter().map(|t| t.parse().unwrap()).collect();
+        assert_eq!(wdl.iter().sum::<u32>(), 1000);
+        engine.quit();
+    }
+
+    #[test]
+    fn limit_strength() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::SetOption("setoption name UCI_LimitStrength value true".into()));
+        engine.send(Command::SetOption("setoption name UCI_Elo value 600".into()));
+        engine.send(Command::Position("position startpos moves e2e4".into()));
+        engine.send(Command::Go("go depth 5".into()));
+        let read = read_until(&lines, "bestmove");
+        assert_eq!(read.iter().filter(|l| l.starts_with("info depth")).count(), 1);
+        let mut game = Game::default();
+        game.try_to_make_uci_move("e2e4");
+        assert!(game.is_legal_uci(read.last().unwrap().split_whitespace().nth(1).unwrap()));
+        engine.quit();
+    }
+
+    #[test]
+    fn infinite_waits_for_stop() {
+        let (engine, lines) = engine();
+        // Stalemate, nothing to search at all
+        engine.send(Command::Position("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1".into()));
+        engine.send(Command::Go("go infinite".into()));
+        assert_eq!(read_until(&lines, "info"), ["info depth 0 score cp 0"]);
+        assert_silent(&lines);
+
+        // Still searching, still alive
+        engine.isready();
+        assert_eq!(read_until(&lines, "readyok"), ["readyok"]);
+
+        engine.stop();
+        assert_eq!(read_until(&lines, "bestmove"), ["bestmove (none)"]);
+        // Nothing to stop anymore
+        engine.stop();
+        assert_silent(&lines);
+        engine.quit();
+    }
+
+    #[test]
+    fn go_replaces_running_search() {
+        let (engine, lines) = engine();
+        engine.send(Command::Go("go infinite".into()));
+        engine.send(Command::Go("go depth 1".into()));
+        let read = read_until(&lines, "bestmove");
+        let read = [read, read_until(&lines, "bestmove")].concat();
+        assert_eq!(read.iter().filter(|l| l.starts_with("bestmove")).count(), 2);
+        engine.quit();
+    }
+
+    #[test]
+    fn isready_after_setoption() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name Hash value 64".into()));
+        engine.isready();
+        assert_eq!(read_until(&lines, "readyok"), ["readyok"]);
+        engine.send(Command::SetOption("setoption name Hash value 0".into()));
+        assert!(read_until(&lines, "info string")[0].contains("OutOfRange"));
+        engine.quit();
+    }
+
+    #[test]
+    fn isready_behind_search() {
+        let (engine, lines) = engine();
+        // Stalemate, the search is quiet until it is stopped
+        engine.send(Command::Position("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1".into()));
+        engine.send(Command::Go("go infinite".into()));
+        engine.send(Command::SetOption("setoption name Hash value 64".into()));
+        engine.send(Command::Position("position startpos".into()));
+        read_until(&lines, "info depth 0");
+        // Right away, whatever is queued behind the search
+        engine.isready();
+        assert_eq!(read_until(&lines, "readyok"), ["readyok"]);
+        engine.stop();
+        assert_eq!(read_until(&lines, "bestmove"), ["bestmove (none)"]);
+        engine.isready();
+        assert_eq!(read_until(&lines, "readyok"), ["readyok"]);
+        engine.quit();
+    }
+
+    #[test]
+    fn bad_moves_are_reported() {
+        let (engine, lines) = engine();
+        engine.send(Command::Position("position startpos moves e2e4 e7e5 x9y9 g1f3".into()));
+        assert!(read_until(&lines, "info string")[0].contains("BadMove(2, InvalidSyntax(\"x9y9\"))"));
+        engine.send(Command::Position("position startpos moves e2e4 e2e4".into()));
+        assert!(read_until(&lines, "info string")[0].contains("BadMove(1, IllegalMove(\"e2e4\"))"));
+
+        // The moves up to the bad one are played
+        engine.send(Command::Display("d".into()));
+        assert!(read_until(&lines, "")[0].contains("Player to move: Black"));
+        engine.quit();
+    }
+
+    #[test]
+    fn display_settings() {
+        let (engine, lines) = engine();
+        engine.send(Command::Display("d unicode flip".into()));
+        let board = read_until(&lines, "")[0].clone();
+        assert!(board.starts_with("1 ♖ ♘ ♗ ♔ ♕ ♗ ♘ ♖"));
+        assert!(board.contains("  h g f e d c b a"));
+        engine.send(Command::Display("d sideways".into()));
+        assert_eq!(read_until(&lines, "info string"), ["info string Bad d command! \"sideways\""]);
+        engine.quit();
+    }
+
+    #[test]
+    fn moves_command() {
+        let (engine, lines) = engine();
+        engine.send(Command::Position("position fen 4k3/8/8/8/8/8/4P3/R3K3 w Q - 0 1".into()));
+        engine.send(Command::Moves);
+        let listed = read_until(&lines, "King");
+        assert_eq!(listed[0], "Legal moves: 17");
+        assert_eq!(listed[1], "Pawn: e2e3 (e3), e2e4 (e4)");
+        assert!(listed[2].starts_with("Rook: a1a2 (Ra2), "));
+        assert!(listed[3].contains("e1c1 (O-O-O)"));
+
+        engine.send(Command::Position("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1".into()));
+        engine.send(Command::Moves);
+        assert_eq!(read_until(&lines, "Legal moves"), ["Legal moves: 0"]);
+        engine.quit();
+    }
+
+    #[test]
+    fn null_move_in_position() {
+        let (engine, lines) = engine();
+        engine.send(Command::Position("position startpos moves e2e4 0000".into()));
+        engine.send(Command::Display("d".into()));
+        assert!(read_until(&lines, "")[0].contains("Player to move: White"));
+
+        // Not out of check, and the rest of the line is not played
+        engine.send(Command::Position("position fen 4k3/8/8/8/8/8/8/4K2r w - - 0 1 moves 0000 e1d2".into()));
+        assert!(read_until(&lines, "info string")[0].contains("BadMove(0, IllegalMove(\"0000\"))"));
+        engine.quit();
+    }
+
+    #[test]
+    fn debug_diagnostics() {
+        let (engine, lines) = engine();
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::Go("go wtime 1000 btime 1000".into()));
+        assert!(!read_until(&lines, "bestmove").iter().any(|l| l.starts_with("info string")));
+
+        engine.send(Command::Debug("debug on".into()));
+        engine.send(Command::Go("go wtime 1000 btime 1000".into()));
+        let read = read_until(&lines, "bestmove");
+        assert!(read[0].starts_with("info string will search for"));
+        assert!(read.iter().any(|l| l.starts_with("info string search stopped")));
+        assert!(read.iter().any(|l| l.starts_with("info string tt probes")));
+        let table = read.iter().position(|l| l.starts_with("info string root move")).unwrap();
+        // All twenty moves, the most searched first
+        assert_eq!(read.len() - table - 2, 20);
+
+        engine.send(Command::Debug("debug sometimes".into()));
+        assert!(read_until(&lines, "info string")[0].contains("Bad debug command"));
+        engine.quit();
+    }
+
+    #[test]
+    fn stats_command() {
+        let (engine, lines) = engine();
+        engine.execute("stats".into());
+        assert_eq!(read_until(&lines, "info string")[0], "info string No search to show the stats of");
+
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::Go("go depth 3".into()));
+        assert!(!read_until(&lines, "bestmove").iter().any(|l| l.starts_with("info string")));
+        engine.execute("stats".into());
+        let read = read_until(&lines, "info string root move");
+        assert!(read[0].starts_with("info string search stopped: Depth"));
+        assert!(read.iter().any(|l| l.starts_with("info string leaf nodes")));
+        assert!(read.iter().any(|l| l.starts_with("info string no null move pruning, no quiescence search")));
+        engine.quit();
+    }
+
+    #[test]
+    fn mate_command() {
+        let (engine, lines) = engine();
+        engine.send(Command::Position("position fen kbK5/pp6/1P6/8/8/8/8/R7 w - - 0 1".into()));
+        engine.send(Command::Mate("mate 1".into()));
+        assert_eq!(read_until(&lines, "Time")[0], "No mate in 1");
+        engine.send(Command::Mate("mate 2".into()));
+        assert_eq!(read_until(&lines, "Time")[0], "Mate in 2: 1. Ra6 bxa6 2. b7#");
+        engine.send(Command::Mate("mate 0".into()));
+        assert!(read_until(&lines, "info string")[0].contains("Bad mate command"));
+        engine.quit();
+    }
+
+    #[test]
+    fn mate_command_stops() {
+        let (engine, lines) = engine();
+        // Far too long to wait for
+        engine.send(Command::Mate("mate 6".into()));
+        engine.isready();
+        assert_eq!(read_until(&lines, "readyok"), ["readyok"]);
+        engine.stop();
+        assert_eq!(read_until(&lines, "Time")[0], "Stopped before trying every move, mate in 6 not ruled out");
+        engine.quit();
+    }
+
+    #[test]
+    fn analyze_command() {
+        let (engine, lines) = engine();
+        engine.send(Command::Position("position fen 4k3/8/8/3pp3/8/8/8/3QK3 w - - 0 1 moves d1d4 e5d4".into()));
+        engine.send(Command::Analyze("analyze 3".into()));
+        let read = read_until(&lines, "1.");
+        assert!(read.contains(&"[FEN \"4k3/8/8/3pp3/8/8/8/3QK3 w - - 0 1\"]".to_string()));
+        assert!(read.contains(&"[Annotator \"chess-engine depth 3\"]".to_string()));
+        assert!(read.last().unwrap().starts_with("1. Qd4?? {Blunder ("));
+        engine.send(Command::Analyze("analyze deep".into()));
+        assert!(read_until(&lines, "info string").last().unwrap().contains("Bad analyze depth"));
+        engine.quit();
+    }
+
+    #[test]
+    fn save_and_load_hash() {
+        let path = std::env::temp_dir().join(format!("chess-engine-hash-{}.bin", std::process::id()));
+        let (engine, lines) = engine();
+        let nodes = |read: Vec<String>| -> u64 {
+            let info = read.iter().rfind(|l| l.starts_with("info depth")).unwrap();
+            let tokens: Vec<&str> = info.split_whitespace().collect();
+            let at = tokens.iter().position(|&t| t == "nodes").unwrap();
+            tokens[at + 1].parse().unwrap()
+        };
+        engine.send(Command::SetOption("setoption name OwnBook value false".into()));
+        engine.send(Command::SetOption(format!("setoption name HashFile value {}", path.display())));
+        engine.send(Command::Go("go depth 5".into()));
+        let fresh = nodes(read_until(&lines, "bestmove"));
+
+        engine.send(Command::SetOption("setoption name Save Hash to File".into()));
+        assert!(read_until(&lines, "info string")[0].starts_with("info string Saved"));
+        engine.send(Command::NewGame);
+        engine.send(Command::SetOption("setoption name Load Hash from File".into()));
+        assert!(read_until(&lines, "info string")[0].starts_with("info string Loaded"));
+        engine.send(Command::Go("go depth 5".into()));
+        assert!(nodes(read_until(&lines, "bestmove")) < fresh);
+
+        std::fs::remove_file(&path).unwrap();
+        engine.send(Command::SetOption("setoption name Load Hash from File".into()));
+        assert!(read_until(&lines, "info string")[0].starts_with("info string Can not load"));
+        engine.quit();
+    }
+
+    #[test]
+    fn debug_log_file() {
+        let path = std::env::temp_dir().join(format!("chess-engine-log-{}.txt", std::process::id()));
+        let (engine, lines) = engine();
+        engine.execute(format!("setoption name Debug Log File value {}", path.display()));
+        // Queued behind it, so the log is open by the time this is answered
+        engine.send(Command::SetOption("setoption name Nope".into()));
+        read_until(&lines, "info string");
+        engine.execute("position startpos moves e2e4 e7e5 x9y9".into());
+        read_until(&lines, "info string");
+        engine.execute("setoption name Debug Log File value".into());
+        engine.quit();
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let logged: Vec<&str> = log.lines().map(|l| l.split_once(' ').unwrap().1).collect();
+        assert_eq!(logged[..3], [
+            "<< info string Failed to set option! UnknownOption(\"Nope\")",
+            ">> position startpos moves e2e4 e7e5 x9y9",
+            "<< info string Bad position command! BadMove(2, InvalidSyntax(\"x9y9\"))",
+        ]);
+        assert_eq!(logged[3], ">> setoption name Debug Log File value");
+        assert_eq!(logged.len(), 4);
+    }
+
+    #[test]
+    fn quit_during_search() {
+        let (engine, lines) = engine();
+        engine.send(Command::Go("go infinite".into()));
+        engine.quit();
+        assert!(read_until(&lines, "bestmove")[0].starts_with("info"));
+    }
+}
@@ -0,0 +1,135 @@
+// `chess-engine --json`: every line the engine would send is written as a JSON object instead,
+// one per line, for programs that would rather not parse UCI. `info` and `bestmove` get their
+// fields, anything else is passed on as `{"type":"text","text":...}`
+use super::writer::UciWriter;
+
+// `info` fields that are followed by a single number
+const NUMBERS: [&str; 9] = ["depth", "seldepth", "multipv", "time", "nodes", "nps", "hashfull", "tbhits", "currmovenumber"];
+
+// Translates the lines for `writer`
+pub struct JsonWriter<W: UciWriter>(pub W);
+
+impl<W: UciWriter> UciWriter for JsonWriter<W> {
+    fn write_line(&mut self, line: &str) {
+        self.0.write_line(&to_json(line));
+    }
+}
+
+pub fn to_json(line: &str) -> String {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let fields = match tokens.split_first() {
+        Some((&"info", rest)) => info_fields(rest),
+        Some((&"bestmove", rest)) => bestmove_fields(rest),
+        _ => vec![("type", string("text")), ("text", string(line))],
+    };
+    let fields: Vec<String> = fields.iter().map(|(key, value)| format!("{}:{}", string(key), value)).collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+// "info depth 5 score cp 20 wdl 500 400 100 pv e2e4 e7e5" and the like. `string` and `pv` take
+// the rest of the line, a field that is not understood is kept as text
+fn info_fields<'a>(tokens: &[&'a str]) -> Vec<(&'a str, String)> {
+    let mut fields = vec![("type", string("info"))];
+    let mut i = 0;
+    while i < tokens.len() {
+        let key = tokens[i];
+        let value = tokens.get(i + 1);
+        i += 2;
+        match key {
+            "string" => {
+                fields.push(("string", string(&tokens[i - 1..].join(" "))));
+                break;
+            }
+            "pv" => {
+                fields.push(("pv", array(tokens[i - 1..].iter().map(|m| string(m)))));
+                break;
+            }
+            "score" => {
+                let n = tokens.get(i).copied().unwrap_or_default();
+                fields.push(("score", format!("{{{}:{}}}", string(value.copied().unwrap_or_default()), number(n))));
+                i += 1;
+                if let Some(&bound @ ("lowerbound" | "upperbound")) = tokens.get(i) {
+                    fields.push(("bound", string(bound)));
+                    i += 1;
+                }
+            }
+            "wdl" => {
+                let wdl = tokens.get(i - 1..(i + 2).min(tokens.len())).unwrap_or_default();
+                fields.push(("wdl", array(wdl.iter().map(|n| number(n)))));
+                i += 2;
+            }
+            _ if NUMBERS.contains(&key) => fields.push((key, number(value.copied().unwrap_or_default()))),
+            _ => fields.push((key, string(value.copied().unwrap_or_default()))),
+        }
+    }
+    fields
+}
+
+// "bestmove e2e4 [ponder e7e5]", and "bestmove (none)" without a move
+fn bestmove_fields<'a>(tokens: &[&'a str]) -> Vec<(&'a str, String)> {
+    let best = match tokens.first() {
+        Some(&"(none)") | None => "null".to_string(),
+        Some(m) => string(m),
+    };
+    let mut fields = vec![("type", string("bestmove")), ("move", best)];
+    if let (Some(&"ponder"), Some(m)) = (tokens.get(1), tokens.get(2)) {
+        fields.push(("ponder", string(m)));
+    }
+    fields
+}
+
+// Numbers as they are, anything else that ended up where one should be as a string
+fn number(token: &str) -> String {
+    match token.parse::<i64>() {
+        Ok(n) => n.to_string(),
+        Err(_) => string(token),
+    }
+}
+
+fn array(values: impl Iterator<Item = String>) -> String {
+    format!("[{}]", values.collect::<Vec<_>>().join(","))
+}
+
+fn string(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"'  => quoted += "\\\"",
+            '\\' => quoted += "\\\\",
+            '\n' => quoted += "\\n",
+            '\t' => quoted += "\\t",
+            c if c.is_control() => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn info() {
+        assert_eq!(
+            to_json("info depth 5 multipv 1 score cp -20 wdl 100 500 400 time 12 nodes 3400 nps 283333 hashfull 1 pv e2e4 e7e5"),
+            r#"{"type":"info","depth":5,"multipv":1,"score":{"cp":-20},"wdl":[100,500,400],"time":12,"nodes":3400,"nps":283333,"hashfull":1,"pv":["e2e4","e7e5"]}"#
+        );
+        assert_eq!(to_json("info depth 0 score mate 0"), r#"{"type":"info","depth":0,"score":{"mate":0}}"#);
+        assert_eq!(
+            to_json("info depth 3 currmove g1f3 currmovenumber 2"),
+            r#"{"type":"info","depth":3,"currmove":"g1f3","currmovenumber":2}"#
+        );
+        assert_eq!(to_json("info string book move"), r#"{"type":"info","string":"book move"}"#);
+    }
+
+    #[test]
+    fn other_lines() {
+        assert_eq!(to_json("bestmove e2e4 ponder e7e5"), r#"{"type":"bestmove","move":"e2e4","ponder":"e7e5"}"#);
+        assert_eq!(to_json("bestmove (none)"), r#"{"type":"bestmove","move":null}"#);
+        assert_eq!(to_json("readyok"), r#"{"type":"text","text":"readyok"}"#);
+        assert_eq!(to_json(r#"Bad FEN! "a\b""#), r#"{"type":"text","text":"Bad FEN! \"a\\b\""}"#);
+    }
+}
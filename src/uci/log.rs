@@ -0,0 +1,61 @@
+// `Debug Log File`: every line the engine reads (`>>`) and sends (`<<`), with the time it was
+// read or sent, appended to a file. For the GUI and the engine disagreeing about what was said
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Output;
+
+// Shared by the engine thread, which opens and closes it, and whoever reads input and writes output
+#[derive(Debug, Clone, Default)]
+pub struct DebugLog(Arc<Mutex<Option<File>>>);
+
+impl DebugLog {
+    // Closes the log that was open. An empty path leaves it at that
+    pub fn open(&self, path: &str) -> io::Result<()> {
+        let file = match path {
+            "" => None,
+            path => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        };
+        *self.0.lock().unwrap() = file;
+        Ok(())
+    }
+
+    pub fn input(&self, line: &str) {
+        self.write(">>", line);
+    }
+
+    pub fn output(&self, line: &str) {
+        self.write("<<", line);
+    }
+
+    // An `Output` whose lines are logged on their way to `out`
+    pub fn tee(&self, out: Output) -> Output {
+        let (tee, lines) = mpsc::channel::<String>();
+        let log = self.clone();
+        thread::spawn(move || {
+            for line in lines {
+                log.output(&line);
+                if out.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+        tee
+    }
+
+    // A line that can not be written is lost, the engine goes on regardless
+    fn write(&self, direction: &str, line: &str) {
+        if let Some(file) = self.0.lock().unwrap().as_mut() {
+            let _ = file.write_all(format!("{} {} {}\n", timestamp(), direction, line).as_bytes());
+        }
+    }
+}
+
+// Seconds since the Unix epoch, to the millisecond
+fn timestamp() -> String {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!("{}.{:03}", since_epoch.as_secs(), since_epoch.subsec_millis())
+}
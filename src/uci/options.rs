@@ -0,0 +1,335 @@
+use std::fmt;
+#[cfg(feature = "tune")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "tune")]
+use crate::core::evaluate::tune::{self, TUNABLES};
+use crate::core::search::{skill::{Skill, MAX_SKILL_LEVEL}, strength::{Strength, MAX_ELO, MIN_ELO}};
+
+pub const HASH:           &str = "Hash";
+pub const THREADS:        &str = "Threads";
+pub const MULTI_PV:       &str = "MultiPV";
+pub const PONDER:         &str = "Ponder";
+pub const MOVE_OVERHEAD:  &str = "Move Overhead";
+pub const CLEAR_HASH:     &str = "Clear Hash";
+pub const OWN_BOOK:       &str = "OwnBook";
+pub const LIMIT_STRENGTH: &str = "UCI_LimitStrength";
+pub const ELO:            &str = "UCI_Elo";
+pub const SKILL_LEVEL:    &str = "Skill Level";
+pub const SHOW_WDL:       &str = "UCI_ShowWDL";
+pub const HASH_FILE:      &str = "HashFile";
+pub const SAVE_HASH:      &str = "Save Hash to File";
+pub const LOAD_HASH:      &str = "Load Hash from File";
+pub const DEBUG_LOG_FILE: &str = "Debug Log File";
+pub const NODESTIME:      &str = "nodestime";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionType {
+    Spin { default: i64, min: i64, max: i64 },
+    Check { default: bool },
+    String { default: &'static str },
+    Button,
+}
+
+// The declaration of an option, as announced in reply to `uci`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UciOption {
+    pub name: &'static str,
+    pub kind: OptionType,
+}
+
+// Every option the engine supports, in the order they are announced
+pub const OPTIONS: &[UciOption] = &[
+    UciOption { name: HASH,           kind: OptionType::Spin { default: 16, min: 1, max: 1024 } },
+    UciOption { name: THREADS,        kind: OptionType::Spin { default: 1, min: 1, max: 1 } },
+    UciOption { name: MULTI_PV,       kind: OptionType::Spin { default: 1, min: 1, max: 64 } },
+    UciOption { name: PONDER,         kind: OptionType::Check { default: false } },
+    UciOption { name: MOVE_OVERHEAD,  kind: OptionType::Spin { default: 10, min: 0, max: 5000 } },
+    UciOption { name: CLEAR_HASH,     kind: OptionType::Button },
+    UciOption { name: OWN_BOOK,       kind: OptionType::Check { default: true } },
+    UciOption { name: LIMIT_STRENGTH, kind: OptionType::Check { default: false } },
+    UciOption { name: ELO,            kind: OptionType::Spin { default: 1500, min: MIN_ELO as i64, max: MAX_ELO as i64 } },
+    UciOption { name: SKILL_LEVEL,    kind: OptionType::Spin { default: 20, min: 0, max: MAX_SKILL_LEVEL as i64 } },
+    UciOption { name: SHOW_WDL,       kind: OptionType::Check { default: false } },
+    UciOption { name: HASH_FILE,      kind: OptionType::String { default: "hash.bin" } },
+    UciOption { name: SAVE_HASH,      kind: OptionType::Button },
+    UciOption { name: LOAD_HASH,      kind: OptionType::Button },
+    UciOption { name: DEBUG_LOG_FILE, kind: OptionType::String { default: "" } },
+    UciOption { name: NODESTIME,      kind: OptionType::Spin { default: 0, min: 0, max: 100000 } },
+];
+
+// The evaluation weights, after the options above
+#[cfg(feature = "tune")]
+fn tunable_options() -> &'static [UciOption] {
+    static TUNABLE_OPTIONS: OnceLock<Vec<UciOption>> = OnceLock::new();
+    TUNABLE_OPTIONS.get_or_init(|| {
+        TUNABLES.iter().map(|t| UciOption {
+            name: t.name,
+            kind: OptionType::Spin { default: t.default as i64, min: t.min as i64, max: t.max as i64 },
+        }).collect()
+    })
+}
+
+// `OPTIONS`, and with the `tune` feature the evaluation weights
+pub fn all_options() -> impl Iterator<Item = &'static UciOption> {
+    let options = OPTIONS.iter();
+    #[cfg(feature = "tune")]
+    let options = options.chain(tunable_options());
+    options
+}
+
+impl fmt::Display for UciOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "option name {} type ", self.name)?;
+        match self.kind {
+            OptionType::Spin { default, min, max } => write!(f, "spin default {} min {} max {}", default, min, max),
+            OptionType::Check { default }          => write!(f, "check default {}", default),
+            OptionType::String { default: "" }     => write!(f, "string default <empty>"),
+            OptionType::String { default }         => write!(f, "string default {}", default),
+            OptionType::Button                     => write!(f, "button"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionValue {
+    Spin(i64),
+    Check(bool),
+    String(String),
+    Button,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SetOptionError {
+    InvalidSyntax,
+    UnknownOption(String),
+    InvalidValue(String),
+    OutOfRange(String),
+}
+
+// Option values the rest of the engine reads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineOptions {
+    pub hash_mb: usize,
+    pub threads: usize,
+    pub multi_pv: usize,
+    pub ponder: bool,
+    pub move_overhead_ms: u64,
+    pub own_book: bool,
+    pub limit_strength: bool,
+    pub elo: u32,
+    pub skill_level: usize,
+    pub show_wdl: bool,
+    pub hash_file: String,  // where `Save Hash to File` and `Load Hash from File` go
+    pub debug_log_file: String,  // empty for no log
+    pub nodestime: u64,  // nodes to the millisecond of search time, 0 for the real clock
+    pub debug: bool,  // `debug on|off`, not an option of its own
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        let mut options = EngineOptions {
+            hash_mb: 0,
+            threads: 0,
+            multi_pv: 0,
+            ponder: false,
+            move_overhead_ms: 0,
+            own_book: false,
+            limit_strength: false,
+            elo: 0,
+            skill_level: 0,
+            show_wdl: false,
+            hash_file: String::new(),
+            debug_log_file: String::new(),
+            nodestime: 0,
+            debug: false,
+        };
+        for option in OPTIONS {
+            options.apply(option.name, &option.default_value());
+        }
+        options
+    }
+}
+
+impl UciOption {
+    pub fn find(name: &str) -> Option<&'static UciOption> {
+        // Option names are case-insensitive
+        all_options().find(|o| o.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn default_value(&self) -> OptionValue {
+        match self.kind {
+            OptionType::Spin { default, .. } => OptionValue::Spin(default),
+            OptionType::Check { default }    => OptionValue::Check(default),
+            OptionType::String { default }   => OptionValue::String(default.to_string()),
+            OptionType::Button               => OptionValue::Button,
+        }
+    }
+
+    fn parse_value(&self, value: Option<&str>) -> Result<OptionValue, SetOptionError> {
+        let invalid = || SetOptionError::InvalidValue(self.name.to_string());
+        match (self.kind, value) {
+            (OptionType::Button, _) => Ok(OptionValue::Button),
+            (OptionType::String { .. }, None) => Ok(OptionValue::String(String::new())),
+            (OptionType::String { .. }, Some("<empty>")) => Ok(OptionValue::String(String::new())),
+            (OptionType::String { .. }, Some(v)) => Ok(OptionValue::String(v.to_string())),
+            (OptionType::Check { .. }, Some(v)) => match v.to_ascii_lowercase().as_str() {
+                "true"  => Ok(OptionValue::Check(true)),
+                "false" => Ok(OptionValue::Check(false)),
+                _       => Err(invalid()),
+            },
+            (OptionType::Spin { min, max, .. }, Some(v)) => {
+                let n: i64 = v.parse().map_err(|_| invalid())?;
+                if n < min || n > max {
+                    return Err(SetOptionError::OutOfRange(self.name.to_string()));
+                }
+                Ok(OptionValue::Spin(n))
+            }
+            (_, None) => Err(invalid()),
+        }
+    }
+}
+
+// The option a `setoption` line is about, and its value as it was given
+pub fn parse_setoption(tokens: &[&str]) -> Result<(&'static UciOption, Option<String>), SetOptionError> {
+    if tokens.get(1) != Some(&"name") {
+        return Err(SetOptionError::InvalidSyntax);
+    }
+    let value_at = tokens.iter().position(|&t| t == "value");
+    let name = tokens[2..value_at.unwrap_or(tokens.len())].join(" ");
+    let value = value_at.map(|i| tokens[i + 1..].join(" "));
+    let option = UciOption::find(&name).ok_or(SetOptionError::UnknownOption(name))?;
+    Ok((option, value))
+}
+
+impl EngineOptions {
+    // `setoption name <name> [value <value>]`, both parts may contain spaces.
+    // Returns the option that was set, buttons have no state here and are up to the caller
+    pub fn set(&mut self, tokens: &[&str]) -> Result<&'static UciOption, SetOptionError> {
+        let (option, value) = parse_setoption(tokens)?;
+        let value = option.parse_value(value.as_deref().filter(|v| !v.is_empty()))?;
+        self.apply(option.name, &value);
+        Ok(option)
+    }
+
+    // None unless `UCI_LimitStrength` is on
+    pub fn strength(&self) -> Option<Strength> {
+        self.limit_strength.then(|| Strength::from_elo(self.elo))
+    }
+
+    // `UCI_LimitStrength` takes precedence
+    pub fn skill(&self) -> Option<Skill> {
+        Some(Skill::new(self.skill_level)).filter(|skill| skill.is_enabled() && !self.limit_strength)
+    }
+
+    fn apply(&mut self, name: &str, value: &OptionValue) {
+        match (name, value) {
+            (HASH,           OptionValue::Spin(n))   => self.hash_mb = *n as usize,
+            (THREADS,        OptionValue::Spin(n))   => self.threads = *n as usize,
+            (MULTI_PV,       OptionValue::Spin(n))   => self.multi_pv = *n as usize,
+            (PONDER,         OptionValue::Check(b))  => self.ponder = *b,
+            (MOVE_OVERHEAD,  OptionValue::Spin(n))   => self.move_overhead_ms = *n as u64,
+            (OWN_BOOK,       OptionValue::Check(b))  => self.own_book = *b,
+            (LIMIT_STRENGTH, OptionValue::Check(b))  => self.limit_strength = *b,
+            (ELO,            OptionValue::Spin(n))   => self.elo = *n as u32,
+            (SKILL_LEVEL,    OptionValue::Spin(n))   => self.skill_level = *n as usize,
+            (SHOW_WDL,       OptionValue::Check(b))  => self.show_wdl = *b,
+            (HASH_FILE,      OptionValue::String(s)) => self.hash_file = s.clone(),
+            (DEBUG_LOG_FILE, OptionValue::String(s)) => self.debug_log_file = s.clone(),
+            (NODESTIME,      OptionValue::Spin(n))   => self.nodestime = *n as u64,
+            #[cfg(feature = "tune")]
+            (name,           OptionValue::Spin(n))   => if let Some(t) = tune::find(name) { t.set(*n as i32) },
+            _ => {}
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(options: &mut EngineOptions, command: &str) -> Result<&'static str, SetOptionError> {
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        options.set(&tokens).map(|o| o.name)
+    }
+
+    #[test]
+    fn defaults() {
+        let options = EngineOptions::default();
+        assert_eq!(options.hash_mb, 16);
+        assert_eq!(options.threads, 1);
+        assert_eq!(options.multi_pv, 1);
+        assert!(!options.ponder);
+        assert_eq!(options.move_overhead_ms, 10);
+        assert!(options.own_book);
+        assert_eq!(options.strength(), None);
+        assert_eq!(options.skill(), None);
+        assert!(!options.show_wdl);
+        assert_eq!(options.hash_file, "hash.bin");
+        assert_eq!(options.debug_log_file, "");
+        assert_eq!(options.nodestime, 0);
+    }
+
+    #[test]
+    fn announce() {
+        let announced: Vec<String> = OPTIONS.iter().map(|o| o.to_string()).collect();
+        assert!(announced.contains(&"option name Hash type spin default 16 min 1 max 1024".to_string()));
+        assert!(announced.contains(&"option name Ponder type check default false".to_string()));
+        let button = UciOption { name: "Clear", kind: OptionType::Button };
+        assert_eq!(button.to_string(), "option name Clear type button");
+    }
+
+    #[test]
+    fn setoption() {
+        let mut options = EngineOptions::default();
+        assert_eq!(set(&mut options, "setoption name Hash value 64"), Ok(HASH));
+        assert_eq!(options.hash_mb, 64);
+        assert_eq!(set(&mut options, "setoption name move overhead value 100"), Ok(MOVE_OVERHEAD));
+        assert_eq!(options.move_overhead_ms, 100);
+        assert_eq!(set(&mut options, "setoption name Ponder value true"), Ok(PONDER));
+        assert!(options.ponder);
+        assert_eq!(set(&mut options, "setoption name OwnBook value false"), Ok(OWN_BOOK));
+        assert!(!options.own_book);
+        assert_eq!(set(&mut options, "setoption name Clear Hash"), Ok(CLEAR_HASH));
+        assert_eq!(set(&mut options, "setoption name UCI_Elo value 1200"), Ok(ELO));
+        assert_eq!(options.strength(), None);
+        assert_eq!(set(&mut options, "setoption name UCI_LimitStrength value true"), Ok(LIMIT_STRENGTH));
+        assert_eq!(options.strength(), Some(Strength::from_elo(1200)));
+        assert_eq!(set(&mut options, "setoption name Skill Level value 5"), Ok(SKILL_LEVEL));
+        assert_eq!(options.skill(), None);
+        assert_eq!(set(&mut options, "setoption name UCI_LimitStrength value false"), Ok(LIMIT_STRENGTH));
+        assert_eq!(options.skill(), Some(Skill::new(5)));
+        assert_eq!(set(&mut options, "setoption name MultiPV value 3"), Ok(MULTI_PV));
+        assert_eq!(options.multi_pv, 3);
+        assert_eq!(set(&mut options, "setoption name HashFile value /tmp/long analysis.tt"), Ok(HASH_FILE));
+        assert_eq!(options.hash_file, "/tmp/long analysis.tt");
+    }
+
+    #[test]
+    fn setoption_errors() {
+        let mut options = EngineOptions::default();
+        assert_eq!(set(&mut options, "setoption Hash value 64"), Err(SetOptionError::InvalidSyntax));
+        assert_eq!(set(&mut options, "setoption name Nope value 1"), Err(SetOptionError::UnknownOption("Nope".into())));
+        assert_eq!(set(&mut options, "setoption name Hash value lots"), Err(SetOptionError::InvalidValue(HASH.into())));
+        assert_eq!(set(&mut options, "setoption name Hash"), Err(SetOptionError::InvalidValue(HASH.into())));
+        assert_eq!(set(&mut options, "setoption name Hash value 0"), Err(SetOptionError::OutOfRange(HASH.into())));
+        assert_eq!(set(&mut options, "setoption name Ponder value maybe"), Err(SetOptionError::InvalidValue(PONDER.into())));
+        assert_eq!(set(&mut options, "setoption name UCI_Elo value 100"), Err(SetOptionError::OutOfRange(ELO.into())));
+        assert_eq!(options, EngineOptions::default());
+    }
+
+    // Only announces and sets the defaults, the weights are shared by every test running
+    #[cfg(feature = "tune")]
+    #[test]
+    fn tunables() {
+        let announced: Vec<String> = all_options().map(|o| o.to_string()).collect();
+        assert!(announced.contains(&"option name PawnValue type spin default 100 min 50 max 200".to_string()));
+        assert_eq!(announced.last().unwrap(), "option name TropismBishopEg type spin default 0 min 0 max 20");
+
+        let mut options = EngineOptions::default();
+        assert_eq!(set(&mut options, "setoption name tempo value 15"), Ok("Tempo"));
+        assert_eq!(tune::TEMPO.get(), 15);
+        assert_eq!(set(&mut options, "setoption name QueenValue value 2000"), Err(SetOptionError::OutOfRange("QueenValue".into())));
+    }
+}
@@ -0,0 +1,169 @@
+use crate::core::{chess_move::UciMoveParseError, position::FenParseError};
+
+// Whatever is wrong with a command line, reported back as an `info string`
+#[derive(Debug, PartialEq, Eq)]
+pub enum UciParseError {
+    MissingValue(String),          // "go depth"
+    InvalidValue(String, String),  // "go depth abc"
+    UnknownParameter(String),
+    UnsupportedParameter(String),
+    MissingPosition,               // neither "startpos" nor "fen"
+    BadFen(FenParseError),
+    BadMove(usize, UciMoveParseError),  // index into the `moves` list
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GoParams {
+    pub perft:    Option<usize>,
+    pub movetime: Option<usize>,
+    pub depth:    Option<usize>,
+    pub nodes:    Option<usize>,
+    pub infinite: bool,
+    pub wtime:    Option<usize>,
+    pub btime:    Option<usize>,
+    pub winc:     Option<usize>,
+    pub binc:     Option<usize>,
+}
+
+// A bad parameter is reported and left out, the rest of the command still counts
+pub fn parse_go_params(tokens: &[&str]) -> (GoParams, Vec<UciParseError>) {
+    let mut params = GoParams::default();
+    let mut errors = Vec::new();
+
+    // Treat `go` as `go infinite`
+    if tokens.len() == 1 {
+        params.infinite = true;
+        return (params, errors);
+    }
+
+    let mut i = 1;  // skip the "go"
+    let parse = |target: &mut Option<usize>, i: &mut usize| {
+        let name = tokens[*i];
+        let value = tokens.get(*i + 1).ok_or_else(|| UciParseError::MissingValue(name.into()))?;
+        *i += 1;
+        *target = Some(value.parse().map_err(|_| UciParseError::InvalidValue(name.into(), value.to_string()))?);
+        Ok(())
+    };
+
+    while i < tokens.len() {
+        let result = match tokens[i] {
+            "perft"    => parse(&mut params.perft,    &mut i),
+            "movetime" => parse(&mut params.movetime, &mut i),
+            "depth"    => parse(&mut params.depth,    &mut i),
+            "nodes"    => parse(&mut params.nodes,    &mut i),
+            "wtime"    => parse(&mut params.wtime,    &mut i),
+            "btime"    => parse(&mut params.btime,    &mut i),
+            "winc"     => parse(&mut params.winc,     &mut i),
+            "binc"     => parse(&mut params.binc,     &mut i),
+            "infinite" => {
+                params.infinite = true;
+                Ok(())
+            }
+            name @ ("movestogo" | "mate") => parse(&mut None, &mut i)
+                .and(Err(UciParseError::UnsupportedParameter(name.into()))),
+            name @ ("ponder" | "searchmoves") => {
+                // `searchmoves` takes the rest of the line with it
+                if name == "searchmoves" {
+                    i = tokens.len();
+                }
+                Err(UciParseError::UnsupportedParameter(name.into()))
+            }
+            token => Err(UciParseError::UnknownParameter(token.into())),
+        };
+        if let Err(e) = result {
+            errors.push(e);
+        }
+        i += 1;
+    }
+    (params, errors)
+}
+
+// `position [startpos | fen <fen>] [moves <move>...]`
+#[derive(Debug, PartialEq, Eq)]
+pub struct PositionParams<'a> {
+    pub fen: Option<String>,  // None for the starting position
+    pub moves: &'a [&'a str],
+}
+
+pub fn parse_position_params<'a>(tokens: &'a [&'a str]) -> Result<PositionParams<'a>, UciParseError> {
+    let moves_at = tokens.iter().position(|t| *t == "moves").unwrap_or(tokens.len());
+    let moves = tokens.get(moves_at + 1..).unwrap_or(&[]);
+
+    let fen = match tokens.get(1) {
+        Some(&"startpos") => match tokens.get(2..moves_at) {
+            Some([]) => None,
+            Some(extra) => return Err(UciParseError::UnknownParameter(extra[0].into())),
+            None => return Err(UciParseError::MissingPosition),
+        },
+        Some(&"fen") => match tokens.get(2..moves_at) {
+            Some(fields) if !fields.is_empty() => Some(fields.join(" ")),
+            _ => return Err(UciParseError::BadFen(FenParseError::BadFieldCount)),
+        },
+        Some(token) if *token != "moves" => return Err(UciParseError::UnknownParameter(token.to_string())),
+        _ => return Err(UciParseError::MissingPosition),
+    };
+    Ok(PositionParams { fen, moves })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(line: &str) -> Vec<&str> {
+        line.split_whitespace().collect()
+    }
+
+    #[test]
+    fn go() {
+        let (params, errors) = parse_go_params(&tokens("go wtime 1000 btime 2000 winc 10"));
+        assert_eq!(params, GoParams { wtime: Some(1000), btime: Some(2000), winc: Some(10), ..GoParams::default() });
+        assert!(errors.is_empty());
+
+        let (params, errors) = parse_go_params(&tokens("go"));
+        assert!(params.infinite && errors.is_empty());
+
+        let (params, errors) = parse_go_params(&tokens("go depth 8 movetime 500 nodes 100000"));
+        assert_eq!(params, GoParams { depth: Some(8), movetime: Some(500), nodes: Some(100000), ..GoParams::default() });
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn go_errors() {
+        let (params, errors) = parse_go_params(&tokens("go depth abc movetime 100 frobnicate mate 5 depth"));
+        assert_eq!(params, GoParams { movetime: Some(100), ..GoParams::default() });
+        assert_eq!(errors, [
+            UciParseError::InvalidValue("depth".into(), "abc".into()),
+            UciParseError::UnknownParameter("frobnicate".into()),
+            UciParseError::UnsupportedParameter("mate".into()),
+            UciParseError::MissingValue("depth".into()),
+        ]);
+
+        let (params, errors) = parse_go_params(&tokens("go searchmoves e2e4 d2d4 depth 3"));
+        assert_eq!(params, GoParams::default());
+        assert_eq!(errors, [UciParseError::UnsupportedParameter("searchmoves".into())]);
+    }
+
+    #[test]
+    fn position() {
+        let t = tokens("position startpos moves e2e4 e7e5");
+        assert_eq!(parse_position_params(&t), Ok(PositionParams { fen: None, moves: &["e2e4", "e7e5"] }));
+
+        let t = tokens("position fen 8/8/8/8/8/8/8/K6k w - - 0 1");
+        assert_eq!(parse_position_params(&t), Ok(PositionParams { fen: Some("8/8/8/8/8/8/8/K6k w - - 0 1".into()), moves: &[] }));
+    }
+
+    #[test]
+    fn position_errors() {
+        for (line, error) in [
+            ("position",                   UciParseError::MissingPosition),
+            ("position moves e2e4",        UciParseError::MissingPosition),
+            ("position somewhere",         UciParseError::UnknownParameter("somewhere".into())),
+            ("position startpos e2e4",     UciParseError::UnknownParameter("e2e4".into())),
+            ("position fen",               UciParseError::BadFen(FenParseError::BadFieldCount)),
+            ("position fen moves e2e4",    UciParseError::BadFen(FenParseError::BadFieldCount)),
+        ] {
+            assert_eq!(parse_position_params(&tokens(line)), Err(error), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+use super::Output;
+
+// Where the protocol output ends up, one line at a time
+pub trait UciWriter: Send {
+    fn write_line(&mut self, line: &str);
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdoutWriter;
+
+impl UciWriter for StdoutWriter {
+    fn write_line(&mut self, line: &str) {
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "{}", line).unwrap();
+        stdout.flush().unwrap();
+    }
+}
+
+// Hands the lines on to someone else, to a test for example
+impl UciWriter for Sender<String> {
+    fn write_line(&mut self, line: &str) {
+        let _ = self.send(line.to_string());
+    }
+}
+
+// The lines sent to the returned `Output`, from any number of threads, reach `writer`
+// in the order they were sent. The thread finishes once every `Output` clone is dropped
+pub fn spawn_writer(mut writer: impl UciWriter + 'static) -> (Output, JoinHandle<()>) {
+    let (out, lines) = mpsc::channel::<String>();
+    let thread = thread::spawn(move || {
+        for line in lines {
+            writer.write_line(&line);
+        }
+    });
+    (out, thread)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uci::uci;
+
+    #[test]
+    fn captures_output() {
+        let (captured, lines) = mpsc::channel();
+        let (out, thread) = spawn_writer(captured);
+        uci(&out);
+        drop(out);
+        thread.join().unwrap();
+
+        let lines: Vec<String> = lines.iter().collect();
+        assert_eq!(lines.first().map(String::as_str), Some("id name chess-engine"));
+        assert_eq!(lines.last().map(String::as_str), Some("uciok"));
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::{atomic::AtomicBool, Arc};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+use crate::core::{
+    game::Game,
+    search::{iterative_deepening, SearchInfo, SearchLimits},
+    tt::TranspositionTable,
+};
+
+// Without either limit, a search would never end
+const DEFAULT_DEPTH: usize = 8;
+
+// The engine as JavaScript sees it, built with `wasm-pack build --target web`.
+// The search blocks, so a browser page should run it in a Web Worker
+#[wasm_bindgen]
+pub struct Engine {
+    game: Game,
+    tt: TranspositionTable,
+}
+
+#[wasm_bindgen]
+impl Engine {
+    #[wasm_bindgen(constructor)]
+    pub fn new(hash_mb: usize) -> Engine {
+        Engine { game: Game::default(), tt: TranspositionTable::new(hash_mb) }
+    }
+
+    // `fen` is a FEN or "startpos", `moves` are played from there, in UCI notation
+    pub fn set_position(&mut self, fen: &str, moves: Vec<String>) -> Result<(), JsError> {
+        let mut game = match fen {
+            "startpos" => Game::default(),
+            _ => Game::from_fen(fen).map_err(|e| JsError::new(&format!("Bad FEN: {:?}", e)))?,
+        };
+        for m in &moves {
+            if !game.try_to_make_uci_move(m) {
+                return Err(JsError::new(&format!("Illegal move: {}", m)));
+            }
+        }
+        self.game = game;
+        Ok(())
+    }
+
+    pub fn fen(&self) -> String {
+        self.game.to_fen()
+    }
+
+    // In UCI notation
+    pub fn legal_moves(&self) -> Vec<String> {
+        self.game.legal_moves().map(|m| m.to_string()).collect()
+    }
+
+    // `on_info(depth, score, pv)` is called after every iteration, `score` in centipawns from the side
+    // to move's point of view and `pv` a space-separated line. Returns the best move, if there is one.
+    // Goes `DEFAULT_DEPTH` deep if neither `depth` nor `movetime_ms` is given
+    pub fn search(&mut self, depth: Option<usize>, movetime_ms: Option<u32>, on_info: &js_sys::Function) -> Option<String> {
+        let depth = match (depth, movetime_ms) {
+            (None, None) => Some(DEFAULT_DEPTH),
+            _ => depth,
+        };
+        let limits = SearchLimits {
+            depth,
+            movetime: movetime_ms.map(|ms| Duration::from_millis(ms as u64)),
+            ..Default::default()
+        };
+        let report = |info: &SearchInfo| {
+            let pv: Vec<String> = info.pv.iter().map(|m| m.to_string()).collect();
+            let _ = on_info.call3(
+                &JsValue::NULL,
+                &JsValue::from(info.depth as u32),
+                &JsValue::from(info.score),
+                &JsValue::from(pv.join(" ")),
+            );
+        };
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let result = iterative_deepening(&mut self.game, limits, None, stop_flag, &mut self.tt, report);
+        result.best_move.map(|m| m.to_string())
+    }
+}